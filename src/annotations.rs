@@ -7,7 +7,8 @@
 //! The registry covers the ~25 most useful named quantities, using Numbat's
 //! `core/dimensions.nbt` as reference.
 
-use crate::units::dimension::{Dimension, DimensionMap};
+use crate::units::dimension::{Dimension, DimensionMap, create_dimensions};
+use crate::units::rational::Rational;
 use std::collections::HashMap;
 use std::sync::OnceLock;
 
@@ -31,8 +32,8 @@ pub fn quantity_name_count() -> usize {
 /// use runits::units::dimension::Dimension;
 ///
 /// let dims = dimensions_for_name("velocity").unwrap();
-/// assert_eq!(dims.get(&Dimension::Length), Some(&1));
-/// assert_eq!(dims.get(&Dimension::Time), Some(&-1));
+/// assert_eq!(dims[&Dimension::Length], 1);
+/// assert_eq!(dims[&Dimension::Time], -1);
 /// ```
 pub fn dimensions_for_name(name: &str) -> Option<DimensionMap> {
     let name_lower = name.to_lowercase();
@@ -77,14 +78,14 @@ fn parse_dim_key(key: &str) -> DimensionMap {
         // Parse the exponent digits (including leading minus).
         let mut exp_str = String::new();
         while let Some(&next) = chars.peek() {
-            if next == '-' || next.is_ascii_digit() {
+            if next == '-' || next == '/' || next.is_ascii_digit() {
                 exp_str.push(next);
                 chars.next();
             } else {
                 break;
             }
         }
-        if let Ok(exp) = exp_str.parse::<i8>() {
+        if let Some(exp) = Rational::parse(&exp_str) {
             map.insert(dim, exp);
         }
     }
@@ -129,8 +130,7 @@ fn registry() -> &'static Registry {
 
 /// Convenience: build a dim_key from a slice of (Dimension, exponent) pairs.
 fn key(pairs: &[(Dimension, i8)]) -> String {
-    let map: DimensionMap = pairs.iter().cloned().collect();
-    dim_key(&map)
+    dim_key(&create_dimensions(pairs))
 }
 
 fn build_registry() -> Registry {
@@ -327,7 +327,7 @@ mod tests {
     use super::*;
 
     fn dims(pairs: &[(Dimension, i8)]) -> DimensionMap {
-        pairs.iter().cloned().collect()
+        create_dimensions(pairs)
     }
 
     #[test]
@@ -370,8 +370,8 @@ mod tests {
     #[test]
     fn dimensions_for_velocity() {
         let d = dimensions_for_name("velocity").unwrap();
-        assert_eq!(d.get(&Dimension::Length), Some(&1));
-        assert_eq!(d.get(&Dimension::Time), Some(&-1));
+        assert_eq!(d[&Dimension::Length], 1);
+        assert_eq!(d[&Dimension::Time], -1);
     }
 
     #[test]
@@ -0,0 +1,363 @@
+//! Automatic target units: `0.000047 F -> auto` is `47 microfarad`.
+//!
+//! The `auto` target picks a unit for the value instead of the user. For
+//! the default [`UnitFamily::Si`] family it finds the *stem* — the
+//! prefixable unit behind the source (`farad` for `mF`, `gram` for `kg`),
+//! or the dimension's coherent SI unit when the source has none (`meter`
+//! for `ft`) — and then the prefix that puts the mantissa in `[1, 1000)`:
+//!
+//! ```text
+//! 0.000047 F -> auto          47 microfarad
+//! 123456789 byte -> auto      117.738 mebibyte
+//! 5280 ft -> auto             1.6093 kilometer
+//! 5280 ft -> auto imperial    1 mile
+//! ```
+//!
+//! Only the powers-of-1000 prefixes from [`SI_PREFIXES`] are candidates, so
+//! `hecto` and `centi` never appear. Information units use
+//! [`BINARY_PREFIXES`] instead, with a `[1, 1024)` mantissa. A source with
+//! no stem (`km/h`, `degC`, a bare number) keeps its unit. Times of a
+//! minute or more read in minutes, hours, days or years instead
+//! (`5400 s -> auto` is `1.5 hour`, not `5.4 kilosecond`).
+//!
+//! A family other than SI restricts the choice to that family's units of
+//! the same dimension, picking the largest one the value reaches at least
+//! once. Dimensions the family has no units for fall back to SI prefixes.
+//!
+//! "Reaches" is judged on the value as it will be shown, rounded to the
+//! output's significant figures, so `999.9999 m` is `1 kilometer` rather
+//! than a `1000 meter` that has outgrown its unit.
+//!
+//! FUTURE(unit-families): families are fixed ladders of seed names here.
+//! When definition files can tag units with a system, the ladders could
+//! come from the database instead.
+
+use crate::database::{self, BINARY_PREFIXES, SI_PREFIXES, UnitDatabase};
+use crate::error::RUnitsError;
+use crate::units::dimension::Dimension;
+use crate::units::{Quantity, Unit};
+use serde::Deserialize;
+use std::fmt;
+
+/// The target word that asks for an automatic unit.
+pub const AUTO_TARGET: &str = "auto";
+
+/// A preferred system of units for the `auto` target.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, clap::ValueEnum, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum UnitFamily {
+    /// SI units with the best metric (or binary) prefix.
+    #[default]
+    Si,
+    /// Inches to miles, ounces to stones, fluid ounces to gallons.
+    Imperial,
+}
+
+impl UnitFamily {
+    /// Every family, in the order error messages list them.
+    pub const ALL: [UnitFamily; 2] = [UnitFamily::Si, UnitFamily::Imperial];
+
+    /// Name as accepted by `--auto`, the `auto` config key and `-> auto <family>`.
+    pub fn name(self) -> &'static str {
+        match self {
+            UnitFamily::Si => "si",
+            UnitFamily::Imperial => "imperial",
+        }
+    }
+
+    /// Parse a name, case-insensitively (`-> auto Imperial`).
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|f| f.name().eq_ignore_ascii_case(name))
+    }
+
+    /// Unit ladders, smallest unit first. Names missing from the database
+    /// tier in use are skipped.
+    fn ladders(self) -> &'static [&'static [&'static str]] {
+        match self {
+            UnitFamily::Si => &[],
+            UnitFamily::Imperial => &[
+                &["inch", "foot", "yard", "mile"],
+                &["ounce", "pound", "stone"],
+                &["fluid_ounce", "pint", "quart", "gallon"],
+            ],
+        }
+    }
+}
+
+impl fmt::Display for UnitFamily {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// Recognize an `auto` target: `auto` alone uses `default`, `auto <family>`
+/// names one. `None` when `target` is an ordinary unit.
+pub fn parse_target(target: &str, default: UnitFamily) -> Option<Result<UnitFamily, RUnitsError>> {
+    let mut words = target.split_whitespace();
+    if words.next() != Some(AUTO_TARGET) {
+        return None;
+    }
+    let family = match (words.next(), words.next()) {
+        (None, _) => Ok(default),
+        (Some(name), None) => UnitFamily::from_name(name).ok_or_else(|| unknown_family(name)),
+        (Some(_), Some(_)) => Err(unknown_family(target[AUTO_TARGET.len()..].trim())),
+    };
+    Some(family)
+}
+
+fn unknown_family(name: &str) -> RUnitsError {
+    let known: Vec<&str> = UnitFamily::ALL.iter().map(|f| f.name()).collect();
+    RUnitsError::UnknownUnitFamily {
+        name: name.to_string(),
+        known: known.join(", "),
+    }
+}
+
+/// Named time units, smallest first, used from a minute up.
+const TIME_LADDER: &[&str] = &["minute", "hour", "day", "year"];
+
+/// The unit `quantity` reads best in, within `family`, when shown with
+/// `sig_figs` significant figures.
+pub fn best_unit(
+    quantity: &Quantity,
+    family: UnitFamily,
+    db: &UnitDatabase,
+    sig_figs: usize,
+) -> Unit {
+    let unit = &quantity.unit;
+    if !unit.is_linear() || unit.dimensions.is_empty() {
+        return unit.clone();
+    }
+    let base = unit.to_base_value(quantity.value).abs();
+    // Whether the value shows as at least one of a unit of this factor.
+    let reaches = |factor: f64| round_sig(base / factor, sig_figs) >= 1.0 - 1e-12;
+    if let Some(ladder) = family
+        .ladders()
+        .iter()
+        .find_map(|names| ladder(names, unit, db))
+    {
+        return pick_from_ladder(ladder, reaches);
+    }
+    // Nobody reads a duration in kiloseconds: prefixes only below a minute.
+    if let Some(ladder) = ladder(TIME_LADDER, unit, db)
+        && reaches(ladder[0].conversion_factor())
+    {
+        return pick_from_ladder(ladder, reaches);
+    }
+    match stem(unit, db) {
+        Some(stem) => best_prefix(base, &stem, reaches),
+        None => unit.clone(),
+    }
+}
+
+/// The units named in `names` that share `unit`'s dimension, in order;
+/// `None` if there are none in this database.
+fn ladder(names: &[&str], unit: &Unit, db: &UnitDatabase) -> Option<Vec<Unit>> {
+    let ladder: Vec<Unit> = names
+        .iter()
+        .filter_map(|name| db.lookup(name))
+        .filter(|u| u.is_linear() && u.dimensions == unit.dimensions)
+        .collect();
+    (!ladder.is_empty()).then_some(ladder)
+}
+
+/// The largest ladder unit the value reaches at least once, or the
+/// smallest when it reaches none.
+fn pick_from_ladder(ladder: Vec<Unit>, reaches: impl Fn(f64) -> bool) -> Unit {
+    let mut chosen = ladder[0].clone();
+    for unit in ladder {
+        if reaches(unit.conversion_factor()) {
+            chosen = unit;
+        }
+    }
+    chosen
+}
+
+/// `v` rounded to `sig_figs` significant figures.
+fn round_sig(v: f64, sig_figs: usize) -> f64 {
+    if v == 0.0 || !v.is_finite() {
+        return v;
+    }
+    let quantum = 10f64.powi(v.abs().log10().floor() as i32 - sig_figs.max(1) as i32 + 1);
+    (v / quantum).round() * quantum
+}
+
+/// The unprefixed, prefixable unit behind `unit`: the base of a prefixed
+/// name (`microfarad`, and `kilogram`, whose own factor is 1), `unit`
+/// itself, or else the dimension's coherent SI unit.
+fn stem(unit: &Unit, db: &UnitDatabase) -> Option<Unit> {
+    let same_dims = |u: &Unit| u.prefixable && u.is_linear() && u.dimensions == unit.dimensions;
+    SI_PREFIXES
+        .iter()
+        .chain(BINARY_PREFIXES)
+        .find_map(|(long, _, _)| {
+            let rest = unit.name.strip_prefix(long)?;
+            db.lookup(rest).filter(same_dims)
+        })
+        .or_else(|| db.lookup(&unit.name).filter(same_dims))
+        .or_else(|| coherent_unit(db, same_dims))
+}
+
+/// A prefixable unit of the same dimension whose factor is 1 or itself a
+/// prefix (`gram`, `liter`). Closest to 1 wins, then the shortest name, so
+/// `hertz` beats `becquerel`.
+fn coherent_unit(db: &UnitDatabase, same_dims: impl Fn(&Unit) -> bool) -> Option<Unit> {
+    let is_prefix_scale = |f: f64| {
+        (f - 1.0).abs() < 1e-12
+            || SI_PREFIXES
+                .iter()
+                .any(|&(_, _, scale)| ((f - scale) / scale).abs() < 1e-12)
+    };
+    db.entries()
+        .map(|(_, u)| u)
+        .filter(|u| same_dims(u) && is_prefix_scale(u.conversion_factor()))
+        .min_by(|a, b| {
+            let distance = |u: &Unit| u.conversion_factor().log10().abs();
+            distance(a)
+                .total_cmp(&distance(b))
+                .then(a.name.len().cmp(&b.name.len()))
+                .then(a.name.cmp(&b.name))
+        })
+        .cloned()
+}
+
+/// `stem` with the prefix that puts `base` (in base units) into `[1, 1000)`,
+/// or `[1, 1024)` with binary prefixes for information units, as judged
+/// by `reaches`.
+fn best_prefix(base: f64, stem: &Unit, reaches: impl Fn(f64) -> bool) -> Unit {
+    let value = base / stem.conversion_factor();
+    if value == 0.0 || !value.is_finite() {
+        return stem.clone();
+    }
+    let binary = stem.dimensions.contains_key(&Dimension::Information);
+    let prefixes = if binary { BINARY_PREFIXES } else { SI_PREFIXES };
+    let mut candidates = vec![("", 1.0)];
+    candidates.extend(
+        prefixes
+            .iter()
+            .filter(|&&(_, _, scale)| binary || is_power_of_1000(scale))
+            .map(|&(long, _, scale)| (long, scale)),
+    );
+
+    // The largest prefix the value reaches; below them all, the smallest.
+    let by_scale = |a: &(&str, f64), b: &(&str, f64)| a.1.total_cmp(&b.1);
+    let (long, scale) = candidates
+        .iter()
+        .copied()
+        .filter(|&(_, scale)| reaches(stem.conversion_factor() * scale))
+        .max_by(by_scale)
+        .or_else(|| candidates.iter().copied().min_by(by_scale))
+        .expect("candidates always holds the empty prefix");
+    if scale == 1.0 {
+        return stem.clone();
+    }
+    database::with_prefix(stem, long, scale).unwrap_or_else(|| stem.clone())
+}
+
+fn is_power_of_1000(scale: f64) -> bool {
+    let exponent = scale.log10().round() as i32;
+    exponent % 3 == 0 && ((scale - 10f64.powi(exponent)) / scale).abs() < 1e-12
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn auto(input: &str, family: UnitFamily) -> Quantity {
+        let db = UnitDatabase::new();
+        let q = crate::parser::parse_quantity(input, &db).unwrap();
+        let unit = best_unit(&q, family, &db, 6);
+        q.convert_to(&unit).unwrap()
+    }
+
+    #[test]
+    fn si_prefix_puts_mantissa_in_range() {
+        let q = auto("0.000047 F", UnitFamily::Si);
+        assert_eq!(q.unit.name, "microfarad");
+        assert!((q.value - 47.0).abs() < 1e-9);
+
+        let q = auto("0.5 kg", UnitFamily::Si);
+        assert_eq!(q.unit.name, "gram");
+        assert!((q.value - 500.0).abs() < 1e-9);
+
+        assert_eq!(auto("2500 mm", UnitFamily::Si).unit.name, "meter");
+        assert_eq!(auto("1e-30 m", UnitFamily::Si).unit.name, "yoctometer");
+    }
+
+    #[test]
+    fn information_uses_binary_prefixes() {
+        let q = auto("123456789 byte", UnitFamily::Si);
+        assert_eq!(q.unit.name, "mebibyte");
+        assert!((q.value - 117.738).abs() < 1e-3);
+        assert_eq!(auto("1000 byte", UnitFamily::Si).unit.name, "byte");
+    }
+
+    #[test]
+    fn non_prefixable_source_uses_the_coherent_unit() {
+        let q = auto("5280 ft", UnitFamily::Si);
+        assert_eq!(q.unit.name, "kilometer");
+        assert_eq!(auto("120 rpm", UnitFamily::Si).unit.name, "hertz");
+        // No named unit for velocity: the source unit stays.
+        assert_eq!(auto("100 km/h", UnitFamily::Si).unit.name, "kilometer/hour");
+        assert_eq!(auto("20 degC", UnitFamily::Si).unit.name, "celsius");
+    }
+
+    #[test]
+    fn imperial_family_picks_the_largest_reached_unit() {
+        let q = auto("5280 ft", UnitFamily::Imperial);
+        assert_eq!(q.unit.name, "mile");
+        assert!((q.value - 1.0).abs() < 1e-9);
+        assert_eq!(auto("31 cm", UnitFamily::Imperial).unit.name, "foot");
+        assert_eq!(auto("1 mm", UnitFamily::Imperial).unit.name, "inch");
+        assert_eq!(auto("20 lb", UnitFamily::Imperial).unit.name, "stone");
+        // Nothing imperial for capacitance: SI prefixes instead.
+        assert_eq!(
+            auto("0.002 F", UnitFamily::Imperial).unit.name,
+            "millifarad"
+        );
+    }
+
+    #[test]
+    fn time_reads_in_named_units_from_a_minute_up() {
+        let q = auto("5400 s", UnitFamily::Si);
+        assert_eq!(q.unit.name, "hour");
+        assert!((q.value - 1.5).abs() < 1e-9);
+        assert_eq!(auto("90 s", UnitFamily::Si).unit.name, "minute");
+        assert_eq!(auto("3 day", UnitFamily::Si).unit.name, "day");
+        assert_eq!(auto("1e8 s", UnitFamily::Si).unit.name, "year");
+        assert_eq!(auto("0.5 s", UnitFamily::Si).unit.name, "millisecond");
+        assert_eq!(auto("45 s", UnitFamily::Si).unit.name, "second");
+        assert_eq!(auto("5400 s", UnitFamily::Imperial).unit.name, "hour");
+    }
+
+    #[test]
+    fn choice_follows_the_rounded_value() {
+        let db = UnitDatabase::new();
+        let pick = |input: &str, sig_figs| {
+            let q = crate::parser::parse_quantity(input, &db).unwrap();
+            best_unit(&q, UnitFamily::Si, &db, sig_figs).name
+        };
+        // 0.9999999 km shows as 1 at six figures: never "1000 meter".
+        assert_eq!(pick("999.9999 m", 6), "kilometer");
+        assert_eq!(pick("999.9999 m", 8), "meter");
+        assert_eq!(pick("3599.9999 s", 6), "hour");
+        assert_eq!(pick("59.9999999 s", 6), "minute");
+    }
+
+    #[test]
+    fn parse_target_reads_the_family() {
+        assert!(parse_target("m", UnitFamily::Si).is_none());
+        assert!(parse_target("automobile", UnitFamily::Si).is_none());
+        let family = |t: &str| parse_target(t, UnitFamily::Imperial).unwrap();
+        assert_eq!(family("auto").unwrap(), UnitFamily::Imperial);
+        assert_eq!(family("auto si").unwrap(), UnitFamily::Si);
+        assert_eq!(family("auto Imperial").unwrap(), UnitFamily::Imperial);
+        let err = family("auto metric").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "unknown unit family 'metric'. Known: si, imperial"
+        );
+    }
+}
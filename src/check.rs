@@ -0,0 +1,399 @@
+//! `runits check`: audit a unit database for problems.
+//!
+//! The audit looks for things that load fine but bite later:
+//!
+//! - **duplicate aliases** — the seed's `add` helper registered the same
+//!   alias for two different units, so the later one silently won;
+//! - **prefix ambiguity** — an alias that prefix stripping could also read
+//!   as prefix + unit with a *different* meaning (`min` = milli-inch,
+//!   `ft` = femto-tonne). Direct lookup wins, so these resolve correctly,
+//!   but a reader can't tell;
+//! - **naming rule** — canonical names that break the lowercase/singular
+//!   rule documented on [`Unit::name`](crate::units::Unit::name);
+//! - **definition cycles** and other definition-file errors;
+//! - **constant quantity** — constants whose dimensions disagree with
+//!   their declared quantity.
+//!
+//! When definition files are given, only names they define are audited, so
+//! `runits check my.units` can gate a custom file in CI without tripping
+//! over the builtins.
+
+use crate::database::constants::ConstantsDatabase;
+use crate::database::definitions::quantity_dimensions;
+use crate::database::{BINARY_PREFIXES, SI_PREFIXES, UnitDatabase};
+use crate::error::RUnitsError;
+use crate::format::json_string;
+use crate::theme::Theme;
+use crate::units::unit::ConversionKind;
+use std::collections::{BTreeSet, HashSet};
+
+/// What kind of problem a [`Finding`] reports.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum FindingKind {
+    DuplicateAlias,
+    PrefixAmbiguity,
+    NamingRule,
+    DefinitionCycle,
+    DefinitionError,
+    ConstantQuantity,
+}
+
+impl FindingKind {
+    /// Stable kebab-case identifier, used in text and JSON output.
+    pub fn slug(self) -> &'static str {
+        match self {
+            FindingKind::DuplicateAlias => "duplicate-alias",
+            FindingKind::PrefixAmbiguity => "prefix-ambiguity",
+            FindingKind::NamingRule => "naming-rule",
+            FindingKind::DefinitionCycle => "definition-cycle",
+            FindingKind::DefinitionError => "definition-error",
+            FindingKind::ConstantQuantity => "constant-quantity",
+        }
+    }
+}
+
+/// One problem found by the audit.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Finding {
+    pub kind: FindingKind,
+    /// The alias, unit, constant or file the problem is about.
+    pub subject: String,
+    pub message: String,
+}
+
+impl Finding {
+    fn new(kind: FindingKind, subject: &str, message: String) -> Self {
+        Finding {
+            kind,
+            subject: subject.to_string(),
+            message,
+        }
+    }
+}
+
+/// Turn a definition-file load error into a finding.
+pub fn load_error(err: &RUnitsError) -> Finding {
+    let (subject, reason) = match err {
+        RUnitsError::Definition { file, line, reason } if *line > 0 => {
+            (format!("{file}:{line}"), reason.as_str())
+        }
+        RUnitsError::Definition { file, reason, .. } => (file.clone(), reason.as_str()),
+        other => return Finding::new(FindingKind::DefinitionError, "", other.to_string()),
+    };
+    let kind = if reason.starts_with("definition cycle") || reason.starts_with("include cycle") {
+        FindingKind::DefinitionCycle
+    } else {
+        FindingKind::DefinitionError
+    };
+    Finding::new(kind, &subject, reason.to_string())
+}
+
+/// Audit `units` and `constants`. With `scope`, only names in it are
+/// checked (e.g. the names a definition file defined). Findings are sorted
+/// by kind, then subject.
+pub fn audit(
+    units: &UnitDatabase,
+    constants: &ConstantsDatabase,
+    scope: Option<&HashSet<String>>,
+) -> Vec<Finding> {
+    let in_scope = |name: &str| scope.is_none_or(|s| s.contains(name));
+    let mut findings = BTreeSet::new();
+
+    for c in units.collisions() {
+        if in_scope(&c.alias) {
+            findings.insert(Finding::new(
+                FindingKind::DuplicateAlias,
+                &c.alias,
+                format!(
+                    "registered for both '{}' and '{}'; '{}' wins",
+                    c.previous, c.current, c.current
+                ),
+            ));
+        }
+    }
+
+    for (alias, unit) in units.entries().filter(|(alias, _)| in_scope(alias)) {
+        if let Some(message) = prefix_reading(units, alias, unit) {
+            findings.insert(Finding::new(FindingKind::PrefixAmbiguity, alias, message));
+        }
+        if alias == unit.name
+            && let Some(message) = naming_violation(units, &unit.name)
+        {
+            findings.insert(Finding::new(FindingKind::NamingRule, alias, message));
+        }
+    }
+
+    for constant in constants.all_unique() {
+        if !in_scope(&constant.name) {
+            continue;
+        }
+        let Some(quantity) = &constant.quantity else {
+            continue;
+        };
+        let message = match quantity_dimensions(quantity) {
+            None => format!("declared quantity '{quantity}' is unknown"),
+            Some(dims) if dims != constant.unit.dimensions => format!(
+                "declared as {quantity} but has dimensions {}",
+                constant.unit.dimension_string()
+            ),
+            Some(_) => continue,
+        };
+        findings.insert(Finding::new(
+            FindingKind::ConstantQuantity,
+            &constant.name,
+            message,
+        ));
+    }
+
+    findings.into_iter().collect()
+}
+
+/// If `alias` also splits into a known prefix + a directly registered unit
+/// that means something else, describe that reading.
+fn prefix_reading(units: &UnitDatabase, alias: &str, unit: &crate::units::Unit) -> Option<String> {
+    for &(long, short, scale) in SI_PREFIXES.iter().chain(BINARY_PREFIXES) {
+        for prefix in [long, short] {
+            let Some(rest) = alias.strip_prefix(prefix).filter(|r| !r.is_empty()) else {
+                continue;
+            };
+            let Some(base) = units.lookup(rest).filter(|_| units.contains(rest)) else {
+                continue;
+            };
+            let same = match (&unit.conversion, &base.conversion) {
+                (ConversionKind::Linear(a), ConversionKind::Linear(b)) => {
+                    unit.dimensions == base.dimensions && ((a - b * scale) / a).abs() < 1e-9
+                }
+                // A level or a reading isn't a multiple of anything, so a
+                // prefix can't relate it to another unit (`decibel` is not
+                // a tenth of a `bel` the way a `decimeter` is).
+                _ => continue,
+            };
+            if !same {
+                return Some(format!(
+                    "resolves to '{}' but also reads as {prefix} + {rest} ({long}{})",
+                    unit.name, base.name
+                ));
+            }
+        }
+    }
+    None
+}
+
+/// The lowercase/singular rule for canonical names. Names spelled as their
+/// symbol (`dBm`, `pH`, `mmHg`, `tempC`) keep the symbol's capitals; only a
+/// leading capital (`BTU`, `Widgets`) breaks the rule.
+fn naming_violation(units: &UnitDatabase, name: &str) -> Option<String> {
+    if name.starts_with(|c: char| c.is_uppercase()) {
+        return Some(format!("canonical name '{name}' is not lowercase"));
+    }
+    let singular = name
+        .strip_suffix("es")
+        .filter(|stem| units.contains(stem))
+        .or_else(|| name.strip_suffix('s').filter(|stem| units.contains(stem)))?;
+    Some(format!(
+        "canonical name '{name}' looks plural (singular '{singular}' exists)"
+    ))
+}
+
+/// Human-readable report: one line per finding, then a tally.
+pub fn format_report(findings: &[Finding], checked: usize, t: &Theme) -> String {
+    let mut lines = Vec::new();
+    let width = findings
+        .iter()
+        .map(|f| f.kind.slug().len())
+        .max()
+        .unwrap_or(0);
+    for f in findings {
+        let pad = width - f.kind.slug().len();
+        lines.push(format!(
+            "  {}{:pad$}  {}: {}",
+            t.err(f.kind.slug()),
+            "",
+            t.kw(&f.subject),
+            f.message
+        ));
+    }
+    if findings.is_empty() {
+        lines.push(format!("No problems found ({checked} names checked)."));
+    } else {
+        let noun = if findings.len() == 1 {
+            "problem"
+        } else {
+            "problems"
+        };
+        lines.push(format!(
+            "{} {noun} found ({checked} names checked).",
+            findings.len()
+        ));
+    }
+    lines.join("\n")
+}
+
+/// JSON report: `{"checked":N,"problems":[{"kind":..,"subject":..,"message":..}]}`.
+pub fn format_json(findings: &[Finding], checked: usize) -> String {
+    let problems: Vec<String> = findings
+        .iter()
+        .map(|f| {
+            format!(
+                "{{\"kind\":{},\"subject\":{},\"message\":{}}}",
+                json_string(f.kind.slug()),
+                json_string(&f.subject),
+                json_string(&f.message)
+            )
+        })
+        .collect();
+    format!(
+        "{{\"checked\":{checked},\"problems\":[{}]}}",
+        problems.join(",")
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::constants::Constant;
+    use crate::database::definitions;
+    use crate::units::Unit;
+    use crate::units::dimension::Dimension;
+
+    fn kinds(findings: &[Finding], kind: FindingKind) -> Vec<&str> {
+        findings
+            .iter()
+            .filter(|f| f.kind == kind)
+            .map(|f| f.subject.as_str())
+            .collect()
+    }
+
+    #[test]
+    fn builtin_prefix_ambiguities_are_found() {
+        let db = UnitDatabase::new();
+        let findings = audit(&db, &ConstantsDatabase::new(), None);
+        let ambiguous = kinds(&findings, FindingKind::PrefixAmbiguity);
+        for alias in ["min", "ft", "cd", "yd"] {
+            assert!(ambiguous.contains(&alias), "{alias}: {ambiguous:?}");
+        }
+        // Prefix readings that mean the same unit are fine.
+        for alias in ["km", "ms", "kilogram", "MB"] {
+            assert!(!ambiguous.contains(&alias), "{alias}");
+        }
+    }
+
+    #[test]
+    fn builtin_naming_violations_are_found() {
+        let findings = audit(&UnitDatabase::new(), &ConstantsDatabase::new(), None);
+        let naming = kinds(&findings, FindingKind::NamingRule);
+        assert!(naming.contains(&"BTU"), "{naming:?}");
+        assert!(!naming.contains(&"meter"));
+    }
+
+    #[test]
+    fn shipped_tiers_have_no_new_findings() {
+        // The prefix ambiguities are real and documented above; nothing
+        // else in the shipped tiers should be flagged.
+        let known = [
+            "amps", "cd", "ct", "ft", "kt", "min", "nmi", "pt", "yd", "BTU",
+        ];
+        for tier in [
+            crate::database::Tier::Builtin,
+            crate::database::Tier::Standard,
+        ] {
+            let dbs = crate::database::tiers::build(tier, None).unwrap();
+            let findings = audit(&dbs.units, &dbs.constants, None);
+            let unexpected: Vec<_> = findings
+                .iter()
+                .filter(|f| !known.contains(&f.subject.as_str()))
+                .collect();
+            assert!(unexpected.is_empty(), "{tier:?}: {unexpected:?}");
+        }
+    }
+
+    #[test]
+    fn symbol_spelled_and_logarithmic_names_are_not_flagged() {
+        let findings = audit(&UnitDatabase::new(), &ConstantsDatabase::new(), None);
+        for name in ["decibel", "dB", "dBm", "dBW", "pH", "tempC", "mmHg"] {
+            assert!(
+                !findings.iter().any(|f| f.subject == name),
+                "{name}: {findings:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn builtin_constants_match_declared_quantities() {
+        let findings = audit(&UnitDatabase::new(), &ConstantsDatabase::new(), None);
+        assert!(kinds(&findings, FindingKind::ConstantQuantity).is_empty());
+        assert!(kinds(&findings, FindingKind::DuplicateAlias).is_empty());
+    }
+
+    #[test]
+    fn constant_with_wrong_quantity_is_found() {
+        let mut constants = ConstantsDatabase::empty();
+        constants.insert(
+            Constant {
+                name: "wrong".into(),
+                value: 1.0,
+                unit: Unit::meter(),
+                description: String::new(),
+                quantity: Some("Mass".into()),
+            },
+            &[],
+        );
+        let findings = audit(&UnitDatabase::empty(), &constants, None);
+        assert_eq!(kinds(&findings, FindingKind::ConstantQuantity), ["wrong"]);
+        assert!(findings[0].message.contains("Length"));
+    }
+
+    #[test]
+    fn scope_limits_audit_to_file_names() {
+        let mut units = UnitDatabase::new();
+        let mut constants = ConstantsDatabase::new();
+        let summary = definitions::load_str(
+            "unit Widgets: Length = 2 m\nunit myft = 1 ft\n",
+            "custom.units",
+            &mut units,
+            &mut constants,
+        )
+        .unwrap();
+        let scope: HashSet<String> = summary.defined.into_iter().collect();
+        let findings = audit(&units, &constants, Some(&scope));
+        assert_eq!(findings.len(), 1, "{findings:?}");
+        assert_eq!(findings[0].kind, FindingKind::NamingRule);
+        assert_eq!(findings[0].subject, "Widgets");
+    }
+
+    #[test]
+    fn plural_canonical_name_is_found() {
+        let mut units = UnitDatabase::empty();
+        units.insert(Unit::new("widget", 1.0, &[(Dimension::Length, 1)]), &[]);
+        units.insert(Unit::new("widgets", 2.0, &[(Dimension::Length, 1)]), &[]);
+        let findings = audit(&units, &ConstantsDatabase::empty(), None);
+        assert_eq!(kinds(&findings, FindingKind::NamingRule), ["widgets"]);
+    }
+
+    #[test]
+    fn cycle_load_error_is_classified() {
+        let err = definitions::load_str(
+            "unit aa = 2 bb\nunit bb = 2 aa\n",
+            "loop.units",
+            &mut UnitDatabase::new(),
+            &mut ConstantsDatabase::new(),
+        )
+        .unwrap_err();
+        let finding = load_error(&err);
+        assert_eq!(finding.kind, FindingKind::DefinitionCycle);
+        assert_eq!(finding.subject, "loop.units:2");
+    }
+
+    #[test]
+    fn json_report_escapes_strings() {
+        let findings = vec![Finding::new(
+            FindingKind::NamingRule,
+            "a\"b",
+            "msg".to_string(),
+        )];
+        assert_eq!(
+            format_json(&findings, 3),
+            r#"{"checked":3,"problems":[{"kind":"naming-rule","subject":"a\"b","message":"msg"}]}"#
+        );
+    }
+}
@@ -0,0 +1,1022 @@
+//! The periodic table: symbol, name, atomic number, standard atomic
+//! weight, density and melting/boiling points for all 118 elements.
+//!
+//! Atomic weights are the IUPAC standard (abridged) values in g/mol. For
+//! elements with no stable isotope the mass number of the longest-lived
+//! isotope is used, as periodic tables conventionally print in brackets.
+//!
+//! Densities are in g/cm³ near room temperature, for gases at 0 °C and
+//! 1 atm; melting and boiling points are in kelvin at 1 atm. A property is
+//! `None` where it has only been predicted (most elements past fermium),
+//! or where the element does not melt or boil at 1 atm (helium does not
+//! solidify; carbon and arsenic sublime, so only the sublimation point is
+//! listed, as the boiling point).
+
+/// One element of the periodic table.
+#[derive(Debug, PartialEq)]
+pub struct Element {
+    pub number: u32,
+    pub symbol: &'static str,
+    pub name: &'static str,
+    /// Standard atomic weight, g/mol (numerically, daltons).
+    pub atomic_mass: f64,
+    /// Density, g/cm³.
+    pub density: Option<f64>,
+    /// Melting point, K.
+    pub melting_point: Option<f64>,
+    /// Boiling point, K.
+    pub boiling_point: Option<f64>,
+}
+
+const fn el(
+    number: u32,
+    symbol: &'static str,
+    name: &'static str,
+    atomic_mass: f64,
+    density: Option<f64>,
+    melting_point: Option<f64>,
+    boiling_point: Option<f64>,
+) -> Element {
+    Element {
+        number,
+        symbol,
+        name,
+        atomic_mass,
+        density,
+        melting_point,
+        boiling_point,
+    }
+}
+
+/// Every element, indexed by atomic number − 1.
+pub const ELEMENTS: &[Element] = &[
+    el(
+        1,
+        "H",
+        "hydrogen",
+        1.008,
+        Some(0.00008988),
+        Some(14.01),
+        Some(20.28),
+    ),
+    el(
+        2,
+        "He",
+        "helium",
+        4.002602,
+        Some(0.0001785),
+        None,
+        Some(4.22),
+    ),
+    el(
+        3,
+        "Li",
+        "lithium",
+        6.94,
+        Some(0.534),
+        Some(453.69),
+        Some(1560.0),
+    ),
+    el(
+        4,
+        "Be",
+        "beryllium",
+        9.0121831,
+        Some(1.85),
+        Some(1560.0),
+        Some(2742.0),
+    ),
+    el(
+        5,
+        "B",
+        "boron",
+        10.81,
+        Some(2.34),
+        Some(2349.0),
+        Some(4200.0),
+    ),
+    el(6, "C", "carbon", 12.011, Some(2.267), None, Some(4098.0)),
+    el(
+        7,
+        "N",
+        "nitrogen",
+        14.007,
+        Some(0.0012506),
+        Some(63.15),
+        Some(77.36),
+    ),
+    el(
+        8,
+        "O",
+        "oxygen",
+        15.999,
+        Some(0.001429),
+        Some(54.36),
+        Some(90.20),
+    ),
+    el(
+        9,
+        "F",
+        "fluorine",
+        18.998403163,
+        Some(0.001696),
+        Some(53.53),
+        Some(85.03),
+    ),
+    el(
+        10,
+        "Ne",
+        "neon",
+        20.1797,
+        Some(0.0009002),
+        Some(24.56),
+        Some(27.07),
+    ),
+    el(
+        11,
+        "Na",
+        "sodium",
+        22.98976928,
+        Some(0.968),
+        Some(370.87),
+        Some(1156.0),
+    ),
+    el(
+        12,
+        "Mg",
+        "magnesium",
+        24.305,
+        Some(1.738),
+        Some(923.0),
+        Some(1363.0),
+    ),
+    el(
+        13,
+        "Al",
+        "aluminium",
+        26.9815385,
+        Some(2.70),
+        Some(933.47),
+        Some(2792.0),
+    ),
+    el(
+        14,
+        "Si",
+        "silicon",
+        28.085,
+        Some(2.3296),
+        Some(1687.0),
+        Some(3538.0),
+    ),
+    el(
+        15,
+        "P",
+        "phosphorus",
+        30.973761998,
+        Some(1.82),
+        Some(317.30),
+        Some(550.0),
+    ),
+    el(
+        16,
+        "S",
+        "sulfur",
+        32.06,
+        Some(2.067),
+        Some(388.36),
+        Some(717.87),
+    ),
+    el(
+        17,
+        "Cl",
+        "chlorine",
+        35.45,
+        Some(0.003214),
+        Some(171.6),
+        Some(239.11),
+    ),
+    el(
+        18,
+        "Ar",
+        "argon",
+        39.948,
+        Some(0.0017837),
+        Some(83.80),
+        Some(87.30),
+    ),
+    el(
+        19,
+        "K",
+        "potassium",
+        39.0983,
+        Some(0.89),
+        Some(336.53),
+        Some(1032.0),
+    ),
+    el(
+        20,
+        "Ca",
+        "calcium",
+        40.078,
+        Some(1.54),
+        Some(1115.0),
+        Some(1757.0),
+    ),
+    el(
+        21,
+        "Sc",
+        "scandium",
+        44.955908,
+        Some(2.989),
+        Some(1814.0),
+        Some(3109.0),
+    ),
+    el(
+        22,
+        "Ti",
+        "titanium",
+        47.867,
+        Some(4.54),
+        Some(1941.0),
+        Some(3560.0),
+    ),
+    el(
+        23,
+        "V",
+        "vanadium",
+        50.9415,
+        Some(6.11),
+        Some(2183.0),
+        Some(3680.0),
+    ),
+    el(
+        24,
+        "Cr",
+        "chromium",
+        51.9961,
+        Some(7.15),
+        Some(2180.0),
+        Some(2944.0),
+    ),
+    el(
+        25,
+        "Mn",
+        "manganese",
+        54.938044,
+        Some(7.44),
+        Some(1519.0),
+        Some(2334.0),
+    ),
+    el(
+        26,
+        "Fe",
+        "iron",
+        55.845,
+        Some(7.874),
+        Some(1811.0),
+        Some(3134.0),
+    ),
+    el(
+        27,
+        "Co",
+        "cobalt",
+        58.933194,
+        Some(8.86),
+        Some(1768.0),
+        Some(3200.0),
+    ),
+    el(
+        28,
+        "Ni",
+        "nickel",
+        58.6934,
+        Some(8.912),
+        Some(1728.0),
+        Some(3186.0),
+    ),
+    el(
+        29,
+        "Cu",
+        "copper",
+        63.546,
+        Some(8.96),
+        Some(1357.77),
+        Some(2835.0),
+    ),
+    el(
+        30,
+        "Zn",
+        "zinc",
+        65.38,
+        Some(7.134),
+        Some(692.88),
+        Some(1180.0),
+    ),
+    el(
+        31,
+        "Ga",
+        "gallium",
+        69.723,
+        Some(5.907),
+        Some(302.9146),
+        Some(2673.0),
+    ),
+    el(
+        32,
+        "Ge",
+        "germanium",
+        72.630,
+        Some(5.323),
+        Some(1211.40),
+        Some(3106.0),
+    ),
+    el(
+        33,
+        "As",
+        "arsenic",
+        74.921595,
+        Some(5.776),
+        None,
+        Some(887.0),
+    ),
+    el(
+        34,
+        "Se",
+        "selenium",
+        78.971,
+        Some(4.809),
+        Some(453.0),
+        Some(958.0),
+    ),
+    el(
+        35,
+        "Br",
+        "bromine",
+        79.904,
+        Some(3.122),
+        Some(265.8),
+        Some(332.0),
+    ),
+    el(
+        36,
+        "Kr",
+        "krypton",
+        83.798,
+        Some(0.003733),
+        Some(115.79),
+        Some(119.93),
+    ),
+    el(
+        37,
+        "Rb",
+        "rubidium",
+        85.4678,
+        Some(1.532),
+        Some(312.46),
+        Some(961.0),
+    ),
+    el(
+        38,
+        "Sr",
+        "strontium",
+        87.62,
+        Some(2.64),
+        Some(1050.0),
+        Some(1655.0),
+    ),
+    el(
+        39,
+        "Y",
+        "yttrium",
+        88.90584,
+        Some(4.469),
+        Some(1799.0),
+        Some(3609.0),
+    ),
+    el(
+        40,
+        "Zr",
+        "zirconium",
+        91.224,
+        Some(6.506),
+        Some(2128.0),
+        Some(4682.0),
+    ),
+    el(
+        41,
+        "Nb",
+        "niobium",
+        92.90637,
+        Some(8.57),
+        Some(2750.0),
+        Some(5017.0),
+    ),
+    el(
+        42,
+        "Mo",
+        "molybdenum",
+        95.95,
+        Some(10.22),
+        Some(2896.0),
+        Some(4912.0),
+    ),
+    el(
+        43,
+        "Tc",
+        "technetium",
+        98.0,
+        Some(11.5),
+        Some(2430.0),
+        Some(4538.0),
+    ),
+    el(
+        44,
+        "Ru",
+        "ruthenium",
+        101.07,
+        Some(12.37),
+        Some(2607.0),
+        Some(4423.0),
+    ),
+    el(
+        45,
+        "Rh",
+        "rhodium",
+        102.90550,
+        Some(12.41),
+        Some(2237.0),
+        Some(3968.0),
+    ),
+    el(
+        46,
+        "Pd",
+        "palladium",
+        106.42,
+        Some(12.02),
+        Some(1828.05),
+        Some(3236.0),
+    ),
+    el(
+        47,
+        "Ag",
+        "silver",
+        107.8682,
+        Some(10.501),
+        Some(1234.93),
+        Some(2435.0),
+    ),
+    el(
+        48,
+        "Cd",
+        "cadmium",
+        112.414,
+        Some(8.69),
+        Some(594.22),
+        Some(1040.0),
+    ),
+    el(
+        49,
+        "In",
+        "indium",
+        114.818,
+        Some(7.31),
+        Some(429.75),
+        Some(2345.0),
+    ),
+    el(
+        50,
+        "Sn",
+        "tin",
+        118.710,
+        Some(7.287),
+        Some(505.08),
+        Some(2875.0),
+    ),
+    el(
+        51,
+        "Sb",
+        "antimony",
+        121.760,
+        Some(6.685),
+        Some(903.78),
+        Some(1860.0),
+    ),
+    el(
+        52,
+        "Te",
+        "tellurium",
+        127.60,
+        Some(6.232),
+        Some(722.66),
+        Some(1261.0),
+    ),
+    el(
+        53,
+        "I",
+        "iodine",
+        126.90447,
+        Some(4.93),
+        Some(386.85),
+        Some(457.4),
+    ),
+    el(
+        54,
+        "Xe",
+        "xenon",
+        131.293,
+        Some(0.005887),
+        Some(161.4),
+        Some(165.03),
+    ),
+    el(
+        55,
+        "Cs",
+        "caesium",
+        132.90545196,
+        Some(1.873),
+        Some(301.59),
+        Some(944.0),
+    ),
+    el(
+        56,
+        "Ba",
+        "barium",
+        137.327,
+        Some(3.594),
+        Some(1000.0),
+        Some(2170.0),
+    ),
+    el(
+        57,
+        "La",
+        "lanthanum",
+        138.90547,
+        Some(6.145),
+        Some(1193.0),
+        Some(3737.0),
+    ),
+    el(
+        58,
+        "Ce",
+        "cerium",
+        140.116,
+        Some(6.77),
+        Some(1068.0),
+        Some(3716.0),
+    ),
+    el(
+        59,
+        "Pr",
+        "praseodymium",
+        140.90766,
+        Some(6.773),
+        Some(1208.0),
+        Some(3793.0),
+    ),
+    el(
+        60,
+        "Nd",
+        "neodymium",
+        144.242,
+        Some(7.007),
+        Some(1297.0),
+        Some(3347.0),
+    ),
+    el(
+        61,
+        "Pm",
+        "promethium",
+        145.0,
+        Some(7.26),
+        Some(1315.0),
+        Some(3273.0),
+    ),
+    el(
+        62,
+        "Sm",
+        "samarium",
+        150.36,
+        Some(7.52),
+        Some(1345.0),
+        Some(2067.0),
+    ),
+    el(
+        63,
+        "Eu",
+        "europium",
+        151.964,
+        Some(5.243),
+        Some(1099.0),
+        Some(1802.0),
+    ),
+    el(
+        64,
+        "Gd",
+        "gadolinium",
+        157.25,
+        Some(7.895),
+        Some(1585.0),
+        Some(3546.0),
+    ),
+    el(
+        65,
+        "Tb",
+        "terbium",
+        158.92535,
+        Some(8.229),
+        Some(1629.0),
+        Some(3503.0),
+    ),
+    el(
+        66,
+        "Dy",
+        "dysprosium",
+        162.500,
+        Some(8.55),
+        Some(1680.0),
+        Some(2840.0),
+    ),
+    el(
+        67,
+        "Ho",
+        "holmium",
+        164.93033,
+        Some(8.795),
+        Some(1734.0),
+        Some(2993.0),
+    ),
+    el(
+        68,
+        "Er",
+        "erbium",
+        167.259,
+        Some(9.066),
+        Some(1802.0),
+        Some(3141.0),
+    ),
+    el(
+        69,
+        "Tm",
+        "thulium",
+        168.93422,
+        Some(9.321),
+        Some(1818.0),
+        Some(2223.0),
+    ),
+    el(
+        70,
+        "Yb",
+        "ytterbium",
+        173.045,
+        Some(6.965),
+        Some(1097.0),
+        Some(1469.0),
+    ),
+    el(
+        71,
+        "Lu",
+        "lutetium",
+        174.9668,
+        Some(9.84),
+        Some(1925.0),
+        Some(3675.0),
+    ),
+    el(
+        72,
+        "Hf",
+        "hafnium",
+        178.49,
+        Some(13.31),
+        Some(2506.0),
+        Some(4876.0),
+    ),
+    el(
+        73,
+        "Ta",
+        "tantalum",
+        180.94788,
+        Some(16.654),
+        Some(3290.0),
+        Some(5731.0),
+    ),
+    el(
+        74,
+        "W",
+        "tungsten",
+        183.84,
+        Some(19.25),
+        Some(3695.0),
+        Some(5828.0),
+    ),
+    el(
+        75,
+        "Re",
+        "rhenium",
+        186.207,
+        Some(21.02),
+        Some(3459.0),
+        Some(5869.0),
+    ),
+    el(
+        76,
+        "Os",
+        "osmium",
+        190.23,
+        Some(22.59),
+        Some(3306.0),
+        Some(5285.0),
+    ),
+    el(
+        77,
+        "Ir",
+        "iridium",
+        192.217,
+        Some(22.56),
+        Some(2719.0),
+        Some(4701.0),
+    ),
+    el(
+        78,
+        "Pt",
+        "platinum",
+        195.084,
+        Some(21.45),
+        Some(2041.4),
+        Some(4098.0),
+    ),
+    el(
+        79,
+        "Au",
+        "gold",
+        196.966569,
+        Some(19.3),
+        Some(1337.33),
+        Some(3129.0),
+    ),
+    el(
+        80,
+        "Hg",
+        "mercury",
+        200.592,
+        Some(13.534),
+        Some(234.43),
+        Some(629.88),
+    ),
+    el(
+        81,
+        "Tl",
+        "thallium",
+        204.38,
+        Some(11.85),
+        Some(577.0),
+        Some(1746.0),
+    ),
+    el(
+        82,
+        "Pb",
+        "lead",
+        207.2,
+        Some(11.34),
+        Some(600.61),
+        Some(2022.0),
+    ),
+    el(
+        83,
+        "Bi",
+        "bismuth",
+        208.98040,
+        Some(9.807),
+        Some(544.7),
+        Some(1837.0),
+    ),
+    el(
+        84,
+        "Po",
+        "polonium",
+        209.0,
+        Some(9.32),
+        Some(527.0),
+        Some(1235.0),
+    ),
+    el(85, "At", "astatine", 210.0, None, None, None),
+    el(
+        86,
+        "Rn",
+        "radon",
+        222.0,
+        Some(0.00973),
+        Some(202.0),
+        Some(211.3),
+    ),
+    el(87, "Fr", "francium", 223.0, None, None, None),
+    el(
+        88,
+        "Ra",
+        "radium",
+        226.0,
+        Some(5.5),
+        Some(973.0),
+        Some(2010.0),
+    ),
+    el(
+        89,
+        "Ac",
+        "actinium",
+        227.0,
+        Some(10.07),
+        Some(1323.0),
+        Some(3471.0),
+    ),
+    el(
+        90,
+        "Th",
+        "thorium",
+        232.0377,
+        Some(11.72),
+        Some(2023.0),
+        Some(5061.0),
+    ),
+    el(
+        91,
+        "Pa",
+        "protactinium",
+        231.03588,
+        Some(15.37),
+        Some(1841.0),
+        Some(4300.0),
+    ),
+    el(
+        92,
+        "U",
+        "uranium",
+        238.02891,
+        Some(18.95),
+        Some(1405.3),
+        Some(4404.0),
+    ),
+    el(
+        93,
+        "Np",
+        "neptunium",
+        237.0,
+        Some(20.45),
+        Some(917.0),
+        Some(4273.0),
+    ),
+    el(
+        94,
+        "Pu",
+        "plutonium",
+        244.0,
+        Some(19.84),
+        Some(912.5),
+        Some(3501.0),
+    ),
+    el(
+        95,
+        "Am",
+        "americium",
+        243.0,
+        Some(13.69),
+        Some(1449.0),
+        Some(2880.0),
+    ),
+    el(
+        96,
+        "Cm",
+        "curium",
+        247.0,
+        Some(13.51),
+        Some(1613.0),
+        Some(3383.0),
+    ),
+    el(
+        97,
+        "Bk",
+        "berkelium",
+        247.0,
+        Some(14.79),
+        Some(1259.0),
+        Some(2900.0),
+    ),
+    el(
+        98,
+        "Cf",
+        "californium",
+        251.0,
+        Some(15.1),
+        Some(1173.0),
+        None,
+    ),
+    el(
+        99,
+        "Es",
+        "einsteinium",
+        252.0,
+        Some(8.84),
+        Some(1133.0),
+        None,
+    ),
+    el(100, "Fm", "fermium", 257.0, None, None, None),
+    el(101, "Md", "mendelevium", 258.0, None, None, None),
+    el(102, "No", "nobelium", 259.0, None, None, None),
+    el(103, "Lr", "lawrencium", 266.0, None, None, None),
+    el(104, "Rf", "rutherfordium", 267.0, None, None, None),
+    el(105, "Db", "dubnium", 268.0, None, None, None),
+    el(106, "Sg", "seaborgium", 269.0, None, None, None),
+    el(107, "Bh", "bohrium", 270.0, None, None, None),
+    el(108, "Hs", "hassium", 277.0, None, None, None),
+    el(109, "Mt", "meitnerium", 278.0, None, None, None),
+    el(110, "Ds", "darmstadtium", 281.0, None, None, None),
+    el(111, "Rg", "roentgenium", 282.0, None, None, None),
+    el(112, "Cn", "copernicium", 285.0, None, None, None),
+    el(113, "Nh", "nihonium", 286.0, None, None, None),
+    el(114, "Fl", "flerovium", 289.0, None, None, None),
+    el(115, "Mc", "moscovium", 290.0, None, None, None),
+    el(116, "Lv", "livermorium", 293.0, None, None, None),
+    el(117, "Ts", "tennessine", 294.0, None, None, None),
+    el(118, "Og", "oganesson", 294.0, None, None, None),
+];
+
+/// Look up an element by its (case-sensitive) symbol.
+pub fn by_symbol(symbol: &str) -> Option<&'static Element> {
+    ELEMENTS.iter().find(|e| e.symbol == symbol)
+}
+
+/// Look up an element by symbol (case-sensitive) or English name
+/// (case-insensitive): `Fe`, `iron` and `Iron` all find iron.
+pub fn lookup(query: &str) -> Option<&'static Element> {
+    by_symbol(query).or_else(|| ELEMENTS.iter().find(|e| e.name.eq_ignore_ascii_case(query)))
+}
+
+/// Suggest element symbols for an unknown one: a case-insensitive symbol
+/// match first (`CL` → `Cl`), then Jaro-Winkler over symbols and names
+/// with the usual 0.7 cutoff. Returns symbols.
+pub fn suggest(unknown: &str, max: usize) -> Vec<String> {
+    let lower = unknown.to_lowercase();
+    let mut scored: Vec<(&str, f64)> = ELEMENTS
+        .iter()
+        .map(|e| {
+            let score = if e.symbol.to_lowercase() == lower {
+                2.0
+            } else {
+                strsim::jaro_winkler(&lower, &e.symbol.to_lowercase())
+                    .max(strsim::jaro_winkler(&lower, e.name))
+            };
+            (e.symbol, score)
+        })
+        .filter(|(_, score)| *score > 0.7)
+        .collect();
+    scored.sort_by(|a, b| b.1.total_cmp(&a.1));
+    scored
+        .into_iter()
+        .take(max)
+        .map(|(symbol, _)| symbol.to_string())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn table_is_complete_and_ordered() {
+        assert_eq!(ELEMENTS.len(), 118);
+        for (i, e) in ELEMENTS.iter().enumerate() {
+            assert_eq!(e.number as usize, i + 1, "{}", e.symbol);
+        }
+    }
+
+    #[test]
+    fn symbols_are_unique_and_case_sensitive() {
+        assert_eq!(by_symbol("Co").unwrap().name, "cobalt");
+        assert!(by_symbol("CO").is_none());
+        let mut symbols: Vec<&str> = ELEMENTS.iter().map(|e| e.symbol).collect();
+        symbols.sort();
+        symbols.dedup();
+        assert_eq!(symbols.len(), 118);
+    }
+
+    #[test]
+    fn lookup_accepts_symbol_or_name() {
+        assert_eq!(lookup("Fe").unwrap().number, 26);
+        assert_eq!(lookup("Iron").unwrap().number, 26);
+        assert!(lookup("fe").is_none());
+    }
+
+    #[test]
+    fn melting_points_sit_below_boiling_points() {
+        for e in ELEMENTS {
+            if let (Some(melt), Some(boil)) = (e.melting_point, e.boiling_point) {
+                assert!(melt < boil, "{}", e.symbol);
+            }
+        }
+        assert_eq!(by_symbol("Hg").unwrap().melting_point, Some(234.43));
+    }
+
+    #[test]
+    fn suggests_case_fix_first() {
+        assert_eq!(suggest("CL", 3).first().map(String::as_str), Some("Cl"));
+        assert!(suggest("Iron", 3).contains(&"Fe".to_string()));
+    }
+}
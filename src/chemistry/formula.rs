@@ -0,0 +1,207 @@
+//! Chemical formula parser: `H2SO4`, `Ca(OH)2`, `K4[Fe(CN)6]`, `CuSO4·5H2O`.
+//!
+//! A small recursive-descent parser over characters, kept separate from
+//! the pest grammar: the grammar only delimits the formula text inside
+//! `molar_mass(...)`, and this module gives it meaning. The accepted
+//! shapes:
+//!
+//! ```text
+//! formula  → part (sep count? part)*        sep ∈ {·, •, ., *}
+//! part     → group+
+//! group    → (element | "(" part ")" | "[" part "]") count?
+//! element  → Upper lower*
+//! ```
+//!
+//! The separator introduces a hydrate or adduct, and its leading count
+//! multiplies the whole part: `CuSO4·5H2O` is one CuSO4 plus five H2O.
+//! Element symbols are case-sensitive (`Co` is cobalt, `CO` carbon
+//! monoxide); an unknown symbol fails with fuzzy suggestions.
+
+use super::elements::{self, Element};
+use crate::error::RUnitsError;
+
+/// Hydrate / adduct separators: middle dot, bullet, and ASCII stand-ins.
+const SEPARATORS: &[char] = &['·', '•', '.', '*'];
+
+/// A parsed formula: each element with its total atom count, in order of
+/// first appearance.
+pub type Composition = Vec<(&'static Element, u32)>;
+
+/// Parse `formula` into its elemental composition.
+pub fn parse(formula: &str) -> Result<Composition, RUnitsError> {
+    let mut parser = Parser {
+        formula,
+        chars: formula.chars().collect(),
+        pos: 0,
+    };
+    let mut total = Composition::new();
+    loop {
+        let multiplier = parser.count()?.unwrap_or(1);
+        let part = parser.sequence(None)?;
+        merge(&mut total, &part, multiplier);
+        match parser.peek() {
+            None => break,
+            Some(c) if SEPARATORS.contains(&c) => parser.pos += 1,
+            Some(c) => return Err(parser.error(format!("unexpected '{c}'"))),
+        }
+    }
+    Ok(total)
+}
+
+/// Molar mass of `formula` in g/mol.
+pub fn molar_mass(formula: &str) -> Result<f64, RUnitsError> {
+    Ok(parse(formula)?
+        .iter()
+        .map(|(element, count)| element.atomic_mass * f64::from(*count))
+        .sum())
+}
+
+fn merge(total: &mut Composition, part: &Composition, multiplier: u32) {
+    for &(element, count) in part {
+        match total.iter_mut().find(|(e, _)| e.number == element.number) {
+            Some((_, existing)) => *existing += count * multiplier,
+            None => total.push((element, count * multiplier)),
+        }
+    }
+}
+
+struct Parser<'a> {
+    formula: &'a str,
+    chars: Vec<char>,
+    pos: usize,
+}
+
+impl Parser<'_> {
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.pos).copied()
+    }
+
+    fn error(&self, reason: String) -> RUnitsError {
+        RUnitsError::ChemicalFormula {
+            formula: self.formula.to_string(),
+            reason,
+        }
+    }
+
+    /// An optional positive integer count.
+    fn count(&mut self) -> Result<Option<u32>, RUnitsError> {
+        let start = self.pos;
+        while self.peek().is_some_and(|c| c.is_ascii_digit()) {
+            self.pos += 1;
+        }
+        if start == self.pos {
+            return Ok(None);
+        }
+        let digits: String = self.chars[start..self.pos].iter().collect();
+        match digits.parse::<u32>() {
+            Ok(0) => Err(self.error("a count must be at least 1".to_string())),
+            Ok(n) => Ok(Some(n)),
+            Err(_) => Err(self.error(format!("count '{digits}' is too large"))),
+        }
+    }
+
+    /// One or more groups, up to `closer` (or a separator / the end when
+    /// `closer` is `None`).
+    fn sequence(&mut self, closer: Option<char>) -> Result<Composition, RUnitsError> {
+        let mut composition = Composition::new();
+        loop {
+            let group = match self.peek() {
+                Some(c) if Some(c) == closer => break,
+                None => match closer {
+                    Some(closer) => return Err(self.error(format!("missing '{closer}'"))),
+                    None => break,
+                },
+                Some(c) if SEPARATORS.contains(&c) && closer.is_none() => break,
+                Some(open @ ('(' | '[')) => {
+                    self.pos += 1;
+                    let close = if open == '(' { ')' } else { ']' };
+                    let inner = self.sequence(Some(close))?;
+                    self.pos += 1;
+                    inner
+                }
+                Some(c) if c.is_ascii_uppercase() => vec![(self.element()?, 1)],
+                Some(c) => {
+                    return Err(self.error(format!("expected an element symbol at '{c}'")));
+                }
+            };
+            let count = self.count()?.unwrap_or(1);
+            merge(&mut composition, &group, count);
+        }
+        if composition.is_empty() {
+            return Err(self.error("empty formula".to_string()));
+        }
+        Ok(composition)
+    }
+
+    fn element(&mut self) -> Result<&'static Element, RUnitsError> {
+        let start = self.pos;
+        self.pos += 1;
+        while self.peek().is_some_and(|c| c.is_ascii_lowercase()) {
+            self.pos += 1;
+        }
+        let symbol: String = self.chars[start..self.pos].iter().collect();
+        elements::by_symbol(&symbol).ok_or_else(|| RUnitsError::UnknownElement {
+            suggestions: elements::suggest(&symbol, 3),
+            symbol,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn mass(formula: &str) -> f64 {
+        molar_mass(formula).unwrap()
+    }
+
+    #[test]
+    fn simple_formulas() {
+        assert!((mass("H2O") - 18.015).abs() < 1e-3);
+        assert!((mass("H2SO4") - 98.072).abs() < 1e-3);
+        assert!((mass("NaCl") - 58.440).abs() < 1e-3);
+    }
+
+    #[test]
+    fn groups_multiply_their_contents() {
+        let composition = parse("Ca(OH)2").unwrap();
+        let counts: Vec<(&str, u32)> = composition.iter().map(|(e, n)| (e.symbol, *n)).collect();
+        assert_eq!(counts, vec![("Ca", 1), ("O", 2), ("H", 2)]);
+        assert!((mass("K4[Fe(CN)6]") - 368.35).abs() < 1e-2);
+    }
+
+    #[test]
+    fn hydrates_add_with_their_multiplier() {
+        assert!((mass("CuSO4·5H2O") - 249.68).abs() < 1e-2);
+        assert_eq!(mass("CuSO4.5H2O"), mass("CuSO4·5H2O"));
+        assert_eq!(mass("CuSO4*5H2O"), mass("CuSO4•5H2O"));
+    }
+
+    #[test]
+    fn unknown_element_suggests_symbols() {
+        match parse("NaCL").unwrap_err() {
+            RUnitsError::UnknownElement {
+                symbol,
+                suggestions,
+            } => {
+                assert_eq!(symbol, "L");
+                assert!(!suggestions.is_empty());
+            }
+            other => panic!("expected UnknownElement, got {other:?}"),
+        }
+        match parse("Xx2").unwrap_err() {
+            RUnitsError::UnknownElement { symbol, .. } => assert_eq!(symbol, "Xx"),
+            other => panic!("expected UnknownElement, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn malformed_formulas_are_rejected() {
+        for bad in ["", "Ca(OH", "H0", "h2o", "()", "H2O·"] {
+            assert!(
+                matches!(parse(bad), Err(RUnitsError::ChemicalFormula { .. })),
+                "{bad}"
+            );
+        }
+    }
+}
@@ -0,0 +1,263 @@
+//! Chemistry: the periodic table, a formula parser, and the functions that
+//! take a formula or an element instead of a quantity.
+//!
+//! `molar_mass(H2SO4)` cannot be an ordinary [`MathFn`](crate::math::MathFn):
+//! its argument is not an expression (`Ca(OH)2` would read as a call, and
+//! `CuSO4·5H2O` does not parse at all). The grammar's `chem_call` rule
+//! captures such raw argument text instead, and [`ChemFn`] evaluates it. A
+//! plain name (`density(Fe)`, `density(water)`, `density(x)`) parses as an
+//! ordinary call, and the evaluator resolves it: a session variable first,
+//! then a registered substance, and only then an element or formula. The
+//! registry follows `math.rs`'s enum-plus-exhaustive-match shape, so adding
+//! a function is the same four-place edit (plus the name in the grammar's
+//! `chem_fn` rule).
+//!
+//! ```text
+//! 3 mol * molar_mass(NaCl) -> g        175.32 g
+//! molar_mass(CuSO4·5H2O)               249.68 g/mol
+//! density(Fe) * 1 cm^3 -> g            7.874 g
+//! melting_point(Ga) -> degC            29.7646 °C
+//! ```
+//!
+//! Element properties come from [`elements::ELEMENTS`]; the same
+//! [`ChemFn::element_property`] backs both the functions and the `element`
+//! command, so the two cannot disagree.
+
+pub mod elements;
+pub mod formula;
+
+use crate::database::substances::Substance;
+use crate::error::RUnitsError;
+use crate::units::dimension::Dimension;
+use crate::units::{Quantity, Unit};
+use elements::Element;
+
+/// The built-in functions that take a chemical formula or an element.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChemFn {
+    // Formula argument
+    MolarMass,
+    // Element argument
+    AtomicNumber,
+    AtomicMass,
+    Density,
+    MeltingPoint,
+    BoilingPoint,
+}
+
+impl ChemFn {
+    /// Every chemistry function, in declaration order.
+    pub const ALL: &'static [ChemFn] = &[
+        ChemFn::MolarMass,
+        ChemFn::AtomicNumber,
+        ChemFn::AtomicMass,
+        ChemFn::Density,
+        ChemFn::MeltingPoint,
+        ChemFn::BoilingPoint,
+    ];
+
+    /// The element properties, in the order the `element` command lists
+    /// them.
+    pub const PROPERTIES: &'static [ChemFn] = &[
+        ChemFn::AtomicNumber,
+        ChemFn::AtomicMass,
+        ChemFn::Density,
+        ChemFn::MeltingPoint,
+        ChemFn::BoilingPoint,
+    ];
+
+    /// The function's name as it appears in user input.
+    pub fn name(self) -> &'static str {
+        match self {
+            ChemFn::MolarMass => "molar_mass",
+            ChemFn::AtomicNumber => "atomic_number",
+            ChemFn::AtomicMass => "atomic_mass",
+            ChemFn::Density => "density",
+            ChemFn::MeltingPoint => "melting_point",
+            ChemFn::BoilingPoint => "boiling_point",
+        }
+    }
+
+    /// Human-readable signature for `?`-help output.
+    pub fn signature(self) -> &'static str {
+        match self {
+            ChemFn::MolarMass => "molar_mass(Formula) → Mass/Amount",
+            ChemFn::AtomicNumber => "atomic_number(Element) → Scalar",
+            ChemFn::AtomicMass => "atomic_mass(Element) → Mass/Amount",
+            ChemFn::Density => "density(Element) → Mass/Volume",
+            ChemFn::MeltingPoint => "melting_point(Element) → Temperature",
+            ChemFn::BoilingPoint => "boiling_point(Element) → Temperature",
+        }
+    }
+
+    /// Label for the `element` command's property rows.
+    pub fn label(self) -> &'static str {
+        match self {
+            ChemFn::MolarMass => "Molar mass",
+            ChemFn::AtomicNumber => "Atomic number",
+            ChemFn::AtomicMass => "Atomic mass",
+            ChemFn::Density => "Density",
+            ChemFn::MeltingPoint => "Melting point",
+            ChemFn::BoilingPoint => "Boiling point",
+        }
+    }
+
+    /// What the argument should look like, for the error when an
+    /// expression is passed instead (`density(3 m)`).
+    pub fn expected_argument(self) -> &'static str {
+        match self {
+            ChemFn::MolarMass => "a chemical formula like H2SO4",
+            ChemFn::Density => "an element or substance name like Fe or water",
+            _ => "an element symbol or name like Fe",
+        }
+    }
+
+    /// Evaluate the function on its raw argument text.
+    pub fn apply(self, arg: &str) -> Result<Quantity, RUnitsError> {
+        if self == ChemFn::MolarMass {
+            return Ok(Quantity::new(formula::molar_mass(arg)?, gram_per_mole()));
+        }
+        let element = elements::lookup(arg).ok_or_else(|| RUnitsError::UnknownElement {
+            symbol: arg.to_string(),
+            suggestions: elements::suggest(arg, 3),
+        })?;
+        self.element_property(element)
+            .ok_or_else(|| RUnitsError::FunctionDomainError {
+                name: self.name().to_string(),
+                reason: format!("no known value for {}", element.name),
+            })
+    }
+
+    /// The property of a registered substance (`density(water)`), in the
+    /// same units the element table uses. Only density and molar mass are
+    /// recorded for substances.
+    pub fn substance_property(self, substance: &Substance) -> Result<Quantity, RUnitsError> {
+        let value = match self {
+            ChemFn::Density => substance
+                .density
+                .map(|d| Quantity::new(d / 1e3, gram_per_cm3())),
+            ChemFn::MolarMass => substance
+                .molar_mass
+                .map(|m| Quantity::new(m * 1e3, gram_per_mole())),
+            _ => None,
+        };
+        value.ok_or_else(|| RUnitsError::SubstanceProperty {
+            substance: substance.name.to_string(),
+            property: self.label().to_lowercase(),
+        })
+    }
+
+    /// The property of `element` as a quantity, `None` when the table has
+    /// no value (or for `MolarMass`, which takes a formula).
+    pub fn element_property(self, element: &Element) -> Option<Quantity> {
+        match self {
+            ChemFn::MolarMass => None,
+            ChemFn::AtomicNumber => Some(Quantity::new(
+                f64::from(element.number),
+                Unit::dimensionless(),
+            )),
+            ChemFn::AtomicMass => Some(Quantity::new(element.atomic_mass, gram_per_mole())),
+            ChemFn::Density => element.density.map(|d| Quantity::new(d, gram_per_cm3())),
+            ChemFn::MeltingPoint => element
+                .melting_point
+                .map(|k| Quantity::new(k, Unit::kelvin())),
+            ChemFn::BoilingPoint => element
+                .boiling_point
+                .map(|k| Quantity::new(k, Unit::kelvin())),
+        }
+    }
+}
+
+fn gram_per_cm3() -> Unit {
+    Unit::new(
+        "gram/centimeter^3",
+        1e3,
+        &[(Dimension::Mass, 1), (Dimension::Length, -3)],
+    )
+}
+
+fn gram_per_mole() -> Unit {
+    Unit::new(
+        "gram/mole",
+        1e-3,
+        &[(Dimension::Mass, 1), (Dimension::AmountOfSubstance, -1)],
+    )
+}
+
+/// Look up a chemistry function by its user-facing name.
+pub fn lookup(name: &str) -> Option<ChemFn> {
+    ChemFn::ALL.iter().copied().find(|f| f.name() == name)
+}
+
+/// Suggest the closest chemistry function names for a misspelled input,
+/// with the same Jaro-Winkler `> 0.7` threshold as `math::suggest`.
+pub fn suggest(unknown: &str, max: usize) -> Vec<String> {
+    let unknown_lower = unknown.to_lowercase();
+    let mut scored: Vec<_> = ChemFn::ALL
+        .iter()
+        .map(|f| (f.name(), strsim::jaro_winkler(&unknown_lower, f.name())))
+        .filter(|(_, score)| *score > 0.7)
+        .collect();
+    scored.sort_by(|a, b| b.1.total_cmp(&a.1));
+    scored
+        .into_iter()
+        .take(max)
+        .map(|(name, _)| name.to_string())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn molar_mass_is_mass_per_amount() {
+        let q = ChemFn::MolarMass.apply("NaCl").unwrap();
+        assert!((q.value - 58.44).abs() < 1e-2);
+        assert_eq!(q.unit.dimension_string(), "Mass*Amount^-1");
+        let kg_per_mol = q.unit.to_base_value(q.value);
+        assert!((kg_per_mol - 0.05844).abs() < 1e-5);
+    }
+
+    #[test]
+    fn lookup_and_suggest() {
+        assert_eq!(lookup("molar_mass"), Some(ChemFn::MolarMass));
+        assert!(lookup("molarmass").is_none());
+        assert_eq!(suggest("molarmass", 3)[0], "molar_mass");
+    }
+
+    #[test]
+    fn element_properties_carry_units() {
+        let rho = ChemFn::Density.apply("Fe").unwrap();
+        assert!((rho.unit.to_base_value(rho.value) - 7874.0).abs() < 1e-9);
+        let melt = ChemFn::MeltingPoint.apply("iron").unwrap();
+        assert_eq!(melt.unit.dimension_string(), "Temperature");
+        assert_eq!(ChemFn::AtomicNumber.apply("Og").unwrap().value, 118.0);
+    }
+
+    #[test]
+    fn substance_properties_use_table_units() {
+        let water = crate::database::substances::lookup("water").unwrap();
+        let rho = ChemFn::Density.substance_property(water).unwrap();
+        assert_eq!(rho.unit.name, "gram/centimeter^3");
+        assert!((rho.value - 0.9982).abs() < 1e-9);
+        let m = ChemFn::MolarMass.substance_property(water).unwrap();
+        assert!((m.value - 18.015).abs() < 1e-9);
+        let err = ChemFn::MeltingPoint.substance_property(water).unwrap_err();
+        assert!(
+            matches!(err, RUnitsError::SubstanceProperty { .. }),
+            "{err:?}"
+        );
+    }
+
+    #[test]
+    fn missing_property_and_unknown_element_are_errors() {
+        let err = ChemFn::Density.apply("Og").unwrap_err();
+        assert!(
+            err.to_string().contains("no known value for oganesson"),
+            "{err}"
+        );
+        let err = ChemFn::Density.apply("Irn").unwrap_err();
+        assert!(matches!(err, RUnitsError::UnknownElement { .. }), "{err:?}");
+    }
+}
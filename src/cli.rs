@@ -31,15 +31,41 @@ pub struct Cli {
     #[arg(long)]
     pub explain: bool,
 
+    /// Refuse reciprocal conversions (ohm -> S, Hz -> s) instead of converting via 1/x
+    #[arg(long)]
+    pub strict: bool,
+
+    /// Allow cross-dimension conversions through an equivalency
+    /// (e.g. `--equiv spectral` for "500 nm" -> "eV"); repeatable
+    #[arg(long, value_enum)]
+    pub equiv: Vec<crate::equiv::Equivalency>,
+
+    /// Pick the target unit automatically when none is given (best SI or
+    /// binary prefix; `--auto=imperial` for imperial units)
+    #[arg(
+        long,
+        value_enum,
+        value_name = "FAMILY",
+        num_args = 0..=1,
+        require_equals = true,
+        default_missing_value = "si"
+    )]
+    pub auto: Option<crate::auto::UnitFamily>,
+
     /// Use Unicode symbols in output (kg·m/s² instead of kg*m/s^2)
     #[arg(long)]
     pub pretty: bool,
 
     /// Output result as JSON
-    #[arg(long)]
+    #[arg(long, global = true)]
     pub json: bool,
 
-    /// Read conversions from stdin, one per line
+    /// List the constants and units closest to the quantity, instead of
+    /// converting it (e.g. `--what "9.81 m/s^2"`)
+    #[arg(long, conflicts_with = "target")]
+    pub what: bool,
+
+    /// Read conversions (and `x = <qty>` / `f(x) = <expr>` definitions) from stdin, one per line
     #[arg(long)]
     pub batch: bool,
 
@@ -51,6 +77,10 @@ pub struct Cli {
     #[arg(long, value_enum)]
     pub intro_banner: Option<BannerMode>,
 
+    /// Unit database tier (default: builtin for one-shot, standard for REPL)
+    #[arg(long, value_enum)]
+    pub db: Option<crate::database::Tier>,
+
     #[command(subcommand)]
     pub command: Option<Commands>,
 }
@@ -75,6 +105,27 @@ pub enum Commands {
         #[command(subcommand)]
         what: ListWhat,
     },
+    /// Import a GNU Units `definitions.units` file and report what it adds
+    ImportGnu {
+        /// Path to the GNU definitions file
+        path: std::path::PathBuf,
+    },
+    /// Show an element's atomic number, atomic mass, density and
+    /// melting/boiling points
+    Element {
+        /// Element symbol or name (e.g., "Fe", "iron")
+        name: String,
+    },
+    /// Check the unit database, or definition files, for problems
+    ///
+    /// Reports duplicate aliases, aliases that also read as prefix + unit,
+    /// naming-rule violations, definition cycles, and constants whose
+    /// dimensions disagree with their declared quantity. Exits 1 when any
+    /// problem is found.
+    Check {
+        /// Definition files to load and check (default: the whole database)
+        files: Vec<std::path::PathBuf>,
+    },
 }
 
 #[derive(Subcommand, Debug)]
@@ -2,9 +2,14 @@
 //!
 //! All fields are optional — missing file or missing keys use sensible defaults.
 //! CLI flags always override config values.
+//!
+//! The same directory may hold `units.conf`, a definition file (see
+//! [`crate::database::definitions`]) layered over the builtin database.
+//! `Config::load` only *finds* it; `main` loads it before the global
+//! databases are first used.
 
 use serde::Deserialize;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 /// User configuration deserialized from TOML.
 #[derive(Debug, Deserialize, Default)]
@@ -17,18 +22,42 @@ pub struct Config {
     pub unicode: Option<bool>,
     /// Intro banner mode: "long", "short", or "off".
     pub intro_banner: Option<String>,
+    /// Database tier: "builtin", "standard", or "full" (overridden by `--db`).
+    pub db: Option<crate::database::Tier>,
+    /// Refuse reciprocal conversions (overridden on by `--strict`).
+    pub strict: Option<bool>,
+    /// Equivalency contexts on by default, e.g. `["spectral"]` (replaced by `--equiv`).
+    pub equiv: Option<Vec<crate::equiv::Equivalency>>,
+    /// Pick a target unit automatically when none is given: "si" or
+    /// "imperial" (overridden by `--auto`).
+    pub auto: Option<crate::auto::UnitFamily>,
+    /// GNU `definitions.units` for the full tier, if not in a standard location.
+    pub gnu_definitions: Option<PathBuf>,
+    /// Path to `units.conf`, if one exists next to `config.toml`.
+    /// Discovered by [`Config::load`], never read from the TOML itself.
+    #[serde(skip)]
+    pub units_file: Option<PathBuf>,
 }
 
 impl Config {
     /// Load config from `~/.config/runits/config.toml`.
     ///
     /// Returns `Config::default()` if the file doesn't exist or can't be parsed.
-    /// Prints a warning to stderr on parse errors.
+    /// Prints a warning to stderr on parse errors. Either way, `units_file`
+    /// is set when `units.conf` exists alongside.
     pub fn load() -> Self {
-        let Some(path) = config_path() else {
+        let Some(dir) = config_dir() else {
             return Config::default();
         };
-        match std::fs::read_to_string(&path) {
+        let units_file = Some(dir.join(UNITS_FILE)).filter(|p| p.is_file());
+        Config {
+            units_file,
+            ..Self::load_toml(&dir.join("config.toml"))
+        }
+    }
+
+    fn load_toml(path: &Path) -> Self {
+        match std::fs::read_to_string(path) {
             Ok(contents) => match toml::from_str(&contents) {
                 Ok(config) => config,
                 Err(e) => {
@@ -41,17 +70,17 @@ impl Config {
     }
 }
 
-/// Returns `~/.config/runits/config.toml`.
-fn config_path() -> Option<PathBuf> {
+/// File name of the user definition file inside the config directory.
+pub const UNITS_FILE: &str = "units.conf";
+
+/// Returns `~/.config/runits` (or `$XDG_CONFIG_HOME/runits`).
+fn config_dir() -> Option<PathBuf> {
     if let Ok(xdg) = std::env::var("XDG_CONFIG_HOME") {
-        Some(PathBuf::from(xdg).join("runits").join("config.toml"))
+        Some(PathBuf::from(xdg).join("runits"))
     } else {
-        std::env::var("HOME").ok().map(|h| {
-            PathBuf::from(h)
-                .join(".config")
-                .join("runits")
-                .join("config.toml")
-        })
+        std::env::var("HOME")
+            .ok()
+            .map(|h| PathBuf::from(h).join(".config").join("runits"))
     }
 }
 
@@ -65,6 +94,7 @@ mod tests {
         assert!(c.precision.is_none());
         assert!(c.color.is_none());
         assert!(c.unicode.is_none());
+        assert!(c.units_file.is_none());
     }
 
     #[test]
@@ -73,8 +103,22 @@ mod tests {
             precision = 8
             color = true
             unicode = false
+            db = "standard"
+            strict = true
+            equiv = ["spectral", "mass-energy"]
+            auto = "imperial"
         "#;
         let c: Config = toml::from_str(toml_str).unwrap();
+        assert_eq!(c.db, Some(crate::database::Tier::Standard));
+        assert_eq!(c.strict, Some(true));
+        assert_eq!(
+            c.equiv,
+            Some(vec![
+                crate::equiv::Equivalency::Spectral,
+                crate::equiv::Equivalency::MassEnergy
+            ])
+        );
+        assert_eq!(c.auto, Some(crate::auto::UnitFamily::Imperial));
         assert_eq!(c.precision, Some(8));
         assert_eq!(c.color, Some(true));
         assert_eq!(c.unicode, Some(false));
@@ -87,4 +131,10 @@ mod tests {
         assert_eq!(c.precision, Some(3));
         assert!(c.color.is_none());
     }
+
+    #[test]
+    fn units_file_is_not_a_toml_key() {
+        let c: Config = toml::from_str("units_file = \"/tmp/x\"\n").unwrap();
+        assert!(c.units_file.is_none());
+    }
 }
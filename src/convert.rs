@@ -2,12 +2,20 @@
 //!
 //! This module extracts the parse→convert pipeline into a reusable function
 //! that the one-shot CLI, REPL, and batch mode all share.
+//!
+//! A target may be a unit list (`ft;in`, `h;min;s`): the conversion goes to
+//! the first (largest) unit, and [`decompose`] splits the result across the
+//! list at display time, once the output precision is known.
 
 use crate::annotations::quantity_name;
+use crate::auto::{self, UnitFamily};
 use crate::database::UnitDatabase;
+use crate::database::substances::{self, Substance, SubstanceBridge};
+use crate::equiv::{Bridge, Equivalency};
 use crate::error::RUnitsError;
+use crate::eval::EvalContext;
 use crate::parser;
-use crate::units::Quantity;
+use crate::units::{Quantity, Unit};
 
 /// The structured output of a single conversion, before any formatting.
 #[derive(Debug, Clone)]
@@ -25,27 +33,235 @@ pub struct ConversionResult {
     /// user's raw expression when it's non-trivial (e.g. `5 m + 3 ft`),
     /// without the noise of echoing it for simple `10 ft` conversions.
     pub source_expr: Option<String>,
+    /// The dimensions were exact inverses and the result is `1/x` of the
+    /// source (`50 ohm -> S`). Shown by `--explain` and `--json`.
+    pub reciprocal: bool,
+    /// The equivalency that bridged different dimensions (`500 nm -> eV`
+    /// under `--equiv spectral`), if any. `--explain` names its constants.
+    pub bridge: Option<Bridge>,
+    /// The substance whose density or molar mass bridged the dimensions
+    /// (`2 cup flour -> g`), if any.
+    pub substance: Option<SubstanceBridge>,
+    /// The units of a `;`-separated target (`ft;in`), largest first; empty
+    /// for a plain target. `result` is then expressed in the first unit.
+    pub unit_list: Vec<Unit>,
+}
+
+impl ConversionResult {
+    /// A result that echoes `quantity` unconverted — a bare REPL expression
+    /// or a variable assignment.
+    pub fn echo(quantity: Quantity, source: &str) -> Self {
+        ConversionResult {
+            annotation: quantity_name(&quantity.unit.dimensions),
+            source: quantity.clone(),
+            result: quantity,
+            source_expr: Some(source.to_string()),
+            reciprocal: false,
+            bridge: None,
+            substance: None,
+            unit_list: Vec::new(),
+        }
+    }
+}
+
+/// Conversion policy, set from CLI flags and `config.toml`. Separate from
+/// [`crate::format::FormatOptions`]: these change *what* is computed, not
+/// how it's shown.
+#[derive(Debug, Clone, Default)]
+pub struct ConvertOptions {
+    /// Refuse reciprocal conversions (`--strict`, `strict = true`): `ohm ->
+    /// S` is then an `IncompatibleDimensions` error like any other mismatch.
+    pub strict: bool,
+    /// Equivalency contexts in effect (`--equiv`, `equiv = [...]`, REPL
+    /// `using`). Empty means dimensions must match (or be reciprocal).
+    pub equivalencies: Vec<Equivalency>,
+    /// Pick the target unit automatically when none is given (`--auto`,
+    /// `auto = "si"`), in this family. An explicit `-> auto` target works
+    /// either way and defaults to this family, or SI.
+    pub auto: Option<UnitFamily>,
+    /// Significant figures the result will be shown with (`--precision`,
+    /// default 6), so `auto` never picks a unit the rounded value outgrows.
+    pub precision: Option<usize>,
+}
+
+/// The outcome of [`convert_quantity`]: the converted quantity plus how the
+/// dimensions were reconciled.
+#[derive(Debug, Clone)]
+pub struct Converted {
+    pub quantity: Quantity,
+    pub reciprocal: bool,
+    pub bridge: Option<Bridge>,
+    pub substance: Option<SubstanceBridge>,
+}
+
+impl Converted {
+    fn direct(quantity: Quantity) -> Self {
+        Converted {
+            quantity,
+            reciprocal: false,
+            bridge: None,
+            substance: None,
+        }
+    }
+}
+
+/// Convert `source` to `target`. When the dimensions differ, a named
+/// `substance` is tried first (density / molar mass), then an enabled
+/// equivalency, then a reciprocal conversion when the dimensions are exact
+/// inverses and `opts` allows it. A mismatch that only a substance could
+/// bridge is reported as [`RUnitsError::SubstanceRequired`].
+pub fn convert_quantity(
+    source: &Quantity,
+    target: &Unit,
+    substance: Option<&'static Substance>,
+    opts: &ConvertOptions,
+) -> Result<Converted, RUnitsError> {
+    match source.convert_to(target) {
+        Err(err @ RUnitsError::IncompatibleDimensions { .. }) => {
+            if let Some(substance) = substance
+                && let Some(via) = SubstanceBridge::find(substance, &source.unit, target)?
+            {
+                return Ok(Converted {
+                    quantity: via.convert(source, target)?,
+                    substance: Some(via),
+                    ..Converted::direct(source.clone())
+                });
+            }
+            if let Some(bridge) = Bridge::find(
+                &opts.equivalencies,
+                &source.unit.dimensions,
+                &target.dimensions,
+            ) {
+                return Ok(Converted {
+                    quantity: bridge.convert(source, target)?,
+                    bridge: Some(bridge),
+                    ..Converted::direct(source.clone())
+                });
+            }
+            if !opts.strict && source.unit.is_reciprocal_of(target) {
+                return Ok(Converted {
+                    quantity: source.convert_to_reciprocal(target)?,
+                    reciprocal: true,
+                    ..Converted::direct(source.clone())
+                });
+            }
+            if substance.is_none()
+                && let Some(property) =
+                    substances::needed_property(&source.unit.dimensions, &target.dimensions)
+            {
+                return Err(RUnitsError::SubstanceRequired {
+                    from: source.unit.name.clone(),
+                    to: target.name.clone(),
+                    property: property.to_string(),
+                });
+            }
+            Err(err)
+        }
+        other => other.map(Converted::direct),
+    }
 }
 
 /// Run a single conversion: parse source and target, convert, annotate.
+///
+/// A trailing substance name on the source (`2 cup flour`) is split off
+/// before parsing and offered to [`convert_quantity`].
 pub fn run_conversion(
     source: &str,
     target: &str,
     db: &UnitDatabase,
+    opts: &ConvertOptions,
+) -> Result<ConversionResult, RUnitsError> {
+    run_conversion_in(source, target, &EvalContext::one_shot(db), opts)
+}
+
+/// [`run_conversion`] under a caller-provided context: the REPL and
+/// `--batch` evaluate the source with `_` and session variables in scope.
+pub fn run_conversion_in(
+    source: &str,
+    target: &str,
+    ctx: &EvalContext,
+    opts: &ConvertOptions,
+) -> Result<ConversionResult, RUnitsError> {
+    let (expr, substance) = substances::split_substance(source);
+    let source_qty = parser::parse_and_eval(expr, ctx)?;
+    convert_to_target(source_qty, source, target, substance, ctx.units, opts)
+}
+
+/// Convert an already-evaluated source to the `target` text — a unit, a
+/// `;` unit list, or `auto` (see [`crate::auto`]) — and package the result.
+pub fn convert_to_target(
+    source_qty: Quantity,
+    source: &str,
+    target: &str,
+    substance: Option<&'static Substance>,
+    db: &UnitDatabase,
+    opts: &ConvertOptions,
 ) -> Result<ConversionResult, RUnitsError> {
-    let source_qty = parser::parse_quantity(source, db)?;
-    let target_unit = parser::parse_unit_name(target, db)?;
-    let result = source_qty.clone().convert_to(&target_unit)?;
-    let annotation = quantity_name(&result.unit.dimensions);
+    let mut units = match auto::parse_target(target, opts.auto.unwrap_or_default()) {
+        Some(family) => vec![auto::best_unit(
+            &source_qty,
+            family?,
+            db,
+            opts.precision.unwrap_or(6),
+        )],
+        None => parser::parse_unit_list(target, db)?,
+    };
+    let converted = convert_quantity(&source_qty, &units[0], substance, opts)?;
+    let annotation = quantity_name(&converted.quantity.unit.dimensions);
+    if units.len() == 1 {
+        units.clear();
+    }
 
     Ok(ConversionResult {
         source: source_qty,
-        result,
+        result: converted.quantity,
         annotation,
         source_expr: Some(source.to_string()),
+        reciprocal: converted.reciprocal,
+        bridge: converted.bridge,
+        substance: converted.substance,
+        unit_list: units,
     })
 }
 
+/// Split `total` across `units` (largest first, all linear and of the same
+/// dimension, as [`parser::parse_unit_list`] guarantees): `6.25 ft` over
+/// `ft;in` is `[6 ft, 3 in]`.
+///
+/// Every component but the last is a whole number. The last carries the
+/// remainder, rounded to `sig_figs` significant figures *of the total*: the
+/// total is first rounded to a whole number of quanta in the last unit and
+/// the split is done on that, so float noise can't produce `5 ft 12 in` or
+/// `1 h 59.99999 min`. A negative total gives all-negative components.
+pub fn decompose(total: &Quantity, units: &[Unit], sig_figs: usize) -> Vec<Quantity> {
+    let Some((last, leading)) = units.split_last() else {
+        return Vec::new();
+    };
+    let base = total.unit.to_base_value(total.value);
+    let sign = if base < 0.0 { -1.0 } else { 1.0 };
+    let in_last = base.abs() / last.conversion_factor();
+
+    // One quantum = the last displayed digit of the total, in the last unit.
+    let magnitude = if in_last > 0.0 {
+        in_last.log10().floor() as i32
+    } else {
+        0
+    };
+    let quantum = 10f64.powi(magnitude - sig_figs.max(1) as i32 + 1);
+    let mut remaining = (in_last / quantum).round();
+
+    let mut parts = Vec::with_capacity(units.len());
+    for unit in leading {
+        let per_unit = unit.conversion_factor() / last.conversion_factor() / quantum;
+        let count = (remaining / per_unit + 1e-9).floor();
+        remaining = (remaining - count * per_unit).max(0.0);
+        parts.push(Quantity::new(sign * count, unit.clone()));
+    }
+    let rest = (remaining.round() * quantum * 1e12).round() / 1e12;
+    parts.push(Quantity::new(sign * rest, last.clone()));
+    parts
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -53,7 +269,7 @@ mod tests {
     #[test]
     fn basic_conversion() {
         let db = UnitDatabase::new();
-        let r = run_conversion("10 ft", "m", &db).unwrap();
+        let r = run_conversion("10 ft", "m", &db, &ConvertOptions::default()).unwrap();
         assert!((r.result.value - 3.048).abs() < 1e-9);
         assert_eq!(r.annotation, Some("Length"));
     }
@@ -61,7 +277,7 @@ mod tests {
     #[test]
     fn annotated_conversion() {
         let db = UnitDatabase::new();
-        let r = run_conversion("100 km/h", "m/s", &db).unwrap();
+        let r = run_conversion("100 km/h", "m/s", &db, &ConvertOptions::default()).unwrap();
         assert_eq!(r.annotation, Some("Velocity"));
     }
 
@@ -69,17 +285,136 @@ mod tests {
     fn unknown_unit_error() {
         let db = UnitDatabase::new();
         // With expression-based parsing, unknown identifiers land here.
-        let err = run_conversion("10 foozle", "m", &db).unwrap_err();
+        let err = run_conversion("10 foozle", "m", &db, &ConvertOptions::default()).unwrap_err();
         assert!(matches!(err, RUnitsError::UnknownIdentifier { .. }));
     }
 
     #[test]
     fn expression_source_succeeds() {
         let db = UnitDatabase::new();
-        let r = run_conversion("5 m + 3 ft", "cm", &db).unwrap();
+        let r = run_conversion("5 m + 3 ft", "cm", &db, &ConvertOptions::default()).unwrap();
         // source_qty is in meters (LHS wins); converted to cm
         assert_eq!(r.source.unit.name, "meter");
         assert_eq!(r.result.unit.name, "centimeter");
         assert!((r.result.value - 591.44).abs() < 1e-6);
     }
+
+    #[test]
+    fn reciprocal_conversion_is_marked() {
+        let db = UnitDatabase::new();
+        let r = run_conversion("4 Hz", "ms", &db, &ConvertOptions::default()).unwrap();
+        assert!(r.reciprocal);
+        assert!((r.result.value - 250.0).abs() < 1e-9);
+        let r = run_conversion("4 Hz", "kHz", &db, &ConvertOptions::default()).unwrap();
+        assert!(!r.reciprocal);
+    }
+
+    #[test]
+    fn strict_refuses_reciprocal_conversion() {
+        let db = UnitDatabase::new();
+        let strict = ConvertOptions {
+            strict: true,
+            ..Default::default()
+        };
+        let err = run_conversion("4 Hz", "s", &db, &strict).unwrap_err();
+        assert!(matches!(err, RUnitsError::IncompatibleDimensions { .. }));
+    }
+
+    #[test]
+    fn reciprocal_of_zero_is_an_error() {
+        let db = UnitDatabase::new();
+        let err = run_conversion("0 Hz", "s", &db, &ConvertOptions::default()).unwrap_err();
+        assert!(matches!(err, RUnitsError::OutOfDomain { .. }));
+    }
+
+    #[test]
+    fn fuel_economy_converts_both_ways() {
+        let db = UnitDatabase::new();
+        let opts = ConvertOptions::default();
+        let r = run_conversion("30 mpg", "L/(100 km)", &db, &opts).unwrap();
+        assert!(r.reciprocal);
+        assert!((r.result.value - 7.840_49).abs() < 1e-4);
+        let r = run_conversion("7.84049 L_per_100km", "mpg", &db, &opts).unwrap();
+        assert!((r.result.value - 30.0).abs() < 1e-4);
+        // Same family: an ordinary linear conversion.
+        let r = run_conversion("40 mpg_imp", "mpg", &db, &opts).unwrap();
+        assert!(!r.reciprocal);
+        assert!((r.result.value - 33.307).abs() < 1e-3);
+    }
+
+    #[test]
+    fn equivalency_bridges_only_when_enabled() {
+        let db = UnitDatabase::new();
+        let err = run_conversion("500 nm", "eV", &db, &ConvertOptions::default()).unwrap_err();
+        assert!(matches!(err, RUnitsError::IncompatibleDimensions { .. }));
+        let opts = ConvertOptions {
+            equivalencies: vec![Equivalency::Spectral],
+            ..Default::default()
+        };
+        let r = run_conversion("500 nm", "eV", &db, &opts).unwrap();
+        assert!((r.result.value - 2.479_68).abs() < 1e-4);
+        assert_eq!(r.bridge.map(|b| b.equivalency), Some(Equivalency::Spectral));
+        assert!(!r.reciprocal);
+    }
+
+    #[test]
+    fn substance_bridges_volume_and_mass() {
+        let db = UnitDatabase::new();
+        let opts = ConvertOptions::default();
+        let r = run_conversion("2 cup flour", "g", &db, &opts).unwrap();
+        assert!((r.result.value - 250.0).abs() < 1e-9);
+        assert_eq!(r.substance.map(|s| s.substance.name), Some("flour"));
+        // A substance on a same-dimension conversion is simply unused.
+        let r = run_conversion("2 cup flour", "mL", &db, &opts).unwrap();
+        assert!(r.substance.is_none());
+    }
+
+    #[test]
+    fn unit_list_decomposes_with_a_clean_remainder() {
+        let db = UnitDatabase::new();
+        let opts = ConvertOptions::default();
+        let split = |src: &str, dst: &str| -> Vec<f64> {
+            let r = run_conversion(src, dst, &db, &opts).unwrap();
+            decompose(&r.result, &r.unit_list, 6)
+                .iter()
+                .map(|q| q.value)
+                .collect()
+        };
+        assert_eq!(split("6.25 ft", "ft;in"), vec![6.0, 3.0]);
+        assert_eq!(split("5400 s", "h;min;s"), vec![1.0, 30.0, 0.0]);
+        // 2 m is 78.7402 in to six figures: 6 ft 6.7402 in, not
+        // 6 ft 6.740157480314961 in.
+        assert_eq!(split("2 m", "ft;in"), vec![6.0, 6.7402]);
+        // Float noise just under a whole unit rounds up, not to "59.9999 min".
+        assert_eq!(split("1.9999999999 h", "h;min"), vec![2.0, 0.0]);
+        assert_eq!(split("-90 min", "h;min"), vec![-1.0, -30.0]);
+    }
+
+    #[test]
+    fn unit_list_rejects_mixed_or_misordered_units() {
+        let db = UnitDatabase::new();
+        let opts = ConvertOptions::default();
+        for bad in ["ft;kg", "in;ft", "ft;degC"] {
+            let err = run_conversion("1 ft", bad, &db, &opts).unwrap_err();
+            assert!(
+                matches!(err, RUnitsError::UnitList { .. }),
+                "{bad}: {err:?}"
+            );
+        }
+        let r = run_conversion("1 ft", "in", &db, &opts).unwrap();
+        assert!(r.unit_list.is_empty());
+    }
+
+    #[test]
+    fn missing_substance_is_a_clear_error() {
+        let db = UnitDatabase::new();
+        let opts = ConvertOptions::default();
+        let err = run_conversion("2 cup", "g", &db, &opts).unwrap_err();
+        assert!(
+            matches!(&err, RUnitsError::SubstanceRequired { property, .. } if property == "density")
+        );
+        // Unrelated mismatches keep the plain dimension error.
+        let err = run_conversion("2 cup", "s", &db, &opts).unwrap_err();
+        assert!(matches!(err, RUnitsError::IncompatibleDimensions { .. }));
+    }
 }
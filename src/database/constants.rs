@@ -1,7 +1,8 @@
 //! Physical constants database.
 //!
 //! Parallel to the unit registry: a `ConstantsDatabase` holding named physical
-//! constants (speed of light, Planck constant, etc.) with alias lookup.
+//! constants (speed of light, Planck constant, etc.) with alias lookup, plus
+//! the dimensionless mathematical constants `pi`, `tau` and `e`.
 //! Constants are semantically distinct from units — each is a `Quantity`
 //! (a specific numeric value with dimensions), not a conversion scale.
 //!
@@ -18,13 +19,17 @@ use std::sync::OnceLock;
 #[derive(Debug, Clone)]
 pub struct Constant {
     /// Canonical name (descriptive, e.g. "speed_of_light").
-    pub name: &'static str,
+    pub name: String,
     /// Numeric value in the given unit.
     pub value: f64,
     /// The unit this value is expressed in (e.g., m/s for speed of light).
     pub unit: Unit,
     /// Short human-readable description.
-    pub description: &'static str,
+    pub description: String,
+    /// Declared physical quantity (an annotation-registry name such as
+    /// "Velocity"), when one exists. `runits check` verifies it against
+    /// the unit's dimensions.
+    pub quantity: Option<String>,
 }
 
 /// A collection of physical constants keyed by every acceptable alias.
@@ -43,6 +48,28 @@ impl ConstantsDatabase {
         ConstantsDatabase { constants }
     }
 
+    /// Build a database with no constants at all. Used as the starting
+    /// point when loading constants from definition files.
+    pub fn empty() -> Self {
+        ConstantsDatabase {
+            constants: HashMap::new(),
+        }
+    }
+
+    /// Register `constant` under its canonical name plus every alias.
+    pub fn insert(&mut self, constant: Constant, aliases: &[String]) {
+        self.constants
+            .insert(constant.name.clone(), constant.clone());
+        for alias in aliases {
+            self.constants.insert(alias.clone(), constant.clone());
+        }
+    }
+
+    /// Forget `name` (one alias; other aliases of the same constant stay).
+    pub fn remove(&mut self, name: &str) {
+        self.constants.remove(name);
+    }
+
     /// Look up a constant by name or alias.
     pub fn lookup(&self, name: &str) -> Option<&Constant> {
         self.constants.get(name)
@@ -67,7 +94,7 @@ impl ConstantsDatabase {
             .iter()
             .map(|(alias, constant)| {
                 let score = strsim::jaro_winkler(&unknown_lower, &alias.to_lowercase());
-                (alias.as_str(), constant.name, score)
+                (alias.as_str(), constant.name.as_str(), score)
             })
             .filter(|(_, _, score)| *score > 0.7)
             .collect();
@@ -88,10 +115,25 @@ impl ConstantsDatabase {
         let mut seen = HashSet::new();
         self.constants
             .values()
-            .filter(|c| seen.insert(c.name))
+            .filter(|c| seen.insert(c.name.as_str()))
             .collect()
     }
 
+    /// Find all alias strings that map to a given canonical constant name.
+    ///
+    /// Returns sorted aliases, excluding the canonical name itself — the
+    /// counterpart of [`UnitDatabase::aliases_for`](super::UnitDatabase::aliases_for).
+    pub fn aliases_for(&self, canonical: &str) -> Vec<String> {
+        let mut aliases: Vec<String> = self
+            .constants
+            .iter()
+            .filter(|(alias, c)| c.name == canonical && alias.as_str() != canonical)
+            .map(|(alias, _)| alias.clone())
+            .collect();
+        aliases.sort();
+        aliases
+    }
+
     /// How many aliases are registered (every alias counts).
     pub fn len(&self) -> usize {
         self.constants.len()
@@ -110,10 +152,20 @@ impl Default for ConstantsDatabase {
 
 /// Returns the process-wide singleton constants database.
 pub fn global() -> &'static ConstantsDatabase {
-    static DB: OnceLock<ConstantsDatabase> = OnceLock::new();
-    DB.get_or_init(ConstantsDatabase::new)
+    GLOBAL.get_or_init(ConstantsDatabase::new)
+}
+
+/// Install `db` as the process-wide constants database in place of the builtin seed
+/// (e.g. builtins + the user's `units.conf`).
+///
+/// Only takes effect before the first [`global`] call; otherwise the
+/// database is handed back unchanged.
+pub fn set_global(db: ConstantsDatabase) -> Result<(), ConstantsDatabase> {
+    GLOBAL.set(db)
 }
 
+static GLOBAL: OnceLock<ConstantsDatabase> = OnceLock::new();
+
 /// Helper: register every `alias` as a lookup key for `constant`.
 fn add(map: &mut HashMap<String, Constant>, aliases: &[&str], constant: Constant) {
     for alias in aliases {
@@ -132,10 +184,11 @@ fn seed_all(map: &mut HashMap<String, Constant>) {
         map,
         &["speed_of_light", "c_0"],
         Constant {
-            name: "speed_of_light",
+            name: "speed_of_light".into(),
             value: 299_792_458.0,
             unit: Unit::meter() / Unit::second(),
-            description: "Speed of light in vacuum",
+            description: "Speed of light in vacuum".into(),
+            quantity: Some("Velocity".into()),
         },
     );
 
@@ -143,7 +196,7 @@ fn seed_all(map: &mut HashMap<String, Constant>) {
         map,
         &["gravitational_constant"],
         Constant {
-            name: "gravitational_constant",
+            name: "gravitational_constant".into(),
             value: 6.674_30e-11,
             unit: Unit::new(
                 "m³/(kg·s²)",
@@ -154,7 +207,8 @@ fn seed_all(map: &mut HashMap<String, Constant>) {
                     (Dimension::Time, -2),
                 ],
             ),
-            description: "Newtonian constant of gravitation",
+            description: "Newtonian constant of gravitation".into(),
+            quantity: None,
         },
     );
 
@@ -162,14 +216,15 @@ fn seed_all(map: &mut HashMap<String, Constant>) {
         map,
         &["gravity", "g0", "g_n"],
         Constant {
-            name: "gravity",
+            name: "gravity".into(),
             value: 9.806_65,
             unit: Unit::new(
                 "m/s²",
                 1.0,
                 &[(Dimension::Length, 1), (Dimension::Time, -2)],
             ),
-            description: "Standard acceleration of gravity on Earth",
+            description: "Standard acceleration of gravity on Earth".into(),
+            quantity: Some("Acceleration".into()),
         },
     );
 
@@ -177,7 +232,7 @@ fn seed_all(map: &mut HashMap<String, Constant>) {
         map,
         &["planck_constant"],
         Constant {
-            name: "planck_constant",
+            name: "planck_constant".into(),
             value: 6.626_070_15e-34,
             unit: Unit::new(
                 "J·s",
@@ -188,7 +243,8 @@ fn seed_all(map: &mut HashMap<String, Constant>) {
                     (Dimension::Time, -1),
                 ],
             ),
-            description: "Planck constant",
+            description: "Planck constant".into(),
+            quantity: None,
         },
     );
 
@@ -196,7 +252,7 @@ fn seed_all(map: &mut HashMap<String, Constant>) {
         map,
         &["hbar", "h_bar", "ℏ"],
         Constant {
-            name: "hbar",
+            name: "hbar".into(),
             value: 1.054_571_817e-34,
             unit: Unit::new(
                 "J·s",
@@ -207,7 +263,8 @@ fn seed_all(map: &mut HashMap<String, Constant>) {
                     (Dimension::Time, -1),
                 ],
             ),
-            description: "Reduced Planck constant (h/2π)",
+            description: "Reduced Planck constant (h/2π)".into(),
+            quantity: None,
         },
     );
 
@@ -217,7 +274,7 @@ fn seed_all(map: &mut HashMap<String, Constant>) {
         map,
         &["boltzmann_constant", "k_B"],
         Constant {
-            name: "boltzmann_constant",
+            name: "boltzmann_constant".into(),
             value: 1.380_649e-23,
             unit: Unit::new(
                 "J/K",
@@ -229,7 +286,8 @@ fn seed_all(map: &mut HashMap<String, Constant>) {
                     (Dimension::Temperature, -1),
                 ],
             ),
-            description: "Boltzmann constant",
+            description: "Boltzmann constant".into(),
+            quantity: Some("Entropy".into()),
         },
     );
 
@@ -237,10 +295,11 @@ fn seed_all(map: &mut HashMap<String, Constant>) {
         map,
         &["avogadro_constant", "N_A"],
         Constant {
-            name: "avogadro_constant",
+            name: "avogadro_constant".into(),
             value: 6.022_140_76e23,
             unit: Unit::new("mol⁻¹", 1.0, &[(Dimension::AmountOfSubstance, -1)]),
-            description: "Avogadro constant",
+            description: "Avogadro constant".into(),
+            quantity: None,
         },
     );
 
@@ -248,7 +307,7 @@ fn seed_all(map: &mut HashMap<String, Constant>) {
         map,
         &["gas_constant", "R_gas"],
         Constant {
-            name: "gas_constant",
+            name: "gas_constant".into(),
             value: 8.314_462_618,
             unit: Unit::new(
                 "J/(mol·K)",
@@ -261,7 +320,8 @@ fn seed_all(map: &mut HashMap<String, Constant>) {
                     (Dimension::Temperature, -1),
                 ],
             ),
-            description: "Molar gas constant",
+            description: "Molar gas constant".into(),
+            quantity: None,
         },
     );
 
@@ -271,10 +331,11 @@ fn seed_all(map: &mut HashMap<String, Constant>) {
         map,
         &["elementary_charge", "electron_charge"],
         Constant {
-            name: "elementary_charge",
+            name: "elementary_charge".into(),
             value: 1.602_176_634e-19,
             unit: Unit::new("C", 1.0, &[(Dimension::Current, 1), (Dimension::Time, 1)]),
-            description: "Elementary charge",
+            description: "Elementary charge".into(),
+            quantity: Some("Electric Charge".into()),
         },
     );
 
@@ -282,7 +343,7 @@ fn seed_all(map: &mut HashMap<String, Constant>) {
         map,
         &["electric_constant", "eps0", "ε0"],
         Constant {
-            name: "electric_constant",
+            name: "electric_constant".into(),
             value: 8.854_187_812_8e-12,
             unit: Unit::new(
                 "F/m",
@@ -294,7 +355,8 @@ fn seed_all(map: &mut HashMap<String, Constant>) {
                     (Dimension::Length, -3),
                 ],
             ),
-            description: "Vacuum electric permittivity",
+            description: "Vacuum electric permittivity".into(),
+            quantity: None,
         },
     );
 
@@ -302,7 +364,7 @@ fn seed_all(map: &mut HashMap<String, Constant>) {
         map,
         &["magnetic_constant", "mu0", "µ0"],
         Constant {
-            name: "magnetic_constant",
+            name: "magnetic_constant".into(),
             value: 1.256_637_062_12e-6,
             unit: Unit::new(
                 "N/A²",
@@ -314,7 +376,8 @@ fn seed_all(map: &mut HashMap<String, Constant>) {
                     (Dimension::Current, -2),
                 ],
             ),
-            description: "Vacuum magnetic permeability",
+            description: "Vacuum magnetic permeability".into(),
+            quantity: None,
         },
     );
 
@@ -324,10 +387,11 @@ fn seed_all(map: &mut HashMap<String, Constant>) {
         map,
         &["electron_mass"],
         Constant {
-            name: "electron_mass",
+            name: "electron_mass".into(),
             value: 9.109_383_701_5e-31,
             unit: Unit::kilogram(),
-            description: "Electron mass",
+            description: "Electron mass".into(),
+            quantity: Some("Mass".into()),
         },
     );
 
@@ -335,10 +399,11 @@ fn seed_all(map: &mut HashMap<String, Constant>) {
         map,
         &["proton_mass"],
         Constant {
-            name: "proton_mass",
+            name: "proton_mass".into(),
             value: 1.672_621_923_69e-27,
             unit: Unit::kilogram(),
-            description: "Proton mass",
+            description: "Proton mass".into(),
+            quantity: Some("Mass".into()),
         },
     );
 
@@ -348,10 +413,11 @@ fn seed_all(map: &mut HashMap<String, Constant>) {
         map,
         &["fine_structure_constant", "alpha", "α"],
         Constant {
-            name: "fine_structure_constant",
+            name: "fine_structure_constant".into(),
             value: 7.297_352_569_3e-3,
             unit: Unit::dimensionless(),
-            description: "Fine-structure constant",
+            description: "Fine-structure constant".into(),
+            quantity: None,
         },
     );
 
@@ -359,10 +425,49 @@ fn seed_all(map: &mut HashMap<String, Constant>) {
         map,
         &["bohr_radius", "a0"],
         Constant {
-            name: "bohr_radius",
+            name: "bohr_radius".into(),
             value: 5.291_772_109_03e-11,
             unit: Unit::meter(),
-            description: "Bohr radius",
+            description: "Bohr radius".into(),
+            quantity: Some("Length".into()),
+        },
+    );
+
+    // ---- Mathematical ----
+
+    add(
+        map,
+        &["pi", "π"],
+        Constant {
+            name: "pi".into(),
+            value: std::f64::consts::PI,
+            unit: Unit::dimensionless(),
+            description: "Ratio of a circle's circumference to its diameter".into(),
+            quantity: None,
+        },
+    );
+
+    add(
+        map,
+        &["tau", "τ"],
+        Constant {
+            name: "tau".into(),
+            value: std::f64::consts::TAU,
+            unit: Unit::dimensionless(),
+            description: "One full turn in radians, 2π".into(),
+            quantity: None,
+        },
+    );
+
+    add(
+        map,
+        &["e"],
+        Constant {
+            name: "e".into(),
+            value: std::f64::consts::E,
+            unit: Unit::dimensionless(),
+            description: "Euler's number, the base of the natural logarithm".into(),
+            quantity: None,
         },
     );
 }
@@ -434,10 +539,11 @@ mod tests {
     }
 
     #[test]
-    fn all_unique_returns_15_constants() {
+    fn all_unique_returns_18_constants() {
         let db = ConstantsDatabase::new();
         let unique = db.all_unique();
-        assert_eq!(unique.len(), 15);
+        // 15 physical constants plus pi, tau and e.
+        assert_eq!(unique.len(), 18);
     }
 
     #[test]
@@ -447,6 +553,20 @@ mod tests {
         assert!(db.lookup("speed_of_light").is_some());
     }
 
+    #[test]
+    fn mathematical_constants_are_dimensionless() {
+        let db = ConstantsDatabase::new();
+        for (name, value) in [
+            ("pi", std::f64::consts::PI),
+            ("τ", std::f64::consts::TAU),
+            ("e", std::f64::consts::E),
+        ] {
+            let c = db.lookup(name).unwrap();
+            assert_eq!(c.value, value);
+            assert!(c.unit.dimensions.is_empty());
+        }
+    }
+
     #[test]
     fn unicode_aliases_work() {
         let db = ConstantsDatabase::new();
@@ -455,4 +575,11 @@ mod tests {
         assert!(db.lookup("µ0").is_some());
         assert!(db.lookup("α").is_some());
     }
+
+    #[test]
+    fn aliases_for_gravity() {
+        let db = ConstantsDatabase::new();
+        assert_eq!(db.aliases_for("gravity"), vec!["g0", "g_n"]);
+        assert!(db.aliases_for("xyzzy").is_empty());
+    }
 }
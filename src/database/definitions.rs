@@ -0,0 +1,1545 @@
+//! Declarative unit-definition files.
+//!
+//! A small, line-oriented format for describing units, constants and named
+//! dimensions outside the Rust seed table. Inspired by Numbat's module files
+//! (see `docs/roadmap.md`, Phase 5c), but deliberately *not* a language:
+//! every statement declares something, nothing executes.
+//!
+//! ```text
+//! # Comments run to the end of the line.
+//! include "astronomy"                   # relative to this file; ".units" optional
+//!
+//! dimension Torque = Force * Length
+//!
+//! unit meter: Length                    @prefixable @aliases(m, meters, metres)
+//! unit furlong: Length = 220 yard       @aliases(furlongs)
+//! unit solar_mass: Mass = 1.98847e30 kg @aliases(M_sun)
+//!
+//! table ringsize[mm]: Length = 3 14.05, 4 14.86, 5 15.70, 6 16.51
+//!
+//! const c_light: Velocity = 299792458 m/s
+//! const G_newton = 6.67430e-11 m^3 / (kg * s^2)  @description(Newtonian constant of gravitation)
+//!
+//! fn ke(m: Mass, v: Velocity): Energy = 0.5 * m * v^2
+//! ```
+//!
+//! ## Statements
+//!
+//! | Statement | Meaning |
+//! |---|---|
+//! | `include "path"` | read another file; relative to the including file, `.units` appended if the bare path doesn't exist |
+//! | `dimension Name = Expr` | name a dimension built from known quantity names with `*`, `/`, `^` |
+//! | `unit name[: Quantity] = expr` | linear unit defined by any source-side expression |
+//! | `unit name: Quantity` | base unit (factor 1) for a quantity — how the seed table's base units are expressed |
+//! | `table name[unit][: Quantity] = x y, x y, ...` | piecewise-linear [table unit](crate::units::table); `y` values are in `unit` (any expression, or a bare absolute temperature scale), or in base units when it is omitted |
+//! | `const name[: Quantity] = expr` | named constant |
+//! | `fn name(a[: Quantity], ...)[: Quantity] = expr` | [user-defined function](crate::functions); the body is checked for syntax only, and evaluated when called |
+//!
+//! Annotations follow the statement: `@aliases(a, b, ...)`, `@prefixable`
+//! (linear units only — accepts SI/binary prefixes, like [`Unit::new_si`]) and
+//! `@description(free text)` (constants only).
+//!
+//! Redefining a name that the databases already know (a builtin, or an
+//! earlier file) is an error unless the statement carries `@override`.
+//! Names a [tier](super::tiers) added on top of the builtins are the
+//! exception: once the tier is built, a file loaded over it replaces them
+//! with just a warning, so a `units.conf` defining `knot` loads the same in
+//! the REPL (standard tier) as in one-shot mode (builtin tier).
+//! Replacing a unit by its canonical name takes its other aliases along
+//! (`@override foot` repoints `ft` and `feet` too, unless the file defines
+//! them itself), and each replacement is reported in
+//! [`LoadSummary::warnings`] so the shadowing is never silent.
+//!
+//! The optional `: Quantity` annotation is checked: a unit declared as
+//! `Length` whose right-hand side evaluates to a mass is an error. A
+//! constant keeps its declared quantity, so a mismatch there is only a
+//! warning, which `runits check` reports as a constant-quantity problem. Quantity
+//! names come from `dimension` statements, the annotation registry
+//! ([`crate::annotations`], with `_` standing in for spaces, so
+//! `Electric_Charge` works) and the base [`Dimension`] names.
+//!
+//! ## Resolution
+//!
+//! Loading happens in two passes. The first pass reads every file
+//! (following `include`s, each file at most once) into a flat list of
+//! statements. The second pass resolves units and constants **on demand**:
+//! before a right-hand side is evaluated, every identifier it mentions that
+//! names a not-yet-resolved definition — directly or behind an SI/binary
+//! prefix — is resolved first, depth-first. Definitions may therefore appear
+//! in any order, across files. A definition that is reached again while it
+//! is still being resolved is a cycle, reported with the full chain
+//! (`a -> b -> a`).
+//!
+//! Right-hand sides are ordinary source-side expressions (the `expression`
+//! rule in `grammar.pest`) evaluated by [`crate::eval`] against the
+//! databases being built, so anything the CLI accepts — prefixes, `^`,
+//! parentheses, constants, math functions — works here too.
+//!
+//! Every error carries the file and line of the statement that caused it
+//! ([`RUnitsError::Definition`]). Loading stops at the first error;
+//! definitions resolved before the failure stay in the caller's databases,
+//! so callers that care should load into a scratch copy.
+
+use super::constants::{Constant, ConstantsDatabase};
+use super::tiers::{self, Tier};
+use super::{BINARY_PREFIXES, SI_PREFIXES, UnitDatabase};
+use crate::annotations;
+use crate::error::RUnitsError;
+use crate::eval::{EvalContext, eval};
+use crate::expr::{BinOp, Expr, parse_expression};
+use crate::functions::{Functions, define, parse_definition_with};
+use crate::units::Quantity;
+use crate::units::dimension::{Dimension, DimensionMap, create_dimensions};
+use crate::units::rational::Rational;
+use crate::units::table::PiecewiseTable;
+use crate::units::unit::{ConversionKind, Unit};
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+/// Extension appended to `include` targets that don't name an existing file.
+pub const FILE_EXTENSION: &str = "units";
+
+/// What a successful load added to the databases.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct LoadSummary {
+    /// Files read, including every `include`d file.
+    pub files: usize,
+    /// Units defined (each counted once, however many aliases it has).
+    pub units: usize,
+    /// Constants defined.
+    pub constants: usize,
+    /// Named dimensions declared.
+    pub dimensions: usize,
+    /// Non-fatal diagnostics (`file:line: message`), e.g. `@override`
+    /// shadowing an existing unit. Callers print these.
+    pub warnings: Vec<String>,
+    /// Every unit/constant name and alias the load registered.
+    pub defined: Vec<String>,
+    /// Functions declared by `fn` statements. They don't live in either
+    /// database, so callers install them (see [`crate::functions::set_global`]).
+    pub functions: Functions,
+}
+
+/// Load a definition file, plus everything it includes, into the given
+/// databases.
+pub fn load_file(
+    path: &Path,
+    units: &mut UnitDatabase,
+    constants: &mut ConstantsDatabase,
+) -> Result<LoadSummary, RUnitsError> {
+    let mut reader = Reader::default();
+    reader.read_file(path, None)?;
+    reader.resolve(units, constants)
+}
+
+/// Load definitions from an in-memory string. `label` stands in for the
+/// file name in error messages; relative `include`s resolve against the
+/// current directory.
+pub fn load_str(
+    source: &str,
+    label: &str,
+    units: &mut UnitDatabase,
+    constants: &mut ConstantsDatabase,
+) -> Result<LoadSummary, RUnitsError> {
+    let mut reader = Reader::default();
+    reader.read_source(source, label, None)?;
+    reader.resolve(units, constants)
+}
+
+// ---------------------------------------------------------------------------
+// Pass 1: reading files into statements.
+// ---------------------------------------------------------------------------
+
+/// Where a statement came from, for error reporting.
+#[derive(Debug, Clone)]
+struct Origin {
+    file: String,
+    line: usize,
+}
+
+impl Origin {
+    fn warning(&self, message: impl std::fmt::Display) -> String {
+        format!("{}:{}: {message}", self.file, self.line)
+    }
+
+    fn error(&self, reason: impl Into<String>) -> RUnitsError {
+        RUnitsError::Definition {
+            file: self.file.clone(),
+            line: self.line,
+            reason: reason.into(),
+        }
+    }
+}
+
+/// Annotations attached to a `unit` or `const` statement.
+#[derive(Debug, Clone, Default)]
+struct Attributes {
+    aliases: Vec<String>,
+    prefixable: bool,
+    description: Option<String>,
+    /// `@override`: allowed to shadow an existing unit or constant.
+    overrides: bool,
+}
+
+#[derive(Debug, Clone)]
+enum Statement {
+    Dimension {
+        name: String,
+        rhs: String,
+    },
+    Unit {
+        name: String,
+        quantity: Option<String>,
+        rhs: Option<String>,
+        attrs: Attributes,
+    },
+    Table {
+        name: String,
+        quantity: Option<String>,
+        /// The `[unit]` the `y` values are written in.
+        y_unit: Option<String>,
+        points: Vec<(f64, f64)>,
+        attrs: Attributes,
+    },
+    Const {
+        name: String,
+        quantity: Option<String>,
+        rhs: String,
+        attrs: Attributes,
+    },
+    /// `fn` statement; `text` is everything after the keyword, parsed once
+    /// every `dimension` is known.
+    Function {
+        name: String,
+        text: String,
+    },
+}
+
+impl Statement {
+    /// Canonical name plus aliases: every key this statement will register.
+    fn names(&self) -> Vec<&str> {
+        match self {
+            Statement::Dimension { name, .. } | Statement::Function { name, .. } => vec![name],
+            Statement::Unit { name, attrs, .. }
+            | Statement::Table { name, attrs, .. }
+            | Statement::Const { name, attrs, .. } => std::iter::once(name.as_str())
+                .chain(attrs.aliases.iter().map(String::as_str))
+                .collect(),
+        }
+    }
+}
+
+/// One meaningful line of a definition file.
+enum Line {
+    Include(String),
+    Statement(Statement),
+}
+
+/// Accumulates statements across a file and its includes.
+#[derive(Default)]
+struct Reader {
+    statements: Vec<(Statement, Origin)>,
+    /// Canonical paths already read — includes are read at most once, so a
+    /// "diamond" (two modules including the same base) is fine.
+    seen: HashSet<PathBuf>,
+    /// Canonical paths currently being read, for include-cycle detection.
+    stack: Vec<PathBuf>,
+    files: usize,
+}
+
+impl Reader {
+    fn read_file(&mut self, path: &Path, from: Option<&Origin>) -> Result<(), RUnitsError> {
+        let read_error = |e: std::io::Error| match from {
+            Some(origin) => origin.error(format!("cannot include '{}': {e}", path.display())),
+            None => RUnitsError::Definition {
+                file: path.display().to_string(),
+                line: 0,
+                reason: e.to_string(),
+            },
+        };
+        let canonical = path.canonicalize().map_err(read_error)?;
+        if let Some(pos) = self.stack.iter().position(|p| *p == canonical) {
+            let chain: Vec<String> = self.stack[pos..]
+                .iter()
+                .chain(std::iter::once(&canonical))
+                .map(|p| p.display().to_string())
+                .collect();
+            let origin = from.expect("a file on the stack can only be re-entered via include");
+            return Err(origin.error(format!("include cycle: {}", chain.join(" -> "))));
+        }
+        if !self.seen.insert(canonical.clone()) {
+            return Ok(());
+        }
+        let source = std::fs::read_to_string(path).map_err(read_error)?;
+        self.stack.push(canonical);
+        self.read_source(&source, &path.display().to_string(), path.parent())?;
+        self.stack.pop();
+        Ok(())
+    }
+
+    fn read_source(
+        &mut self,
+        source: &str,
+        label: &str,
+        dir: Option<&Path>,
+    ) -> Result<(), RUnitsError> {
+        self.files += 1;
+        for (index, raw) in source.lines().enumerate() {
+            let origin = Origin {
+                file: label.to_string(),
+                line: index + 1,
+            };
+            match parse_line(raw).map_err(|reason| origin.error(reason))? {
+                None => {}
+                Some(Line::Include(target)) => {
+                    let path = include_path(dir, &target);
+                    self.read_file(&path, Some(&origin))?;
+                }
+                Some(Line::Statement(statement)) => self.statements.push((statement, origin)),
+            }
+        }
+        Ok(())
+    }
+
+    fn resolve(
+        self,
+        units: &mut UnitDatabase,
+        constants: &mut ConstantsDatabase,
+    ) -> Result<LoadSummary, RUnitsError> {
+        let mut resolver = Resolver::new(units, constants);
+        resolver.summary.files = self.files;
+        resolver.run(self.statements)?;
+        Ok(resolver.summary)
+    }
+}
+
+/// Resolve an `include` target relative to the including file's directory,
+/// appending the default extension when the bare path doesn't exist.
+fn include_path(dir: Option<&Path>, target: &str) -> PathBuf {
+    let path = match dir {
+        Some(dir) => dir.join(target),
+        None => PathBuf::from(target),
+    };
+    if path.extension().is_none() && !path.exists() {
+        path.with_extension(FILE_EXTENSION)
+    } else {
+        path
+    }
+}
+
+/// Parse one raw line. `Ok(None)` for blank and comment-only lines; `Err`
+/// carries the reason (the caller attaches file and line).
+fn parse_line(raw: &str) -> Result<Option<Line>, String> {
+    let text = strip_comment(raw).trim();
+    if text.is_empty() {
+        return Ok(None);
+    }
+    let (body, attrs) = match text.find('@') {
+        Some(at) => (text[..at].trim(), parse_attributes(&text[at..])?),
+        None => (text, Attributes::default()),
+    };
+    let (keyword, rest) = body
+        .split_once(char::is_whitespace)
+        .map(|(k, r)| (k, r.trim()))
+        .unwrap_or((body, ""));
+
+    match keyword {
+        "include" => {
+            let target = rest
+                .strip_prefix('"')
+                .and_then(|r| r.strip_suffix('"'))
+                .filter(|t| !t.is_empty())
+                .ok_or_else(|| format!("expected a quoted path after 'include', found '{rest}'"))?;
+            Ok(Some(Line::Include(target.to_string())))
+        }
+        "dimension" => {
+            let (name, rhs) = rest
+                .split_once('=')
+                .ok_or_else(|| format!("expected 'dimension {rest} = <expression>'"))?;
+            let name = checked_identifier(name.trim(), "dimension")?;
+            Ok(Some(Line::Statement(Statement::Dimension {
+                name,
+                rhs: non_empty_rhs(rhs)?,
+            })))
+        }
+        "unit" => {
+            let (name, quantity, rhs) = parse_declaration(rest, "unit")?;
+            if attrs.description.is_some() {
+                return Err("@description only applies to constants".to_string());
+            }
+            Ok(Some(Line::Statement(Statement::Unit {
+                name,
+                quantity,
+                rhs,
+                attrs,
+            })))
+        }
+        "table" => {
+            let (head, rhs) = parse_declaration_head(rest);
+            let (head, y_unit) = match head.split_once('[') {
+                Some((name, tail)) => {
+                    let (unit, after) = tail
+                        .split_once(']')
+                        .ok_or_else(|| format!("unclosed '[' in table '{}'", name.trim()))?;
+                    let unit = non_empty_rhs(unit)
+                        .map_err(|_| format!("missing unit in '{}[]'", name.trim()))?;
+                    (format!("{name}{after}"), Some(unit))
+                }
+                None => (head.to_string(), None),
+            };
+            let (name, quantity, _) = parse_declaration(&head, "table")?;
+            if attrs.prefixable || attrs.description.is_some() {
+                return Err("tables only accept @aliases and @override".to_string());
+            }
+            let rhs =
+                rhs.ok_or_else(|| format!("table '{name}' needs points ('= x y, x y, ...')"))?;
+            Ok(Some(Line::Statement(Statement::Table {
+                name,
+                quantity,
+                y_unit,
+                points: parse_points(rhs)?,
+                attrs,
+            })))
+        }
+        "const" => {
+            let (name, quantity, rhs) = parse_declaration(rest, "const")?;
+            if attrs.prefixable {
+                return Err("@prefixable only applies to units".to_string());
+            }
+            let rhs =
+                rhs.ok_or_else(|| format!("constant '{name}' needs a value ('= <expression>')"))?;
+            Ok(Some(Line::Statement(Statement::Const {
+                name,
+                quantity,
+                rhs,
+                attrs,
+            })))
+        }
+        "fn" => {
+            let (name, _) = rest
+                .split_once('(')
+                .filter(|(_, tail)| tail.contains('='))
+                .ok_or_else(|| "expected 'fn name(<parameters>) = <expression>'".to_string())?;
+            let name = checked_identifier(name.trim(), "function")?;
+            if !attrs.aliases.is_empty()
+                || attrs.prefixable
+                || attrs.overrides
+                || attrs.description.is_some()
+            {
+                return Err("functions take no annotations".to_string());
+            }
+            Ok(Some(Line::Statement(Statement::Function {
+                name,
+                text: rest.to_string(),
+            })))
+        }
+        other => Err(format!(
+            "unknown statement '{other}' (expected include, dimension, unit, table, const or fn)"
+        )),
+    }
+}
+
+/// Everything before the first `#` that isn't inside a quoted string.
+fn strip_comment(raw: &str) -> &str {
+    let mut in_quotes = false;
+    for (i, c) in raw.char_indices() {
+        match c {
+            '"' => in_quotes = !in_quotes,
+            '#' if !in_quotes => return &raw[..i],
+            _ => {}
+        }
+    }
+    raw
+}
+
+/// Split `name[: Quantity] [= rhs]`.
+fn parse_declaration(
+    text: &str,
+    keyword: &str,
+) -> Result<(String, Option<String>, Option<String>), String> {
+    let (head, rhs) = match text.split_once('=') {
+        Some((head, rhs)) => (head, Some(non_empty_rhs(rhs)?)),
+        None => (text, None),
+    };
+    let (name, quantity) = match head.split_once(':') {
+        Some((name, quantity)) => (name, Some(checked_identifier(quantity.trim(), "quantity")?)),
+        None => (head, None),
+    };
+    Ok((checked_identifier(name.trim(), keyword)?, quantity, rhs))
+}
+
+/// Split `head [= rhs]` without interpreting either side.
+fn parse_declaration_head(text: &str) -> (&str, Option<&str>) {
+    match text.split_once('=') {
+        Some((head, rhs)) => (head, Some(rhs)),
+        None => (text, None),
+    }
+}
+
+/// Parse a table's `x y, x y, ...` point list.
+fn parse_points(text: &str) -> Result<Vec<(f64, f64)>, String> {
+    text.split(',')
+        .map(|pair| {
+            let numbers: Vec<f64> = pair
+                .split_whitespace()
+                .map(|n| {
+                    n.parse()
+                        .map_err(|_| format!("invalid number '{n}' in table"))
+                })
+                .collect::<Result<_, _>>()?;
+            match numbers[..] {
+                [x, y] => Ok((x, y)),
+                _ => Err(format!(
+                    "expected an 'x y' pair in table, found '{}'",
+                    pair.trim()
+                )),
+            }
+        })
+        .collect()
+}
+
+fn non_empty_rhs(rhs: &str) -> Result<String, String> {
+    let rhs = rhs.trim();
+    if rhs.is_empty() {
+        Err("missing expression after '='".to_string())
+    } else {
+        Ok(rhs.to_string())
+    }
+}
+
+/// Names follow the expression grammar's `ident` rule (a letter, `µ` or `°`,
+/// then alphanumerics and `_`) so that every defined name can be typed back.
+fn checked_identifier(name: &str, what: &str) -> Result<String, String> {
+    if is_identifier(name) {
+        Ok(name.to_string())
+    } else if name.is_empty() {
+        Err(format!("missing {what} name"))
+    } else {
+        Err(format!("invalid {what} name '{name}'"))
+    }
+}
+
+/// Whether `name` matches the expression grammar's `ident` rule.
+pub(crate) fn is_identifier(name: &str) -> bool {
+    let mut chars = name.chars();
+    let valid_start = chars
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == 'µ' || c == '°');
+    valid_start && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+/// Parse a run of `@key` / `@key(args)` annotations.
+fn parse_attributes(text: &str) -> Result<Attributes, String> {
+    let mut attrs = Attributes::default();
+    let mut rest = text.trim();
+    while !rest.is_empty() {
+        let after_at = rest
+            .strip_prefix('@')
+            .ok_or_else(|| format!("unexpected text '{rest}' (annotations start with '@')"))?;
+        let key_len = after_at
+            .find(|c: char| !(c.is_ascii_alphanumeric() || c == '_'))
+            .unwrap_or(after_at.len());
+        let key = &after_at[..key_len];
+        let mut tail = after_at[key_len..].trim_start();
+        let mut args = None;
+        if let Some(open) = tail.strip_prefix('(') {
+            let close = open
+                .find(')')
+                .ok_or_else(|| format!("unclosed '(' in @{key}"))?;
+            args = Some(open[..close].trim());
+            tail = open[close + 1..].trim_start();
+        }
+        match (key, args) {
+            ("aliases", Some(list)) => {
+                for alias in list.split(',').map(str::trim) {
+                    if alias.is_empty() || alias.contains(char::is_whitespace) {
+                        return Err(format!("invalid alias '{alias}' in @aliases"));
+                    }
+                    attrs.aliases.push(alias.to_string());
+                }
+            }
+            ("prefixable", None) => attrs.prefixable = true,
+            ("override", None) => attrs.overrides = true,
+            ("description", Some(text)) => attrs.description = Some(text.to_string()),
+            ("aliases" | "description", None) => {
+                return Err(format!("@{key} needs an argument list: @{key}(...)"));
+            }
+            ("prefixable" | "override", Some(_)) => {
+                return Err(format!("@{key} takes no arguments"));
+            }
+            (other, _) => return Err(format!("unknown annotation '@{other}'")),
+        }
+        rest = tail;
+    }
+    Ok(attrs)
+}
+
+// ---------------------------------------------------------------------------
+// Pass 2: resolving statements into database entries.
+// ---------------------------------------------------------------------------
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum State {
+    Pending,
+    InProgress,
+    Done,
+}
+
+struct Resolver<'a> {
+    units: &'a mut UnitDatabase,
+    constants: &'a mut ConstantsDatabase,
+    /// `unit` and `const` statements, in file order.
+    definitions: Vec<(Statement, Origin)>,
+    /// Every name and alias → index into `definitions`.
+    by_name: HashMap<String, usize>,
+    state: Vec<State>,
+    /// Definitions currently being resolved, innermost last.
+    stack: Vec<usize>,
+    /// Quantity names declared by `dimension` statements.
+    dimensions: HashMap<String, DimensionMap>,
+    /// Aliases of a unit a definition replaces, by definition index: they
+    /// follow the replacement once it is defined.
+    repoint: HashMap<usize, Vec<String>>,
+    summary: LoadSummary,
+}
+
+impl<'a> Resolver<'a> {
+    fn new(units: &'a mut UnitDatabase, constants: &'a mut ConstantsDatabase) -> Self {
+        Resolver {
+            units,
+            constants,
+            definitions: Vec::new(),
+            by_name: HashMap::new(),
+            state: Vec::new(),
+            stack: Vec::new(),
+            dimensions: HashMap::new(),
+            repoint: HashMap::new(),
+            summary: LoadSummary::default(),
+        }
+    }
+
+    fn run(&mut self, statements: Vec<(Statement, Origin)>) -> Result<(), RUnitsError> {
+        // Dimensions first, in file order: they only ever refer to quantity
+        // names, never to units, so they need no dependency ordering.
+        let mut functions = Vec::new();
+        let mut seen_at: HashMap<String, Origin> = HashMap::new();
+        for (statement, origin) in statements {
+            for name in statement.names() {
+                if let Some(previous) = seen_at.get(name) {
+                    return Err(origin.error(format!(
+                        "'{name}' is already defined at {}:{}",
+                        previous.file, previous.line
+                    )));
+                }
+                seen_at.insert(name.to_string(), origin.clone());
+            }
+            match statement {
+                Statement::Dimension { name, rhs } => {
+                    self.declare_dimension(name, &rhs, &origin)?
+                }
+                Statement::Function { text, .. } => functions.push((text, origin)),
+                other => {
+                    for name in other.names() {
+                        self.check_shadowing(&other, name, &origin)?;
+                        self.by_name
+                            .insert(name.to_string(), self.definitions.len());
+                    }
+                    self.definitions.push((other, origin));
+                    self.state.push(State::Pending);
+                }
+            }
+        }
+        for index in 0..self.definitions.len() {
+            self.resolve(index)?;
+        }
+        // Function bodies are only evaluated when called; annotations may
+        // name any dimension the files declare.
+        for (text, origin) in functions {
+            let function = parse_definition_with(&text, &|q| self.quantity_dims(q))
+                .expect("parse_line checked the shape of 'fn' statements")
+                .map_err(|e| origin.error(e.to_string()))?;
+            define(&mut self.summary.functions, function, self.units)
+                .map_err(|e| origin.error(e.to_string()))?;
+        }
+        Ok(())
+    }
+
+    /// Reject (or, under `@override`, warn about) a name that the databases
+    /// already resolve.
+    fn check_shadowing(
+        &mut self,
+        statement: &Statement,
+        name: &str,
+        origin: &Origin,
+    ) -> Result<(), RUnitsError> {
+        let (kind, overrides) = match statement {
+            Statement::Unit { attrs, .. } | Statement::Table { attrs, .. } => {
+                ("unit", attrs.overrides)
+            }
+            Statement::Const { attrs, .. } => ("constant", attrs.overrides),
+            Statement::Dimension { .. } | Statement::Function { .. } => return Ok(()),
+        };
+        let existing = if self.units.contains(name) {
+            "unit"
+        } else if self.constants.lookup(name).is_some() {
+            "constant"
+        } else {
+            return Ok(());
+        };
+        // Replacing a unit by its canonical name takes its other aliases
+        // along, so `ft` can't keep resolving to the old `foot`.
+        let followers: Vec<String> = if kind == "unit" && existing == "unit" {
+            let own = statement.names();
+            self.units
+                .aliases_of(name)
+                .into_iter()
+                .filter(|alias| !own.contains(&alias.as_str()))
+                .collect()
+        } else {
+            Vec::new()
+        };
+        let follow_note = if followers.is_empty() {
+            String::new()
+        } else {
+            format!(" (so do its aliases {})", followers.join(", "))
+        };
+        let tier = self.units.tier();
+        if tier != Tier::Builtin && !tiers::is_seeded(name) {
+            // A tier module (or GNU import) defined it: the user's file
+            // wins, whatever kind either definition is.
+            match existing {
+                "unit" => self.units.remove(name),
+                _ => self.constants.remove(name),
+            }
+            self.summary.warnings.push(origin.warning(format!(
+                "{kind} '{name}' replaces the {tier} tier's {existing}{follow_note}"
+            )));
+            self.repoint
+                .entry(self.definitions.len())
+                .or_default()
+                .extend(followers);
+            return Ok(());
+        }
+        if !overrides {
+            return Err(origin.error(format!(
+                "'{name}' is already defined as a {existing} (add @override to replace it)"
+            )));
+        }
+        if kind == "constant" && existing == "unit" {
+            // Identifiers resolve as units before constants, so this
+            // override could never take effect.
+            return Err(origin.error(format!(
+                "constant '{name}' cannot override a unit: units take precedence in expressions"
+            )));
+        }
+        self.summary.warnings.push(origin.warning(format!(
+            "{kind} '{name}' overrides an existing {existing}{follow_note}"
+        )));
+        self.repoint
+            .entry(self.definitions.len())
+            .or_default()
+            .extend(followers);
+        Ok(())
+    }
+
+    fn declare_dimension(
+        &mut self,
+        name: String,
+        rhs: &str,
+        origin: &Origin,
+    ) -> Result<(), RUnitsError> {
+        if self.quantity_dims(&name).is_some() {
+            return Err(origin.error(format!("quantity '{name}' is already defined")));
+        }
+        let expr = parse_expression(rhs)
+            .map_err(|e| origin.error(format!("invalid dimension expression '{rhs}': {e}")))?;
+        let dims = self
+            .dimension_of(&expr)
+            .map_err(|reason| origin.error(reason))?;
+        self.dimensions.insert(name, dims);
+        self.summary.dimensions += 1;
+        Ok(())
+    }
+
+    /// Evaluate a dimension expression (`Force * Length`, `Energy / Amount`,
+    /// `Length^3`, `1 / Time`) to its dimension map.
+    fn dimension_of(&self, expr: &Expr) -> Result<DimensionMap, String> {
+        let combine = |lhs: &Expr, rhs: &Expr, sign: i32| -> Result<DimensionMap, String> {
+            let mut dims = self.dimension_of(lhs)?;
+            for (dim, exp) in self.dimension_of(rhs)? {
+                let entry = dims.entry(dim).or_insert(Rational::ZERO);
+                *entry = entry
+                    .checked_add(Rational::from(sign) * exp)
+                    .ok_or("dimension exponent overflow")?;
+            }
+            dims.retain(|_, exp| !exp.is_zero());
+            Ok(dims)
+        };
+        match expr {
+            Expr::Ident(name) => self
+                .quantity_dims(name)
+                .ok_or_else(|| format!("unknown quantity '{name}'")),
+            Expr::Number(n) if *n == 1.0 => Ok(DimensionMap::new()),
+            Expr::BinOp(BinOp::Mul, lhs, rhs) => combine(lhs, rhs, 1),
+            Expr::BinOp(BinOp::Div, lhs, rhs) => combine(lhs, rhs, -1),
+            Expr::Pow(base, n) => {
+                let mut dims = crate::units::unit::scale_dimensions(&self.dimension_of(base)?, *n)
+                    .ok_or("dimension exponent overflow")?;
+                dims.retain(|_, exp| !exp.is_zero());
+                Ok(dims)
+            }
+            _ => Err(
+                "dimension expressions may only combine quantity names with '*', '/' and '^'"
+                    .to_string(),
+            ),
+        }
+    }
+
+    /// Resolve a quantity name: file-declared dimensions, then the
+    /// annotation registry (`_` for spaces), then base dimension names.
+    fn quantity_dims(&self, name: &str) -> Option<DimensionMap> {
+        if let Some(dims) = self.dimensions.get(name) {
+            return Some(dims.clone());
+        }
+        quantity_dimensions(name)
+    }
+
+    fn resolve(&mut self, index: usize) -> Result<(), RUnitsError> {
+        match self.state[index] {
+            State::Done => return Ok(()),
+            State::InProgress => return Err(self.cycle_error(index)),
+            State::Pending => {}
+        }
+        self.state[index] = State::InProgress;
+        self.stack.push(index);
+        self.define(index)?;
+        self.stack.pop();
+        self.state[index] = State::Done;
+        Ok(())
+    }
+
+    /// Register a defined unit, pointing the aliases of any unit it
+    /// replaced at it too — except those this file defines itself.
+    fn install_unit(&mut self, index: usize, unit: Unit, aliases: &[String]) {
+        let followers: Vec<String> = self
+            .repoint
+            .remove(&index)
+            .unwrap_or_default()
+            .into_iter()
+            .filter(|alias| !self.by_name.contains_key(alias))
+            .collect();
+        self.units.insert(unit.clone(), aliases);
+        self.units.insert(unit, &followers);
+    }
+
+    fn cycle_error(&self, index: usize) -> RUnitsError {
+        let start = self
+            .stack
+            .iter()
+            .position(|&i| i == index)
+            .expect("an in-progress definition is on the stack");
+        let chain: Vec<&str> = self.stack[start..]
+            .iter()
+            .chain(std::iter::once(&index))
+            .map(|&i| self.definitions[i].0.names()[0])
+            .collect();
+        // Report at the definition whose right-hand side closes the loop.
+        let closing = *self.stack.last().expect("stack is non-empty");
+        self.definitions[closing]
+            .1
+            .error(format!("definition cycle: {}", chain.join(" -> ")))
+    }
+
+    fn define(&mut self, index: usize) -> Result<(), RUnitsError> {
+        let (statement, origin) = self.definitions[index].clone();
+        match statement {
+            Statement::Unit {
+                name,
+                quantity,
+                rhs,
+                attrs,
+            } => {
+                let declared = self.declared_dims(quantity.as_deref(), &origin)?;
+                let (factor, dimensions, temperature_delta) = match rhs {
+                    Some(rhs) => {
+                        let q = self.evaluate(&name, &rhs, &origin)?;
+                        (
+                            q.unit.to_base_value(q.value),
+                            q.unit.dimensions,
+                            q.unit.temperature_delta,
+                        )
+                    }
+                    None => {
+                        let dims = declared.clone().ok_or_else(|| {
+                            origin.error(format!(
+                                "base unit '{name}' needs a quantity (e.g. 'unit {name}: Length')"
+                            ))
+                        })?;
+                        (1.0, dims, false)
+                    }
+                };
+                if !factor.is_finite() || factor <= 0.0 {
+                    return Err(origin.error(format!(
+                        "unit '{name}' must be a positive, finite multiple of its base unit (got {factor})"
+                    )));
+                }
+                let unit = Unit {
+                    name,
+                    conversion: ConversionKind::Linear(factor),
+                    dimensions,
+                    prefixable: attrs.prefixable,
+                    temperature_delta,
+                };
+                check_declared(&unit, quantity.as_deref(), declared.as_ref(), &origin)?;
+                self.summary.defined.push(unit.name.clone());
+                self.summary.defined.extend(attrs.aliases.iter().cloned());
+                self.install_unit(index, unit, &attrs.aliases);
+                self.summary.units += 1;
+            }
+            Statement::Table {
+                name,
+                quantity,
+                y_unit,
+                points,
+                attrs,
+            } => {
+                let declared = self.declared_dims(quantity.as_deref(), &origin)?;
+                let (points, dimensions) = match y_unit {
+                    Some(y_unit) => {
+                        let q = self.evaluate_any(&y_unit, &origin)?;
+                        let points = match q.unit.conversion {
+                            ConversionKind::Linear(_) => {
+                                let factor = q.unit.to_base_value(q.value);
+                                points.iter().map(|&(x, y)| (x, y * factor)).collect()
+                            }
+                            // A bare scale (`[degF]`): the values are readings on it.
+                            ConversionKind::Affine { .. } if q.value == 1.0 => points
+                                .iter()
+                                .map(|&(x, y)| (x, q.unit.to_base_value(y)))
+                                .collect(),
+                            _ => {
+                                return Err(origin.error(format!(
+                                    "table '{name}' values must be in a linear unit or a bare temperature scale (got '{y_unit}')"
+                                )));
+                            }
+                        };
+                        (points, q.unit.dimensions)
+                    }
+                    None => {
+                        let dims = declared.clone().ok_or_else(|| {
+                            origin.error(format!(
+                                "table '{name}' needs a unit for its values (e.g. 'table {name}[mm]') or a quantity"
+                            ))
+                        })?;
+                        (points, dims)
+                    }
+                };
+                let table = PiecewiseTable::new(points)
+                    .map_err(|reason| origin.error(format!("table '{name}': {reason}")))?;
+                let unit = Unit {
+                    name,
+                    conversion: ConversionKind::Table(Arc::new(table)),
+                    dimensions,
+                    prefixable: false,
+                    temperature_delta: false,
+                };
+                check_declared(&unit, quantity.as_deref(), declared.as_ref(), &origin)?;
+                self.summary.defined.push(unit.name.clone());
+                self.summary.defined.extend(attrs.aliases.iter().cloned());
+                self.install_unit(index, unit, &attrs.aliases);
+                self.summary.units += 1;
+            }
+            Statement::Const {
+                name,
+                quantity,
+                rhs,
+                attrs,
+            } => {
+                let declared = self.declared_dims(quantity.as_deref(), &origin)?;
+                let q = self.evaluate(&name, &rhs, &origin)?;
+                // A constant keeps a global quantity with its value, so a
+                // mismatch is left for `runits check` to report as a
+                // constant-quantity problem; here it only warns.
+                let global = quantity
+                    .as_deref()
+                    .is_some_and(|q| quantity_dimensions(q).is_some());
+                match check_declared(&q.unit, quantity.as_deref(), declared.as_ref(), &origin) {
+                    Err(RUnitsError::Definition { reason, .. }) if global => self
+                        .summary
+                        .warnings
+                        .push(origin.warning(format!("constant '{name}' is {reason}"))),
+                    other => other?,
+                }
+                let constant = Constant {
+                    name,
+                    value: q.value,
+                    unit: q.unit,
+                    description: attrs.description.unwrap_or_default(),
+                    // Quantities named by this file's `dimension` statements
+                    // mean nothing outside it, so only global names are kept.
+                    quantity: quantity.filter(|q| quantity_dimensions(q).is_some()),
+                };
+                self.summary.defined.push(constant.name.clone());
+                self.summary.defined.extend(attrs.aliases.iter().cloned());
+                self.constants.insert(constant, &attrs.aliases);
+                self.summary.constants += 1;
+            }
+            Statement::Dimension { .. } | Statement::Function { .. } => {
+                unreachable!("dimensions and functions are declared in run()")
+            }
+        }
+        Ok(())
+    }
+
+    fn declared_dims(
+        &self,
+        quantity: Option<&str>,
+        origin: &Origin,
+    ) -> Result<Option<DimensionMap>, RUnitsError> {
+        quantity
+            .map(|q| {
+                self.quantity_dims(q)
+                    .ok_or_else(|| origin.error(format!("unknown quantity '{q}'")))
+            })
+            .transpose()
+    }
+
+    /// [`evaluate_any`](Self::evaluate_any), requiring a linear result.
+    fn evaluate(
+        &mut self,
+        name: &str,
+        rhs: &str,
+        origin: &Origin,
+    ) -> Result<Quantity, RUnitsError> {
+        let q = self.evaluate_any(rhs, origin)?;
+        if q.unit.is_affine() {
+            return Err(origin.error(format!(
+                "'{name}' must be defined by a linear expression ('{}' is an absolute temperature scale)",
+                q.unit.name
+            )));
+        }
+        if q.unit.is_logarithmic() {
+            return Err(origin.error(format!(
+                "'{name}' must be defined by a linear expression ('{}' is a logarithmic level)",
+                q.unit.name
+            )));
+        }
+        if q.unit.is_nonlinear() {
+            return Err(origin.error(format!(
+                "'{name}' must be defined by a linear expression ('{}' is a function unit; call it instead)",
+                q.unit.name
+            )));
+        }
+        Ok(q)
+    }
+
+    /// Resolve the definitions `rhs` depends on, then evaluate it.
+    fn evaluate_any(&mut self, rhs: &str, origin: &Origin) -> Result<Quantity, RUnitsError> {
+        let expr = parse_expression(rhs)
+            .map_err(|e| origin.error(format!("invalid expression '{rhs}': {e}")))?;
+        let mut identifiers = Vec::new();
+        collect_identifiers(&expr, &mut identifiers);
+        for ident in identifiers {
+            if let Some(dependency) = self.definition_for(ident) {
+                self.resolve(dependency)?;
+            }
+        }
+        let ctx = EvalContext::with_previous(self.units, self.constants, None);
+        eval(&expr, &ctx).map_err(|e| origin.error(e.to_string()))
+    }
+
+    /// The pending definition an identifier refers to, if any: a direct
+    /// name/alias, or a prefix in front of one (`kfurlong`). Names the
+    /// databases already resolve are left alone so that a prefix reading
+    /// never invents a dependency on top of an existing unit.
+    fn definition_for(&self, ident: &str) -> Option<usize> {
+        if let Some(&index) = self.by_name.get(ident) {
+            return Some(index);
+        }
+        if self.units.lookup(ident).is_some() || self.constants.lookup(ident).is_some() {
+            return None;
+        }
+        SI_PREFIXES
+            .iter()
+            .chain(BINARY_PREFIXES)
+            .flat_map(|&(long, short, _)| [long, short])
+            .filter_map(|prefix| ident.strip_prefix(prefix))
+            .find_map(|rest| self.by_name.get(rest).copied())
+    }
+}
+
+/// Resolve a globally known quantity name: the annotation registry (`_`
+/// standing in for spaces), then the base [`Dimension`] names.
+pub(crate) fn quantity_dimensions(name: &str) -> Option<DimensionMap> {
+    annotations::dimensions_for_name(&name.replace('_', " ")).or_else(|| {
+        Dimension::ALL
+            .iter()
+            .find(|d| d.name().eq_ignore_ascii_case(name))
+            .map(|d| create_dimensions(&[(d.clone(), 1)]))
+    })
+}
+
+/// Compare a definition's evaluated dimensions against its `: Quantity`.
+fn check_declared(
+    unit: &Unit,
+    quantity: Option<&str>,
+    declared: Option<&DimensionMap>,
+    origin: &Origin,
+) -> Result<(), RUnitsError> {
+    match (quantity, declared) {
+        (Some(quantity), Some(expected)) if *expected != unit.dimensions => {
+            Err(origin.error(format!(
+                "declared as {quantity} but the definition has dimensions {}",
+                unit.dimension_string()
+            )))
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Every identifier an expression mentions, in source order.
+fn collect_identifiers<'e>(expr: &'e Expr, out: &mut Vec<&'e str>) {
+    match expr {
+        Expr::Number(_) | Expr::Previous | Expr::ChemCall(..) => {}
+        Expr::Ident(name) => out.push(name),
+        Expr::BinOp(_, lhs, rhs) => {
+            collect_identifiers(lhs, out);
+            collect_identifiers(rhs, out);
+        }
+        Expr::Neg(inner) | Expr::Pow(inner, _) | Expr::InverseCall(_, inner) => {
+            collect_identifiers(inner, out)
+        }
+        Expr::FuncCall(_, args) => {
+            for arg in args {
+                collect_identifiers(arg, out);
+            }
+        }
+        Expr::Mixed(parts) => {
+            for (_, unit) in parts {
+                collect_identifiers(unit, out);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Load `source` on top of the builtin databases.
+    fn load(source: &str) -> Result<(UnitDatabase, ConstantsDatabase, LoadSummary), RUnitsError> {
+        let mut units = UnitDatabase::new();
+        let mut constants = ConstantsDatabase::new();
+        let summary = load_str(source, "test.units", &mut units, &mut constants)?;
+        Ok((units, constants, summary))
+    }
+
+    fn load_err(source: &str) -> String {
+        match load(source) {
+            Ok(_) => panic!("expected a load error for:\n{source}"),
+            Err(e) => e.to_string(),
+        }
+    }
+
+    /// A scratch directory under the system temp dir, unique per test.
+    fn scratch_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("runits-defs-{}-{name}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    // ---- Statements ----
+
+    #[test]
+    fn linear_unit_with_aliases() {
+        let (units, _, summary) =
+            load("unit smoot: Length = 67 inch @aliases(smoots, Sm)").unwrap();
+        assert_eq!(summary.units, 1);
+        let u = units.lookup("Sm").unwrap();
+        assert_eq!(u.name, "smoot");
+        assert!((u.conversion_factor() - 67.0 * 0.0254).abs() < 1e-12);
+        assert!(units.lookup("smoots").is_some());
+    }
+
+    #[test]
+    fn base_units_express_the_seed_table() {
+        // A self-contained file: base units + derived units, no builtins.
+        let source = "
+            unit meter: Length   @prefixable @aliases(m)
+            unit second: Time    @prefixable @aliases(s)
+            unit kilogram: Mass  @aliases(kg)
+            unit newton: Force = kg m / s^2  @prefixable @aliases(N)
+            unit foot = 0.3048 m @aliases(ft)
+        ";
+        let mut units = UnitDatabase::empty();
+        let mut constants = ConstantsDatabase::empty();
+        load_str(source, "seed.units", &mut units, &mut constants).unwrap();
+
+        let builtin = UnitDatabase::new();
+        for name in ["m", "s", "kg", "N", "ft", "kN", "ms"] {
+            let ours = units.lookup(name).unwrap();
+            let theirs = builtin.lookup(name).unwrap();
+            assert!((ours.conversion_factor() - theirs.conversion_factor()).abs() < 1e-12);
+            assert_eq!(ours.dimensions, theirs.dimensions, "{name}");
+        }
+    }
+
+    #[test]
+    fn prefixable_annotation_enables_prefixes() {
+        let (units, _, _) =
+            load("unit gauss2: Magnetic_Flux_Density = 1e-4 kg / (A s^2) @prefixable").unwrap();
+        let u = units.lookup("mgauss2").unwrap();
+        assert!((u.conversion_factor() - 1e-7).abs() < 1e-18);
+    }
+
+    #[test]
+    fn constants_are_defined_and_usable() {
+        let source = "
+            const g_mars: Acceleration = 3.72076 m/s^2 @aliases(g_M) @description(Mars surface gravity)
+            unit mars_weight_kg = 1 kg * g_mars
+        ";
+        let (units, constants, summary) = load(source).unwrap();
+        assert_eq!(summary.constants, 1);
+        let c = constants.lookup("g_M").unwrap();
+        assert_eq!(c.name, "g_mars");
+        assert_eq!(c.description, "Mars surface gravity");
+        let u = units.lookup("mars_weight_kg").unwrap();
+        assert!((u.conversion_factor() - 3.72076).abs() < 1e-9);
+        assert_eq!(u.dimension_string(), "Length*Mass*Time^-2");
+    }
+
+    #[test]
+    fn dimension_declaration_is_usable_as_annotation() {
+        let source = "
+            dimension Torque = Force * Length
+            dimension MolarEnthalpy = Energy / Amount
+            unit foot_pound: Torque = 1 ft * 1 lbf
+            unit kJ_per_mol: MolarEnthalpy = 1000 J / mol
+        ";
+        let (units, _, summary) = load(source).unwrap();
+        assert_eq!(summary.dimensions, 2);
+        assert!(units.lookup("foot_pound").is_some());
+        assert!(units.lookup("kJ_per_mol").is_some());
+    }
+
+    #[test]
+    fn comments_and_blank_lines_are_ignored() {
+        let (_, _, summary) =
+            load("# header\n\n   # indented\nunit x1: Length = 2 m # trailing\n").unwrap();
+        assert_eq!(summary.units, 1);
+    }
+
+    // ---- Tables ----
+
+    #[test]
+    fn table_unit_interpolates_in_its_value_unit() {
+        let source = "table bolt[mm]: Length = 2 1.6, 3 2.5, 4 3.3 @aliases(M_bolt)";
+        let (units, _, summary) = load(source).unwrap();
+        assert_eq!(summary.units, 1);
+        let u = units.lookup("M_bolt").unwrap();
+        assert_eq!(u.name, "bolt");
+        assert!(u.is_nonlinear());
+        assert!((u.try_to_base_value(2.5).unwrap() - 0.00205).abs() < 1e-12);
+        assert!((u.try_from_base_value(0.0029).unwrap() - 3.5).abs() < 1e-9);
+        assert!(u.try_to_base_value(5.0).is_err());
+    }
+
+    #[test]
+    fn table_values_on_a_temperature_scale() {
+        let (units, _, _) = load("table oven[degC] = 1 140, 2 150, 3 170").unwrap();
+        let u = units.lookup("oven").unwrap();
+        assert!((u.to_base_value(1.5) - 418.15).abs() < 1e-9);
+    }
+
+    #[test]
+    fn table_without_unit_uses_base_values() {
+        let (units, _, _) = load("table steps: Length = 0 0, 1 0.5").unwrap();
+        assert_eq!(units.lookup("steps").unwrap().to_base_value(0.5), 0.25);
+        let err = load_err("table steps = 0 0, 1 0.5");
+        assert!(err.contains("needs a unit"), "{err}");
+    }
+
+    #[test]
+    fn table_errors() {
+        let err = load_err("table tbl[mm] = 1 2, 2 1, 3 2");
+        assert!(
+            err.contains("strictly increasing or strictly decreasing"),
+            "{err}"
+        );
+        let err = load_err("table tbl[mm] = 1 2, 3");
+        assert!(err.contains("expected an 'x y' pair"), "{err}");
+        let err = load_err("table tbl[mm]: Mass = 1 2, 2 3");
+        assert!(err.contains("declared as Mass"), "{err}");
+        let err = load_err("table tbl[dBm] = 1 2, 2 3");
+        assert!(
+            err.contains("linear unit or a bare temperature scale"),
+            "{err}"
+        );
+        let err = load_err("table tbl[mm] = 1 2, 2 3 @prefixable");
+        assert!(err.contains("only accept @aliases"), "{err}");
+        // A table is a function unit: it can't define a linear one.
+        let err = load_err("table tbl[mm] = 1 2, 2 3\nunit u = 2 tbl");
+        assert!(err.contains("function unit"), "{err}");
+    }
+
+    // ---- Recursive resolution ----
+
+    #[test]
+    fn forward_references_resolve_in_any_order() {
+        let source = "
+            unit league2 = 3 mile2
+            unit mile2 = 8 furlong2
+            unit furlong2: Length = 10 chain2
+            unit chain2 = 22 yd
+        ";
+        let (units, _, _) = load(source).unwrap();
+        let league = units.lookup("league2").unwrap();
+        assert!((league.conversion_factor() - 4828.032).abs() < 1e-6);
+    }
+
+    #[test]
+    fn prefixed_forward_reference_resolves() {
+        let source = "
+            unit marathon = 42.195 kstadion
+            unit stadion: Length = 1 m @prefixable
+        ";
+        let (units, _, _) = load(source).unwrap();
+        assert!((units.lookup("marathon").unwrap().conversion_factor() - 42_195.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn cycle_is_reported_with_chain_and_location() {
+        let msg = load_err("unit aa = 2 bb\nunit bb = 3 cc\nunit cc = 4 aa\n");
+        assert!(
+            msg.contains("definition cycle: aa -> bb -> cc -> aa"),
+            "{msg}"
+        );
+        assert!(msg.starts_with("test.units:3:"), "{msg}");
+    }
+
+    #[test]
+    fn self_reference_is_a_cycle() {
+        let msg = load_err("unit selfish = 2 selfish");
+        assert!(
+            msg.contains("definition cycle: selfish -> selfish"),
+            "{msg}"
+        );
+    }
+
+    // ---- Functions ----
+
+    #[test]
+    fn fn_statements_are_handed_back() {
+        let (_, _, summary) = load(
+            "fn ke(m: Mass, v: Velocity): Energy = 0.5 * m * v^2\n\
+             fn torque(f: Force, r: Arm) = f * r\n\
+             dimension Arm = Length\n",
+        )
+        .unwrap();
+        assert_eq!(summary.functions.len(), 2);
+        assert_eq!(
+            summary.functions["torque"].signature(),
+            "torque(f: Force, r: Arm)"
+        );
+    }
+
+    #[test]
+    fn fn_statement_errors_report_line() {
+        let msg = load_err("unit ok2 = 2 m\nfn f(x: Wobble) = x\n");
+        assert!(msg.starts_with("test.units:2:"), "{msg}");
+        assert!(msg.contains("unknown quantity 'Wobble'"), "{msg}");
+        let msg = load_err("fn f(x) = g(x)\nfn g(x) = f(x)\n");
+        assert!(msg.contains("would call itself"), "{msg}");
+        assert!(load_err("fn f = 2").contains("expected 'fn name(<parameters>)"));
+        assert!(load_err("fn f(x) = x @prefixable").contains("no annotations"));
+    }
+
+    // ---- Errors carry file and line ----
+
+    #[test]
+    fn unknown_identifier_reports_line() {
+        let msg = load_err("unit ok1 = 2 m\n\nunit broken = 3 florp\n");
+        assert!(msg.starts_with("test.units:3:"), "{msg}");
+        assert!(msg.contains("florp"), "{msg}");
+    }
+
+    #[test]
+    fn declared_quantity_mismatch_is_an_error() {
+        let msg = load_err("unit heavy_foot: Mass = 1 ft");
+        assert!(msg.contains("declared as Mass"), "{msg}");
+        assert!(msg.contains("Length"), "{msg}");
+    }
+
+    #[test]
+    fn constant_quantity_mismatch_is_a_warning() {
+        let (_, constants, summary) = load("const g_wrong: Acceleration = 9.8 m/s").unwrap();
+        assert_eq!(
+            constants.lookup("g_wrong").unwrap().quantity.as_deref(),
+            Some("Acceleration")
+        );
+        assert!(
+            summary.warnings[0].contains("constant 'g_wrong' is declared as Acceleration"),
+            "{:?}",
+            summary.warnings
+        );
+    }
+
+    #[test]
+    fn unknown_quantity_is_an_error() {
+        let msg = load_err("unit x2: Wobble = 1 m");
+        assert!(msg.contains("unknown quantity 'Wobble'"), "{msg}");
+    }
+
+    #[test]
+    fn duplicate_definition_reports_first_location() {
+        let msg = load_err("unit dup1 = 1 m\nunit dup2 = 2 m @aliases(dup1)\n");
+        assert!(msg.contains("already defined at test.units:1"), "{msg}");
+    }
+
+    #[test]
+    fn redefining_an_existing_unit_is_an_error() {
+        let msg = load_err("unit foot = 0.3 m");
+        assert!(msg.contains("'foot' is already defined"), "{msg}");
+    }
+
+    #[test]
+    fn tier_definitions_yield_to_a_later_file() {
+        let super::tiers::TierDatabases {
+            mut units,
+            mut constants,
+            ..
+        } = super::tiers::build(Tier::Standard, None).unwrap();
+        let summary = load_str(
+            "unit knot: Velocity = 0.5 m/s\nconst fortnight = 14\n",
+            "user.units",
+            &mut units,
+            &mut constants,
+        )
+        .unwrap();
+        assert!((units.lookup("knot").unwrap().conversion_factor() - 0.5).abs() < 1e-12);
+        assert!(units.lookup("fortnight").is_none());
+        assert_eq!(constants.lookup("fortnight").unwrap().value, 14.0);
+        assert_eq!(summary.warnings.len(), 2);
+        assert!(
+            summary.warnings[0].contains("unit 'knot' replaces the standard tier's unit"),
+            "{:?}",
+            summary.warnings
+        );
+        // Builtin seeds still need @override, whatever the tier.
+        let err = load_str(
+            "unit foot = 0.3 m",
+            "user.units",
+            &mut units,
+            &mut constants,
+        )
+        .unwrap_err();
+        assert!(err.to_string().contains("'foot' is already defined"));
+    }
+
+    #[test]
+    fn override_replaces_builtin_with_warning() {
+        let (units, _, summary) = load("unit cup: Volume = 250 mL @override").unwrap();
+        assert!((units.lookup("cup").unwrap().conversion_factor() - 250e-6).abs() < 1e-15);
+        assert_eq!(summary.warnings.len(), 1);
+        assert!(
+            summary.warnings[0].starts_with("test.units:1: unit 'cup' overrides"),
+            "{:?}",
+            summary.warnings
+        );
+    }
+
+    #[test]
+    fn override_takes_the_aliases_along() {
+        let (units, _, summary) =
+            load("unit foot: Length = 0.3 m @aliases(pes) @override\nunit feet = 2 m @override")
+                .unwrap();
+        let ft = units.lookup("ft").unwrap();
+        assert_eq!(ft.name, "foot");
+        assert!((ft.conversion_factor() - 0.3).abs() < 1e-12);
+        assert!((units.lookup("pes").unwrap().conversion_factor() - 0.3).abs() < 1e-12);
+        // The file's own definition of an alias wins.
+        assert!((units.lookup("feet").unwrap().conversion_factor() - 2.0).abs() < 1e-12);
+        assert!(
+            summary.warnings[0].contains("(so do its aliases feet, ft)"),
+            "{:?}",
+            summary.warnings
+        );
+    }
+
+    #[test]
+    fn override_without_shadowing_is_silent() {
+        let (_, _, summary) = load("unit pallet: Length = 1.2 m @override").unwrap();
+        assert!(summary.warnings.is_empty());
+    }
+
+    #[test]
+    fn constant_cannot_override_unit() {
+        let msg = load_err("const foot = 3 @override");
+        assert!(msg.contains("units take precedence"), "{msg}");
+    }
+
+    #[test]
+    fn affine_definition_is_rejected() {
+        let msg = load_err("unit warm = degC");
+        assert!(msg.contains("linear"), "{msg}");
+    }
+
+    #[test]
+    fn syntax_errors_are_reported() {
+        assert!(load_err("frobnicate x = 1").contains("unknown statement 'frobnicate'"));
+        assert!(load_err("unit 9lives = 1 m").contains("invalid unit name"));
+        assert!(load_err("unit x3 = 1 m @colour(red)").contains("unknown annotation"));
+        assert!(load_err("const k9").contains("needs a value"));
+        assert!(load_err("unit bare3").contains("needs a quantity"));
+    }
+
+    // ---- Includes ----
+
+    #[test]
+    fn include_resolves_relative_with_default_extension() {
+        let dir = scratch_dir("include");
+        std::fs::create_dir_all(dir.join("extra")).unwrap();
+        std::fs::write(
+            dir.join("main.units"),
+            "include \"extra/astro\"\nunit half_au = 0.5 au_light\n",
+        )
+        .unwrap();
+        std::fs::write(
+            dir.join("extra").join("astro.units"),
+            "unit au_light: Length = 1 AU @aliases(AUl)\n",
+        )
+        .unwrap();
+
+        let mut units = UnitDatabase::new();
+        let mut constants = ConstantsDatabase::new();
+        let summary = load_file(&dir.join("main.units"), &mut units, &mut constants).unwrap();
+        assert_eq!(summary.files, 2);
+        assert_eq!(summary.units, 2);
+        assert!(units.lookup("AUl").is_some());
+    }
+
+    #[test]
+    fn include_cycle_is_reported() {
+        let dir = scratch_dir("include-cycle");
+        std::fs::write(dir.join("a.units"), "include \"b\"\n").unwrap();
+        std::fs::write(dir.join("b.units"), "include \"a\"\n").unwrap();
+        let mut units = UnitDatabase::new();
+        let mut constants = ConstantsDatabase::new();
+        let err = load_file(&dir.join("a.units"), &mut units, &mut constants).unwrap_err();
+        assert!(err.to_string().contains("include cycle"), "{err}");
+    }
+
+    #[test]
+    fn missing_file_is_reported() {
+        let mut units = UnitDatabase::new();
+        let mut constants = ConstantsDatabase::new();
+        let err = load_file(
+            Path::new("/nonexistent/runits/none.units"),
+            &mut units,
+            &mut constants,
+        )
+        .unwrap_err();
+        assert!(matches!(err, RUnitsError::Definition { line: 0, .. }));
+    }
+}
@@ -0,0 +1,1120 @@
+//! Importer for GNU Units' `definitions.units`.
+//!
+//! GNU's database is the single biggest gap against GNU Units (see
+//! `docs/gnu-units-parity.md`). Rather than teach the expression grammar
+//! GNU's dialect, this module reads a local copy of the file and maps what
+//! RUnits can represent onto plain [`Unit`]s:
+//!
+//! | GNU line | Becomes |
+//! |---|---|
+//! | `m !` (primitive) | base unit for the matching [`Dimension`] |
+//! | `furlong 1\|8 mile` (linear) | `ConversionKind::Linear` unit |
+//! | `kilo- 1e3`, `k- kilo` (prefix) | prefix used while evaluating definitions |
+//! | `metre meter` (alias chain) | extra alias of the chain's final unit |
+//!
+//! Everything else is **skipped and counted** in the [`ImportReport`]:
+//! `!` directives, lines inside inactive `!locale`/`!var` blocks, nonlinear
+//! function definitions (`tempC(x) ...`), piecewise tables (`wiregauge[in]`),
+//! primitives with no RUnits dimension, definitions needing fractional
+//! dimension exponents, names the expression grammar can't type, and names
+//! that already exist (builtins always win).
+//!
+//! Definitions are evaluated in GNU's own name space with GNU's rules:
+//! juxtaposition binds tighter than `*` and `/`, `|` divides numbers,
+//! `^`/`**` raise to a power, trailing digits are exponents (`cm3`), plurals
+//! fall back to the singular, and any unit may carry one prefix. GNU's `h`
+//! (Planck) is therefore Planck's constant *inside* GNU definitions even
+//! though RUnits' `h` stays the hour.
+//!
+//! Imported units are prefixable, as every unit is in GNU Units. GNU prefix
+//! definitions only feed the evaluation; lookups still use RUnits' own
+//! prefix tables.
+
+use super::UnitDatabase;
+use super::constants::ConstantsDatabase;
+use super::definitions::is_identifier;
+use crate::error::RUnitsError;
+use crate::format::json_string;
+use crate::units::dimension::{Dimension, DimensionMap, create_dimensions};
+use crate::units::rational::Rational;
+use crate::units::unit::{ConversionKind, Unit};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fmt;
+use std::path::Path;
+
+/// GNU primitive units and the RUnits dimensions they stand for.
+const PRIMITIVES: &[(&str, &[(Dimension, i8)])] = &[
+    ("m", &[(Dimension::Length, 1)]),
+    ("kg", &[(Dimension::Mass, 1)]),
+    ("s", &[(Dimension::Time, 1)]),
+    ("K", &[(Dimension::Temperature, 1)]),
+    ("A", &[(Dimension::Current, 1)]),
+    ("mol", &[(Dimension::AmountOfSubstance, 1)]),
+    ("cd", &[(Dimension::LuminousIntensity, 1)]),
+    ("radian", &[(Dimension::Angle, 1)]),
+    ("sr", &[(Dimension::Angle, 2)]),
+    ("bit", &[(Dimension::Information, 1)]),
+    ("US$", &[(Dimension::Currency, 1)]),
+];
+
+/// GNU's defaults for `!var` blocks: `UNITS_ENGLISH=US`, no `UNITS_SYSTEM`.
+const DEFAULT_VARS: &[(&str, &str)] = &[("UNITS_ENGLISH", "US"), ("UNITS_SYSTEM", "default")];
+
+/// The locale whose `!locale` blocks are read.
+const DEFAULT_LOCALE: &str = "en_US";
+
+/// How many skipped names to keep per reason for the report.
+const MAX_EXAMPLES: usize = 3;
+
+/// Why an entry of the GNU file didn't make it into the database.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum SkipReason {
+    /// `!include`, `!locale`, `!set`, ... lines.
+    Directive,
+    /// Definitions inside a `!locale`/`!var` block that isn't active.
+    ConditionalBlock,
+    /// `name(x) ...` function definitions, or definitions calling them.
+    Nonlinear,
+    /// `name[unit] ...` piecewise-linear tables.
+    Table,
+    /// A `!` primitive with no RUnits dimension (or a unit built on one).
+    UnsupportedPrimitive,
+    /// Needs a non-integer dimension exponent (e.g. `sqrt(dyne) cm`).
+    ///
+    /// FUTURE(gnu-fractional-dims): [`DimensionMap`] holds rational
+    /// exponents now, so these could be imported rather than skipped.
+    FractionalDimension,
+    /// Unknown name, syntax error, or a dependency cycle.
+    Unresolvable,
+    /// Evaluates to zero, a negative or a non-finite factor.
+    InvalidValue,
+    /// Name the expression grammar can't type (`US$`, `%`, ...).
+    InvalidName,
+    /// Name already known to RUnits; the builtin wins.
+    AlreadyDefined,
+    /// Second definition of the same name within the GNU file.
+    Redefinition,
+}
+
+impl SkipReason {
+    /// Short human-readable explanation for the report.
+    pub fn description(self) -> &'static str {
+        match self {
+            SkipReason::Directive => "directive",
+            SkipReason::ConditionalBlock => "inactive locale/variable block",
+            SkipReason::Nonlinear => "nonlinear function",
+            SkipReason::Table => "piecewise-linear table",
+            SkipReason::UnsupportedPrimitive => "unsupported primitive dimension",
+            SkipReason::FractionalDimension => "fractional dimension exponent",
+            SkipReason::Unresolvable => "unresolvable definition",
+            SkipReason::InvalidValue => "zero, negative or non-finite value",
+            SkipReason::InvalidName => "name not expressible in RUnits syntax",
+            SkipReason::AlreadyDefined => "already defined (builtin kept)",
+            SkipReason::Redefinition => "redefinition within the file",
+        }
+    }
+
+    /// Stable kebab-case identifier, used as the JSON key.
+    pub fn slug(self) -> &'static str {
+        match self {
+            SkipReason::Directive => "directive",
+            SkipReason::ConditionalBlock => "conditional-block",
+            SkipReason::Nonlinear => "nonlinear",
+            SkipReason::Table => "table",
+            SkipReason::UnsupportedPrimitive => "unsupported-primitive",
+            SkipReason::FractionalDimension => "fractional-dimension",
+            SkipReason::Unresolvable => "unresolvable",
+            SkipReason::InvalidValue => "invalid-value",
+            SkipReason::InvalidName => "invalid-name",
+            SkipReason::AlreadyDefined => "already-defined",
+            SkipReason::Redefinition => "redefinition",
+        }
+    }
+}
+
+/// Outcome of an import: what was loaded, what was skipped, and why.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ImportReport {
+    /// New units added to the database.
+    pub units: usize,
+    /// New aliases of existing units (GNU alias chains).
+    pub aliases: usize,
+    /// Prefix definitions understood.
+    pub prefixes: usize,
+    /// Skipped entries per reason.
+    pub skipped: BTreeMap<SkipReason, usize>,
+    /// The first few skipped names per reason, for the report.
+    pub examples: BTreeMap<SkipReason, Vec<String>>,
+}
+
+impl ImportReport {
+    /// Total number of skipped entries across all reasons.
+    pub fn skipped_total(&self) -> usize {
+        self.skipped.values().sum()
+    }
+
+    fn skip(&mut self, reason: SkipReason, what: &str) {
+        *self.skipped.entry(reason).or_insert(0) += 1;
+        let examples = self.examples.entry(reason).or_default();
+        if examples.len() < MAX_EXAMPLES {
+            examples.push(what.to_string());
+        }
+    }
+}
+
+impl fmt::Display for ImportReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "loaded {} units, {} aliases and {} prefixes; skipped {}",
+            self.units,
+            self.aliases,
+            self.prefixes,
+            self.skipped_total()
+        )?;
+        for (reason, count) in &self.skipped {
+            write!(f, "\n  {count:>6}  {}", reason.description())?;
+            if let Some(examples) = self.examples.get(reason) {
+                let more = if *count > examples.len() { ", …" } else { "" };
+                write!(f, " (e.g. {}{more})", examples.join(", "))?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// JSON report: `{"file":..,"units":N,"aliases":N,"prefixes":N,"skipped":N,
+/// "reasons":[{"reason":..,"description":..,"count":N,"examples":[..]}]}`.
+pub fn format_json(report: &ImportReport, file: &Path) -> String {
+    let reasons: Vec<String> = report
+        .skipped
+        .iter()
+        .map(|(reason, count)| {
+            let examples: Vec<String> = report
+                .examples
+                .get(reason)
+                .into_iter()
+                .flatten()
+                .map(|e| json_string(e))
+                .collect();
+            format!(
+                "{{\"reason\":{},\"description\":{},\"count\":{count},\"examples\":[{}]}}",
+                json_string(reason.slug()),
+                json_string(reason.description()),
+                examples.join(",")
+            )
+        })
+        .collect();
+    format!(
+        "{{\"file\":{},\"units\":{},\"aliases\":{},\"prefixes\":{},\"skipped\":{},\"reasons\":[{}]}}",
+        json_string(&file.display().to_string()),
+        report.units,
+        report.aliases,
+        report.prefixes,
+        report.skipped_total(),
+        reasons.join(",")
+    )
+}
+
+/// Import a GNU `definitions.units` file (following its `!include`s) into
+/// `units`. Names already known to `units` or `constants` are left alone.
+pub fn import_file(
+    path: &Path,
+    units: &mut UnitDatabase,
+    constants: &ConstantsDatabase,
+) -> Result<ImportReport, RUnitsError> {
+    let text = std::fs::read_to_string(path).map_err(|e| RUnitsError::Definition {
+        file: path.display().to_string(),
+        line: 0,
+        reason: e.to_string(),
+    })?;
+    let mut report = ImportReport::default();
+    let mut source = Source::default();
+    let mut seen = HashSet::new();
+    if let Ok(canonical) = path.canonicalize() {
+        seen.insert(canonical);
+    }
+    source.read(&text, path.parent(), &mut seen, &mut report);
+    source.install(units, constants, &mut report);
+    Ok(report)
+}
+
+/// Import GNU definitions from an in-memory string (`!include`s resolve
+/// against the current directory).
+pub fn import_str(
+    text: &str,
+    units: &mut UnitDatabase,
+    constants: &ConstantsDatabase,
+) -> ImportReport {
+    let mut report = ImportReport::default();
+    let mut source = Source::default();
+    source.read(text, None, &mut HashSet::new(), &mut report);
+    source.install(units, constants, &mut report);
+    report
+}
+
+// ---------------------------------------------------------------------------
+// Reading: lines → unit and prefix definitions.
+// ---------------------------------------------------------------------------
+
+/// Raw definitions collected from the file(s), in file order.
+#[derive(Default)]
+struct Source {
+    units: Vec<(String, String)>,
+    unit_index: HashMap<String, usize>,
+    prefixes: HashMap<String, String>,
+}
+
+impl Source {
+    fn read(
+        &mut self,
+        text: &str,
+        dir: Option<&Path>,
+        seen: &mut HashSet<std::path::PathBuf>,
+        report: &mut ImportReport,
+    ) {
+        // One entry per open `!locale`/`!var`/`!utf8` block: is it active?
+        let mut blocks: Vec<bool> = Vec::new();
+        for line in logical_lines(text) {
+            let line = line.split('#').next().unwrap_or("").trim();
+            if line.is_empty() {
+                continue;
+            }
+            let active = blocks.iter().all(|&b| b);
+
+            if let Some(directive) = line.strip_prefix('!') {
+                let mut words = directive.split_whitespace();
+                let command = words.next().unwrap_or("");
+                let args: Vec<&str> = words.collect();
+                match command {
+                    "locale" => blocks.push(args.first() == Some(&DEFAULT_LOCALE)),
+                    "var" => blocks.push(var_matches(&args)),
+                    "varnot" => blocks.push(!var_matches(&args)),
+                    "utf8" => blocks.push(true),
+                    "endlocale" | "endvar" | "endutf8" => {
+                        blocks.pop();
+                    }
+                    "include" if active => {
+                        if let Some(file) = args.first() {
+                            self.include(file, dir, seen, report);
+                        }
+                    }
+                    _ => {}
+                }
+                report.skip(SkipReason::Directive, &format!("!{command}"));
+                continue;
+            }
+
+            let (name, definition) = line
+                .split_once(char::is_whitespace)
+                .map(|(n, d)| (n, d.trim()))
+                .unwrap_or((line, ""));
+            if !active {
+                report.skip(SkipReason::ConditionalBlock, name);
+            } else if name.contains('(') {
+                report.skip(SkipReason::Nonlinear, name);
+            } else if name.contains('[') {
+                report.skip(SkipReason::Table, name);
+            } else if definition.is_empty() {
+                report.skip(SkipReason::Unresolvable, name);
+            } else if let Some(prefix) = name.strip_suffix('-') {
+                if self.prefixes.contains_key(prefix) {
+                    report.skip(SkipReason::Redefinition, name);
+                } else {
+                    self.prefixes
+                        .insert(prefix.to_string(), definition.to_string());
+                }
+            } else if self.unit_index.contains_key(name) {
+                report.skip(SkipReason::Redefinition, name);
+            } else {
+                self.unit_index.insert(name.to_string(), self.units.len());
+                self.units.push((name.to_string(), definition.to_string()));
+            }
+        }
+    }
+
+    fn include(
+        &mut self,
+        file: &str,
+        dir: Option<&Path>,
+        seen: &mut HashSet<std::path::PathBuf>,
+        report: &mut ImportReport,
+    ) {
+        let path = dir.map_or_else(|| Path::new(file).to_path_buf(), |d| d.join(file));
+        let Ok(canonical) = path.canonicalize() else {
+            return; // Optional data files (currency, CPI) are often absent.
+        };
+        if !seen.insert(canonical) {
+            return;
+        }
+        if let Ok(text) = std::fs::read_to_string(&path) {
+            self.read(&text, path.parent(), seen, report);
+        }
+    }
+
+    /// Resolve every definition and add the representable ones to `units`.
+    fn install(
+        &self,
+        units: &mut UnitDatabase,
+        constants: &ConstantsDatabase,
+        report: &mut ImportReport,
+    ) {
+        let mut resolver = Resolver::new(self);
+        report.prefixes = self
+            .prefixes
+            .keys()
+            .filter(|p| resolver.prefix_value(p).is_ok())
+            .count();
+
+        let mut aliases = Vec::new();
+        for (name, definition) in &self.units {
+            if units.contains(name) || constants.lookup(name).is_some() {
+                report.skip(SkipReason::AlreadyDefined, name);
+                continue;
+            }
+            if !is_identifier(name) {
+                report.skip(SkipReason::InvalidName, name);
+                continue;
+            }
+            let value = match resolver.resolve_unit(name) {
+                Ok(value) if value.factor.is_finite() && value.factor > 0.0 => value,
+                Ok(_) => {
+                    report.skip(SkipReason::InvalidValue, name);
+                    continue;
+                }
+                Err(reason) => {
+                    report.skip(reason, name);
+                    continue;
+                }
+            };
+            let unit = Unit {
+                name: name.clone(),
+                conversion: ConversionKind::Linear(value.factor),
+                dimensions: value.dims,
+                prefixable: true,
+                temperature_delta: false,
+            };
+            match self.alias_target(definition) {
+                // Aliases go in last, once their targets are in the database.
+                Some(target) => aliases.push((unit, target)),
+                None => {
+                    units.insert(unit, &[]);
+                    report.units += 1;
+                }
+            }
+        }
+
+        for (unit, target) in aliases {
+            // Only alias a unit that really is the same quantity: a builtin
+            // may use the name for something else (GNU `h` is Planck's
+            // constant, RUnits' `h` is the hour).
+            match units.lookup(target).filter(|t| same_unit(t, &unit)) {
+                Some(existing) => {
+                    units.insert(existing, std::slice::from_ref(&unit.name));
+                    report.aliases += 1;
+                }
+                None => {
+                    units.insert(unit, &[]);
+                    report.units += 1;
+                }
+            }
+        }
+    }
+
+    /// For a definition that is just another defined name, the last name in
+    /// the chain (`metre meter`, `meter m` → `m`).
+    fn alias_target<'s>(&'s self, definition: &'s str) -> Option<&'s str> {
+        let mut target = definition;
+        let mut hops = 0;
+        while let Some(&index) = self.unit_index.get(target) {
+            let next = self.units[index].1.as_str();
+            if hops > self.units.len() || !self.unit_index.contains_key(next) {
+                return Some(target);
+            }
+            target = next;
+            hops += 1;
+        }
+        None
+    }
+}
+
+/// Join `\`-continued lines.
+fn logical_lines(text: &str) -> Vec<String> {
+    let mut lines = Vec::new();
+    let mut current = String::new();
+    for raw in text.lines() {
+        match raw.trim_end().strip_suffix('\\') {
+            Some(head) => {
+                current.push_str(head);
+                current.push(' ');
+            }
+            None => {
+                current.push_str(raw);
+                lines.push(std::mem::take(&mut current));
+            }
+        }
+    }
+    if !current.is_empty() {
+        lines.push(current);
+    }
+    lines
+}
+
+/// Does a `!var NAME value...` block match GNU's default environment?
+fn var_matches(args: &[&str]) -> bool {
+    let Some((name, values)) = args.split_first() else {
+        return false;
+    };
+    DEFAULT_VARS
+        .iter()
+        .find(|(var, _)| var == name)
+        .is_some_and(|(_, default)| values.contains(default))
+}
+
+fn same_unit(a: &Unit, b: &Unit) -> bool {
+    match (&a.conversion, &b.conversion) {
+        (ConversionKind::Linear(x), ConversionKind::Linear(y)) => {
+            a.dimensions == b.dimensions && ((x - y) / y).abs() < 1e-9
+        }
+        _ => false,
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Evaluating GNU expressions.
+// ---------------------------------------------------------------------------
+
+/// A resolved GNU expression: factor relative to the primitives + dimensions.
+#[derive(Debug, Clone, PartialEq)]
+struct Value {
+    factor: f64,
+    dims: DimensionMap,
+}
+
+impl Value {
+    fn number(factor: f64) -> Self {
+        Value {
+            factor,
+            dims: DimensionMap::new(),
+        }
+    }
+
+    fn mul(mut self, other: Value, sign: i8) -> Value {
+        self.factor = if sign > 0 {
+            self.factor * other.factor
+        } else {
+            self.factor / other.factor
+        };
+        for (dim, exp) in other.dims {
+            *self.dims.entry(dim).or_insert(Rational::ZERO) += Rational::from(sign) * exp;
+        }
+        self.dims.retain(|_, exp| !exp.is_zero());
+        self
+    }
+
+    fn pow(mut self, exponent: f64) -> Result<Value, SkipReason> {
+        if self.dims.is_empty() {
+            return Ok(Value::number(self.factor.powf(exponent)));
+        }
+        if exponent.fract() != 0.0 || exponent.abs() > i8::MAX as f64 {
+            return Err(SkipReason::FractionalDimension);
+        }
+        let n = exponent as i8;
+        self.factor = self.factor.powi(n as i32);
+        for exp in self.dims.values_mut() {
+            *exp *= Rational::from(n);
+            if exp.abs() > Rational::from(i8::MAX) {
+                return Err(SkipReason::FractionalDimension);
+            }
+        }
+        Ok(self)
+    }
+
+    /// `n`-th root; every dimension exponent must divide evenly.
+    fn root(mut self, n: i8) -> Result<Value, SkipReason> {
+        let n = Rational::from(n);
+        if self.dims.values().any(|&exp| !(exp / n).is_integer()) {
+            return Err(SkipReason::FractionalDimension);
+        }
+        self.factor = self.factor.powf(1.0 / n.to_f64());
+        for exp in self.dims.values_mut() {
+            *exp = *exp / n;
+        }
+        Ok(self)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Number(f64),
+    Name(String),
+    Op(char),
+}
+
+const OPERATORS: &str = "+-*/|^()";
+
+fn tokenize(text: &str) -> Result<Vec<Token>, SkipReason> {
+    let mut tokens = Vec::new();
+    let mut chars = text.char_indices().peekable();
+    while let Some(&(start, c)) = chars.peek() {
+        if c.is_whitespace() {
+            chars.next();
+        } else if c.is_ascii_digit() || c == '.' {
+            let mut end = start;
+            let mut prev = ' ';
+            while let Some(&(i, d)) = chars.peek() {
+                let exponent_sign = (d == '+' || d == '-') && (prev == 'e' || prev == 'E');
+                let exponent = (d == 'e' || d == 'E')
+                    && text[i + 1..]
+                        .trim_start_matches(['+', '-'])
+                        .starts_with(|n: char| n.is_ascii_digit());
+                if d.is_ascii_digit() || d == '.' || exponent || exponent_sign {
+                    end = i + d.len_utf8();
+                    prev = d;
+                    chars.next();
+                } else {
+                    break;
+                }
+            }
+            let number = text[start..end]
+                .parse()
+                .map_err(|_| SkipReason::Unresolvable)?;
+            tokens.push(Token::Number(number));
+        } else if c == '*' {
+            chars.next();
+            if chars.next_if(|&(_, n)| n == '*').is_some() {
+                tokens.push(Token::Op('^'));
+            } else {
+                tokens.push(Token::Op('*'));
+            }
+        } else if OPERATORS.contains(c) {
+            chars.next();
+            tokens.push(Token::Op(c));
+        } else {
+            let mut end = start;
+            while let Some(&(i, d)) = chars.peek() {
+                if d.is_whitespace() || OPERATORS.contains(d) {
+                    break;
+                }
+                end = i + d.len_utf8();
+                chars.next();
+            }
+            match &text[start..end] {
+                "per" => tokens.push(Token::Op('/')),
+                name => tokens.push(Token::Name(name.to_string())),
+            }
+        }
+    }
+    Ok(tokens)
+}
+
+/// Memoizing, cycle-safe evaluator over a [`Source`].
+struct Resolver<'s> {
+    source: &'s Source,
+    units: HashMap<String, Result<Value, SkipReason>>,
+    prefixes: HashMap<String, Result<f64, SkipReason>>,
+    /// Prefix names, longest first, so `kilo` beats `k`.
+    prefix_names: Vec<&'s str>,
+    in_progress: HashSet<String>,
+}
+
+impl<'s> Resolver<'s> {
+    fn new(source: &'s Source) -> Self {
+        let mut prefix_names: Vec<&str> = source.prefixes.keys().map(String::as_str).collect();
+        prefix_names.sort_by(|a, b| b.len().cmp(&a.len()).then(a.cmp(b)));
+        Resolver {
+            source,
+            units: HashMap::new(),
+            prefixes: HashMap::new(),
+            prefix_names,
+            in_progress: HashSet::new(),
+        }
+    }
+
+    fn resolve_unit(&mut self, name: &str) -> Result<Value, SkipReason> {
+        if let Some(result) = self.units.get(name) {
+            return result.clone();
+        }
+        let index = *self
+            .source
+            .unit_index
+            .get(name)
+            .ok_or(SkipReason::Unresolvable)?;
+        if !self.in_progress.insert(name.to_string()) {
+            return Err(SkipReason::Unresolvable);
+        }
+        let definition = &self.source.units[index].1;
+        let result = match definition.strip_prefix('!') {
+            Some(kind) => primitive(name, kind.trim()),
+            None => self.evaluate(definition),
+        };
+        self.in_progress.remove(name);
+        self.units.insert(name.to_string(), result.clone());
+        result
+    }
+
+    fn prefix_value(&mut self, name: &str) -> Result<f64, SkipReason> {
+        if let Some(result) = self.prefixes.get(name) {
+            return *result;
+        }
+        let definition = self
+            .source
+            .prefixes
+            .get(name)
+            .ok_or(SkipReason::Unresolvable)?;
+        let key = format!("{name}-");
+        if !self.in_progress.insert(key.clone()) {
+            return Err(SkipReason::Unresolvable);
+        }
+        let result = self.evaluate(definition).and_then(|v| {
+            if v.dims.is_empty() {
+                Ok(v.factor)
+            } else {
+                Err(SkipReason::Unresolvable)
+            }
+        });
+        self.in_progress.remove(&key);
+        self.prefixes.insert(name.to_string(), result);
+        result
+    }
+
+    fn evaluate(&mut self, definition: &str) -> Result<Value, SkipReason> {
+        let tokens = tokenize(definition)?;
+        let mut parser = Parser {
+            tokens: &tokens,
+            pos: 0,
+            resolver: self,
+        };
+        let value = parser.sum()?;
+        if parser.pos != tokens.len() {
+            return Err(SkipReason::Unresolvable);
+        }
+        Ok(value)
+    }
+
+    /// GNU name lookup: exact unit, bare prefix, plural, prefix + unit, then
+    /// a trailing exponent digit (`cm3`).
+    fn lookup(&mut self, name: &str) -> Result<Value, SkipReason> {
+        if let Some(value) = self.lookup_unprefixed(name) {
+            return value;
+        }
+        let prefixes = self.prefix_names.clone();
+        for prefix in prefixes {
+            if let Some(rest) = name.strip_prefix(prefix).filter(|r| !r.is_empty())
+                && let Some(value) = self.lookup_unprefixed(rest)
+            {
+                let scale = self.prefix_value(prefix)?;
+                return Ok(value?.mul(Value::number(scale), 1));
+            }
+        }
+        let mut chars = name.chars().rev();
+        if let (Some(last), Some(before)) = (chars.next(), chars.next())
+            && ('2'..='9').contains(&last)
+            && !before.is_ascii_digit()
+        {
+            let stem = &name[..name.len() - 1];
+            return self
+                .lookup(stem)?
+                .pow(last.to_digit(10).unwrap_or(1) as f64);
+        }
+        Err(SkipReason::Unresolvable)
+    }
+
+    fn lookup_unprefixed(&mut self, name: &str) -> Option<Result<Value, SkipReason>> {
+        if self.source.unit_index.contains_key(name) {
+            return Some(self.resolve_unit(name));
+        }
+        if self.source.prefixes.contains_key(name) {
+            return Some(self.prefix_value(name).map(Value::number));
+        }
+        if name.len() > 2 {
+            for (suffix, replacement) in [("ies", "y"), ("es", ""), ("s", "")] {
+                if let Some(stem) = name.strip_suffix(suffix) {
+                    let singular = format!("{stem}{replacement}");
+                    if self.source.unit_index.contains_key(&singular) {
+                        return Some(self.resolve_unit(&singular));
+                    }
+                }
+            }
+        }
+        None
+    }
+}
+
+fn primitive(name: &str, kind: &str) -> Result<Value, SkipReason> {
+    if let Some((_, dims)) = PRIMITIVES.iter().find(|(p, _)| *p == name) {
+        return Ok(Value {
+            factor: 1.0,
+            dims: create_dimensions(dims),
+        });
+    }
+    if kind == "dimensionless" {
+        Ok(Value::number(1.0))
+    } else {
+        Err(SkipReason::UnsupportedPrimitive)
+    }
+}
+
+/// Recursive-descent parser for GNU definition expressions, lowest
+/// precedence first: `+ -`, then `* /`, then juxtaposition, then unary
+/// minus, then `^`, then atoms (numbers with `|`, names, calls, parens).
+struct Parser<'t, 'r, 's> {
+    tokens: &'t [Token],
+    pos: usize,
+    resolver: &'r mut Resolver<'s>,
+}
+
+impl Parser<'_, '_, '_> {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos)
+    }
+
+    fn eat(&mut self, op: char) -> bool {
+        if self.peek() == Some(&Token::Op(op)) {
+            self.pos += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn sum(&mut self) -> Result<Value, SkipReason> {
+        let mut value = self.product()?;
+        loop {
+            let sign = if self.eat('+') {
+                1.0
+            } else if self.eat('-') {
+                -1.0
+            } else {
+                return Ok(value);
+            };
+            let rhs = self.product()?;
+            if rhs.dims != value.dims {
+                return Err(SkipReason::Unresolvable);
+            }
+            value.factor += sign * rhs.factor;
+        }
+    }
+
+    fn product(&mut self) -> Result<Value, SkipReason> {
+        let mut value = self.juxtaposition()?;
+        loop {
+            if self.eat('*') {
+                value = value.mul(self.juxtaposition()?, 1);
+            } else if self.eat('/') {
+                value = value.mul(self.juxtaposition()?, -1);
+            } else {
+                return Ok(value);
+            }
+        }
+    }
+
+    fn juxtaposition(&mut self) -> Result<Value, SkipReason> {
+        let mut value = self.unary()?;
+        while matches!(
+            self.peek(),
+            Some(Token::Number(_) | Token::Name(_) | Token::Op('('))
+        ) {
+            value = value.mul(self.power()?, 1);
+        }
+        Ok(value)
+    }
+
+    fn unary(&mut self) -> Result<Value, SkipReason> {
+        if self.eat('-') {
+            let mut value = self.unary()?;
+            value.factor = -value.factor;
+            Ok(value)
+        } else {
+            self.power()
+        }
+    }
+
+    fn power(&mut self) -> Result<Value, SkipReason> {
+        let base = self.atom()?;
+        if !self.eat('^') {
+            return Ok(base);
+        }
+        let exponent = self.unary()?;
+        if !exponent.dims.is_empty() {
+            return Err(SkipReason::Unresolvable);
+        }
+        base.pow(exponent.factor)
+    }
+
+    fn atom(&mut self) -> Result<Value, SkipReason> {
+        let token = self.peek().cloned().ok_or(SkipReason::Unresolvable)?;
+        self.pos += 1;
+        match token {
+            Token::Number(n) => {
+                if self.eat('|') {
+                    match self.peek().cloned() {
+                        Some(Token::Number(d)) => {
+                            self.pos += 1;
+                            Ok(Value::number(n / d))
+                        }
+                        _ => Err(SkipReason::Unresolvable),
+                    }
+                } else {
+                    Ok(Value::number(n))
+                }
+            }
+            Token::Name(name) if self.eat('(') => {
+                let argument = self.sum()?;
+                if !self.eat(')') {
+                    return Err(SkipReason::Unresolvable);
+                }
+                call(&name, argument)
+            }
+            Token::Name(name) => self.resolver.lookup(&name),
+            Token::Op('(') => {
+                let value = self.sum()?;
+                if self.eat(')') {
+                    Ok(value)
+                } else {
+                    Err(SkipReason::Unresolvable)
+                }
+            }
+            Token::Op(_) => Err(SkipReason::Unresolvable),
+        }
+    }
+}
+
+/// The GNU builtin functions that keep a definition linear.
+fn call(function: &str, argument: Value) -> Result<Value, SkipReason> {
+    let dimensionless = |f: fn(f64) -> f64| {
+        if argument.dims.is_empty() {
+            Ok(Value::number(f(argument.factor)))
+        } else {
+            Err(SkipReason::Unresolvable)
+        }
+    };
+    match function {
+        "sqrt" => argument.clone().root(2),
+        "cuberoot" => argument.clone().root(3),
+        "exp" => dimensionless(f64::exp),
+        "ln" => dimensionless(f64::ln),
+        "log" => dimensionless(f64::log10),
+        "log2" => dimensionless(f64::log2),
+        _ => Err(SkipReason::Nonlinear),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A miniature `definitions.units` exercising every line type.
+    const SAMPLE: &str = r#"
+# Primitives
+m       !
+kg      !
+s       !
+K       !
+bit     !
+radian  !dimensionless
+wholenote !
+
+# Prefixes
+kilo-   1e3
+k-      kilo
+milli-  1e-3
+m-      milli
+centi-  1|100
+c-      centi
+kibi-   2^10
+Ki-     kibi
+
+# Linear units and alias chains
+inch    2.54 cm
+foot    12 inch
+yard    3 ft
+ft      foot
+furlong 1|8 mile
+mile    5280 ft
+fathom  6 ft
+smoot   67 inches
+byte    8 bit
+acre    43560 ft^2
+gallon  231 in^3
+in      inch
+litre   1000 cm3
+newton_ish  kg m / s^2
+dyne_ish    g cm / s s
+g       1|1000 kg
+furlongs_alias  furlong
+rod     16.5 \
+        ft
+crotchet wholenote|4
+statC   sqrt(dyne_ish) cm
+
+# Things that can't be represented
+tempC(x) units=[1;K] x K + 273.15
+wiregauge[in] 36 0.005 0 0.325
+US$     !
+dollar  US$
+!locale en_GB
+pint    568.26125 cm3
+!endlocale
+!var UNITS_ENGLISH US
+cup     8 floz_us
+!endvar
+floz_us 29.5735295625 cm3
+!utf8
+Å       1e-10 m
+!endutf8
+!set UNITS_ENGLISH US
+foot    0.3 m
+"#;
+
+    fn import(source: &str) -> (UnitDatabase, ImportReport) {
+        let mut units = UnitDatabase::empty();
+        let report = import_str(source, &mut units, &ConstantsDatabase::empty());
+        (units, report)
+    }
+
+    fn factor(db: &UnitDatabase, name: &str) -> f64 {
+        db.lookup(name)
+            .unwrap_or_else(|| panic!("{name} not imported"))
+            .conversion_factor()
+    }
+
+    // ---- Linear definitions ----
+
+    #[test]
+    fn linear_units_resolve_through_chains() {
+        let (db, _) = import(SAMPLE);
+        assert!((factor(&db, "foot") - 0.3048).abs() < 1e-12);
+        assert!((factor(&db, "furlong") - 201.168).abs() < 1e-9);
+        assert!((factor(&db, "rod") - 5.0292).abs() < 1e-9);
+        assert!((factor(&db, "acre") - 4046.8564224).abs() < 1e-6);
+    }
+
+    #[test]
+    fn gnu_precedence_juxtaposition_binds_tighter_than_slash() {
+        // `g cm / s s` is g·cm/s², not g·cm.
+        let (db, _) = import(SAMPLE);
+        let u = db.lookup("dyne_ish").unwrap();
+        assert!((u.conversion_factor() - 1e-5).abs() < 1e-18);
+        assert_eq!(u.dimension_string(), "Length*Mass*Time^-2");
+    }
+
+    #[test]
+    fn plurals_prefixes_and_trailing_exponents() {
+        let (db, _) = import(SAMPLE);
+        // `67 inches` (plural), `1000 cm3` (prefix + exponent digit).
+        assert!((factor(&db, "smoot") - 1.7018).abs() < 1e-9);
+        assert!((factor(&db, "litre") - 1e-3).abs() < 1e-15);
+    }
+
+    #[test]
+    fn alias_chains_become_aliases() {
+        let (db, report) = import(SAMPLE);
+        assert_eq!(db.lookup("ft").unwrap().name, "foot");
+        assert_eq!(db.lookup("furlongs_alias").unwrap().name, "furlong");
+        assert_eq!(report.aliases, 3); // ft, in, furlongs_alias
+    }
+
+    #[test]
+    fn dimensionless_primitive_and_custom_primitive() {
+        let (db, report) = import(SAMPLE);
+        assert_eq!(db.lookup("radian").unwrap().dimension_string(), "Angle");
+        assert_eq!(report.skipped[&SkipReason::UnsupportedPrimitive], 2); // wholenote, crotchet
+    }
+
+    #[test]
+    fn prefixes_are_counted() {
+        let (_, report) = import(SAMPLE);
+        assert_eq!(report.prefixes, 8);
+    }
+
+    // ---- Skips ----
+
+    #[test]
+    fn unrepresentable_lines_are_skipped_with_reasons() {
+        let (db, report) = import(SAMPLE);
+        assert_eq!(report.skipped[&SkipReason::Nonlinear], 1);
+        assert_eq!(report.skipped[&SkipReason::Table], 1);
+        assert_eq!(report.skipped[&SkipReason::FractionalDimension], 1);
+        assert_eq!(report.skipped[&SkipReason::Redefinition], 1);
+        assert_eq!(report.skipped[&SkipReason::InvalidName], 2); // US$, Å
+        // !locale, !endlocale, !var, !endvar, !utf8, !endutf8, !set
+        assert_eq!(report.skipped[&SkipReason::Directive], 7);
+        assert!(db.lookup("tempC").is_none());
+        // `dollar` is representable even though its primitive's name isn't.
+        assert_eq!(db.lookup("dollar").unwrap().dimension_string(), "Currency");
+    }
+
+    #[test]
+    fn inactive_blocks_are_skipped_active_ones_read() {
+        let (db, report) = import(SAMPLE);
+        assert_eq!(report.skipped[&SkipReason::ConditionalBlock], 1); // GB pint
+        assert!(db.lookup("pint").is_none());
+        assert!((factor(&db, "cup") - 2.365882365e-4).abs() < 1e-12);
+    }
+
+    #[test]
+    fn builtins_win_over_gnu_definitions() {
+        let mut units = UnitDatabase::new();
+        let report = import_str(
+            "m !\nh 6.62607015e-34 m\nhour 3600 s\ns !\n",
+            &mut units,
+            &ConstantsDatabase::new(),
+        );
+        assert_eq!(units.lookup("h").unwrap().name, "hour");
+        assert_eq!(report.skipped[&SkipReason::AlreadyDefined], 4);
+    }
+
+    #[test]
+    fn alias_to_builtin_with_different_meaning_becomes_unit() {
+        // GNU `planck_h h` must not alias RUnits' hour.
+        let mut units = UnitDatabase::new();
+        import_str(
+            "m !\nkg !\ns !\nJ kg m^2 / s^2\nh 6.62607015e-34 J s\nplanck_h h\n",
+            &mut units,
+            &ConstantsDatabase::empty(),
+        );
+        let u = units.lookup("planck_h").unwrap();
+        assert_eq!(u.name, "planck_h");
+    }
+
+    #[test]
+    fn report_lists_reasons_with_examples() {
+        let (_, report) = import(SAMPLE);
+        let text = report.to_string();
+        assert!(text.starts_with("loaded "), "{text}");
+        assert!(
+            text.contains("nonlinear function (e.g. tempC(x))"),
+            "{text}"
+        );
+        assert!(text.contains("piecewise-linear table"), "{text}");
+    }
+
+    #[test]
+    fn json_report_counts_reasons() {
+        let (_, report) = import("aa 2 bb\nbb 3 aa\nm !\n");
+        assert_eq!(
+            format_json(&report, Path::new("defs.units")),
+            r#"{"file":"defs.units","units":1,"aliases":0,"prefixes":0,"skipped":2,"reasons":[{"reason":"unresolvable","description":"unresolvable definition","count":2,"examples":["aa","bb"]}]}"#
+        );
+    }
+
+    #[test]
+    fn cycles_are_unresolvable_not_fatal() {
+        let (db, report) = import("aa 2 bb\nbb 3 aa\nm !\n");
+        assert!(db.lookup("aa").is_none());
+        assert_eq!(report.skipped[&SkipReason::Unresolvable], 2);
+        assert_eq!(report.units, 1);
+    }
+
+    #[test]
+    fn missing_file_is_an_error() {
+        let err = import_file(
+            Path::new("/nonexistent/definitions.units"),
+            &mut UnitDatabase::empty(),
+            &ConstantsDatabase::empty(),
+        )
+        .unwrap_err();
+        assert!(matches!(err, RUnitsError::Definition { line: 0, .. }));
+    }
+}
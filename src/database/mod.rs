@@ -8,9 +8,19 @@
 //! Lookup also supports dynamic SI prefix stripping (e.g., "Gmeter" → giga +
 //! meter) and binary prefixes for information units. Fuzzy suggestions via
 //! Jaro-Winkler scoring help with typos.
+//!
+//! Larger databases are layered on top of the seed by [`tiers`]: embedded
+//! definition modules ([`definitions`]) and GNU's `definitions.units`
+//! ([`gnu`]).
 
 pub mod constants;
+pub mod definitions;
+pub mod gnu;
 mod seed;
+pub mod substances;
+pub mod tiers;
+
+pub use tiers::Tier;
 
 use crate::units::Unit;
 use crate::units::dimension::Dimension;
@@ -45,7 +55,7 @@ pub const SI_PREFIXES: &[(&str, &str, f64)] = &[
 ];
 
 /// IEC binary prefixes for information units only.
-const BINARY_PREFIXES: &[(&str, &str, f64)] = &[
+pub const BINARY_PREFIXES: &[(&str, &str, f64)] = &[
     ("kibi", "Ki", 1024.0),
     ("mebi", "Mi", 1_048_576.0),
     ("gibi", "Gi", 1_073_741_824.0),
@@ -54,6 +64,23 @@ const BINARY_PREFIXES: &[(&str, &str, f64)] = &[
     ("exbi", "Ei", 1_152_921_504_606_846_976.0),
 ];
 
+/// `base` scaled by a prefix: canonical name = long prefix + base name
+/// (`"kN"` → `"kilonewton"`, `"µs"` → `"microsecond"`). `None` for
+/// non-linear units, which have no prefixed form.
+pub fn with_prefix(base: &Unit, long: &str, scale: f64) -> Option<Unit> {
+    let mut prefixed = base.clone();
+    prefixed.name = format!("{}{}", long, base.name);
+    prefixed.prefixable = false;
+    match &mut prefixed.conversion {
+        ConversionKind::Linear(f) => *f *= scale,
+        ConversionKind::Affine { .. }
+        | ConversionKind::Logarithmic { .. }
+        | ConversionKind::Nonlinear(_)
+        | ConversionKind::Table(_) => return None,
+    }
+    Some(prefixed)
+}
+
 /// A collection of units keyed by every acceptable input alias.
 ///
 /// Every alias gets its own entry pointing to a clone of the canonical
@@ -62,14 +89,93 @@ const BINARY_PREFIXES: &[(&str, &str, f64)] = &[
 /// `"foot"`. That keeps output predictable regardless of what the user typed.
 pub struct UnitDatabase {
     units: HashMap<String, Unit>,
+    /// Which tier this database was built for (see [`tiers`]).
+    tier: Tier,
+    /// Seed aliases that were registered twice for different units.
+    collisions: Vec<AliasCollision>,
+}
+
+/// An alias the seed registered for two different units; the later one won.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AliasCollision {
+    pub alias: String,
+    /// Canonical name of the unit that lost the alias.
+    pub previous: String,
+    /// Canonical name of the unit the alias now resolves to.
+    pub current: String,
 }
 
 impl UnitDatabase {
     /// Build a fresh database with all builtin units + aliases seeded.
     pub fn new() -> Self {
-        let mut units = HashMap::new();
-        seed::seed_all(&mut units);
-        UnitDatabase { units }
+        let mut db = Self::empty();
+        seed::seed_all(&mut db);
+        db
+    }
+
+    /// Build a database with no units at all. Used as the starting point
+    /// for databases assembled entirely from definition files.
+    pub fn empty() -> Self {
+        UnitDatabase {
+            units: HashMap::new(),
+            tier: Tier::Builtin,
+            collisions: Vec::new(),
+        }
+    }
+
+    /// Register `unit` under its canonical name plus every alias.
+    ///
+    /// Existing entries with the same key are replaced — callers that care
+    /// about shadowing (the definition loader) check [`contains`](Self::contains)
+    /// first.
+    pub fn insert(&mut self, unit: Unit, aliases: &[String]) {
+        self.units.insert(unit.name.clone(), unit.clone());
+        for alias in aliases {
+            self.units.insert(alias.clone(), unit.clone());
+        }
+    }
+
+    /// Every other alias registered for the unit whose canonical name is
+    /// `name` (`ft`, `feet` for `foot`), sorted.
+    pub fn aliases_of(&self, name: &str) -> Vec<String> {
+        let mut aliases: Vec<String> = self
+            .units
+            .iter()
+            .filter(|(alias, unit)| unit.name == name && alias.as_str() != name)
+            .map(|(alias, _)| alias.clone())
+            .collect();
+        aliases.sort();
+        aliases
+    }
+
+    /// Forget `name` (one alias; other aliases of the same unit stay).
+    pub fn remove(&mut self, name: &str) {
+        self.units.remove(name);
+    }
+
+    /// Seed-time registration of a single alias, recording any collision
+    /// with a different unit (see [`collisions`](Self::collisions)).
+    fn register(&mut self, alias: &str, unit: Unit) {
+        let current = unit.name.clone();
+        if let Some(previous) = self.units.insert(alias.to_string(), unit)
+            && previous.name != current
+        {
+            self.collisions.push(AliasCollision {
+                alias: alias.to_string(),
+                previous: previous.name,
+                current,
+            });
+        }
+    }
+
+    /// Aliases the seed table registered for two different units.
+    pub fn collisions(&self) -> &[AliasCollision] {
+        &self.collisions
+    }
+
+    /// Whether `name` is registered as a direct alias (no prefix stripping).
+    pub fn contains(&self, name: &str) -> bool {
+        self.units.contains_key(name)
     }
 
     /// Look up a unit by name or alias.
@@ -128,16 +234,9 @@ impl UnitDatabase {
                         {
                             continue;
                         }
-                        // Build the prefixed unit: canonical name = long prefix + base name
-                        // e.g., "kN" → "kilonewton", "µs" → "microsecond"
-                        let mut prefixed = base_unit.clone();
-                        prefixed.name = format!("{}{}", long, base_unit.name);
-                        prefixed.prefixable = false;
-                        match &mut prefixed.conversion {
-                            ConversionKind::Linear(f) => *f *= scale,
-                            ConversionKind::Affine { .. } => continue,
+                        if let Some(prefixed) = with_prefix(base_unit, long, scale) {
+                            return Some(prefixed);
                         }
-                        return Some(prefixed);
                     }
                 }
             }
@@ -145,6 +244,14 @@ impl UnitDatabase {
         None
     }
 
+    /// Iterate over every `(alias, unit)` entry (direct aliases only, no
+    /// prefix expansion).
+    pub fn entries(&self) -> impl Iterator<Item = (&str, &Unit)> {
+        self.units
+            .iter()
+            .map(|(alias, unit)| (alias.as_str(), unit))
+    }
+
     /// Iterate over all registered alias strings.
     ///
     /// Exposes the HashMap keys without exposing `Unit` values. Used by
@@ -225,6 +332,11 @@ impl UnitDatabase {
     pub fn is_empty(&self) -> bool {
         self.units.is_empty()
     }
+
+    /// The tier this database was built for.
+    pub fn tier(&self) -> Tier {
+        self.tier
+    }
 }
 
 impl Default for UnitDatabase {
@@ -238,10 +350,20 @@ impl Default for UnitDatabase {
 /// Uses [`OnceLock`] (stdlib since Rust 1.70) so we avoid depending on
 /// `lazy_static` / `once_cell`. Thread-safe by construction.
 pub fn global() -> &'static UnitDatabase {
-    static DB: OnceLock<UnitDatabase> = OnceLock::new();
-    DB.get_or_init(UnitDatabase::new)
+    GLOBAL.get_or_init(UnitDatabase::new)
 }
 
+/// Install `db` as the process-wide database in place of the builtin seed
+/// (e.g. builtins + the user's `units.conf`).
+///
+/// Only takes effect before the first [`global`] call; otherwise the
+/// database is handed back unchanged.
+pub fn set_global(db: UnitDatabase) -> Result<(), UnitDatabase> {
+    GLOBAL.set(db)
+}
+
+static GLOBAL: OnceLock<UnitDatabase> = OnceLock::new();
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -298,6 +420,28 @@ mod tests {
         assert!((converted.value - 1.0).abs() < 1e-9);
     }
 
+    #[test]
+    fn seed_has_no_alias_collisions() {
+        let db = UnitDatabase::new();
+        assert_eq!(db.collisions(), &[]);
+    }
+
+    #[test]
+    fn register_records_collisions() {
+        let mut db = UnitDatabase::empty();
+        db.register("x", Unit::meter());
+        db.register("x", Unit::meter());
+        db.register("x", Unit::foot());
+        assert_eq!(
+            db.collisions(),
+            &[AliasCollision {
+                alias: "x".into(),
+                previous: "meter".into(),
+                current: "foot".into(),
+            }]
+        );
+    }
+
     #[test]
     fn global_singleton_is_populated() {
         let db = global();
@@ -1,13 +1,18 @@
 //! Builtin unit definitions: ~63 units with aliases, grouped by dimension.
 
+use super::UnitDatabase;
 use crate::units::Unit;
 use crate::units::dimension::Dimension;
-use std::collections::HashMap;
+use crate::units::nonlinear;
+use crate::units::table::{self, PiecewiseTable};
 
 /// Helper: register every `alias` as a lookup key for `unit`.
-fn add(map: &mut HashMap<String, Unit>, aliases: &[&str], unit: Unit) {
+///
+/// A later `add` that reuses an alias wins; the collision is recorded on
+/// the database so `runits check` can report it.
+fn add(map: &mut UnitDatabase, aliases: &[&str], unit: Unit) {
     for alias in aliases {
-        map.insert((*alias).to_string(), unit.clone());
+        map.register(alias, unit.clone());
     }
 }
 
@@ -19,8 +24,21 @@ fn rename(mut unit: Unit, new_name: &str) -> Unit {
     unit
 }
 
+/// Helper: a table unit from builtin points whose `y` values are in
+/// `y_unit` (converted to base values here, so affine units work too).
+fn table_unit(name: &str, points: &[(f64, f64)], y_unit: &Unit) -> Unit {
+    let base_points = points
+        .iter()
+        .map(|&(x, y)| (x, y_unit.to_base_value(y)))
+        .collect();
+    let table = PiecewiseTable::new(base_points).expect("builtin tables are valid");
+    let mut unit = Unit::new_table(name, table, &[]);
+    unit.dimensions = y_unit.dimensions.clone();
+    unit
+}
+
 /// Seeds the builtin unit entries. Grouped by dimension for readability.
-pub(super) fn seed_all(map: &mut HashMap<String, Unit>) {
+pub(super) fn seed_all(map: &mut UnitDatabase) {
     // ---- SI base units + base extensions ----
     add(map, &["meter", "m", "meters", "metres"], Unit::meter());
     add(map, &["kilogram", "kg", "kilograms"], Unit::kilogram());
@@ -32,6 +50,41 @@ pub(super) fn seed_all(map: &mut HashMap<String, Unit>) {
     add(map, &["fahrenheit", "degF", "°F"], Unit::fahrenheit());
     add(map, &["rankine", "Ra"], Unit::rankine());
     add(map, &["reaumur", "Re", "°Re"], Unit::reaumur());
+    add(map, &["delisle", "degDe", "°De"], Unit::delisle());
+    add(map, &["romer", "degRo", "°Ro"], Unit::romer());
+    // Temperature differences: one degree of each scale, no offset. These
+    // are what `J/(kg*degC)` composes to automatically.
+    let delta = |unit: Unit| unit.delta_form().expect("affine scale");
+    add(map, &["delta_celsius", "deltaC"], delta(Unit::celsius()));
+    add(
+        map,
+        &["delta_fahrenheit", "deltaF"],
+        delta(Unit::fahrenheit()),
+    );
+    add(
+        map,
+        &["delta_rankine", "deltaR"],
+        Unit::new("delta_rankine", 5.0 / 9.0, &[(Dimension::Temperature, 1)]),
+    );
+    add(map, &["delta_reaumur", "deltaRe"], delta(Unit::reaumur()));
+    // Oven gas marks only exist as a table (gas mark 1/4 to 10).
+    add(
+        map,
+        &["gasmark"],
+        table_unit("gasmark", table::GAS_MARK_FAHRENHEIT, &Unit::fahrenheit()),
+    );
+    // Function forms, GNU-Units style: `tempC(20)` is 293.15 K, and values
+    // below absolute zero are rejected rather than converted.
+    add(
+        map,
+        &["tempC"],
+        Unit::new_nonlinear("tempC", &nonlinear::TEMP_C, &[(Dimension::Temperature, 1)]),
+    );
+    add(
+        map,
+        &["tempF"],
+        Unit::new_nonlinear("tempF", &nonlinear::TEMP_F, &[(Dimension::Temperature, 1)]),
+    );
     add(map, &["mole", "mol", "moles"], Unit::mole());
     add(map, &["candela", "cd"], Unit::candela());
     add(map, &["radian", "rad", "radians"], Unit::radian());
@@ -71,6 +124,50 @@ pub(super) fn seed_all(map: &mut HashMap<String, Unit>) {
         &["yard", "yd", "yards"],
         Unit::new("yard", 0.9144, &[(Dimension::Length, 1)]),
     );
+    // American wire gauge as a function: `wiregauge(10)`, `~wiregauge(2 mm)`.
+    add(
+        map,
+        &["wiregauge"],
+        Unit::new_nonlinear(
+            "wiregauge",
+            &nonlinear::WIRE_GAUGE,
+            &[(Dimension::Length, 1)],
+        ),
+    );
+    // Tabulated gauges and sizes: `12 awg -> mm`, `2 mm -> awg`.
+    add(
+        map,
+        &["awg"],
+        table_unit("awg", table::AWG_INCHES, &Unit::inch()),
+    );
+    add(
+        map,
+        &["sheetgauge"],
+        table_unit("sheetgauge", table::SHEET_GAUGE_INCHES, &Unit::inch()),
+    );
+    add(
+        map,
+        &["shoesize_men"],
+        table_unit("shoesize_men", table::SHOE_SIZE_MEN_INCHES, &Unit::inch()),
+    );
+    add(
+        map,
+        &["shoesize_women"],
+        table_unit(
+            "shoesize_women",
+            table::SHOE_SIZE_WOMEN_INCHES,
+            &Unit::inch(),
+        ),
+    );
+    add(
+        map,
+        &["ringsize"],
+        table_unit(
+            "ringsize",
+            table::RING_SIZE_MM,
+            &Unit::new("millimeter", 0.001, &[(Dimension::Length, 1)]),
+        ),
+    );
     add(
         map,
         &["nautical_mile", "nmi"],
@@ -273,6 +370,31 @@ pub(super) fn seed_all(map: &mut HashMap<String, Unit>) {
         Unit::new("rpm", 1.0 / 60.0, &[(Dimension::Time, -1)]),
     );
 
+    // ---- Fuel economy ----
+    // Distance per volume (Length^-2) and volume per distance (Length^2)
+    // are reciprocal dimensions, so `30 mpg -> L_per_100km` converts via
+    // 1/x. The imperial gallon is 4.54609 L (the `customary` module's
+    // `imperial_gallon` has the same value).
+    let us_gallon = Unit::new("gallon", 3.785411784e-3, &[(Dimension::Length, 3)]);
+    let imperial_gallon = Unit::new("imperial_gallon", 4.54609e-3, &[(Dimension::Length, 3)]);
+    add(
+        map,
+        &["mpg", "mpg_us", "miles_per_gallon"],
+        rename(Unit::mile() / us_gallon, "mpg"),
+    );
+    add(
+        map,
+        &["mpg_imp", "mpg_uk"],
+        rename(Unit::mile() / imperial_gallon, "mpg_imp"),
+    );
+    let liter = Unit::new("liter", 1e-3, &[(Dimension::Length, 3)]);
+    let hundred_km = Unit::new("100km", 1e5, &[(Dimension::Length, 1)]);
+    add(
+        map,
+        &["l_per_100km", "L_per_100km", "litres_per_100km"],
+        rename(liter / hundred_km, "l_per_100km"),
+    );
+
     // ---- Force (M·L·T⁻²) ----
     let force_dims = &[
         (Dimension::Mass, 1),
@@ -379,6 +501,69 @@ pub(super) fn seed_all(map: &mut HashMap<String, Unit>) {
         Unit::new("horsepower", 735.49875, power_dims),
     );
 
+    // ---- Electric potential (M·L²·T⁻³·I⁻¹) ----
+    let voltage_dims = &[
+        (Dimension::Mass, 1),
+        (Dimension::Length, 2),
+        (Dimension::Time, -3),
+        (Dimension::Current, -1),
+    ];
+    add(
+        map,
+        &["volt", "V", "volts"],
+        Unit::new_si("volt", 1.0, voltage_dims),
+    );
+
+    // ---- Charge, capacitance, resistance, conductance ----
+    add(
+        map,
+        &["coulomb", "C", "coulombs"],
+        Unit::new_si(
+            "coulomb",
+            1.0,
+            &[(Dimension::Current, 1), (Dimension::Time, 1)],
+        ),
+    );
+    add(
+        map,
+        &["farad", "F", "farads"],
+        Unit::new_si(
+            "farad",
+            1.0,
+            &[
+                (Dimension::Mass, -1),
+                (Dimension::Length, -2),
+                (Dimension::Time, 4),
+                (Dimension::Current, 2),
+            ],
+        ),
+    );
+    let resistance_dims = &[
+        (Dimension::Mass, 1),
+        (Dimension::Length, 2),
+        (Dimension::Time, -3),
+        (Dimension::Current, -2),
+    ];
+    add(
+        map,
+        &["ohm", "Ω", "ohms"],
+        Unit::new_si("ohm", 1.0, resistance_dims),
+    );
+    add(
+        map,
+        &["siemens", "S"],
+        Unit::new_si(
+            "siemens",
+            1.0,
+            &[
+                (Dimension::Mass, -1),
+                (Dimension::Length, -2),
+                (Dimension::Time, 3),
+                (Dimension::Current, 2),
+            ],
+        ),
+    );
+
     // ---- Historical length ----
     add(
         map,
@@ -465,4 +650,55 @@ pub(super) fn seed_all(map: &mut HashMap<String, Unit>) {
         &["sievert", "Sv"],
         Unit::new_si("sievert", 1.0, dose_dims),
     );
+
+    // ---- Logarithmic levels ----
+    // value = factor * log10(x / reference). Power ratios use factor 10,
+    // field (root-power) ratios 20. Bare dB/B/Np are dimensionless ratios;
+    // the suffixed forms carry the dimensions of their reference.
+    add(
+        map,
+        &["decibel", "dB"],
+        Unit::new_logarithmic("decibel", 1.0, 10.0, &[]),
+    );
+    add(map, &["bel"], Unit::new_logarithmic("bel", 1.0, 1.0, &[]));
+    // 1 Np = 20·log10(e) dB ≈ 8.686 dB: a neper is ln of a *field* ratio,
+    // i.e. power ratio e^(2·value).
+    add(
+        map,
+        &["neper", "Np", "nepers"],
+        Unit::new_logarithmic("neper", 1.0, std::f64::consts::LN_10 / 2.0, &[]),
+    );
+    add(
+        map,
+        &["dBm"],
+        Unit::new_logarithmic("dBm", 1e-3, 10.0, power_dims),
+    );
+    add(
+        map,
+        &["dBW"],
+        Unit::new_logarithmic("dBW", 1.0, 10.0, power_dims),
+    );
+    add(
+        map,
+        &["dBV"],
+        Unit::new_logarithmic("dBV", 1.0, 20.0, voltage_dims),
+    );
+    // Sound pressure level: 0 dB SPL = 20 µPa, the threshold of hearing.
+    add(
+        map,
+        &["dBSPL", "dB_SPL"],
+        Unit::new_logarithmic("dBSPL", 20e-6, 20.0, pressure_dims),
+    );
+    // pH = -log10([H+] / (1 mol/L)). Shadows the "picohenry" prefix reading;
+    // "picohenry" still resolves.
+    add(
+        map,
+        &["pH"],
+        Unit::new_logarithmic(
+            "pH",
+            1000.0,
+            -1.0,
+            &[(Dimension::AmountOfSubstance, 1), (Dimension::Length, -3)],
+        ),
+    );
 }
@@ -0,0 +1,345 @@
+//! Substance registry: densities and molar masses for bridging conversions.
+//!
+//! Volume and mass, or amount of substance and mass, are different
+//! dimensions — `2 cup -> g` has no answer until you say *what* is in the
+//! cup. A trailing substance name supplies the missing factor:
+//!
+//! ```text
+//! 2 cup flour -> g               (Volume -> Mass: × density)
+//! 5.5 mmol/L glucose -> mg/dL    (Amount -> Mass: × molar mass)
+//! ```
+//!
+//! [`SubstanceBridge::find`] looks for the combination of density and
+//! molar mass (each to the power −1, 0 or 1) that turns the source
+//! dimensions into the target's, so `mol water -> mL` (× M / ρ) works too.
+//! When a bridge would work but no substance was named, conversion fails
+//! with [`RUnitsError::SubstanceRequired`] instead of the bare dimension
+//! mismatch.
+//!
+//! Densities are for the form used in recipes (spooned flour, granulated
+//! sugar), taken from common cup-to-gram tables; chemical data is at
+//! 20 °C. Values are stored in SI: kg/m³ and kg/mol.
+//!
+//! FUTURE(substance-defs): let definition files add substances, the way
+//! `unit` and `const` statements extend the other registries.
+
+use crate::error::RUnitsError;
+use crate::units::dimension::{Dimension, DimensionMap};
+use crate::units::{Quantity, Rational, Unit};
+
+/// A named material with an optional density and molar mass.
+#[derive(Debug)]
+pub struct Substance {
+    /// Canonical name (`"flour"`).
+    pub name: &'static str,
+    /// Other accepted names (`"sucrose"` for sugar).
+    pub aliases: &'static [&'static str],
+    /// Density in kg/m³, if meaningful.
+    pub density: Option<f64>,
+    /// Molar mass in kg/mol, if the substance is a single compound.
+    pub molar_mass: Option<f64>,
+}
+
+/// Grams per US cup (236.5882365 mL) → kg/m³.
+const fn per_cup(grams: f64) -> f64 {
+    grams / 236.588_236_5 * 1000.0
+}
+
+/// The builtin substances.
+pub const SUBSTANCES: &[Substance] = &[
+    Substance {
+        name: "water",
+        aliases: &["H2O"],
+        density: Some(998.2),
+        molar_mass: Some(18.015e-3),
+    },
+    Substance {
+        name: "flour",
+        aliases: &["all_purpose_flour"],
+        density: Some(per_cup(125.0)),
+        molar_mass: None,
+    },
+    Substance {
+        name: "sugar",
+        aliases: &["sucrose", "granulated_sugar"],
+        density: Some(per_cup(200.0)),
+        molar_mass: Some(342.297e-3),
+    },
+    Substance {
+        name: "brown_sugar",
+        aliases: &[],
+        density: Some(per_cup(213.0)),
+        molar_mass: None,
+    },
+    Substance {
+        name: "butter",
+        aliases: &[],
+        density: Some(per_cup(227.0)),
+        molar_mass: None,
+    },
+    Substance {
+        name: "salt",
+        aliases: &["table_salt", "NaCl"],
+        density: Some(per_cup(292.0)),
+        molar_mass: Some(58.443e-3),
+    },
+    Substance {
+        name: "honey",
+        aliases: &[],
+        density: Some(1420.0),
+        molar_mass: None,
+    },
+    Substance {
+        name: "milk",
+        aliases: &[],
+        density: Some(1030.0),
+        molar_mass: None,
+    },
+    Substance {
+        name: "olive_oil",
+        aliases: &["oil"],
+        density: Some(911.0),
+        molar_mass: None,
+    },
+    Substance {
+        name: "rice",
+        aliases: &[],
+        density: Some(per_cup(185.0)),
+        molar_mass: None,
+    },
+    Substance {
+        name: "ethanol",
+        aliases: &["alcohol"],
+        density: Some(789.3),
+        molar_mass: Some(46.069e-3),
+    },
+    Substance {
+        name: "glucose",
+        aliases: &["dextrose"],
+        density: Some(1540.0),
+        molar_mass: Some(180.156e-3),
+    },
+    Substance {
+        name: "cholesterol",
+        aliases: &[],
+        density: Some(1067.0),
+        molar_mass: Some(386.654e-3),
+    },
+];
+
+/// Look up a substance by canonical name or alias.
+pub fn lookup(name: &str) -> Option<&'static Substance> {
+    SUBSTANCES
+        .iter()
+        .find(|s| s.name == name || s.aliases.contains(&name))
+}
+
+/// Split a trailing substance name off a source expression: `"2 cup flour"`
+/// → `("2 cup", Some(flour))`. Only the last word is considered, and only
+/// when something precedes it; anything else comes back unchanged.
+pub fn split_substance(input: &str) -> (&str, Option<&'static Substance>) {
+    let trimmed = input.trim_end();
+    if let Some((head, last)) = trimmed.rsplit_once([' ', '\t'])
+        && !head.trim().is_empty()
+        && let Some(substance) = lookup(last)
+    {
+        return (head.trim_end(), Some(substance));
+    }
+    (input, None)
+}
+
+const DENSITY: &[(Dimension, i8)] = &[(Dimension::Mass, 1), (Dimension::Length, -3)];
+const MOLAR_MASS: &[(Dimension, i8)] = &[(Dimension::Mass, 1), (Dimension::AmountOfSubstance, -1)];
+
+/// How a substance's properties bridge two dimensions: the target base
+/// value is the source's × density^`density_power` × molar mass^`molar_power`.
+#[derive(Debug, Clone, Copy)]
+pub struct SubstanceBridge {
+    pub substance: &'static Substance,
+    pub density_power: i32,
+    pub molar_power: i32,
+}
+
+impl SubstanceBridge {
+    /// The density/molar-mass powers that turn `from` into `to`, if any.
+    pub fn powers(from: &DimensionMap, to: &DimensionMap) -> Option<(i32, i32)> {
+        let candidates = [
+            (1, 0),
+            (-1, 0),
+            (0, 1),
+            (0, -1),
+            (-1, 1),
+            (1, -1),
+            (1, 1),
+            (-1, -1),
+        ];
+        candidates.into_iter().find(|&(d, m)| {
+            let mut dims = from.clone();
+            let scaled = DENSITY
+                .iter()
+                .map(|(dim, exp)| (dim, exp * d as i8))
+                .chain(MOLAR_MASS.iter().map(|(dim, exp)| (dim, exp * m as i8)));
+            for (dim, exp) in scaled {
+                let entry = dims.entry(dim.clone()).or_insert(Rational::ZERO);
+                // An exponent that overflows can't equal the target's.
+                let Some(sum) = entry.checked_add(Rational::from(exp)) else {
+                    return false;
+                };
+                *entry = sum;
+            }
+            dims.retain(|_, e| !e.is_zero());
+            dims == *to
+        })
+    }
+
+    /// Bridge `source` to `target` with `substance`. `Ok(None)` when no
+    /// combination of its properties fits the dimensions; an error when
+    /// one does but the substance lacks the property.
+    pub fn find(
+        substance: &'static Substance,
+        source: &Unit,
+        target: &Unit,
+    ) -> Result<Option<Self>, RUnitsError> {
+        let Some((density_power, molar_power)) =
+            Self::powers(&source.dimensions, &target.dimensions)
+        else {
+            return Ok(None);
+        };
+        let missing = |property: &str| RUnitsError::SubstanceProperty {
+            substance: substance.name.to_string(),
+            property: property.to_string(),
+        };
+        if density_power != 0 && substance.density.is_none() {
+            return Err(missing("density"));
+        }
+        if molar_power != 0 && substance.molar_mass.is_none() {
+            return Err(missing("molar mass"));
+        }
+        Ok(Some(SubstanceBridge {
+            substance,
+            density_power,
+            molar_power,
+        }))
+    }
+
+    /// The SI factor applied to the source base value.
+    pub fn factor(&self) -> f64 {
+        let density = self.substance.density.unwrap_or(1.0);
+        let molar = self.substance.molar_mass.unwrap_or(1.0);
+        density.powi(self.density_power) * molar.powi(self.molar_power)
+    }
+
+    /// What the factor is made of, for `--explain`: `"density"`,
+    /// `"molar mass / density"`, ….
+    pub fn describe(&self) -> String {
+        let mut num = Vec::new();
+        let mut den = Vec::new();
+        for (power, name) in [
+            (self.density_power, "density"),
+            (self.molar_power, "molar mass"),
+        ] {
+            match power {
+                1 => num.push(name),
+                -1 => den.push(name),
+                _ => {}
+            }
+        }
+        let num = if num.is_empty() {
+            "1".to_string()
+        } else {
+            num.join(" * ")
+        };
+        if den.is_empty() {
+            num
+        } else {
+            format!("{num} / {}", den.join(" / "))
+        }
+    }
+
+    /// Convert `source` to `target` through the substance.
+    pub fn convert(&self, source: &Quantity, target: &Unit) -> Result<Quantity, RUnitsError> {
+        let base = source.unit.to_base_value(source.value) * self.factor();
+        let base_unit = Unit::from_dimensions(
+            &target.to_base_unit_string(),
+            1.0,
+            target.dimensions.clone(),
+        );
+        Quantity::new(base, base_unit).convert_to(target)
+    }
+}
+
+/// Which property a substance would need to bridge `from` → `to`, if any:
+/// used to explain a dimension mismatch when no substance was given.
+pub fn needed_property(from: &DimensionMap, to: &DimensionMap) -> Option<&'static str> {
+    SubstanceBridge::powers(from, to).map(|(d, m)| match (d != 0, m != 0) {
+        (true, false) => "density",
+        (false, true) => "molar mass",
+        _ => "density and molar mass",
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::UnitDatabase;
+    use crate::parser::{parse_quantity, parse_unit_name};
+
+    fn convert(src: &str, dst: &str) -> Result<f64, RUnitsError> {
+        let db = UnitDatabase::new();
+        let (expr, substance) = split_substance(src);
+        let q = parse_quantity(expr, &db).unwrap();
+        let t = parse_unit_name(dst, &db).unwrap();
+        let bridge = SubstanceBridge::find(substance.unwrap(), &q.unit, &t)?.unwrap();
+        Ok(bridge.convert(&q, &t)?.value)
+    }
+
+    #[test]
+    fn splits_trailing_substance() {
+        let (expr, s) = split_substance("2 cup flour");
+        assert_eq!(expr, "2 cup");
+        assert_eq!(s.unwrap().name, "flour");
+        let (expr, s) = split_substance("5.5 mmol/L dextrose");
+        assert_eq!(expr, "5.5 mmol/L");
+        assert_eq!(s.unwrap().name, "glucose");
+        // A lone word, or a non-substance, is left alone.
+        assert!(split_substance("flour").1.is_none());
+        assert!(split_substance("2 cup").1.is_none());
+    }
+
+    #[test]
+    fn volume_to_mass_uses_density() {
+        let g = convert("2 cup flour", "g").unwrap();
+        assert!((g - 250.0).abs() < 1e-9, "{g}");
+        let cups = convert("100 g sugar", "cup").unwrap();
+        assert!((cups - 0.5).abs() < 1e-9, "{cups}");
+    }
+
+    #[test]
+    fn amount_to_mass_uses_molar_mass() {
+        let mg_dl = convert("5.5 mmol/L glucose", "mg/dL").unwrap();
+        assert!((mg_dl - 99.0858).abs() < 1e-3, "{mg_dl}");
+        // Amount -> volume needs both: M / ρ.
+        let ml = convert("1 mol water", "mL").unwrap();
+        assert!((ml - 18.0475).abs() < 1e-3, "{ml}");
+    }
+
+    #[test]
+    fn missing_property_is_an_error() {
+        let err = convert("1 mol flour", "g").unwrap_err();
+        assert!(err.to_string().contains("molar mass"), "{err}");
+    }
+
+    #[test]
+    fn describes_the_factor() {
+        let db = UnitDatabase::new();
+        let mol = parse_unit_name("mol", &db).unwrap();
+        let ml = parse_unit_name("mL", &db).unwrap();
+        let water = lookup("water").unwrap();
+        let bridge = SubstanceBridge::find(water, &mol, &ml).unwrap().unwrap();
+        assert_eq!(bridge.describe(), "molar mass / density");
+        assert_eq!(
+            needed_property(&ml.dimensions, &mol.dimensions),
+            Some("density and molar mass")
+        );
+    }
+}
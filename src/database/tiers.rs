@@ -0,0 +1,187 @@
+//! Database tiers: how much of the unit universe to load at startup.
+//!
+//! | Tier | Contents | Default for |
+//! |---|---|---|
+//! | `builtin` | the Rust seed table ([`UnitDatabase::new`]) | one-shot, batch, subcommands |
+//! | `standard` | builtin + the definition modules embedded from `modules/*.units` | REPL |
+//! | `full` | standard + a local GNU `definitions.units` ([`super::gnu`]) | opt-in |
+//!
+//! One-shot startup stays fast because nothing is parsed; the REPL can
+//! afford a few hundred definitions. [`build`] assembles the unit *and*
+//! constant databases together, so constants defined by modules and the
+//! units that reference them always come from the same tier.
+
+use super::constants::ConstantsDatabase;
+use super::gnu::{self, ImportReport};
+use super::{UnitDatabase, definitions};
+use crate::error::RUnitsError;
+use serde::Deserialize;
+use std::collections::HashSet;
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+/// Which set of units is loaded into the global databases.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, clap::ValueEnum, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Tier {
+    /// Hand-seeded builtins only.
+    #[default]
+    Builtin,
+    /// Builtins plus the embedded definition modules.
+    Standard,
+    /// Standard plus GNU Units' `definitions.units`, when installed.
+    Full,
+}
+
+impl Tier {
+    /// Lowercase name, as accepted by `--db` and the `db` config key.
+    pub fn name(self) -> &'static str {
+        match self {
+            Tier::Builtin => "builtin",
+            Tier::Standard => "standard",
+            Tier::Full => "full",
+        }
+    }
+}
+
+impl fmt::Display for Tier {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// Definition modules compiled into the binary for the standard tier,
+/// loaded in this order.
+const MODULES: &[(&str, &str)] = &[
+    ("astronomy.units", include_str!("modules/astronomy.units")),
+    ("customary.units", include_str!("modules/customary.units")),
+    ("nautical.units", include_str!("modules/nautical.units")),
+    ("time.units", include_str!("modules/time.units")),
+    ("typography.units", include_str!("modules/typography.units")),
+];
+
+/// Where GNU Units installs `definitions.units` on common systems.
+pub const GNU_SEARCH_PATHS: &[&str] = &[
+    "/usr/share/units/definitions.units",
+    "/usr/local/share/units/definitions.units",
+    "/opt/homebrew/share/units/definitions.units",
+];
+
+/// The first GNU `definitions.units` found in [`GNU_SEARCH_PATHS`].
+pub fn find_gnu_definitions() -> Option<PathBuf> {
+    GNU_SEARCH_PATHS
+        .iter()
+        .map(PathBuf::from)
+        .find(|path| path.is_file())
+}
+
+/// Whether `name` is a unit or constant from the Rust seed tables, rather
+/// than one added by a tier's modules or GNU import. A user file may
+/// replace the latter without `@override`: which tier is loaded depends on
+/// the mode (REPL or one-shot), and a `units.conf` shouldn't work in one
+/// and fail in the other.
+pub fn is_seeded(name: &str) -> bool {
+    static SEEDED: OnceLock<HashSet<String>> = OnceLock::new();
+    SEEDED
+        .get_or_init(|| {
+            let units = UnitDatabase::new();
+            let constants = ConstantsDatabase::new();
+            units
+                .unit_names()
+                .chain(constants.constant_names())
+                .map(str::to_string)
+                .collect()
+        })
+        .contains(name)
+}
+
+/// Unit and constant databases built for one tier.
+pub struct TierDatabases {
+    pub units: UnitDatabase,
+    pub constants: ConstantsDatabase,
+    /// Outcome of the GNU import (full tier with a definitions file only).
+    pub gnu_report: Option<ImportReport>,
+}
+
+/// Build both databases for `tier`. `gnu_file` is only read for
+/// [`Tier::Full`]; without one, the full tier equals the standard tier.
+pub fn build(tier: Tier, gnu_file: Option<&Path>) -> Result<TierDatabases, RUnitsError> {
+    let mut units = UnitDatabase::new();
+    let mut constants = ConstantsDatabase::new();
+    let mut gnu_report = None;
+
+    if tier != Tier::Builtin {
+        for (label, source) in MODULES {
+            definitions::load_str(source, label, &mut units, &mut constants)?;
+        }
+    }
+    if tier == Tier::Full
+        && let Some(path) = gnu_file
+    {
+        gnu_report = Some(gnu::import_file(path, &mut units, &constants)?);
+    }
+
+    units.tier = tier;
+    Ok(TierDatabases {
+        units,
+        constants,
+        gnu_report,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn builtin_tier_is_the_seed() {
+        let db = build(Tier::Builtin, None).unwrap();
+        assert_eq!(db.units.len(), UnitDatabase::new().len());
+        assert_eq!(db.units.tier(), Tier::Builtin);
+        assert!(db.units.lookup("knot").is_none());
+    }
+
+    #[test]
+    fn standard_tier_adds_every_module() {
+        let db = build(Tier::Standard, None).unwrap();
+        assert_eq!(db.units.tier(), Tier::Standard);
+        assert!(db.units.len() > UnitDatabase::new().len());
+        for name in ["M_sun", "quart", "knot", "fortnight", "pica"] {
+            assert!(db.units.lookup(name).is_some(), "{name} missing");
+        }
+    }
+
+    #[test]
+    fn full_tier_without_gnu_file_matches_standard() {
+        let full = build(Tier::Full, None).unwrap();
+        let standard = build(Tier::Standard, None).unwrap();
+        assert_eq!(full.units.len(), standard.units.len());
+        assert!(full.gnu_report.is_none());
+        assert_eq!(full.units.tier(), Tier::Full);
+    }
+
+    #[test]
+    fn full_tier_imports_gnu_file() {
+        let dir = std::env::temp_dir().join(format!("runits-tiers-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("definitions.units");
+        std::fs::write(&path, "m !\nsmoot 1.7018 m\nfurlong 660 ft\n").unwrap();
+
+        let db = build(Tier::Full, Some(&path)).unwrap();
+        let report = db.gnu_report.unwrap();
+        assert_eq!(report.units, 1);
+        assert!(db.units.lookup("smoot").is_some());
+    }
+
+    #[test]
+    fn tier_names_round_trip_through_config() {
+        #[derive(Deserialize)]
+        struct Probe {
+            db: Tier,
+        }
+        let probe: Probe = toml::from_str("db = \"full\"").unwrap();
+        assert_eq!(probe.db, Tier::Full);
+        assert_eq!(Tier::Standard.to_string(), "standard");
+    }
+}
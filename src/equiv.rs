@@ -0,0 +1,358 @@
+//! Equivalency contexts: opt-in conversions across dimensions.
+//!
+//! Some conversions are not dimensionally valid but are physically routine
+//! once a fundamental constant is taken as given. A photon's wavelength,
+//! frequency, energy and wavenumber are all the same fact (`E = h·ν = h·c/λ`);
+//! a mass *is* an energy up to `c²`; a temperature is an energy scale up to
+//! `k_B`. Like astropy's equivalencies, these bridges are off by default and
+//! enabled per conversion (`--equiv spectral`) or per REPL session
+//! (`using spectral`), so `500 nm -> eV` stays an error unless asked for.
+//!
+//! Each [`Equivalency`] is a small hub-and-spoke graph. Every [`Member`]
+//! relates to the hub quantity (frequency for spectral, energy for the
+//! others) by a power law `hub = K · x^p`, with `p = ±1` and `K` a product
+//! of constants. Converting between two members goes through the hub, so
+//! `--explain` can print each leg with the constant that bridged it.
+//! Constant values come from [`crate::database::constants`].
+
+use crate::database::constants;
+use crate::error::RUnitsError;
+use crate::units::dimension::{Dimension, DimensionMap, create_dimensions};
+use crate::units::{Quantity, Unit};
+use serde::Deserialize;
+use std::fmt;
+
+/// A named family of cross-dimension conversions.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Equivalency {
+    /// Wavelength, frequency, photon energy and wavenumber (`c`, `h`).
+    Spectral,
+    /// Rest mass and energy, `E = m·c²`.
+    MassEnergy,
+    /// Temperature and energy, `E = k_B·T`.
+    Thermal,
+}
+
+impl Equivalency {
+    /// Every equivalency, in the order `using` lists them.
+    pub const ALL: [Equivalency; 3] = [
+        Equivalency::Spectral,
+        Equivalency::MassEnergy,
+        Equivalency::Thermal,
+    ];
+
+    /// Name as accepted by `--equiv`, the `equiv` config key and `using`.
+    pub fn name(self) -> &'static str {
+        match self {
+            Equivalency::Spectral => "spectral",
+            Equivalency::MassEnergy => "mass-energy",
+            Equivalency::Thermal => "thermal",
+        }
+    }
+
+    /// Parse a name, case-insensitively (`using Spectral`).
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|e| e.name().eq_ignore_ascii_case(name))
+    }
+
+    /// Unit of the hub quantity (frequency or energy), for `--explain`.
+    fn hub_unit(self) -> &'static str {
+        match self {
+            Equivalency::Spectral => "Hz",
+            Equivalency::MassEnergy | Equivalency::Thermal => "J",
+        }
+    }
+
+    fn members(self) -> &'static [Member] {
+        match self {
+            Equivalency::Spectral => SPECTRAL,
+            Equivalency::MassEnergy => MASS_ENERGY,
+            Equivalency::Thermal => THERMAL,
+        }
+    }
+}
+
+impl fmt::Display for Equivalency {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// One quantity in an equivalency, related to the hub by `hub = K · x^power`
+/// where `K` is the product of `constants` (name, exponent).
+#[derive(Debug)]
+pub struct Member {
+    /// What the quantity is called (`"wavelength"`).
+    pub name: &'static str,
+    dimensions: &'static [(Dimension, i8)],
+    power: i32,
+    constants: &'static [(&'static str, i32)],
+    /// The relation solved for the hub (`"ν = c / λ"`); empty for the hub.
+    pub to_hub: &'static str,
+    /// The relation solved for this member (`"λ = c / ν"`); empty for the hub.
+    pub from_hub: &'static str,
+}
+
+const ENERGY: &[(Dimension, i8)] = &[
+    (Dimension::Mass, 1),
+    (Dimension::Length, 2),
+    (Dimension::Time, -2),
+];
+
+const SPECTRAL: &[Member] = &[
+    Member {
+        name: "wavelength",
+        dimensions: &[(Dimension::Length, 1)],
+        power: -1,
+        constants: &[("speed_of_light", 1)],
+        to_hub: "ν = c / λ",
+        from_hub: "λ = c / ν",
+    },
+    Member {
+        name: "frequency",
+        dimensions: &[(Dimension::Time, -1)],
+        power: 1,
+        constants: &[],
+        to_hub: "",
+        from_hub: "",
+    },
+    Member {
+        name: "energy",
+        dimensions: ENERGY,
+        power: 1,
+        constants: &[("planck_constant", -1)],
+        to_hub: "ν = E / h",
+        from_hub: "E = h·ν",
+    },
+    Member {
+        name: "wavenumber",
+        dimensions: &[(Dimension::Length, -1)],
+        power: 1,
+        constants: &[("speed_of_light", 1)],
+        to_hub: "ν = c·ν̃",
+        from_hub: "ν̃ = ν / c",
+    },
+];
+
+const MASS_ENERGY: &[Member] = &[
+    Member {
+        name: "mass",
+        dimensions: &[(Dimension::Mass, 1)],
+        power: 1,
+        constants: &[("speed_of_light", 2)],
+        to_hub: "E = m·c²",
+        from_hub: "m = E / c²",
+    },
+    Member {
+        name: "energy",
+        dimensions: ENERGY,
+        power: 1,
+        constants: &[],
+        to_hub: "",
+        from_hub: "",
+    },
+];
+
+const THERMAL: &[Member] = &[
+    Member {
+        name: "temperature",
+        dimensions: &[(Dimension::Temperature, 1)],
+        power: 1,
+        constants: &[("boltzmann_constant", 1)],
+        to_hub: "E = k_B·T",
+        from_hub: "T = E / k_B",
+    },
+    Member {
+        name: "energy",
+        dimensions: ENERGY,
+        power: 1,
+        constants: &[],
+        to_hub: "",
+        from_hub: "",
+    },
+];
+
+impl Member {
+    fn dims(&self) -> DimensionMap {
+        create_dimensions(self.dimensions)
+    }
+
+    /// `K`, evaluated from the constants database.
+    fn factor(&self) -> f64 {
+        self.constants
+            .iter()
+            .map(|&(name, exp)| constant_si(name).powi(exp))
+            .product()
+    }
+
+    fn hub_from(&self, x: f64) -> f64 {
+        self.factor() * x.powi(self.power)
+    }
+
+    fn value_from_hub(&self, hub: f64) -> f64 {
+        (hub / self.factor()).powi(self.power)
+    }
+}
+
+/// SI value of a builtin constant.
+fn constant_si(name: &str) -> f64 {
+    let c = constants::global()
+        .lookup(name)
+        .unwrap_or_else(|| panic!("equivalency constant '{name}' missing"));
+    c.unit.to_base_value(c.value)
+}
+
+/// A conversion that crossed dimensions through an equivalency: which one,
+/// and the members on either side.
+#[derive(Debug, Clone, Copy)]
+pub struct Bridge {
+    pub equivalency: Equivalency,
+    pub from: &'static Member,
+    pub to: &'static Member,
+}
+
+impl Bridge {
+    /// Find the first enabled equivalency linking the two dimension maps.
+    pub fn find(enabled: &[Equivalency], from: &DimensionMap, to: &DimensionMap) -> Option<Self> {
+        enabled.iter().find_map(|&equivalency| {
+            let members = equivalency.members();
+            let from = members.iter().find(|m| m.dims() == *from)?;
+            let to = members.iter().find(|m| m.dims() == *to)?;
+            Some(Bridge {
+                equivalency,
+                from,
+                to,
+            })
+        })
+    }
+
+    /// Base value of `from` → hub value.
+    pub fn hub_value(&self, base_value: f64) -> f64 {
+        self.from.hub_from(base_value)
+    }
+
+    /// Base value of `from` → hub value → base value of `to`.
+    pub fn apply(&self, base_value: f64) -> f64 {
+        self.to.value_from_hub(self.hub_value(base_value))
+    }
+
+    /// Unit of the hub quantity, for `--explain`.
+    pub fn hub_unit(&self) -> &'static str {
+        self.equivalency.hub_unit()
+    }
+
+    /// Canonical names of the constants used, in order and deduplicated.
+    pub fn constants(&self) -> Vec<&'static str> {
+        let mut names: Vec<&'static str> = Vec::new();
+        for &(name, _) in self.from.constants.iter().chain(self.to.constants) {
+            if !names.contains(&name) {
+                names.push(name);
+            }
+        }
+        names
+    }
+
+    /// Convert `source` to `target` across the bridge. A non-finite result
+    /// (`0 nm -> eV`, an infinite photon energy) is a domain error.
+    pub fn convert(&self, source: &Quantity, target: &Unit) -> Result<Quantity, RUnitsError> {
+        let base = source.unit.to_base_value(source.value);
+        let out = self.apply(base);
+        if !out.is_finite() {
+            return Err(RUnitsError::OutOfDomain {
+                unit: target.name.clone(),
+                value: source.to_string(),
+                valid: format!("non-zero {}s", self.from.name),
+            });
+        }
+        let base_unit = Unit::from_dimensions(
+            &target.to_base_unit_string(),
+            1.0,
+            target.dimensions.clone(),
+        );
+        Quantity::new(out, base_unit).convert_to(target)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::UnitDatabase;
+    use crate::parser::{parse_quantity, parse_unit_name};
+
+    fn convert(equiv: Equivalency, src: &str, dst: &str) -> f64 {
+        let db = UnitDatabase::new();
+        let q = parse_quantity(src, &db).unwrap();
+        let t = parse_unit_name(dst, &db).unwrap();
+        let bridge = Bridge::find(&[equiv], &q.unit.dimensions, &t.dimensions).unwrap();
+        bridge.convert(&q, &t).unwrap().value
+    }
+
+    fn close(a: f64, b: f64) -> bool {
+        ((a - b) / b).abs() < 1e-5
+    }
+
+    #[test]
+    fn spectral_wavelength_energy_frequency() {
+        let ev = convert(Equivalency::Spectral, "500 nm", "eV");
+        assert!(close(ev, 2.479_68), "{ev}");
+        let nm = convert(Equivalency::Spectral, "2.47968 eV", "nm");
+        assert!(close(nm, 500.0), "{nm}");
+        let thz = convert(Equivalency::Spectral, "500 nm", "THz");
+        assert!(close(thz, 599.585), "{thz}");
+        let per_cm = convert(Equivalency::Spectral, "500 nm", "1/cm");
+        assert!(close(per_cm, 20_000.0), "{per_cm}");
+    }
+
+    #[test]
+    fn mass_energy_and_thermal() {
+        let mev = convert(Equivalency::MassEnergy, "1 kg", "J");
+        assert!(close(mev, 8.987_551_787e16), "{mev}");
+        let mev = convert(Equivalency::Thermal, "300 K", "eV");
+        assert!(close(mev, 0.025_852), "{mev}");
+        // Affine targets get their offset back.
+        let c = convert(Equivalency::Thermal, "0.025852 eV", "degC");
+        assert!((c - 26.85).abs() < 0.01, "{c}");
+    }
+
+    #[test]
+    fn only_enabled_equivalencies_bridge() {
+        let db = UnitDatabase::new();
+        let kg = parse_unit_name("kg", &db).unwrap();
+        let j = parse_unit_name("J", &db).unwrap();
+        assert!(Bridge::find(&[Equivalency::Spectral], &kg.dimensions, &j.dimensions).is_none());
+        let bridge = Bridge::find(
+            &[Equivalency::Spectral, Equivalency::MassEnergy],
+            &kg.dimensions,
+            &j.dimensions,
+        )
+        .unwrap();
+        assert_eq!(bridge.equivalency, Equivalency::MassEnergy);
+        assert_eq!(bridge.constants(), vec!["speed_of_light"]);
+    }
+
+    #[test]
+    fn zero_wavelength_is_out_of_domain() {
+        let db = UnitDatabase::new();
+        let q = parse_quantity("0 nm", &db).unwrap();
+        let t = parse_unit_name("eV", &db).unwrap();
+        let bridge =
+            Bridge::find(&[Equivalency::Spectral], &q.unit.dimensions, &t.dimensions).unwrap();
+        let err = bridge.convert(&q, &t).unwrap_err();
+        assert!(matches!(err, RUnitsError::OutOfDomain { .. }));
+    }
+
+    #[test]
+    fn names_round_trip() {
+        for e in Equivalency::ALL {
+            assert_eq!(Equivalency::from_name(e.name()), Some(e));
+        }
+        assert_eq!(
+            Equivalency::from_name("Mass-Energy"),
+            Some(Equivalency::MassEnergy)
+        );
+        assert_eq!(Equivalency::from_name("nuclear"), None);
+    }
+}
@@ -43,27 +43,67 @@ pub enum RUnitsError {
         to_dim: String,
     },
 
-    /// Affine units (e.g., temperature scales) cannot form compound units.
-    ///
-    /// Fired during *unit composition* (e.g. `celsius*m` at parse time).
-    /// The sibling variant [`AffineInExpression`] fires during *quantity
-    /// evaluation* (e.g. `20 celsius + 5 celsius`). Kept separate so the
-    /// follow-up colored-errors step can render a temperature-specific hint
-    /// for the expression case without affecting the composition-path
-    /// messaging.
-    #[error(
-        "cannot compose affine unit '{0}' in multiplication/division (temperature units cannot form compound units)"
-    )]
-    AffineComposition(String),
-
     /// Affine unit used in an expression-level operation (+ - * / ^, or a
     /// math function). Carries the offending unit name plus a short string
     /// describing the operator context (e.g. `"+"`, `"sqrt"`, `"^"`).
+    ///
+    /// Bare scales inside compound units (`J/(kg*degC)`) never get here:
+    /// composition swaps in the scale's delta form instead.
     #[error(
-        "cannot use affine unit '{unit}' with '{op_context}': temperature units cannot be used in arithmetic expressions (use kelvin for temperature deltas)"
+        "cannot use affine unit '{unit}' with '{op_context}': temperature units cannot be used in arithmetic expressions (use kelvin or deltaC/deltaF for temperature differences)"
     )]
     AffineInExpression { unit: String, op_context: String },
 
+    /// Logarithmic units (dB, neper, pH) cannot form compound units.
+    /// Fired at parse time for `dBm/s` or `dB^2`; unlike temperature scales,
+    /// levels have no linear "delta" form to fall back on.
+    #[error(
+        "cannot compose logarithmic unit '{0}' in multiplication/division (levels like dB and pH cannot form compound units)"
+    )]
+    LogarithmicComposition(String),
+
+    /// Logarithmic unit used in an expression-level operation. The
+    /// counterpart of [`AffineInExpression`]: adding two levels adds their
+    /// *ratios*, which plain `+` would get wrong.
+    #[error(
+        "cannot use logarithmic unit '{unit}' with '{op_context}': levels cannot be used in arithmetic expressions (convert to a linear unit first, e.g. dBm -> W)"
+    )]
+    LogarithmicInExpression { unit: String, op_context: String },
+
+    /// Function-defined units (`tempC`, `wiregauge`) cannot form compound
+    /// units. Fired at parse time, like [`LogarithmicComposition`].
+    #[error(
+        "cannot compose function unit '{0}' in multiplication/division (call it instead, e.g. {0}(10))"
+    )]
+    NonlinearComposition(String),
+
+    /// Function-defined unit used in an expression-level operation.
+    /// `wiregauge(10) * 2` is fine — the call yields a plain length — but
+    /// `10 wiregauge * 2` would scale a gauge number.
+    #[error(
+        "cannot use function unit '{unit}' with '{op_context}': call it instead (e.g. {unit}(10)) to get a plain quantity"
+    )]
+    NonlinearInExpression { unit: String, op_context: String },
+
+    /// A dimension exponent grew past what an `i32` fraction holds:
+    /// `m^(1/65537) * m^(1/65539)` needs a denominator beyond 2³¹.
+    /// `expression` is the unit being built.
+    #[error(
+        "dimension exponent overflow in '{expression}': the exponent's fraction is too large to represent"
+    )]
+    ExponentOverflow { expression: String },
+
+    /// A value fell outside what a nonlinear conversion accepts: `tempC(-300)`
+    /// (below the function's domain), `-5 K -> tempC` (outside its range), or
+    /// `-1 W -> dBm` (a level of a non-positive quantity). Replaces the NaN
+    /// the bare formula would produce.
+    #[error("{value} is out of range for '{unit}' (valid: {valid})")]
+    OutOfDomain {
+        unit: String,
+        value: String,
+        valid: String,
+    },
+
     /// `lhs + rhs` or `lhs - rhs` where the two operands have different
     /// dimensions. Carries both unit names AND both dimension strings so the
     /// colored-errors step can highlight each in its dimension color.
@@ -79,6 +119,28 @@ pub enum RUnitsError {
         rhs_dim: String,
     },
 
+    /// The dimensions differ by a density or molar mass, but the input
+    /// named no substance (`2 cup -> g`). `property` is what's missing.
+    #[error(
+        "cannot convert '{from}' to '{to}' without a substance: the conversion needs a {property} (name one after the quantity, e.g. '1 {from} water -> {to}')"
+    )]
+    SubstanceRequired {
+        from: String,
+        to: String,
+        property: String,
+    },
+
+    /// A substance was named but lacks the property the conversion needs
+    /// (`1 mol flour -> g`: flour has no molar mass).
+    #[error("substance '{substance}' has no known {property}")]
+    SubstanceProperty { substance: String, property: String },
+
+    /// `+` / `-` on absolute temperature readings in a combination with no
+    /// physical meaning: two readings added, or a reading subtracted from a
+    /// difference. `expression` echoes the operands; `reason` says why.
+    #[error("cannot compute '{expression}': {reason}")]
+    TemperatureArithmetic { expression: String, reason: String },
+
     /// An identifier in an expression couldn't be resolved against either
     /// the unit database or the constants database. Carries fuzzy suggestions
     /// merged from both pools.
@@ -96,19 +158,76 @@ pub enum RUnitsError {
         suggestions: Vec<String>,
     },
 
-    /// A function call passed the wrong number of arguments.
+    /// A mixed-unit input (`6 ft 3 in`) whose parts don't add up: different
+    /// dimensions, a non-linear unit, or units not ordered largest first.
+    #[error("invalid mixed-unit quantity '{expression}': {reason}")]
+    MixedUnits { expression: String, reason: String },
+
+    /// A `;`-separated target unit list (`ft;in`) mixes dimensions, holds a
+    /// non-linear unit, or is not ordered largest first.
+    #[error("invalid unit list '{list}': {reason}")]
+    UnitList { list: String, reason: String },
+
+    /// `-> auto <family>` named a unit family that doesn't exist.
+    #[error("unknown unit family '{name}'. Known: {known}")]
+    UnknownUnitFamily { name: String, known: String },
+
+    /// A chemical formula named an element symbol that doesn't exist.
+    /// Carries fuzzy suggestions over element symbols and names.
+    #[error("{}", format_unknown_element(.symbol, .suggestions))]
+    UnknownElement {
+        symbol: String,
+        suggestions: Vec<String>,
+    },
+
+    /// A chemical formula was malformed: unbalanced brackets, a zero count,
+    /// a lowercase start, ....
+    #[error("invalid chemical formula '{formula}': {reason}")]
+    ChemicalFormula { formula: String, reason: String },
+
+    /// A function call passed the wrong number of arguments. `name` is
+    /// owned because function units (`tempC(x)`) are called too, and their
+    /// names come from the database.
     #[error("function '{name}' expects {expected} argument(s), got {got}")]
     ArityMismatch {
-        name: &'static str,
+        name: String,
         expected: usize,
         got: usize,
     },
 
+    /// A builtin with a variable argument count (`max`, `hypot`) was called
+    /// with too few or too many. `expected` is the
+    /// [`Arity`](crate::math::Arity) as text: `at least 2`, `1 to 2`.
+    #[error("function '{name}' expects {expected} argument(s), got {got}")]
+    ArityRangeMismatch {
+        name: String,
+        expected: String,
+        got: usize,
+    },
+
+    /// A function was called with an argument, or produced a result, of the
+    /// wrong dimension: against a user function's `: Quantity` annotation,
+    /// or against the first argument of a builtin like `max` or `hypot`
+    /// whose arguments must agree. `what` names the slot: `parameter 'm'`,
+    /// `argument 2` or `result`.
+    #[error("function '{name}': {what} must be {expected}, got {got}")]
+    TypedParameterMismatch {
+        name: String,
+        what: String,
+        expected: String,
+        got: String,
+    },
+
+    /// A `name(params) = body` definition is malformed, reuses a builtin
+    /// function's name, or would make the function call itself.
+    #[error("invalid function definition '{definition}': {reason}")]
+    FunctionDefinition { definition: String, reason: String },
+
     /// A math function rejected its argument for a domain-specific reason
     /// (e.g. `sqrt` of a negative number, `sin` of a dimensioned quantity,
     /// `sqrt` of a dimension with an odd exponent).
     #[error("function '{name}': {reason}")]
-    FunctionDomainError { name: &'static str, reason: String },
+    FunctionDomainError { name: String, reason: String },
 
     /// The previous-result variable `_` was used before any successful
     /// evaluation in the current REPL session (or in a context where no
@@ -116,6 +235,18 @@ pub enum RUnitsError {
     #[error("no previous result: '_' has no value yet (evaluate an expression first)")]
     PreviousResultUnavailable,
 
+    /// A unit-definition file (or one of its includes) failed to load.
+    /// Carries the file and 1-based line of the offending statement so the
+    /// message reads like a compiler diagnostic: `units.conf:12: ...`.
+    /// `line` is 0 when the failure isn't tied to a line (e.g. the file
+    /// itself can't be read).
+    #[error("{}", format_definition_error(.file, *.line, .reason))]
+    Definition {
+        file: String,
+        line: usize,
+        reason: String,
+    },
+
     /// Input didn't match the quantity grammar. The inner pest error
     /// carries a source-span pointer for precise user feedback. Boxed so
     /// `RUnitsError` stays small (clippy's `result_large_err` guidance).
@@ -155,3 +286,22 @@ fn format_unknown_function(name: &str, suggestions: &[String]) -> String {
         )
     }
 }
+
+fn format_unknown_element(symbol: &str, suggestions: &[String]) -> String {
+    if suggestions.is_empty() {
+        format!("unknown element: '{symbol}'")
+    } else {
+        format!(
+            "unknown element: '{symbol}'. Did you mean: {}?",
+            suggestions.join(", ")
+        )
+    }
+}
+
+fn format_definition_error(file: &str, line: usize, reason: &str) -> String {
+    if line == 0 {
+        format!("{file}: {reason}")
+    } else {
+        format!("{file}:{line}: {reason}")
+    }
+}
@@ -9,6 +9,9 @@
 //!
 //! When `Expr::Ident(name)` is evaluated, the resolver tries, in order:
 //!
+//! 0. **Session variables** (`x = 5 ft` in the REPL or `--batch`), when the
+//!    context carries any. The REPL warns when an assignment shadows a unit
+//!    or constant, so this can't silently change what `m` means.
 //! 1. **Unit database first** — handles SI/binary prefix stripping, so
 //!    `kmeter`, `µs`, `Gbyte` resolve here.
 //! 2. **Constants database second** — constants are deliberately named to
@@ -16,19 +19,30 @@
 //!    so unit-first doesn't steal constant lookups.
 //! 3. **Fuzzy suggestions** from both databases on failure.
 //!
-//! The order is pinned by the `ident_resolution_units_first` test below and
-//! matches Numbat's behaviour.
+//! The order is pinned by the `ident_resolution_units_first` and
+//! `variables_shadow_units` tests below and, for 1–3, matches Numbat's
+//! behaviour.
 
+use crate::chemistry;
 use crate::database::UnitDatabase;
 use crate::database::constants::ConstantsDatabase;
+use crate::database::substances;
 use crate::error::RUnitsError;
 use crate::expr::{BinOp, Expr};
+use crate::functions::Functions;
 use crate::math;
 use crate::units::Quantity;
 use crate::units::Unit;
+use crate::units::quantity::{compose_operand, require_linear};
+use crate::units::unit::{ConversionKind, power_suffix};
+use std::collections::BTreeMap;
 
-/// Evaluation context — supplies the databases and the optional previous
-/// result for the `_` variable.
+/// Named session variables (`x = 5 ft`), sorted by name for `vars`.
+pub type Variables = BTreeMap<String, Quantity>;
+
+/// Evaluation context — supplies the databases, the optional previous
+/// result for the `_` variable, and any session variables and user-defined
+/// functions.
 ///
 /// Held as references so the evaluator can borrow from long-lived singletons
 /// and a short-lived REPL state without any ownership gymnastics.
@@ -36,17 +50,21 @@ pub struct EvalContext<'a> {
     pub units: &'a UnitDatabase,
     pub constants: &'a ConstantsDatabase,
     pub previous: Option<&'a Quantity>,
+    pub variables: Option<&'a Variables>,
+    pub functions: Option<&'a Functions>,
 }
 
 impl<'a> EvalContext<'a> {
     /// Build a context for one-shot CLI evaluation: no previous result,
-    /// global constants singleton. The caller supplies the `UnitDatabase`
+    /// global constants and user-function singletons. The caller supplies the `UnitDatabase`
     /// so tests can use a fresh instance rather than the global singleton.
     pub fn one_shot(units: &'a UnitDatabase) -> Self {
         Self {
             units,
             constants: crate::database::constants::global(),
             previous: None,
+            variables: None,
+            functions: Some(crate::functions::global()),
         }
     }
 
@@ -61,7 +79,36 @@ impl<'a> EvalContext<'a> {
             units,
             constants,
             previous,
+            variables: None,
+            functions: None,
+        }
+    }
+
+    /// The same context, resolving identifiers against `variables` first.
+    pub fn with_variables(self, variables: &'a Variables) -> Self {
+        Self {
+            variables: Some(variables),
+            ..self
+        }
+    }
+
+    /// The same context, with `functions` callable.
+    pub fn with_functions(self, functions: &'a Functions) -> Self {
+        Self {
+            functions: Some(functions),
+            ..self
+        }
+    }
+
+    /// What `name` means without session variables — the unit or constant
+    /// a variable of that name would shadow, as `"unit 'meter'"`.
+    pub fn shadowed(&self, name: &str) -> Option<String> {
+        if let Some(unit) = self.units.lookup(name) {
+            return Some(format!("unit '{}'", unit.name));
         }
+        self.constants
+            .lookup(name)
+            .map(|c| format!("constant '{}'", c.name))
     }
 }
 
@@ -113,6 +160,9 @@ pub fn eval(expr: &Expr, ctx: &EvalContext) -> Result<Quantity, RUnitsError> {
         // `UnknownIdentifier` — this is the intended behaviour and has a
         // dedicated test.
         Expr::Ident(name) => {
+            if let Some(value) = ctx.variables.and_then(|vars| vars.get(name)) {
+                return Ok(value.clone());
+            }
             if let Some(unit) = ctx.units.lookup(name) {
                 return Ok(Quantity::new(1.0, unit));
             }
@@ -143,24 +193,268 @@ pub fn eval(expr: &Expr, ctx: &EvalContext) -> Result<Quantity, RUnitsError> {
             match op {
                 BinOp::Add => lhs_q.try_add(rhs_q),
                 BinOp::Sub => lhs_q.try_sub(rhs_q),
-                BinOp::Mul => lhs_q.mul(rhs_q),
-                BinOp::Div => lhs_q.div(rhs_q),
+                BinOp::Mul => {
+                    // A number times a bare non-linear unit name applies the
+                    // unit: `20 degC` is a reading, `30 dBm` a level.
+                    let is_scalar =
+                        |q: &Quantity| q.unit.dimensions.is_empty() && q.unit.is_linear();
+                    let applies = |expr: &Expr, q: &Quantity, other: &Quantity| {
+                        !q.unit.is_linear() && is_unit_reference(expr, ctx) && is_scalar(other)
+                    };
+                    if applies(lhs, &lhs_q, &rhs_q) || applies(rhs, &rhs_q, &lhs_q) {
+                        return lhs_q.mul(rhs_q);
+                    }
+                    let lhs_q = composed_operand(lhs, lhs_q, "*", ctx)?;
+                    let rhs_q = composed_operand(rhs, rhs_q, "*", ctx)?;
+                    lhs_q.mul(rhs_q)
+                }
+                BinOp::Div => {
+                    // `2 degC/m` arrives as `(2 degC) / m`: a reading over a
+                    // dimensioned unit name only makes sense as a gradient.
+                    // Over a value (`20 degC / 1 m`) it stays a reading.
+                    let gradient = is_unit_reference(rhs, ctx)
+                        && !rhs_q.unit.dimensions.is_empty()
+                        && lhs_q.unit.delta_form().is_some();
+                    let lhs_q = if gradient {
+                        compose_operand(lhs_q)
+                    } else {
+                        composed_operand(lhs, lhs_q, "/", ctx)?
+                    };
+                    let rhs_q = composed_operand(rhs, rhs_q, "/", ctx)?;
+                    lhs_q.div(rhs_q)
+                }
             }
         }
 
         Expr::Neg(inner) => Ok(eval(inner, ctx)?.neg()),
 
-        Expr::Pow(base, n) => eval(base, ctx)?.pow_i32(*n),
+        // `6 ft 3 in`: each segment's unit must be linear, share the first
+        // one's dimension and be smaller than the one before it — the order
+        // field notes are written in. The sum keeps the first unit.
+        Expr::Mixed(parts) => {
+            let mut total: Option<Quantity> = None;
+            let mut previous: Option<(Unit, f64)> = None;
+            for (value, unit_expr) in parts {
+                let unit_q = eval(unit_expr, ctx)?;
+                let invalid = |reason: String| RUnitsError::MixedUnits {
+                    expression: mixed_text(parts),
+                    reason,
+                };
+                if !unit_q.unit.is_linear() {
+                    return Err(invalid(format!(
+                        "'{}' is not a linear unit",
+                        unit_q.unit.name
+                    )));
+                }
+                let scale = unit_q.unit.to_base_value(unit_q.value).abs();
+                if let Some((prev_unit, prev_scale)) = &previous {
+                    if prev_unit.dimensions != unit_q.unit.dimensions {
+                        return Err(invalid(format!(
+                            "'{}' ({}) does not match '{}' ({}); write '*' to multiply",
+                            unit_q.unit.name,
+                            unit_q.unit.dimension_string(),
+                            prev_unit.name,
+                            prev_unit.dimension_string()
+                        )));
+                    }
+                    if scale >= *prev_scale {
+                        return Err(invalid(format!(
+                            "units must run from largest to smallest, but '{}' is not smaller than '{}'",
+                            unit_q.unit.name, prev_unit.name
+                        )));
+                    }
+                }
+                let term = Quantity::new(value * unit_q.value, unit_q.unit.clone());
+                total = Some(match total {
+                    Some(sum) => sum.try_add(term)?,
+                    None => term,
+                });
+                previous = Some((unit_q.unit, scale));
+            }
+            Ok(total.expect("a mixed quantity has at least two parts"))
+        }
+
+        Expr::Pow(base, n) => {
+            let q = eval(base, ctx)?;
+            composed_operand(base, q, "^", ctx)?.pow(*n)
+        }
 
+        // Math functions first, then user-defined functions (`ke(m, v)`),
+        // then function-defined units (`tempC(20)`).
+        // A call to a function unit yields a plain quantity in base units,
+        // so `wiregauge(10) * 2` is ordinary arithmetic on a length.
         Expr::FuncCall(name, args) => {
-            let func = math::lookup(name).ok_or_else(|| RUnitsError::UnknownFunction {
+            if let Some(func) = math::lookup(name) {
+                let arg_values: Result<Vec<Quantity>, _> =
+                    args.iter().map(|a| eval(a, ctx)).collect();
+                return func.apply(&arg_values?);
+            }
+            if let Some(func) = ctx.functions.and_then(|f| f.get(name)) {
+                let arg_values: Result<Vec<Quantity>, _> =
+                    args.iter().map(|a| eval(a, ctx)).collect();
+                return func.call(&arg_values?, ctx);
+            }
+            if let Some(func) = chemistry::lookup(name) {
+                return chemistry_call(func, args, ctx);
+            }
+            let unit = function_unit(name, ctx)?;
+            let [arg] = args.as_slice() else {
+                return Err(RUnitsError::ArityMismatch {
+                    name: name.clone(),
+                    expected: 1,
+                    got: args.len(),
+                });
+            };
+            let x = scalar_argument(name, eval(arg, ctx)?)?;
+            let base_value = unit.try_to_base_value(x)?;
+            let mut base = unit.clone();
+            base.name = unit.to_base_unit_string();
+            base.conversion = ConversionKind::Linear(1.0);
+            Ok(Quantity::new(base_value, base))
+        }
+
+        // `~name(q)`: the number `x` such that `name(x) = q`. Converting `q`
+        // to the function unit runs the inverse with its range check.
+        Expr::InverseCall(name, arg) => {
+            let unit = function_unit(name, ctx)?;
+            let q = eval(arg, ctx)?;
+            let x = q.convert_to(&unit)?.value;
+            Ok(Quantity::new(x, Unit::dimensionless()))
+        }
+
+        // `molar_mass(Ca(OH)2)`: the grammar only admits registered names,
+        // so the lookup cannot fail for parsed input.
+        Expr::ChemCall(name, arg) => match chemistry::lookup(name) {
+            Some(func) => func.apply(arg),
+            None => Err(RUnitsError::UnknownFunction {
                 name: name.clone(),
-                suggestions: math::suggest(name, 3),
-            })?;
-            let arg_values: Result<Vec<Quantity>, _> = args.iter().map(|a| eval(a, ctx)).collect();
-            func.apply(&arg_values?)
+                suggestions: chemistry::suggest(name, 3),
+            }),
+        },
+    }
+}
+
+/// A chemistry function called with an expression: `density(Fe)`,
+/// `density(water)`. Only a lone name can mean anything, and it resolves
+/// as a session variable first (which holds a quantity, not a material),
+/// then a registered substance, then an element or formula.
+fn chemistry_call(
+    func: chemistry::ChemFn,
+    args: &[Expr],
+    ctx: &EvalContext,
+) -> Result<Quantity, RUnitsError> {
+    let expects = |detail: String| RUnitsError::FunctionDomainError {
+        name: func.name().to_string(),
+        reason: format!("expects {}{detail}", func.expected_argument()),
+    };
+    let [Expr::Ident(arg)] = args else {
+        return Err(expects(String::new()));
+    };
+    if let Some(value) = ctx.variables.and_then(|vars| vars.get(arg)) {
+        return Err(expects(format!(", but '{arg}' is a variable ({value})")));
+    }
+    if let Some(substance) = substances::lookup(arg) {
+        return func.substance_property(substance);
+    }
+    func.apply(arg)
+}
+
+/// An operand of `*`, `/` or `^`. A non-linear unit written as a bare unit
+/// name is being composed (`kg*degC`, `J/degC`), so a temperature scale
+/// swaps in its delta form. Any other non-linear value (`1 degC`, a
+/// variable holding a level) is rejected: scaling a reading or a level is
+/// meaningless, and a value of 1 is no exception.
+fn composed_operand(
+    expr: &Expr,
+    q: Quantity,
+    op: &str,
+    ctx: &EvalContext,
+) -> Result<Quantity, RUnitsError> {
+    if q.unit.is_linear() {
+        return Ok(q);
+    }
+    if !is_unit_reference(expr, ctx) {
+        require_linear(&q.unit, op)?;
+    }
+    Ok(compose_operand(q))
+}
+
+/// Whether `expr` is a bare unit name, as opposed to a value that happens
+/// to carry a unit: a variable of the same name shadows the unit.
+fn is_unit_reference(expr: &Expr, ctx: &EvalContext) -> bool {
+    match expr {
+        Expr::Ident(name) => !ctx.variables.is_some_and(|vars| vars.contains_key(name)),
+        _ => false,
+    }
+}
+
+/// Echo a mixed-unit quantity for error messages: `6 ft 3 in`. Units are
+/// rendered from the AST, so only plain names and powers are reproduced
+/// exactly.
+fn mixed_text(parts: &[(f64, Expr)]) -> String {
+    fn unit_text(expr: &Expr) -> String {
+        match expr {
+            Expr::Ident(name) => name.clone(),
+            Expr::Pow(base, n) => format!("{}{}", unit_text(base), power_suffix(*n)),
+            Expr::BinOp(BinOp::Mul, lhs, rhs) => {
+                format!("{} {}", unit_text(lhs), unit_text(rhs))
+            }
+            other => format!("{other:?}"),
         }
     }
+    parts
+        .iter()
+        .map(|(value, unit)| format!("{value} {}", unit_text(unit)))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Resolve `name` to a function-defined unit, or fail as an unknown
+/// function with suggestions from the math registry, the user-defined
+/// functions and the function units.
+fn function_unit(name: &str, ctx: &EvalContext) -> Result<Unit, RUnitsError> {
+    if let Some(unit) = ctx.units.lookup(name).filter(Unit::is_nonlinear) {
+        return Ok(unit);
+    }
+    let mut suggestions = math::suggest(name, 3);
+    let user_functions = ctx.functions.into_iter().flat_map(|f| f.keys());
+    for candidate in user_functions.filter(|f| strsim::jaro_winkler(name, f) > 0.7) {
+        suggestions.push(candidate.clone());
+    }
+    for candidate in chemistry::suggest(name, 3) {
+        if !suggestions.contains(&candidate) {
+            suggestions.push(candidate);
+        }
+    }
+    for candidate in ctx.units.suggest(name, 5) {
+        let is_function = ctx
+            .units
+            .lookup(&candidate)
+            .is_some_and(|u| u.is_nonlinear());
+        if is_function && !suggestions.contains(&candidate) {
+            suggestions.push(candidate);
+        }
+    }
+    suggestions.truncate(5);
+    Err(RUnitsError::UnknownFunction {
+        name: name.to_string(),
+        suggestions,
+    })
+}
+
+/// A function unit's argument must be a plain number.
+fn scalar_argument(name: &str, q: Quantity) -> Result<f64, RUnitsError> {
+    if q.unit.dimensions.is_empty() && q.unit.is_linear() {
+        Ok(q.unit.to_base_value(q.value))
+    } else {
+        Err(RUnitsError::FunctionDomainError {
+            name: name.to_string(),
+            reason: format!(
+                "expects dimensionless argument (got {})",
+                q.unit.dimension_string()
+            ),
+        })
+    }
 }
 
 #[cfg(test)]
@@ -314,6 +608,8 @@ mod tests {
             units: &db,
             constants: crate::database::constants::global(),
             previous: Some(&prev),
+            variables: None,
+            functions: None,
         };
         let expr = parse_expression("_ + 5 m").unwrap();
         let q = eval(&expr, &ctx).unwrap();
@@ -333,7 +629,79 @@ mod tests {
     #[test]
     fn affine_addition_fails() {
         let err = eval_one_shot("20 celsius + 5 celsius").unwrap_err();
-        assert!(matches!(err, RUnitsError::AffineInExpression { .. }));
+        assert!(matches!(err, RUnitsError::TemperatureArithmetic { .. }));
+    }
+
+    #[test]
+    fn scaling_a_level_or_reading_fails() {
+        // Only a bare unit name takes the scalar: `1 dBm` is a value.
+        for src in [
+            "2 * 1 dBm",
+            "1 dBm * 2",
+            "2 * 3 dBm",
+            "2 * 98.6 degF",
+            "2 * 1 degF",
+        ] {
+            assert!(eval_one_shot(src).is_err(), "{src} should fail");
+        }
+        let q = eval_one_shot("30 dBm").unwrap();
+        assert_eq!((q.value, q.unit.name.as_str()), (30.0, "dBm"));
+        let q = eval_one_shot("kg*degC").unwrap();
+        assert!(q.unit.is_temperature_delta());
+    }
+
+    #[test]
+    fn only_temperature_unit_names_compose_as_deltas() {
+        // A value is a reading whatever its number, in either order.
+        for src in [
+            "1 kg / 1 degC",
+            "1 kg / 2 degC",
+            "1 degC / 1 kg",
+            "20 degC / 1 m",
+            "1 m / 20 degC",
+            "20 degC * 1 m",
+            "1 m * 20 degC",
+            "1 degC * 1 m",
+        ] {
+            let err = eval_one_shot(src).unwrap_err();
+            assert!(
+                matches!(err, RUnitsError::AffineInExpression { .. }),
+                "{src}: {err:?}"
+            );
+        }
+        // A unit name composes, in either order.
+        for src in ["kg / degC", "degC / kg", "kg * degC", "degC * kg"] {
+            let q = eval_one_shot(src).unwrap();
+            assert!(q.unit.is_temperature_delta(), "{src}");
+        }
+        // A reading over a unit name is a gradient.
+        let q = eval_one_shot("2 degC/m").unwrap();
+        assert_eq!(
+            (q.value, q.unit.name.as_str()),
+            (2.0, "delta_celsius/meter")
+        );
+    }
+
+    #[test]
+    fn affine_point_delta_arithmetic() {
+        let q = eval_one_shot("20 degC + 5 K").unwrap();
+        assert_eq!(q.unit.name, "celsius");
+        assert!((q.value - 25.0).abs() < 1e-9);
+        let q = eval_one_shot("30 degC - 20 degC").unwrap();
+        assert_eq!(q.unit.name, "kelvin");
+        assert!((q.value - 10.0).abs() < 1e-9);
+        // A kelvin value or a function-unit reading a point is subtracted
+        // from is a point.
+        let q = eval_one_shot("300 K - 20 degC").unwrap();
+        assert!((q.value - 6.85).abs() < 1e-9);
+        let q = eval_one_shot("tempC(20) - 20 degC").unwrap();
+        assert!(q.value.abs() < 1e-9);
+        // Added to a point, it is a difference in either order.
+        let q = eval_one_shot("5 K + 20 degC").unwrap();
+        assert_eq!(q.unit.name, "celsius");
+        assert!((q.value - 25.0).abs() < 1e-9);
+        let err = eval_one_shot("5 deltaC - 20 degC").unwrap_err();
+        assert!(matches!(err, RUnitsError::TemperatureArithmetic { .. }));
     }
 
     #[test]
@@ -347,4 +715,144 @@ mod tests {
         let err = eval(&expr, &ctx).unwrap_err();
         assert!(matches!(err, RUnitsError::ArityMismatch { .. }));
     }
+
+    // ---- Function-defined units ----
+
+    #[test]
+    fn function_unit_call_yields_base_quantity() {
+        let q = eval_one_shot("tempC(20)").unwrap();
+        assert!((q.value - 293.15).abs() < 1e-9);
+        assert!(q.unit.is_linear());
+        assert_eq!(q.unit.dimension_string(), "Temperature");
+    }
+
+    #[test]
+    fn function_unit_call_result_supports_arithmetic() {
+        let q = eval_one_shot("wiregauge(10) * 2").unwrap();
+        assert!((q.value - 2.0 * 2.588_19e-3).abs() < 1e-7);
+    }
+
+    #[test]
+    fn function_unit_out_of_domain() {
+        let err = eval_one_shot("tempC(-300)").unwrap_err();
+        match err {
+            RUnitsError::OutOfDomain { unit, valid, .. } => {
+                assert_eq!(unit, "tempC");
+                assert_eq!(valid, "[-273.15, inf)");
+            }
+            other => panic!("expected OutOfDomain, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn inverse_call_returns_argument() {
+        let q = eval_one_shot("~tempC(300 K)").unwrap();
+        assert!((q.value - 26.85).abs() < 1e-9);
+        assert!(q.unit.dimensions.is_empty());
+        let gauge = eval_one_shot("~wiregauge(wiregauge(12))").unwrap();
+        assert!((gauge.value - 12.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn inverse_call_out_of_range() {
+        let err = eval_one_shot("~wiregauge(-1 mm)").unwrap_err();
+        assert!(matches!(err, RUnitsError::OutOfDomain { .. }));
+    }
+
+    #[test]
+    fn function_unit_rejects_dimensioned_argument() {
+        let err = eval_one_shot("tempC(3 m)").unwrap_err();
+        assert!(matches!(err, RUnitsError::FunctionDomainError { .. }));
+    }
+
+    #[test]
+    fn function_unit_arity() {
+        let err = eval_one_shot("tempC(1, 2)").unwrap_err();
+        assert!(matches!(err, RUnitsError::ArityMismatch { got: 2, .. }));
+    }
+
+    #[test]
+    fn inverse_of_linear_unit_is_unknown_function() {
+        let err = eval_one_shot("~meter(5 ft)").unwrap_err();
+        assert!(matches!(err, RUnitsError::UnknownFunction { .. }));
+    }
+
+    // ---- Chemistry functions ----
+
+    #[test]
+    fn molar_mass_times_amount_is_mass() {
+        let db = UnitDatabase::new();
+        let q = eval_one_shot("3 mol * molar_mass(NaCl)").unwrap();
+        let g = q.convert_to(&db.lookup("g").unwrap()).unwrap();
+        assert!((g.value - 175.32).abs() < 1e-2, "{}", g.value);
+    }
+
+    #[test]
+    fn molar_mass_of_an_expression_is_a_domain_error() {
+        let err = eval_one_shot("molar_mass(3 m)").unwrap_err();
+        assert!(err.to_string().contains("chemical formula"), "{err}");
+    }
+
+    #[test]
+    fn molar_mass_unknown_element_suggests() {
+        let err = eval_one_shot("molar_mass(Fx2O3)").unwrap_err();
+        assert!(matches!(err, RUnitsError::UnknownElement { .. }), "{err:?}");
+        assert!(err.to_string().contains("Did you mean"), "{err}");
+    }
+
+    #[test]
+    fn chemistry_name_resolves_variable_then_substance_then_element() {
+        let q = eval_one_shot("density(water)").unwrap();
+        assert!((q.unit.to_base_value(q.value) - 998.2).abs() < 1e-9);
+        let q = eval_one_shot("density(Fe)").unwrap();
+        assert!((q.value - 7.874).abs() < 1e-12);
+
+        let db = UnitDatabase::new();
+        let mut vars = Variables::new();
+        vars.insert("Fe".to_string(), Quantity::new(2.0, Unit::kilogram()));
+        let ctx = EvalContext::one_shot(&db).with_variables(&vars);
+        let err = eval(&parse_expression("density(Fe)").unwrap(), &ctx).unwrap_err();
+        assert!(err.to_string().contains("'Fe' is a variable"), "{err}");
+    }
+
+    // ---- Mixed-unit input ----
+
+    #[test]
+    fn mixed_units_sum_in_the_first_unit() {
+        let q = eval_one_shot("6 ft 3 in").unwrap();
+        assert_eq!(q.unit.name, "foot");
+        assert!((q.value - 6.25).abs() < 1e-12);
+        let q = eval_one_shot("1 h 20 min 5 s").unwrap();
+        assert!((q.unit.to_base_value(q.value) - 4805.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn mixed_units_must_match_and_descend() {
+        for src in ["6 ft 3 s", "6 in 3 ft", "20 degC 5 degF"] {
+            let err = eval_one_shot(src).unwrap_err();
+            assert!(
+                matches!(err, RUnitsError::MixedUnits { .. }),
+                "{src}: {err:?}"
+            );
+        }
+    }
+
+    // ---- Session variables ----
+
+    #[test]
+    fn variables_shadow_units() {
+        let db = UnitDatabase::new();
+        let mut vars = Variables::new();
+        vars.insert("x".to_string(), Quantity::new(5.0, Unit::foot()));
+        vars.insert("m".to_string(), Quantity::new(2.0, Unit::kilogram()));
+        let ctx = EvalContext::one_shot(&db).with_variables(&vars);
+        let q = eval(&parse_expression("2 x").unwrap(), &ctx).unwrap();
+        assert_eq!(q.unit.name, "foot");
+        assert!((q.value - 10.0).abs() < 1e-12);
+        let q = eval(&parse_expression("m").unwrap(), &ctx).unwrap();
+        assert_eq!(q.unit.name, "kilogram");
+        assert_eq!(ctx.shadowed("m").as_deref(), Some("unit 'meter'"));
+        assert_eq!(ctx.shadowed("g_n").as_deref(), Some("constant 'gravity'"));
+        assert!(ctx.shadowed("x").is_none());
+    }
 }
@@ -15,15 +15,19 @@
 //! | `Previous` | the `_` previous-result variable |
 //! | `BinOp(op, lhs, rhs)` | `+ - * /` |
 //! | `Neg(inner)` | unary `-` (unary `+` is a no-op, not represented) |
-//! | `Pow(base, n)` | integer exponent only |
-//! | `FuncCall(name, args)` | `sqrt(9 m^2)`, `sin(0)`, ... |
+//! | `Pow(base, n)` | rational exponent: `m^2`, `Hz^0.5`, `m^(3/2)` |
+//! | `FuncCall(name, args)` | `sqrt(9 m^2)`, `sin(0)`, `tempC(20)`, ... |
+//! | `InverseCall(name, arg)` | `~wiregauge(0.09 in)` — inverse of a function unit |
+//! | `Mixed(parts)` | `6 ft 3 in` — juxtaposed number+unit segments, summed |
+//! | `ChemCall(name, arg)` | `molar_mass(Ca(OH)2)`, `molar_mass(CuSO4·5H2O)` — argument kept as raw text |
 //!
 //! `_` is deliberately a separate variant and not an `Ident("_")` so a
 //! future unit or constant named `_` could not silently hijack the
 //! previous-result variable.
 
 use crate::error::RUnitsError;
-use crate::parser::{QuantityParser, Rule};
+use crate::parser::{QuantityParser, Rule, parse_exponent};
+use crate::units::Rational;
 use pest::Parser;
 use pest::iterators::Pair;
 
@@ -41,10 +45,17 @@ pub enum Expr {
     BinOp(BinOp, Box<Expr>, Box<Expr>),
     /// Unary negation.
     Neg(Box<Expr>),
-    /// Integer power: `base^n`.
-    Pow(Box<Expr>, i32),
+    /// Power: `base^n`, with `n` exact (`2`, `-1`, `1/2`).
+    Pow(Box<Expr>, Rational),
     /// Function call: `name(args...)`.
     FuncCall(String, Vec<Expr>),
+    /// Inverse call of a function-defined unit: `~name(arg)`.
+    InverseCall(String, Box<Expr>),
+    /// Mixed-unit quantity: `6 ft 3 in` is `[(6, ft), (3, in)]`, summed
+    /// largest unit first.
+    Mixed(Vec<(f64, Expr)>),
+    /// Chemistry function on a raw formula or element: `name(arg)`.
+    ChemCall(String, String),
 }
 
 /// Binary arithmetic operators.
@@ -79,19 +90,20 @@ pub fn parse_expression(input: &str) -> Result<Expr, RUnitsError> {
         .into_inner()
         .next()
         .expect("grammar guarantees add_expr inside expression");
-    Ok(build_add(add_expr_pair))
+    build_add(add_expr_pair)
 }
 
 // ---------------------------------------------------------------------------
 // Tree walkers. Each function corresponds to one grammar rule and folds its
-// children left-to-right into an `Expr`.
+// children left-to-right into an `Expr`. They are fallible only because a
+// juxtaposition can be ambiguous (`6 ft 3`); see `build_mul`.
 // ---------------------------------------------------------------------------
 
-fn build_add(pair: Pair<Rule>) -> Expr {
+fn build_add(pair: Pair<Rule>) -> Result<Expr, RUnitsError> {
     debug_assert_eq!(pair.as_rule(), Rule::add_expr);
     let mut inner = pair.into_inner();
     let first = inner.next().expect("add_expr has at least one div_expr");
-    let mut result = build_div(first);
+    let mut result = build_div(first)?;
     while let Some(op_pair) = inner.next() {
         let rhs_pair = inner.next().expect("add_op is followed by a div_expr");
         let op = match op_pair.as_str() {
@@ -99,38 +111,125 @@ fn build_add(pair: Pair<Rule>) -> Expr {
             "-" => BinOp::Sub,
             other => unreachable!("unexpected add_op: {other:?}"),
         };
-        let rhs = build_div(rhs_pair);
+        let rhs = build_div(rhs_pair)?;
         result = Expr::BinOp(op, Box::new(result), Box::new(rhs));
     }
-    result
+    Ok(result)
 }
 
-fn build_div(pair: Pair<Rule>) -> Expr {
+fn build_div(pair: Pair<Rule>) -> Result<Expr, RUnitsError> {
     debug_assert_eq!(pair.as_rule(), Rule::div_expr);
     let mut inner = pair.into_inner();
     let first = inner.next().expect("div_expr has at least one mul_expr");
-    let mut result = build_mul(first);
+    let mut result = build_mul(first)?;
     for mul_pair in inner {
-        let rhs = build_mul(mul_pair);
+        let rhs = build_mul(mul_pair)?;
         result = Expr::BinOp(BinOp::Div, Box::new(result), Box::new(rhs));
     }
-    result
+    Ok(result)
 }
 
-fn build_mul(pair: Pair<Rule>) -> Expr {
+fn build_mul(pair: Pair<Rule>) -> Result<Expr, RUnitsError> {
     debug_assert_eq!(pair.as_rule(), Rule::mul_expr);
-    let mut inner = pair.into_inner();
-    let first = inner.next().expect("mul_expr has at least one pow_expr");
-    let mut result = build_pow_like(first);
+    let span = pair.as_span();
     // mul_expr children alternate between `pow_expr` (leading, and after an
     // explicit `*`) and `pow_nosign` (juxtaposed). Pest silently drops the
-    // literal `"*"` token, so we don't have to distinguish between explicit
-    // and juxtaposed multiplication — both become a `Mul` BinOp here.
-    for child in inner {
-        let rhs = build_pow_like(child);
-        result = Expr::BinOp(BinOp::Mul, Box::new(result), Box::new(rhs));
+    // literal `"*"` token, but the rule tells the two apart. Juxtaposition
+    // binds tighter than `*`: `2 * 3 dBm` is `2 * (3 dBm)`, so a number
+    // stays attached to the unit written after it. Each juxtaposed run can
+    // be a mixed-unit quantity on its own: `2 * 6 ft 3 in`.
+    let mut groups: Vec<Vec<Expr>> = Vec::new();
+    for child in pair.into_inner() {
+        let juxtaposed = child.as_rule() == Rule::pow_nosign;
+        let item = build_pow_like(child)?;
+        match groups.last_mut() {
+            Some(group) if juxtaposed => group.push(item),
+            _ => groups.push(vec![item]),
+        }
+    }
+    let factors = groups
+        .into_iter()
+        .map(|items| Ok(split_mixed(&items, span)?.unwrap_or_else(|| product(items))))
+        .collect::<Result<Vec<_>, RUnitsError>>()?;
+    Ok(product(factors))
+}
+
+/// Recognize a mixed-unit quantity in a juxtaposed chain: `6 ft 3 in`,
+/// `1 h 20 min 5 s`. A number that directly follows a non-number starts a
+/// new `number unit...` segment; without one (`10 m`, `2 3 m`, `10 N m`)
+/// the chain is an ordinary product and `None` comes back.
+///
+/// Once segments are in play every one must be a number followed by at
+/// least one unit, or the input is ambiguous — `6 ft 3` could be a product
+/// or a missing unit — and is rejected with a parse error pointing at it. A
+/// leading minus (`-1 h 30 min`) negates the whole quantity. Dimensions and
+/// ordering are checked at evaluation time, when the units are known.
+fn split_mixed(items: &[Expr], span: pest::Span) -> Result<Option<Expr>, RUnitsError> {
+    let number = |e: &Expr| match e {
+        Expr::Number(x) => Some(*x),
+        Expr::Neg(inner) => match **inner {
+            Expr::Number(x) => Some(-x),
+            _ => None,
+        },
+        _ => None,
+    };
+    let starts_segment =
+        |i: usize| i > 0 && number(&items[i]).is_some() && number(&items[i - 1]).is_none();
+    if !(1..items.len()).any(starts_segment) {
+        return Ok(None);
+    }
+    let ambiguous = |detail: String| -> RUnitsError {
+        let message = format!(
+            "ambiguous mixed-unit input: {detail}; write a unit after every number, or '*' to multiply"
+        );
+        let err = pest::error::Error::new_from_span(
+            pest::error::ErrorVariant::CustomError { message },
+            span,
+        );
+        Box::new(err).into()
+    };
+
+    let Some(first) = number(&items[0]) else {
+        return Err(ambiguous("it does not start with a number".to_string()));
+    };
+    let negative = first < 0.0;
+    let mut parts: Vec<(f64, Expr)> = Vec::new();
+    let mut value = first.abs();
+    let mut units: Vec<Expr> = Vec::new();
+    for (i, item) in items.iter().enumerate().skip(1) {
+        match number(item) {
+            // A boundary follows a unit, so `units` is never empty here.
+            Some(x) if starts_segment(i) => {
+                parts.push((value, product(std::mem::take(&mut units))));
+                value = x;
+            }
+            Some(x) => {
+                return Err(ambiguous(format!("'{value} {x}' has two numbers in a row")));
+            }
+            None => units.push(item.clone()),
+        }
+    }
+    if units.is_empty() {
+        return Err(ambiguous(format!(
+            "the trailing number '{value}' has no unit"
+        )));
     }
-    result
+    parts.push((value, product(units)));
+    let mixed = Expr::Mixed(parts);
+    Ok(Some(if negative {
+        Expr::Neg(Box::new(mixed))
+    } else {
+        mixed
+    }))
+}
+
+/// Fold factors back into a left-nested product.
+fn product(factors: Vec<Expr>) -> Expr {
+    let mut factors = factors.into_iter();
+    let first = factors.next().expect("a product has at least one factor");
+    factors.fold(first, |lhs, rhs| {
+        Expr::BinOp(BinOp::Mul, Box::new(lhs), Box::new(rhs))
+    })
 }
 
 /// Dispatch a mul_expr child to the right pow builder.
@@ -138,7 +237,7 @@ fn build_mul(pair: Pair<Rule>) -> Expr {
 /// Either `pow_expr` (allows unary prefix) or `pow_nosign` (no prefix,
 /// juxtaposed). Both have the same shape beneath the first child, so the
 /// two implementations are thin wrappers around the shared exponent logic.
-fn build_pow_like(pair: Pair<Rule>) -> Expr {
+fn build_pow_like(pair: Pair<Rule>) -> Result<Expr, RUnitsError> {
     match pair.as_rule() {
         Rule::pow_expr => build_pow(pair),
         Rule::pow_nosign => {
@@ -146,59 +245,49 @@ fn build_pow_like(pair: Pair<Rule>) -> Expr {
             let base_pair = inner
                 .next()
                 .expect("pow_nosign has at least one atom_expr child");
-            let base = build_atom(base_pair);
+            let base = build_atom(base_pair)?;
             if let Some(exp_pair) = inner.next() {
-                debug_assert_eq!(exp_pair.as_rule(), Rule::integer);
-                let n: i32 = exp_pair
-                    .as_str()
-                    .parse()
-                    .expect("grammar validated integer");
-                Expr::Pow(Box::new(base), n)
+                Ok(Expr::Pow(Box::new(base), parse_exponent(exp_pair)?))
             } else {
-                base
+                Ok(base)
             }
         }
         other => unreachable!("unexpected rule in mul_expr child: {other:?}"),
     }
 }
 
-fn build_pow(pair: Pair<Rule>) -> Expr {
+fn build_pow(pair: Pair<Rule>) -> Result<Expr, RUnitsError> {
     debug_assert_eq!(pair.as_rule(), Rule::pow_expr);
     let mut inner = pair.into_inner();
     let base_pair = inner.next().expect("pow_expr has at least one unary_atom");
-    let base = build_unary(base_pair);
+    let base = build_unary(base_pair)?;
     if let Some(exp_pair) = inner.next() {
-        debug_assert_eq!(exp_pair.as_rule(), Rule::integer);
-        let n: i32 = exp_pair
-            .as_str()
-            .parse()
-            .expect("grammar validated integer");
-        Expr::Pow(Box::new(base), n)
+        Ok(Expr::Pow(Box::new(base), parse_exponent(exp_pair)?))
     } else {
-        base
+        Ok(base)
     }
 }
 
-fn build_unary(pair: Pair<Rule>) -> Expr {
+fn build_unary(pair: Pair<Rule>) -> Result<Expr, RUnitsError> {
     debug_assert_eq!(pair.as_rule(), Rule::unary_atom);
     let mut inner = pair.into_inner();
     let first = inner.next().expect("unary_atom has at least an atom");
     if first.as_rule() == Rule::unary_op {
         let atom = inner.next().expect("unary_op is followed by an atom");
-        let inner_expr = build_atom(atom);
-        match first.as_str() {
+        let inner_expr = build_atom(atom)?;
+        Ok(match first.as_str() {
             "-" => Expr::Neg(Box::new(inner_expr)),
             // Unary `+` is a no-op — skip the wrapper.
             "+" => inner_expr,
             other => unreachable!("unexpected unary_op: {other:?}"),
-        }
+        })
     } else {
         build_atom(first)
     }
 }
 
-fn build_atom(pair: Pair<Rule>) -> Expr {
-    match pair.as_rule() {
+fn build_atom(pair: Pair<Rule>) -> Result<Expr, RUnitsError> {
+    Ok(match pair.as_rule() {
         Rule::number => {
             let v: f64 = pair.as_str().parse().expect("grammar validated number");
             Expr::Number(v)
@@ -211,20 +300,37 @@ fn build_atom(pair: Pair<Rule>) -> Expr {
                 .as_str()
                 .to_string();
             let args = match inner.next() {
-                Some(arg_list_pair) => arg_list_pair
-                    .into_inner()
-                    .map(build_add)
-                    .collect::<Vec<_>>(),
+                Some(arg_list_pair) => arg_list_pair.into_inner().map(build_add).collect::<Result<
+                    Vec<_>,
+                    _,
+                >>(
+                )?,
                 None => Vec::new(),
             };
             Expr::FuncCall(name, args)
         }
+        Rule::chem_call => {
+            let mut inner = pair.into_inner();
+            let name = inner.next().expect("chem_call has a name").as_str();
+            let formula = inner.next().expect("chem_call has a formula").as_str();
+            Expr::ChemCall(name.to_string(), formula.to_string())
+        }
+        Rule::inverse_call => {
+            let mut inner = pair.into_inner();
+            let name = inner
+                .next()
+                .expect("inverse_call has an ident")
+                .as_str()
+                .to_string();
+            let arg = build_add(inner.next().expect("inverse_call has an argument"))?;
+            Expr::InverseCall(name, Box::new(arg))
+        }
         Rule::paren_expr => {
             let inner_add = pair
                 .into_inner()
                 .next()
                 .expect("paren_expr wraps an add_expr");
-            build_add(inner_add)
+            build_add(inner_add)?
         }
         Rule::previous => Expr::Previous,
         Rule::ident_atom => {
@@ -232,7 +338,7 @@ fn build_atom(pair: Pair<Rule>) -> Expr {
             Expr::Ident(ident.as_str().to_string())
         }
         other => unreachable!("unexpected atom rule: {other:?}"),
-    }
+    })
 }
 
 #[cfg(test)]
@@ -284,16 +390,16 @@ mod tests {
 
     #[test]
     fn explicit_mul_and_juxtaposition_mix() {
-        // `3*4 m` → `((3*4) * m)`
+        // `3*4 m` → `(3 * (4*m))`: juxtaposition binds tighter than `*`.
         let e = parse("3*4 m");
         let expected = Expr::BinOp(
             BinOp::Mul,
+            Box::new(Expr::Number(3.0)),
             Box::new(Expr::BinOp(
                 BinOp::Mul,
-                Box::new(Expr::Number(3.0)),
                 Box::new(Expr::Number(4.0)),
+                Box::new(Expr::Ident("m".to_string())),
             )),
-            Box::new(Expr::Ident("m".to_string())),
         );
         assert_eq!(e, expected);
     }
@@ -304,7 +410,7 @@ mod tests {
         let e = parse("2^10 byte");
         let expected = Expr::BinOp(
             BinOp::Mul,
-            Box::new(Expr::Pow(Box::new(Expr::Number(2.0)), 10)),
+            Box::new(Expr::Pow(Box::new(Expr::Number(2.0)), Rational::from(10))),
             Box::new(Expr::Ident("byte".to_string())),
         );
         assert_eq!(e, expected);
@@ -361,7 +467,10 @@ mod tests {
             vec![Expr::BinOp(
                 BinOp::Mul,
                 Box::new(Expr::Number(9.0)),
-                Box::new(Expr::Pow(Box::new(Expr::Ident("m".to_string())), 2)),
+                Box::new(Expr::Pow(
+                    Box::new(Expr::Ident("m".to_string())),
+                    Rational::from(2),
+                )),
             )],
         );
         assert_eq!(e, expected);
@@ -405,4 +514,108 @@ mod tests {
         );
         assert_eq!(e, expected);
     }
+
+    #[test]
+    fn inverse_call_parses() {
+        let e = parse("~wiregauge(0.09 in)");
+        let expected = Expr::InverseCall(
+            "wiregauge".to_string(),
+            Box::new(Expr::BinOp(
+                BinOp::Mul,
+                Box::new(Expr::Number(0.09)),
+                Box::new(Expr::Ident("in".to_string())),
+            )),
+        );
+        assert_eq!(e, expected);
+    }
+
+    #[test]
+    fn inverse_call_takes_one_argument() {
+        assert!(parse_expression("~tempC(1, 2)").is_err());
+        assert!(parse_expression("~tempC").is_err());
+    }
+
+    #[test]
+    fn chem_call_keeps_formula_text() {
+        let e = parse("3 mol * molar_mass(CuSO4·5H2O)");
+        let expected = Expr::BinOp(
+            BinOp::Mul,
+            Box::new(Expr::BinOp(
+                BinOp::Mul,
+                Box::new(Expr::Number(3.0)),
+                Box::new(Expr::Ident("mol".to_string())),
+            )),
+            Box::new(Expr::ChemCall(
+                "molar_mass".to_string(),
+                "CuSO4·5H2O".to_string(),
+            )),
+        );
+        assert_eq!(e, expected);
+        assert_eq!(
+            parse("molar_mass(Ca(OH)2)"),
+            Expr::ChemCall("molar_mass".to_string(), "Ca(OH)2".to_string())
+        );
+    }
+
+    #[test]
+    fn chem_call_with_expression_falls_back_to_func_call() {
+        assert!(matches!(parse("molar_mass(3 m)"), Expr::FuncCall(..)));
+        // A lone name is resolved at evaluation time.
+        assert_eq!(
+            parse("density(water)"),
+            Expr::FuncCall(
+                "density".to_string(),
+                vec![Expr::Ident("water".to_string())]
+            )
+        );
+    }
+
+    // ---- Mixed-unit input ----
+
+    #[test]
+    fn mixed_units_split_into_segments() {
+        assert_eq!(
+            parse("6 ft 3 in"),
+            Expr::Mixed(vec![
+                (6.0, Expr::Ident("ft".to_string())),
+                (3.0, Expr::Ident("in".to_string())),
+            ])
+        );
+        assert!(
+            matches!(parse("-1 h 30 min"), Expr::Neg(inner) if matches!(*inner, Expr::Mixed(_)))
+        );
+    }
+
+    #[test]
+    fn mixed_units_as_a_factor() {
+        // Juxtaposition binds tighter than `*`, so the chain stays whole.
+        for src in ["2 * 6 ft 3 in", "6 ft 3 in * 2"] {
+            let Expr::BinOp(BinOp::Mul, lhs, rhs) = parse(src) else {
+                panic!("{src} should be a product");
+            };
+            assert!(
+                matches!(*lhs, Expr::Mixed(_)) || matches!(*rhs, Expr::Mixed(_)),
+                "{src}"
+            );
+        }
+        assert!(parse_expression("2 * 6 ft 3").is_err());
+    }
+
+    #[test]
+    fn plain_juxtaposition_stays_a_product() {
+        for src in ["10 N m", "2 3 m", "6 ft * 3 in", "-6 3 m"] {
+            assert!(!format!("{:?}", parse(src)).contains("Mixed"), "{src}");
+        }
+    }
+
+    #[test]
+    fn ambiguous_mixed_input_is_a_parse_error() {
+        for src in ["6 ft 3", "6 ft 3 4 in", "ft 3 in"] {
+            let err = parse_expression(src).unwrap_err();
+            assert!(
+                err.to_string().contains("ambiguous mixed-unit input"),
+                "{src}: {err}"
+            );
+        }
+    }
 }
@@ -10,9 +10,14 @@
 
 use crate::convert::ConversionResult;
 use crate::database::SI_PREFIXES;
+use crate::eval::Variables;
+use crate::functions::Functions;
+use crate::reverse::{Match, MatchKind};
 use crate::theme::Theme;
-use crate::units::dimension::Dimension;
+use crate::units::Quantity;
+use crate::units::dimension::{Dimension, create_dimensions};
 use crate::units::quantity::{format_value, format_value_inner};
+use crate::units::unit::power_suffix;
 
 /// FUTURE(unit-systems): this becomes dynamic when CGS/natural units land.
 pub const UNIT_SYSTEM: &str = "SI";
@@ -90,9 +95,9 @@ pub fn colored_dimensions(
                 styled_sym
             } else {
                 let exp_str = if unicode {
-                    unicode_unit_name(&format!("^{}", exp))
+                    unicode_unit_name(&power_suffix(*exp))
                 } else {
-                    format!("^{}", exp)
+                    power_suffix(*exp)
                 };
                 format!("{}{}", styled_sym, theme.num(&exp_str))
             }
@@ -154,12 +159,36 @@ pub fn format_result(result: &ConversionResult, opts: &FormatOptions) -> String
     let sig_figs = opts.precision.unwrap_or(6);
     let exact = opts.precision.is_some();
 
-    let value_str = if exact {
-        format_value_inner(result.result.value, sig_figs, opts.scientific, true)
+    let fmt_value = |v: f64| {
+        if exact {
+            format_value_inner(v, sig_figs, opts.scientific, true)
+        } else {
+            format_value(v, sig_figs, opts.scientific)
+        }
+    };
+
+    let mut out = if result.unit_list.is_empty() {
+        format_single(result, &fmt_value, opts, &t)
     } else {
-        format_value(result.result.value, sig_figs, opts.scientific)
+        format_unit_list_parts(result, sig_figs, &fmt_value, opts, &t)
     };
 
+    if opts.annotations
+        && let Some(ann) = result.annotation
+    {
+        out.push_str(&format!(" {}", t.dim(&format!("[{}]", ann))));
+    }
+
+    out
+}
+
+/// `<value> <unit>` for an ordinary result.
+fn format_single(
+    result: &ConversionResult,
+    fmt_value: &dyn Fn(f64) -> String,
+    opts: &FormatOptions,
+    t: &Theme,
+) -> String {
     let raw_name = if opts.to_base {
         result.result.unit.to_base_unit_string()
     } else {
@@ -171,19 +200,49 @@ pub fn format_result(result: &ConversionResult, opts: &FormatOptions) -> String
         raw_name
     };
 
-    let mut out = format!(
+    format!(
         "{} {}",
-        t.num(&value_str),
+        t.num(&fmt_value(result.result.value)),
         t.unit_text(&unit_name, &result.result.unit)
-    );
+    )
+}
 
-    if opts.annotations
-        && let Some(ann) = result.annotation
-    {
-        out.push_str(&format!(" {}", t.dim(&format!("[{}]", ann))));
+/// `6 foot 3 inch` for a unit-list target. Zero components are left out
+/// (`5400 s -> h;min;s` is `1 hour 30 minute`), unless all are zero.
+fn format_unit_list_parts(
+    result: &ConversionResult,
+    sig_figs: usize,
+    fmt_value: &dyn Fn(f64) -> String,
+    opts: &FormatOptions,
+    t: &Theme,
+) -> String {
+    let parts = crate::convert::decompose(&result.result, &result.unit_list, sig_figs);
+    let shown: Vec<String> = parts
+        .iter()
+        .filter(|q| q.value != 0.0)
+        .map(|q| {
+            let unit_name = if opts.unicode {
+                unicode_unit_name(&q.unit.name)
+            } else {
+                q.unit.name.clone()
+            };
+            format!(
+                "{} {}",
+                t.num(&fmt_value(q.value)),
+                t.unit_text(&unit_name, &q.unit)
+            )
+        })
+        .collect();
+    if shown.is_empty() {
+        let last = parts.last().expect("a unit list has units");
+        format!(
+            "{} {}",
+            t.num(&fmt_value(0.0)),
+            t.unit_text(&last.unit.name, &last.unit)
+        )
+    } else {
+        shown.join(" ")
     }
-
-    out
 }
 
 /// Format a step-by-step conversion explanation.
@@ -220,6 +279,31 @@ pub fn format_explain(result: &ConversionResult, opts: &FormatOptions) -> String
 
     let base_str = uni(&source.unit.to_base_unit_string());
     let base_value = source.unit.to_base_value(source.value);
+    // A reciprocal conversion (`ohm -> S`) inverts the base value between
+    // the two steps, and the target side has its own base unit string.
+    let target_base_str = uni(&target.unit.to_base_unit_string());
+    let target_base_value = if let Some(via) = &result.substance {
+        base_value * via.factor()
+    } else if let Some(bridge) = &result.bridge {
+        bridge.apply(base_value)
+    } else if result.reciprocal {
+        1.0 / base_value
+    } else {
+        base_value
+    };
+    // Equivalency relations are written with Greek symbols; spell them out
+    // for ASCII output.
+    let relation = |s: &str| -> String {
+        if opts.unicode {
+            s.to_string()
+        } else {
+            s.replace("ν̃", "nu~")
+                .replace('ν', "nu")
+                .replace('λ', "lambda")
+                .replace('·', "*")
+                .replace('²', "^2")
+        }
+    };
 
     // A side is "the base unit" when it's linear with factor 1. Skipping
     // its formula/calculation line avoids trivial `meter × 1 = meter` noise.
@@ -230,9 +314,12 @@ pub fn format_explain(result: &ConversionResult, opts: &FormatOptions) -> String
     // Formula line: describes how a unit converts to base.
     // Linear:  `<unit> × <factor> = <base>`
     // Affine:  `<unit> × <scale> + <offset> = <base>`
+    // Log:     `<reference> × 10^(<unit> / <factor>) = <base>`
+    // Func:    `<unit>(x) = <formula> <base>`
     // The unit name on the LHS is read as "value in this unit" — the common
     // physics-text shorthand.
     let formula = |unit: &crate::units::Unit| -> String {
+        let base_str = uni(&unit.to_base_unit_string());
         match &unit.conversion {
             ConversionKind::Linear(f) => format!(
                 "{} {} {} {} {}",
@@ -251,6 +338,30 @@ pub fn format_explain(result: &ConversionResult, opts: &FormatOptions) -> String
                 t.kw("="),
                 t.unit_text(&base_str, unit),
             ),
+            ConversionKind::Logarithmic { reference, factor } => format!(
+                "{} {} 10^({} {} {}) {} {}",
+                t.num(&fv(*reference)),
+                t.kw(g.times),
+                t.unit_text(&uni(&unit.name), unit),
+                t.kw(g.divide),
+                t.num(&fv(*factor)),
+                t.kw("="),
+                t.unit_text(&base_str, unit),
+            ),
+            ConversionKind::Nonlinear(f) => format!(
+                "{}(x) {} {} {}",
+                t.unit_text(&unit.name, unit),
+                t.kw("="),
+                f.formula,
+                t.unit_text(&base_str, unit),
+            ),
+            ConversionKind::Table(table) => format!(
+                "{}(x) {} {}-point table in {}",
+                t.unit_text(&unit.name, unit),
+                t.kw("="),
+                t.num(&table.points().len().to_string()),
+                t.unit_text(&base_str, unit),
+            ),
         }
     };
 
@@ -276,6 +387,25 @@ pub fn format_explain(result: &ConversionResult, opts: &FormatOptions) -> String
                 t.num(&fv(base_value)),
                 t.unit_text(&base_str, unit),
             ),
+            ConversionKind::Logarithmic { reference, factor } => format!(
+                "{} {} 10^({} {} {}) {} {} {}",
+                t.num(&fv(*reference)),
+                t.kw(g.times),
+                t.num(&fv(source.value)),
+                t.kw(g.divide),
+                t.num(&fv(*factor)),
+                t.kw("="),
+                t.num(&fv(base_value)),
+                t.unit_text(&base_str, unit),
+            ),
+            ConversionKind::Nonlinear(_) | ConversionKind::Table(_) => format!(
+                "{}({}) {} {} {}",
+                t.unit_text(&unit.name, unit),
+                t.num(&fv(source.value)),
+                t.kw("="),
+                t.num(&fv(base_value)),
+                t.unit_text(&base_str, unit),
+            ),
         }
     };
 
@@ -285,7 +415,7 @@ pub fn format_explain(result: &ConversionResult, opts: &FormatOptions) -> String
         match &unit.conversion {
             ConversionKind::Linear(f) => format!(
                 "{} {} {} {} {} {}",
-                t.num(&fv(base_value)),
+                t.num(&fv(target_base_value)),
                 t.kw(g.divide),
                 t.num(&fv(*f)),
                 t.kw("="),
@@ -294,7 +424,7 @@ pub fn format_explain(result: &ConversionResult, opts: &FormatOptions) -> String
             ),
             ConversionKind::Affine { scale, offset } => format!(
                 "({} {} {}) {} {} {} {} {}",
-                t.num(&fv(base_value)),
+                t.num(&fv(target_base_value)),
                 t.kw(g.minus),
                 t.num(&fv(*offset)),
                 t.kw(g.divide),
@@ -303,6 +433,26 @@ pub fn format_explain(result: &ConversionResult, opts: &FormatOptions) -> String
                 t.num(&fv(target.value)),
                 t.unit_text(&target_name, unit),
             ),
+            ConversionKind::Logarithmic { reference, factor } => format!(
+                "{} {} log10({} {} {}) {} {} {}",
+                t.num(&fv(*factor)),
+                t.kw(g.times),
+                t.num(&fv(target_base_value)),
+                t.kw(g.divide),
+                t.num(&fv(*reference)),
+                t.kw("="),
+                t.num(&fv(target.value)),
+                t.unit_text(&target_name, unit),
+            ),
+            ConversionKind::Nonlinear(_) | ConversionKind::Table(_) => format!(
+                "~{}({} {}) {} {} {}",
+                t.unit_text(&target_name, unit),
+                t.num(&fv(target_base_value)),
+                t.unit_text(&target_base_str, unit),
+                t.kw("="),
+                t.num(&fv(target.value)),
+                t.unit_text(&target_name, unit),
+            ),
         }
     };
 
@@ -365,6 +515,77 @@ pub fn format_explain(result: &ConversionResult, opts: &FormatOptions) -> String
         }
     }
 
+    // Temperature row: whether offsets were applied (absolute readings) or
+    // not (differences), since `20 degC -> degF` and `20 deltaC -> deltaF`
+    // give different answers for the same digits.
+    // Skipped when an equivalency bridged the gap: `E = k_B·T` always reads
+    // the absolute temperature.
+    if result.bridge.is_none()
+        && let Some(note) = temperature_note(&source.unit, &target.unit)
+    {
+        lines.push(format!("  {}  {}", t.dim("temperature:"), note));
+    }
+
+    // Reciprocal row: the dimensions were inverses, so the answer is 1/x.
+    if result.reciprocal {
+        lines.push(format!(
+            "  {}  dimensions are inverses, so result = 1 / source",
+            t.dim("reciprocal:"),
+        ));
+    }
+
+    // Substance row: whose density / molar mass bridged the dimensions.
+    if let Some(via) = &result.substance {
+        let mut props = Vec::new();
+        if via.density_power != 0
+            && let Some(d) = via.substance.density
+        {
+            props.push(format!("density = {} kg/m^3", t.num(&fv(d))));
+        }
+        if via.molar_power != 0
+            && let Some(m) = via.substance.molar_mass
+        {
+            props.push(format!("molar mass = {} g/mol", t.num(&fv(m * 1e3))));
+        }
+        lines.push(format!(
+            "  {}  {} ({} {}; {})",
+            t.dim("substance:"),
+            via.substance.name,
+            t.kw(g.times),
+            via.describe(),
+            props.join(", "),
+        ));
+    }
+
+    // Equivalency row: which context bridged the dimensions, and with
+    // which constants.
+    if let Some(bridge) = &result.bridge {
+        let constants: Vec<String> = bridge
+            .constants()
+            .into_iter()
+            .map(
+                |name| match crate::database::constants::global().lookup(name) {
+                    Some(c) => format!(
+                        "{} = {} {}",
+                        t.cst(name),
+                        t.num(&fv(c.value)),
+                        uni(&c.unit.name)
+                    ),
+                    None => t.cst(name),
+                },
+            )
+            .collect();
+        lines.push(format!(
+            "  {}  {} ({} {} {}; {})",
+            t.dim("equivalency:"),
+            bridge.equivalency,
+            bridge.from.name,
+            t.kw(g.arrow),
+            bridge.to.name,
+            constants.join(", "),
+        ));
+    }
+
     // Formula section: how each side relates to base (labels `source:` / `target:`).
     if !source_is_base {
         lines.push(format!("  {}  {}", t.dim("source:"), formula(&source.unit)));
@@ -383,10 +604,61 @@ pub fn format_explain(result: &ConversionResult, opts: &FormatOptions) -> String
     if !source_is_base {
         lines.push(format!("{}{}", indent, to_base_step(&source.unit)));
     }
+    if let Some(via) = &result.substance {
+        lines.push(format!(
+            "{}{} {} {} {} {} {}",
+            indent,
+            t.num(&fv(base_value)),
+            t.kw(g.times),
+            t.num(&fv(via.factor())),
+            t.kw("="),
+            t.num(&fv(target_base_value)),
+            t.unit_text(&target_base_str, &target.unit),
+        ));
+    }
+    if let Some(bridge) = &result.bridge {
+        let hub_unit = bridge.hub_unit();
+        if !bridge.from.to_hub.is_empty() {
+            lines.push(format!(
+                "{}{} {} {} {}",
+                indent,
+                relation(bridge.from.to_hub),
+                t.kw("="),
+                t.num(&fv(bridge.hub_value(base_value))),
+                hub_unit,
+            ));
+        }
+        if !bridge.to.from_hub.is_empty() {
+            lines.push(format!(
+                "{}{} {} {} {}",
+                indent,
+                relation(bridge.to.from_hub),
+                t.kw("="),
+                t.num(&fv(target_base_value)),
+                t.unit_text(&target_base_str, &target.unit),
+            ));
+        }
+    }
+    if result.reciprocal {
+        lines.push(format!(
+            "{}1 {} {} {} {} {}",
+            indent,
+            t.kw(g.divide),
+            t.num(&fv(base_value)),
+            t.kw("="),
+            t.num(&fv(target_base_value)),
+            t.unit_text(&target_base_str, &target.unit),
+        ));
+    }
     if !target_is_base {
         lines.push(format!("{}{}", indent, from_base_step(&target.unit)));
     }
-    if source_is_base && target_is_base {
+    if source_is_base
+        && target_is_base
+        && !result.reciprocal
+        && result.bridge.is_none()
+        && result.substance.is_none()
+    {
         lines.push(format!(
             "{}{} {} {}",
             indent,
@@ -395,10 +667,66 @@ pub fn format_explain(result: &ConversionResult, opts: &FormatOptions) -> String
             t.unit_text(&uni(&target.unit.name), &target.unit),
         ));
     }
+    if !result.unit_list.is_empty() {
+        let mixed = format_unit_list_parts(result, 6, &fv, opts, &t);
+        lines.push(format!("{}{} {}", indent, t.kw("="), mixed));
+    }
 
     lines.join("\n")
 }
 
+/// How a conversion treated temperature, for `--explain`: `None` when no
+/// temperature is involved or when the answer doesn't depend on it (kelvin
+/// ↔ rankine). Non-linear temperature units (`degC`, `tempF`, `gasmark`)
+/// are absolute readings; delta units, and any compound containing a
+/// temperature, are differences. Converting between the two measures the
+/// difference from absolute zero, and the note says which way it went.
+fn temperature_note(
+    source: &crate::units::Unit,
+    target: &crate::units::Unit,
+) -> Option<&'static str> {
+    let is_temperature =
+        |u: &crate::units::Unit| u.dimensions.contains_key(&Dimension::Temperature);
+    if !is_temperature(source) {
+        return None;
+    }
+    let plain = create_dimensions(&[(Dimension::Temperature, 1)]);
+    let absolute = |u: &crate::units::Unit| !u.is_linear();
+    let delta = |u: &crate::units::Unit| u.is_temperature_delta() || u.dimensions != plain;
+    if absolute(source) && delta(target) {
+        return Some("absolute -> delta (the reading is measured from absolute zero)");
+    }
+    if delta(source) && absolute(target) {
+        return Some(
+            "delta -> absolute (the difference is read as a temperature above absolute zero)",
+        );
+    }
+    if absolute(source) || absolute(target) {
+        Some("absolute (scale offsets applied)")
+    } else if delta(source) || delta(target) {
+        Some("delta (a temperature difference; no offsets applied)")
+    } else {
+        None
+    }
+}
+
+/// Quote and escape `s` as a JSON string literal.
+pub fn json_string(s: &str) -> String {
+    let mut out = String::with_capacity(s.len() + 2);
+    out.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            c if (c as u32) < 0x20 => out.push_str(&format!("\\u{:04x}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
 fn format_json(result: &ConversionResult, opts: &FormatOptions) -> String {
     let sig_figs = opts.precision.unwrap_or(6);
     let value_str = format_value(result.result.value, sig_figs, false);
@@ -406,9 +734,41 @@ fn format_json(result: &ConversionResult, opts: &FormatOptions) -> String {
         .annotation
         .map(|a| format!("\"{}\"", a))
         .unwrap_or_else(|| "null".to_string());
+    let equivalency = result
+        .bridge
+        .map(|b| json_string(b.equivalency.name()))
+        .unwrap_or_else(|| "null".to_string());
+    let substance = result
+        .substance
+        .map(|s| json_string(s.substance.name))
+        .unwrap_or_else(|| "null".to_string());
+    // A unit-list target adds its components, zeros included, so consumers
+    // get one value/unit pair per requested unit.
+    let parts = if result.unit_list.is_empty() {
+        String::new()
+    } else {
+        let pairs: Vec<String> =
+            crate::convert::decompose(&result.result, &result.unit_list, sig_figs)
+                .iter()
+                .map(|q| {
+                    format!(
+                        "{{\"value\":{},\"unit\":{}}}",
+                        format_value(q.value, sig_figs, false),
+                        json_string(&q.unit.name)
+                    )
+                })
+                .collect();
+        format!(",\"parts\":[{}]", pairs.join(","))
+    };
     format!(
-        "{{\"value\":{},\"unit\":\"{}\",\"annotation\":{}}}",
-        value_str, result.result.unit.name, annotation,
+        "{{\"value\":{},\"unit\":\"{}\",\"annotation\":{},\"reciprocal\":{},\"equivalency\":{},\"substance\":{}{}}}",
+        value_str,
+        result.result.unit.name,
+        annotation,
+        result.reciprocal,
+        equivalency,
+        substance,
+        parts,
     )
 }
 
@@ -467,7 +827,7 @@ pub fn format_unit_info(
     let sys_label = format!("{} base:", UNIT_SYSTEM);
     lines.push(format!("  {} {}", t.dim(&sys_label), base_colored));
 
-    // Factor / status / affine
+    // Factor / status / affine / logarithmic
     match &unit.conversion {
         crate::units::unit::ConversionKind::Linear(f) if (*f - 1.0).abs() < 1e-15 => {
             lines.push(format!("  {} {} (reference)", t.dim("Factor:"), t.num("1")));
@@ -492,6 +852,33 @@ pub fn format_unit_info(
                 t.num(&offset.to_string()),
             ));
         }
+        crate::units::unit::ConversionKind::Nonlinear(f) => {
+            lines.push(format!(
+                "  {} {} = {} for x in {}",
+                t.dim("Function:"),
+                base_colored,
+                f.formula,
+                f.domain,
+            ));
+        }
+        crate::units::unit::ConversionKind::Table(table) => {
+            lines.push(format!(
+                "  {} {} interpolated from {} points for x in {}",
+                t.dim("Table:"),
+                base_colored,
+                t.num(&table.points().len().to_string()),
+                table.domain(),
+            ));
+        }
+        crate::units::unit::ConversionKind::Logarithmic { reference, factor } => {
+            lines.push(format!(
+                "  {} {} = {} × 10^(value / {})",
+                t.dim("Level:"),
+                base_colored,
+                t.num(&format_value(*reference, 6, false)),
+                t.num(&format_value(*factor, 6, false)),
+            ));
+        }
     }
 
     // Prefix info
@@ -539,7 +926,7 @@ pub fn format_constant_info(
     let mut lines = Vec::new();
 
     // Header: name — styled as a constant (italic purple)
-    lines.push(t.cst(constant.name));
+    lines.push(t.cst(&constant.name));
 
     // Description
     lines.push(format!(
@@ -578,6 +965,243 @@ pub fn format_constant_info(
     lines.join("\n")
 }
 
+// ---------------------------------------------------------------------------
+// Math function info formatting (? help for functions)
+// ---------------------------------------------------------------------------
+
+/// Format `? sqrt` output for a builtin math function: its signature and
+/// what it does.
+pub fn format_math_fn_info(f: crate::math::MathFn, opts: &FormatOptions) -> String {
+    let t = Theme::new(opts.color);
+    [
+        t.kw(f.name()),
+        format!("  {} {}", t.dim("Signature:"), f.signature()),
+        format!("  {} {}", t.dim("Description:"), f.description()),
+    ]
+    .join("\n")
+}
+
+/// Format an element's properties for the `element` command: header with
+/// symbol and name, then one row per known property with dimension-colored
+/// units. Melting and boiling points also show °C.
+pub fn format_element_info(
+    element: &crate::chemistry::elements::Element,
+    opts: &FormatOptions,
+) -> String {
+    use crate::chemistry::ChemFn;
+
+    let t = Theme::new(opts.color);
+    let sig_figs = opts.precision.unwrap_or(6);
+    let mut lines = vec![format!("{}  {}", t.cst(element.symbol), element.name)];
+
+    let width = ChemFn::PROPERTIES
+        .iter()
+        .map(|f| f.label().len())
+        .max()
+        .unwrap_or(0)
+        + 1;
+    for &property in ChemFn::PROPERTIES {
+        let label = format!("{}:", property.label());
+        let Some(q) = property.element_property(element) else {
+            lines.push(format!(
+                "  {} {}",
+                t.dim(&format!("{label:<width$}")),
+                t.dim("unknown")
+            ));
+            continue;
+        };
+        let value = t.num(&format_value(q.value, sig_figs, opts.scientific));
+        let mut row = if q.unit.dimensions.is_empty() {
+            value
+        } else {
+            let unit_name = if opts.unicode {
+                unicode_unit_name(&q.unit.name)
+            } else {
+                q.unit.name.clone()
+            };
+            format!("{value} {}", t.unit_text(&unit_name, &q.unit))
+        };
+        if q.unit.dimensions.contains_key(&Dimension::Temperature) {
+            let celsius = format_value(q.value - 273.15, sig_figs, opts.scientific);
+            let degrees = if opts.unicode { "°C" } else { "degC" };
+            row.push_str(&format!(" {}", t.dim(&format!("({celsius} {degrees})"))));
+        }
+        lines.push(format!("  {} {row}", t.dim(&format!("{label:<width$}"))));
+    }
+    lines.join("\n")
+}
+
+/// JSON form of [`format_element_info`]: the atomic number as a bare
+/// integer, every other property as `{value, unit}` in its table unit
+/// (g/mol, g/cm³, K), `null` where unknown.
+pub fn format_element_json(element: &crate::chemistry::elements::Element) -> String {
+    use crate::chemistry::ChemFn;
+
+    let mut fields = vec![
+        format!("\"symbol\":{}", json_string(element.symbol)),
+        format!("\"name\":{}", json_string(element.name)),
+    ];
+    for &property in ChemFn::PROPERTIES {
+        let value = match property.element_property(element) {
+            None => "null".to_string(),
+            Some(q) if q.unit.dimensions.is_empty() => q.value.to_string(),
+            Some(q) => format!(
+                "{{\"value\":{},\"unit\":{}}}",
+                q.value,
+                json_string(&q.unit.name)
+            ),
+        };
+        fields.push(format!("\"{}\":{value}", property.name()));
+    }
+    format!("{{{}}}", fields.join(","))
+}
+
+// ---------------------------------------------------------------------------
+// Session variables (vars)
+// ---------------------------------------------------------------------------
+
+/// Format the REPL's session variables, one `name = <value> <unit>` per
+/// line, sorted by name. Names take their value's dimension color.
+pub fn format_variables(variables: &Variables, opts: &FormatOptions) -> String {
+    let t = Theme::new(opts.color);
+    if variables.is_empty() {
+        return format!("  {}", t.dim("No variables defined."));
+    }
+    let sig_figs = opts.precision.unwrap_or(6);
+    let width = variables
+        .keys()
+        .map(|n| n.chars().count())
+        .max()
+        .unwrap_or(0);
+    variables
+        .iter()
+        .map(|(name, q)| {
+            let pad = width - name.chars().count();
+            let unit_name = if opts.unicode {
+                unicode_unit_name(&q.unit.name)
+            } else {
+                q.unit.name.clone()
+            };
+            let mut row = format!(
+                "  {}{:pad$} = {} {}",
+                t.paint(name, t.dims_style(&q.unit.dimensions)),
+                "",
+                t.num(&format_value(q.value, sig_figs, opts.scientific)),
+                t.unit_text(&unit_name, &q.unit)
+            );
+            if let Some(ann) = crate::annotations::quantity_name(&q.unit.dimensions) {
+                row.push_str(&format!(" {}", t.dim(&format!("[{ann}]"))));
+            }
+            row
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Format the session's user-defined functions, one definition per line,
+/// sorted by name.
+pub fn format_functions(functions: &Functions, opts: &FormatOptions) -> String {
+    let t = Theme::new(opts.color);
+    functions
+        .values()
+        .map(|f| format!("  {} {} {}", t.kw(&f.signature()), t.dim("="), f.source))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+// ---------------------------------------------------------------------------
+// Reverse lookup (--what / what)
+// ---------------------------------------------------------------------------
+
+/// Format a reverse lookup: the query, then one row per match with its
+/// percentage, name and aliases, value in the query's unit, and (for a
+/// constant) its description.
+pub fn format_what(query: &Quantity, matches: &[Match], opts: &FormatOptions) -> String {
+    let t = Theme::new(opts.color);
+    let sig_figs = opts.precision.unwrap_or(6);
+    let show = |q: &Quantity| {
+        let unit_name = if opts.unicode {
+            unicode_unit_name(&q.unit.name)
+        } else {
+            q.unit.name.clone()
+        };
+        format!(
+            "{} {}",
+            t.num(&format_value(q.value, sig_figs, opts.scientific)),
+            t.unit_text(&unit_name, &q.unit)
+        )
+    };
+    let mut header = show(query);
+    if let Some(ann) = crate::annotations::quantity_name(&query.unit.dimensions) {
+        header.push_str(&format!(" {}", t.dim(&format!("[{ann}]"))));
+    }
+    if matches.is_empty() {
+        return format!("{header}: {}", t.dim("no close constants or units"));
+    }
+
+    let labels: Vec<String> = matches
+        .iter()
+        .map(|m| {
+            if m.aliases.is_empty() {
+                m.name.clone()
+            } else {
+                format!("{} ({})", m.name, m.aliases.join(", "))
+            }
+        })
+        .collect();
+    let width = labels.iter().map(|l| l.chars().count()).max().unwrap_or(0);
+    let mut lines = vec![format!("{header} is close to:")];
+    for (m, label) in matches.iter().zip(&labels) {
+        let name = match m.kind {
+            MatchKind::Constant => t.cst(&m.name),
+            MatchKind::Unit => t.unit_text(&m.name, &m.value.unit),
+        };
+        let pad = width - label.chars().count();
+        let aliases = if m.aliases.is_empty() {
+            String::new()
+        } else {
+            format!(" {}", t.dim(&format!("({})", m.aliases.join(", "))))
+        };
+        let mut row = format!(
+            "  {:>7}  {name}{aliases}{:pad$}  {}",
+            format!("{:.2}%", m.percent()),
+            "",
+            show(&m.value)
+        );
+        if let Some(description) = &m.description {
+            row.push_str(&format!("  {}", t.dim(description)));
+        }
+        lines.push(row);
+    }
+    lines.join("\n")
+}
+
+/// JSON form of [`format_what`]: the query and its matches, best first.
+pub fn format_what_json(query: &Quantity, matches: &[Match], opts: &FormatOptions) -> String {
+    let sig_figs = opts.precision.unwrap_or(6);
+    let entries: Vec<String> = matches
+        .iter()
+        .map(|m| {
+            let aliases: Vec<String> = m.aliases.iter().map(|a| json_string(a)).collect();
+            format!(
+                "{{\"name\":{},\"kind\":\"{}\",\"aliases\":[{}],\"value\":{},\"unit\":{},\"match\":{}}}",
+                json_string(&m.name),
+                m.kind.name(),
+                aliases.join(","),
+                format_value(m.value.value, sig_figs, false),
+                json_string(&m.value.unit.name),
+                format_value(m.percent(), 4, false),
+            )
+        })
+        .collect();
+    format!(
+        "{{\"value\":{},\"unit\":{},\"matches\":[{}]}}",
+        format_value(query.value, sig_figs, false),
+        json_string(&query.unit.name),
+        entries.join(","),
+    )
+}
+
 // ---------------------------------------------------------------------------
 // Unit list formatting (search / list-units)
 // ---------------------------------------------------------------------------
@@ -630,7 +1254,9 @@ pub fn format_all_units_grouped(groups: &[(String, Vec<String>)], opts: &FormatO
 
 /// Transform ASCII compound-unit names to Unicode.
 ///
-/// `*` → `·` (middle dot), `^N` → superscript digits.
+/// `*` → `·` (middle dot), `^N` → superscript digits. A fractional power
+/// `^(3/2)` drops its parentheses and uses the superscript-style slash `ᐟ`
+/// (`m³ᐟ²`), which stays legible where a plain `/` would read as division.
 /// No-op on simple names like "meter".
 pub fn unicode_unit_name(name: &str) -> String {
     let mut out = String::with_capacity(name.len());
@@ -640,16 +1266,21 @@ pub fn unicode_unit_name(name: &str) -> String {
         match c {
             '*' => out.push('\u{00B7}'),
             '^' => {
+                let grouped = chars.next_if_eq(&'(').is_some();
                 while let Some(&next) = chars.peek() {
                     if next == '-' {
                         out.push('\u{207B}');
-                        chars.next();
                     } else if next.is_ascii_digit() {
                         out.push(superscript_digit(next));
-                        chars.next();
+                    } else if grouped && next == '/' {
+                        out.push('\u{141F}');
                     } else {
                         break;
                     }
+                    chars.next();
+                }
+                if grouped {
+                    chars.next_if_eq(&')');
                 }
             }
             _ => out.push(c),
@@ -707,13 +1338,13 @@ pub fn should_color(is_tty: bool) -> bool {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::convert::run_conversion;
+    use crate::convert::{ConvertOptions, run_conversion};
     use crate::database::UnitDatabase;
 
     #[test]
     fn format_plain_no_annotation() {
         let db = UnitDatabase::new();
-        let r = run_conversion("10 ft", "m", &db).unwrap();
+        let r = run_conversion("10 ft", "m", &db, &ConvertOptions::default()).unwrap();
         let opts = FormatOptions::default();
         let out = format_result(&r, &opts);
         assert_eq!(out, "3.048 meter");
@@ -722,7 +1353,7 @@ mod tests {
     #[test]
     fn format_with_annotation() {
         let db = UnitDatabase::new();
-        let r = run_conversion("100 km/h", "m/s", &db).unwrap();
+        let r = run_conversion("100 km/h", "m/s", &db, &ConvertOptions::default()).unwrap();
         let opts = FormatOptions {
             annotations: true,
             ..Default::default()
@@ -735,7 +1366,7 @@ mod tests {
     #[test]
     fn format_json_output() {
         let db = UnitDatabase::new();
-        let r = run_conversion("10 ft", "m", &db).unwrap();
+        let r = run_conversion("10 ft", "m", &db, &ConvertOptions::default()).unwrap();
         let opts = FormatOptions {
             json: true,
             ..Default::default()
@@ -743,6 +1374,7 @@ mod tests {
         let out = format_result(&r, &opts);
         assert!(out.contains("\"value\":3.048"));
         assert!(out.contains("\"unit\":\"meter\""));
+        assert!(out.contains("\"reciprocal\":false"));
     }
 
     #[test]
@@ -756,6 +1388,19 @@ mod tests {
         assert_eq!(unicode_unit_name("m^-1"), "m\u{207B}\u{00B9}");
     }
 
+    #[test]
+    fn unicode_fractional_exponent() {
+        assert_eq!(unicode_unit_name("m^(3/2)"), "m\u{00B3}\u{141F}\u{00B2}");
+        assert_eq!(
+            unicode_unit_name("V/hertz^(1/2)"),
+            "V/hertz\u{00B9}\u{141F}\u{00B2}"
+        );
+        assert_eq!(
+            unicode_unit_name("s^(-1/2)"),
+            "s\u{207B}\u{00B9}\u{141F}\u{00B2}"
+        );
+    }
+
     #[test]
     fn unicode_compound() {
         assert_eq!(unicode_unit_name("kg*m/s^2"), "kg\u{00B7}m/s\u{00B2}");
@@ -772,7 +1417,7 @@ mod tests {
     fn explain_simple_linear_skips_trivial_target_line() {
         // 10 ft → m: target is the base unit, so only `source:` appears.
         let db = UnitDatabase::new();
-        let r = run_conversion("10 ft", "m", &db).unwrap();
+        let r = run_conversion("10 ft", "m", &db, &ConvertOptions::default()).unwrap();
         let opts = FormatOptions {
             explain: true,
             ..Default::default()
@@ -789,7 +1434,7 @@ mod tests {
     #[test]
     fn explain_compound_linear_shows_both_sides() {
         let db = UnitDatabase::new();
-        let r = run_conversion("100 km/h", "mph", &db).unwrap();
+        let r = run_conversion("100 km/h", "mph", &db, &ConvertOptions::default()).unwrap();
         let opts = FormatOptions {
             explain: true,
             ..Default::default()
@@ -808,7 +1453,7 @@ mod tests {
         // Harmonization check: affine conversions use `source:` / `target:`
         // labels, not the old `to base:` / `from base:`.
         let db = UnitDatabase::new();
-        let r = run_conversion("98.6 degF", "degC", &db).unwrap();
+        let r = run_conversion("98.6 degF", "degC", &db, &ConvertOptions::default()).unwrap();
         let opts = FormatOptions {
             explain: true,
             ..Default::default()
@@ -823,11 +1468,117 @@ mod tests {
         assert!(out.contains("37"));
     }
 
+    #[test]
+    fn explain_says_absolute_or_delta() {
+        let db = UnitDatabase::new();
+        let opts = FormatOptions {
+            explain: true,
+            ..Default::default()
+        };
+        let explain = |src: &str, dst: &str| {
+            format_result(
+                &run_conversion(src, dst, &db, &ConvertOptions::default()).unwrap(),
+                &opts,
+            )
+        };
+        assert!(explain("20 degC", "degF").contains("temperature:  absolute (scale offsets"));
+        assert!(explain("20 deltaC", "deltaF").contains("temperature:  delta"));
+        assert!(explain("1 J/(kg*K)", "J/(kg*degF)").contains("temperature:  delta"));
+        assert!(explain("20 degC", "deltaC").contains("absolute -> delta"));
+        assert!(explain("10 deltaC", "degC").contains("delta -> absolute"));
+        assert!(explain("10 deltaF", "tempC").contains("delta -> absolute"));
+        assert!(!explain("300 K", "Ra").contains("temperature:"));
+        assert!(!explain("10 ft", "m").contains("temperature:"));
+    }
+
+    #[test]
+    fn explain_and_json_mark_reciprocal_conversions() {
+        let db = UnitDatabase::new();
+        let r = run_conversion("50 V/A", "A/V", &db, &ConvertOptions::default()).unwrap();
+        assert!(r.reciprocal);
+        let explain = format_result(
+            &r,
+            &FormatOptions {
+                explain: true,
+                ..Default::default()
+            },
+        );
+        assert!(explain.contains("reciprocal:"), "{explain}");
+        assert!(explain.contains("1 / 50 = 0.02"), "{explain}");
+        let json = format_result(
+            &r,
+            &FormatOptions {
+                json: true,
+                ..Default::default()
+            },
+        );
+        assert!(json.contains("\"value\":0.02"), "{json}");
+        assert!(json.contains("\"reciprocal\":true"), "{json}");
+    }
+
+    #[test]
+    fn explain_names_the_equivalency_constants() {
+        let db = UnitDatabase::new();
+        let convert_opts = ConvertOptions {
+            equivalencies: vec![crate::equiv::Equivalency::Spectral],
+            ..Default::default()
+        };
+        let r = run_conversion("500 nm", "eV", &db, &convert_opts).unwrap();
+        let explain = format_result(
+            &r,
+            &FormatOptions {
+                explain: true,
+                ..Default::default()
+            },
+        );
+        assert!(explain.contains("equivalency:  spectral"), "{explain}");
+        assert!(explain.contains("speed_of_light = 2.99792e8"), "{explain}");
+        assert!(explain.contains("planck_constant"), "{explain}");
+        assert!(
+            explain.contains("nu = c / lambda = 5.99585e14 Hz"),
+            "{explain}"
+        );
+        let json = format_result(
+            &r,
+            &FormatOptions {
+                json: true,
+                ..Default::default()
+            },
+        );
+        assert!(json.contains("\"equivalency\":\"spectral\""), "{json}");
+    }
+
+    #[test]
+    fn explain_shows_the_substance_factor() {
+        let db = UnitDatabase::new();
+        let r = run_conversion("2 cup flour", "g", &db, &ConvertOptions::default()).unwrap();
+        let explain = format_result(
+            &r,
+            &FormatOptions {
+                explain: true,
+                ..Default::default()
+            },
+        );
+        assert!(
+            explain.contains("substance:  flour (* density"),
+            "{explain}"
+        );
+        assert!(explain.contains("= 0.25 kg"), "{explain}");
+        let json = format_result(
+            &r,
+            &FormatOptions {
+                json: true,
+                ..Default::default()
+            },
+        );
+        assert!(json.contains("\"substance\":\"flour\""), "{json}");
+    }
+
     #[test]
     fn explain_base_to_base_is_identity() {
         // Both sides are the base unit — only the header + standout `= value`.
         let db = UnitDatabase::new();
-        let r = run_conversion("5 m", "m", &db).unwrap();
+        let r = run_conversion("5 m", "m", &db, &ConvertOptions::default()).unwrap();
         let opts = FormatOptions {
             explain: true,
             ..Default::default()
@@ -843,7 +1594,7 @@ mod tests {
         // The calculation section should be preceded by a blank line so it
         // stands out visually.
         let db = UnitDatabase::new();
-        let r = run_conversion("10 ft", "m", &db).unwrap();
+        let r = run_conversion("10 ft", "m", &db, &ConvertOptions::default()).unwrap();
         let opts = FormatOptions {
             explain: true,
             ..Default::default()
@@ -860,4 +1611,124 @@ mod tests {
         assert_eq!(Glyphs::pick(false).times, "*");
         assert_eq!(Glyphs::pick(true).times, "\u{00D7}");
     }
+
+    // ---- Unit lists ----
+
+    #[test]
+    fn unit_list_renders_mixed_units_and_json_parts() {
+        let db = UnitDatabase::new();
+        let r = run_conversion("5400 s", "h;min;s", &db, &ConvertOptions::default()).unwrap();
+        assert_eq!(
+            format_result(&r, &FormatOptions::default()),
+            "1 hour 30 minute"
+        );
+        let json = format_result(
+            &r,
+            &FormatOptions {
+                json: true,
+                ..Default::default()
+            },
+        );
+        assert!(
+            json.ends_with(
+                r#""parts":[{"value":1,"unit":"hour"},{"value":30,"unit":"minute"},{"value":0,"unit":"second"}]}"#
+            ),
+            "{json}"
+        );
+        let explain = format_result(
+            &r,
+            &FormatOptions {
+                explain: true,
+                ..Default::default()
+            },
+        );
+        assert!(explain.ends_with("= 1 hour 30 minute"), "{explain}");
+    }
+
+    // ---- Element info ----
+
+    #[test]
+    fn element_info_lists_properties_with_celsius() {
+        let iron = crate::chemistry::elements::lookup("Fe").unwrap();
+        let out = format_element_info(iron, &FormatOptions::default());
+        assert!(out.starts_with("Fe  iron"), "{out}");
+        assert!(out.contains("7.874 gram/centimeter^3"), "{out}");
+        assert!(out.contains("1811 kelvin (1537.85 degC)"), "{out}");
+        let json = format_element_json(crate::chemistry::elements::lookup("Og").unwrap());
+        assert!(json.contains(r#""symbol":"Og""#), "{json}");
+        assert!(json.contains(r#""density":null"#), "{json}");
+        let json = format_element_json(iron);
+        assert!(json.contains(r#""atomic_number":26,"#), "{json}");
+        assert!(
+            json.contains(r#""density":{"value":7.874,"unit":"gram/centimeter^3"}"#),
+            "{json}"
+        );
+        assert!(
+            json.contains(r#""melting_point":{"value":1811,"unit":"kelvin"}"#),
+            "{json}"
+        );
+    }
+
+    // ---- Reverse lookup ----
+
+    #[test]
+    fn what_lists_matches_with_percentages() {
+        let units = crate::database::UnitDatabase::new();
+        let constants = crate::database::constants::ConstantsDatabase::new();
+        let q = crate::parser::parse_quantity("9.81 m/s^2", &units).unwrap();
+        let matches = crate::reverse::lookup(&q, &units, &constants, 5);
+        let out = format_what(&q, &matches, &FormatOptions::default());
+        assert!(
+            out.starts_with("9.81 meter/second^2 [Acceleration] is close to:"),
+            "{out}"
+        );
+        assert!(
+            out.contains("99.97%  gravity (g0, g_n)  9.80665 meter/second^2"),
+            "{out}"
+        );
+        let json = format_what_json(&q, &matches, &FormatOptions::default());
+        assert!(
+            json.contains(r#""name":"gravity","kind":"constant""#),
+            "{json}"
+        );
+        assert!(json.contains(r#""match":99.97"#), "{json}");
+
+        let far = crate::parser::parse_quantity("1e30 m", &units).unwrap();
+        let out = format_what(&far, &[], &FormatOptions::default());
+        assert!(out.ends_with("no close constants or units"), "{out}");
+    }
+
+    // ---- Session variables ----
+
+    #[test]
+    fn variables_list_sorted_with_annotations() {
+        let mut vars = Variables::new();
+        vars.insert(
+            "rho".to_string(),
+            Quantity::new(1000.0, crate::Unit::kilogram()),
+        );
+        vars.insert("x".to_string(), Quantity::new(5.0, crate::Unit::foot()));
+        let out = format_variables(&vars, &FormatOptions::default());
+        assert_eq!(out, "  rho = 1000 kilogram [Mass]\n  x   = 5 foot [Length]");
+        let empty = format_variables(&Variables::new(), &FormatOptions::default());
+        assert!(empty.contains("No variables defined."), "{empty}");
+    }
+
+    #[test]
+    fn functions_list_their_definitions() {
+        let mut functions = Functions::new();
+        for line in [
+            "sq(x) = x * x",
+            "ke(m: Mass, v: Velocity): Energy = 0.5 * m * v^2",
+        ] {
+            let f = crate::functions::parse_definition(line).unwrap().unwrap();
+            crate::functions::define(&mut functions, f, &crate::database::UnitDatabase::new())
+                .unwrap();
+        }
+        let out = format_functions(&functions, &FormatOptions::default());
+        assert_eq!(
+            out,
+            "  ke(m: Mass, v: Velocity): Energy = 0.5 * m * v^2\n  sq(x) = x * x"
+        );
+    }
 }
@@ -0,0 +1,468 @@
+//! User-defined functions: `ke(m, v) = 0.5 * m * v^2`.
+//!
+//! Defined on a REPL or `--batch` line, or by an `fn` statement in a
+//! definition file (see [`crate::database::definitions`]), and called like a
+//! builtin [`MathFn`](crate::math::MathFn):
+//!
+//! ```text
+//! >>> ke(m: Mass, v: Velocity): Energy = 0.5 * m * v^2
+//! ke(m: Mass, v: Velocity): Energy = 0.5 * m * v^2
+//! >>> ke(2 kg, 3 m/s) -> J
+//! 9 joule
+//! ```
+//!
+//! ## Annotations
+//!
+//! Parameters and the result may carry a `: Quantity` annotation, using the
+//! same quantity names as definition files (`Velocity`, `Electric_Charge`,
+//! the base dimension names). They are checked at call time: an argument or
+//! result of another dimension is a
+//! [`RUnitsError::TypedParameterMismatch`], a wrong argument count the same
+//! [`RUnitsError::ArityMismatch`] the builtins raise. Unannotated parameters
+//! accept anything.
+//!
+//! ## Scope
+//!
+//! The body sees its parameters first, then whatever the caller's context
+//! sees — session variables, units, constants, other functions — looked up
+//! when the function is called, not when it is defined. Parameters shadow
+//! units, so the `m` in `ke(m, v)` is the mass, never the meter. The `_`
+//! previous result is not available inside a body.
+//!
+//! Builtin function names can't be redefined, and a function may not call
+//! itself, directly or through others: [`define`] rejects the definition
+//! that would close the loop, so every call terminates.
+//!
+//! FUTURE(functions-in-definitions): `fn` statements are handed back after a
+//! definition file has loaded, so `unit` and `const` right-hand sides in the
+//! same file can't call them yet.
+
+use crate::chemistry;
+use crate::database::UnitDatabase;
+use crate::database::definitions::{is_identifier, quantity_dimensions};
+use crate::error::RUnitsError;
+use crate::eval::{EvalContext, eval};
+use crate::expr::{Expr, parse_expression};
+use crate::math;
+use crate::units::Quantity;
+use crate::units::dimension::DimensionMap;
+use std::collections::{BTreeMap, HashSet};
+use std::fmt;
+use std::sync::OnceLock;
+
+/// User-defined functions by name, sorted for `vars`.
+pub type Functions = BTreeMap<String, UserFunction>;
+
+/// A `: Quantity` annotation: the name as written, and what it means.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Annotation {
+    pub quantity: String,
+    pub dimensions: DimensionMap,
+}
+
+/// One formal parameter, optionally annotated.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Parameter {
+    pub name: String,
+    pub annotation: Option<Annotation>,
+}
+
+/// A function defined as `name(params)[: Quantity] = body`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct UserFunction {
+    pub name: String,
+    pub params: Vec<Parameter>,
+    /// The annotation on the result, if any.
+    pub returns: Option<Annotation>,
+    /// The body as written, for display.
+    pub source: String,
+    pub body: Expr,
+}
+
+impl UserFunction {
+    /// The definition's left-hand side: `ke(m: Mass, v: Velocity): Energy`.
+    pub fn signature(&self) -> String {
+        let params: Vec<String> = self
+            .params
+            .iter()
+            .map(|p| match &p.annotation {
+                Some(a) => format!("{}: {}", p.name, a.quantity),
+                None => p.name.clone(),
+            })
+            .collect();
+        let mut signature = format!("{}({})", self.name, params.join(", "));
+        if let Some(returns) = &self.returns {
+            signature.push_str(&format!(": {}", returns.quantity));
+        }
+        signature
+    }
+
+    /// Call the function: check the arity and annotated arguments, evaluate
+    /// the body with the parameters bound, then check the result.
+    pub fn call(&self, args: &[Quantity], ctx: &EvalContext) -> Result<Quantity, RUnitsError> {
+        if args.len() != self.params.len() {
+            return Err(RUnitsError::ArityMismatch {
+                name: self.name.clone(),
+                expected: self.params.len(),
+                got: args.len(),
+            });
+        }
+        let mut scope = ctx.variables.cloned().unwrap_or_default();
+        for (param, arg) in self.params.iter().zip(args) {
+            if let Some(annotation) = &param.annotation {
+                self.check(annotation, arg, || format!("parameter '{}'", param.name))?;
+            }
+            scope.insert(param.name.clone(), arg.clone());
+        }
+        let body_ctx = EvalContext {
+            units: ctx.units,
+            constants: ctx.constants,
+            previous: None,
+            variables: Some(&scope),
+            functions: ctx.functions,
+        };
+        let result = eval(&self.body, &body_ctx)?;
+        if let Some(annotation) = &self.returns {
+            self.check(annotation, &result, || "result".to_string())?;
+        }
+        Ok(result)
+    }
+
+    fn check(
+        &self,
+        annotation: &Annotation,
+        value: &Quantity,
+        what: impl FnOnce() -> String,
+    ) -> Result<(), RUnitsError> {
+        if value.unit.dimensions == annotation.dimensions {
+            return Ok(());
+        }
+        let got = crate::annotations::quantity_name(&value.unit.dimensions)
+            .map(str::to_string)
+            .unwrap_or_else(|| value.unit.dimension_string());
+        Err(RUnitsError::TypedParameterMismatch {
+            name: self.name.clone(),
+            what: what(),
+            expected: annotation.quantity.clone(),
+            got,
+        })
+    }
+}
+
+impl fmt::Display for UserFunction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} = {}", self.signature(), self.source)
+    }
+}
+
+/// Parse a `name(params)[: Quantity] = body` line, resolving quantity names
+/// globally. `None` when the line doesn't have that shape at all, so callers
+/// can fall through to assignments and expressions.
+pub fn parse_definition(line: &str) -> Option<Result<UserFunction, RUnitsError>> {
+    parse_definition_with(line, &quantity_dimensions)
+}
+
+/// [`parse_definition`] with a caller-supplied quantity resolver, so
+/// definition files can use the dimensions they declare.
+pub fn parse_definition_with(
+    line: &str,
+    quantity_dims: &dyn Fn(&str) -> Option<DimensionMap>,
+) -> Option<Result<UserFunction, RUnitsError>> {
+    let (head, body) = line.split_once('=')?;
+    let (name, rest) = head.split_once('(')?;
+    let name = name.trim();
+    if !is_identifier(name) {
+        return None;
+    }
+    Some(
+        parse_parts(name, rest, body, quantity_dims).map_err(|reason| {
+            RUnitsError::FunctionDefinition {
+                definition: line.trim().to_string(),
+                reason,
+            }
+        }),
+    )
+}
+
+/// Parse the pieces of a definition: `rest` is everything after the `(`.
+fn parse_parts(
+    name: &str,
+    rest: &str,
+    body: &str,
+    quantity_dims: &dyn Fn(&str) -> Option<DimensionMap>,
+) -> Result<UserFunction, String> {
+    if math::lookup(name).is_some() || chemistry::lookup(name).is_some() {
+        return Err(format!("'{name}' is a builtin function"));
+    }
+    let annotation = |text: &str| -> Result<Annotation, String> {
+        let quantity = text.trim();
+        let dimensions =
+            quantity_dims(quantity).ok_or_else(|| format!("unknown quantity '{quantity}'"))?;
+        Ok(Annotation {
+            quantity: quantity.to_string(),
+            dimensions,
+        })
+    };
+
+    let (params_text, after) = rest
+        .split_once(')')
+        .ok_or_else(|| "missing ')' after the parameters".to_string())?;
+    let returns = match after.trim() {
+        "" => None,
+        tail => Some(annotation(tail.strip_prefix(':').ok_or_else(|| {
+            format!("unexpected '{tail}' after the parameters (expected ': Quantity' or '=')")
+        })?)?),
+    };
+
+    let mut params: Vec<Parameter> = Vec::new();
+    if !params_text.trim().is_empty() {
+        for text in params_text.split(',') {
+            let (param, quantity) = match text.split_once(':') {
+                Some((param, quantity)) => (param.trim(), Some(annotation(quantity)?)),
+                None => (text.trim(), None),
+            };
+            if !is_identifier(param) {
+                return Err(format!("invalid parameter name '{param}'"));
+            }
+            if params.iter().any(|p| p.name == param) {
+                return Err(format!("parameter '{param}' appears twice"));
+            }
+            params.push(Parameter {
+                name: param.to_string(),
+                annotation: quantity,
+            });
+        }
+    }
+
+    let source = body.trim();
+    if source.is_empty() {
+        return Err("missing expression after '='".to_string());
+    }
+    let body = parse_expression(source).map_err(|e| format!("invalid body: {e}"))?;
+    Ok(UserFunction {
+        name: name.to_string(),
+        params,
+        returns,
+        source: source.to_string(),
+        body,
+    })
+}
+
+/// Add (or replace) `function`, unless it would hide a function unit of
+/// `units` (`tempC`, `wiregauge`) or end up calling itself. Returns the
+/// stored definition, for echoing.
+pub fn define<'f>(
+    functions: &'f mut Functions,
+    function: UserFunction,
+    units: &UnitDatabase,
+) -> Result<&'f UserFunction, RUnitsError> {
+    if units
+        .lookup(&function.name)
+        .is_some_and(|u| u.is_nonlinear())
+    {
+        return Err(RUnitsError::FunctionDefinition {
+            definition: function.to_string(),
+            reason: format!("'{}' is a builtin function unit", function.name),
+        });
+    }
+    let mut chain = vec![function.name.clone()];
+    if reaches(
+        &function.name,
+        &function,
+        functions,
+        &mut chain,
+        &mut HashSet::new(),
+    ) {
+        return Err(RUnitsError::FunctionDefinition {
+            definition: function.to_string(),
+            reason: format!(
+                "'{}' would call itself: {}",
+                function.name,
+                chain.join(" -> ")
+            ),
+        });
+    }
+    let name = function.name.clone();
+    functions.insert(name.clone(), function);
+    Ok(&functions[&name])
+}
+
+/// Whether `from`'s body calls `target`, directly or through the functions
+/// in `functions`. On success `chain` holds the path, ending at `target`.
+fn reaches(
+    target: &str,
+    from: &UserFunction,
+    functions: &Functions,
+    chain: &mut Vec<String>,
+    visited: &mut HashSet<String>,
+) -> bool {
+    let mut callees = Vec::new();
+    collect_calls(&from.body, &mut callees);
+    for callee in callees {
+        chain.push(callee.to_string());
+        if callee == target {
+            return true;
+        }
+        if visited.insert(callee.to_string())
+            && let Some(next) = functions.get(callee)
+            && reaches(target, next, functions, chain, visited)
+        {
+            return true;
+        }
+        chain.pop();
+    }
+    false
+}
+
+/// Every function name an expression calls, in source order.
+fn collect_calls<'e>(expr: &'e Expr, out: &mut Vec<&'e str>) {
+    match expr {
+        Expr::Number(_) | Expr::Ident(_) | Expr::Previous | Expr::ChemCall(..) => {}
+        Expr::BinOp(_, lhs, rhs) => {
+            collect_calls(lhs, out);
+            collect_calls(rhs, out);
+        }
+        Expr::Neg(inner) | Expr::Pow(inner, _) | Expr::InverseCall(_, inner) => {
+            collect_calls(inner, out)
+        }
+        Expr::FuncCall(name, args) => {
+            out.push(name);
+            for arg in args {
+                collect_calls(arg, out);
+            }
+        }
+        Expr::Mixed(parts) => {
+            for (_, unit) in parts {
+                collect_calls(unit, out);
+            }
+        }
+    }
+}
+
+/// The process-wide functions from the user's definition file. Empty unless
+/// [`set_global`] installed some.
+pub fn global() -> &'static Functions {
+    GLOBAL.get_or_init(Functions::new)
+}
+
+/// Install `functions` as the process-wide set (the `fn` statements of the
+/// user's `units.conf`). Fails if [`global`] was already read or set.
+pub fn set_global(functions: Functions) -> Result<(), Functions> {
+    GLOBAL.set(functions)
+}
+
+static GLOBAL: OnceLock<Functions> = OnceLock::new();
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(line: &str) -> Result<UserFunction, RUnitsError> {
+        parse_definition(line).expect("line has the shape of a definition")
+    }
+
+    fn add(functions: &mut Functions, line: &str) -> Result<(), RUnitsError> {
+        define(functions, parse(line)?, &UnitDatabase::new()).map(|_| ())
+    }
+
+    fn call(functions: &Functions, input: &str) -> Result<Quantity, RUnitsError> {
+        let db = UnitDatabase::new();
+        let ctx = EvalContext::one_shot(&db).with_functions(functions);
+        crate::parser::parse_and_eval(input, &ctx)
+    }
+
+    #[test]
+    fn parses_annotated_definition() {
+        let f = parse("ke(m: Mass, v: Velocity): Energy = 0.5 * m * v^2").unwrap();
+        assert_eq!(f.name, "ke");
+        assert_eq!(f.params.len(), 2);
+        assert_eq!(
+            f.params[1].annotation.as_ref().unwrap().quantity,
+            "Velocity"
+        );
+        assert_eq!(
+            f.to_string(),
+            "ke(m: Mass, v: Velocity): Energy = 0.5 * m * v^2"
+        );
+    }
+
+    #[test]
+    fn other_lines_are_not_definitions() {
+        assert!(parse_definition("x = 5 ft").is_none());
+        assert!(parse_definition("sqrt(4 m^2)").is_none());
+        assert!(parse_definition("10 ft -> m").is_none());
+    }
+
+    #[test]
+    fn rejects_malformed_definitions() {
+        for line in [
+            "sqrt(x) = x",
+            "f(x, x) = x",
+            "f(2) = 1",
+            "f(x: Flavour) = x",
+            "f(x) : = x",
+            "f(x =",
+        ] {
+            assert!(
+                matches!(parse(line), Err(RUnitsError::FunctionDefinition { .. })),
+                "{line}"
+            );
+        }
+    }
+
+    #[test]
+    fn calls_bind_parameters_over_units() {
+        let mut functions = Functions::new();
+        add(&mut functions, "ke(m, v) = 0.5 * m * v^2").unwrap();
+        let q = call(&functions, "ke(2 kg, 3 m/s)").unwrap();
+        // 9 J, in base units.
+        assert!((q.unit.to_base_value(q.value) - 9.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn checks_arity_and_annotations() {
+        let mut functions = Functions::new();
+        let ke = "ke(m: Mass, v: Velocity): Energy = 0.5 * m * v^2";
+        add(&mut functions, ke).unwrap();
+        assert!(matches!(
+            call(&functions, "ke(2 kg)"),
+            Err(RUnitsError::ArityMismatch {
+                expected: 2,
+                got: 1,
+                ..
+            })
+        ));
+        let err = call(&functions, "ke(2 m, 3 m/s)").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "function 'ke': parameter 'm' must be Mass, got Length"
+        );
+
+        add(&mut functions, "bad(x: Length): Area = x").unwrap();
+        let err = call(&functions, "bad(1 m)").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "function 'bad': result must be Area, got Length"
+        );
+    }
+
+    #[test]
+    fn rejects_recursive_definitions() {
+        let mut functions = Functions::new();
+        add(&mut functions, "f(x) = g(x)").unwrap();
+        add(&mut functions, "h(x) = 2 * x").unwrap();
+        let err = add(&mut functions, "g(x) = h(f(x))").unwrap_err();
+        assert!(err.to_string().contains("g -> f -> g"), "{err}");
+        assert!(add(&mut functions, "f(x) = f(x)").is_err());
+    }
+
+    #[test]
+    fn rejects_builtin_function_names() {
+        let mut functions = Functions::new();
+        for line in ["tempC(x) = x", "wiregauge(n) = n", "molar_mass(x) = x"] {
+            let err = add(&mut functions, line).unwrap_err();
+            assert!(err.to_string().contains("is a builtin function"), "{err}");
+        }
+        assert!(functions.is_empty());
+    }
+}
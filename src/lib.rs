@@ -39,17 +39,23 @@
 //! ```
 
 pub mod annotations;
+pub mod auto;
+pub mod check;
+pub mod chemistry;
 pub mod cli;
 pub mod config;
 pub mod convert;
 pub mod database;
+pub mod equiv;
 pub mod error;
 pub mod eval;
 pub mod expr;
 pub mod format;
+pub mod functions;
 pub mod math;
 pub mod parser;
 pub mod repl;
+pub mod reverse;
 pub mod theme;
 pub mod units;
 
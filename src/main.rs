@@ -2,7 +2,9 @@
 //!
 //! Dispatches to one-shot conversion, REPL, batch mode, or subcommands
 //! based on CLI arguments. Loads optional config from
-//! `~/.config/runits/config.toml` and merges with CLI flags.
+//! `~/.config/runits/config.toml` and merges with CLI flags, and layers the
+//! user's `~/.config/runits/units.conf` over the selected database tier
+//! (`--db builtin|standard|full`) before anything looks a unit up.
 
 use std::io::IsTerminal;
 
@@ -12,6 +14,7 @@ use runits::{
     config::Config,
     convert, database,
     error::RUnitsError,
+    eval::{EvalContext, Variables},
     format::{self, FormatOptions},
 };
 
@@ -24,10 +27,11 @@ fn main() {
 
 fn run() -> Result<(), RUnitsError> {
     let cli = Cli::parse();
+    let config = Config::load();
+    install_databases(&cli, &config)?;
 
-    // Subcommands take priority (no config needed).
+    // Subcommands take priority.
     if let Some(cmd) = &cli.command {
-        let config = Config::load();
         let opts = resolve_opts(&cli, &config, false);
         return match cmd {
             Commands::Completions { shell } => {
@@ -38,11 +42,12 @@ fn run() -> Result<(), RUnitsError> {
                 run_list(what, &opts);
                 Ok(())
             }
+            Commands::Element { name } => run_element(name, &opts),
+            Commands::ImportGnu { path } => run_import_gnu(path, &opts),
+            Commands::Check { files } => run_check(files, &config, &opts),
         };
     }
 
-    let config = Config::load();
-
     // --info flag: print database/config info and exit.
     if cli.info {
         let opts = resolve_opts(&cli, &config, false);
@@ -52,7 +57,12 @@ fn run() -> Result<(), RUnitsError> {
 
     // Dispatch based on positional args.
     match (&cli.quantity, &cli.target) {
+        (Some(quantity), None) if cli.what => run_what(&cli, &config, quantity),
         (Some(quantity), Some(target)) => run_oneshot(&cli, &config, quantity, target),
+        // `--auto` (or `auto` in config.toml) makes the target optional.
+        (Some(quantity), None) if cli.auto.or(config.auto).is_some() => {
+            run_oneshot(&cli, &config, quantity, runits::auto::AUTO_TARGET)
+        }
         (None, None) if cli.batch => run_batch(&cli, &config),
         (None, None) => run_repl(&cli, &config),
         _ => {
@@ -62,6 +72,92 @@ fn run() -> Result<(), RUnitsError> {
     }
 }
 
+/// Build the unit and constant databases for the selected tier, layer
+/// `units.conf` (if present) on top, and install both as the global
+/// singletons before anything looks a unit up.
+///
+/// The tier comes from `--db`, then the `db` config key, then the mode:
+/// the REPL defaults to `standard`, everything else to `builtin`. A broken
+/// `units.conf` is reported and ignored — like a malformed `config.toml`,
+/// it shouldn't make the builtins unusable.
+fn install_databases(cli: &Cli, config: &Config) -> Result<(), RUnitsError> {
+    let is_repl = cli.command.is_none()
+        && !cli.info
+        && !cli.batch
+        && cli.quantity.is_none()
+        && cli.target.is_none();
+    let tier = cli.db.or(config.db).unwrap_or(if is_repl {
+        database::Tier::Standard
+    } else {
+        database::Tier::Builtin
+    });
+
+    let database::tiers::TierDatabases {
+        mut units,
+        mut constants,
+        ..
+    } = build_tier(tier, config)?;
+
+    let mut functions = runits::functions::Functions::new();
+    if let Some(path) = &config.units_file {
+        match database::definitions::load_file(path, &mut units, &mut constants) {
+            Ok(summary) => {
+                for warning in &summary.warnings {
+                    eprintln!("Warning: {warning}");
+                }
+                functions = summary.functions;
+            }
+            Err(e) => {
+                eprintln!("Warning: ignoring user units: {e}");
+                // Loading stops midway on error; start over without the file.
+                let fresh = database::tiers::build(tier, gnu_definitions(config).as_deref())?;
+                units = fresh.units;
+                constants = fresh.constants;
+            }
+        }
+    }
+
+    // Nothing has touched the singletons yet, so these can't fail.
+    let _ = database::set_global(units);
+    let _ = database::constants::set_global(constants);
+    let _ = runits::functions::set_global(functions);
+    Ok(())
+}
+
+/// Build the databases for `tier`, shared by startup and `runits check`.
+/// The full tier imports GNU Units' `definitions.units` and reports the
+/// import's headline on stderr, or warns when there is no file to import.
+fn build_tier(
+    tier: database::Tier,
+    config: &Config,
+) -> Result<database::tiers::TierDatabases, RUnitsError> {
+    let gnu_file = gnu_definitions(config);
+    if tier == database::Tier::Full && gnu_file.is_none() {
+        eprintln!(
+            "Warning: no GNU definitions.units found (set `gnu_definitions` in config.toml); \
+             using the standard units only"
+        );
+    }
+    let databases = database::tiers::build(tier, gnu_file.as_deref())?;
+    if let (Some(report), Some(path)) = (&databases.gnu_report, &gnu_file) {
+        // Just the headline; `runits import-gnu <path>` breaks down the
+        // skipped entries.
+        let headline = report.to_string();
+        let headline = headline.lines().next().unwrap_or_default();
+        eprintln!("{}: {headline}", path.display());
+    }
+    Ok(databases)
+}
+
+/// The GNU `definitions.units` to import: the `gnu_definitions` config key,
+/// else the first standard install location that exists.
+fn gnu_definitions(config: &Config) -> Option<std::path::PathBuf> {
+    config
+        .gnu_definitions
+        .clone()
+        .or_else(database::tiers::find_gnu_definitions)
+}
+
 /// Build FormatOptions by merging CLI flags over config defaults.
 fn resolve_opts(cli: &Cli, config: &Config, is_repl: bool) -> FormatOptions {
     let is_tty = std::io::stdout().is_terminal();
@@ -78,6 +174,21 @@ fn resolve_opts(cli: &Cli, config: &Config, is_repl: bool) -> FormatOptions {
     }
 }
 
+/// Build ConvertOptions by merging CLI flags over config defaults.
+fn resolve_convert_opts(cli: &Cli, config: &Config) -> convert::ConvertOptions {
+    convert::ConvertOptions {
+        strict: cli.strict || config.strict.unwrap_or(false),
+        // `--equiv` replaces the config list rather than adding to it.
+        equivalencies: if cli.equiv.is_empty() {
+            config.equiv.clone().unwrap_or_default()
+        } else {
+            cli.equiv.clone()
+        },
+        auto: cli.auto.or(config.auto),
+        precision: cli.precision.or(config.precision),
+    }
+}
+
 fn run_oneshot(
     cli: &Cli,
     config: &Config,
@@ -85,12 +196,31 @@ fn run_oneshot(
     target: &str,
 ) -> Result<(), RUnitsError> {
     let db = database::global();
-    let conv = convert::run_conversion(quantity, target, db)?;
+    let conv = convert::run_conversion(quantity, target, db, &resolve_convert_opts(cli, config))?;
     let opts = resolve_opts(cli, config, false);
     println!("{}", format::format_result(&conv, &opts));
     Ok(())
 }
 
+/// `runits --what "9.81 m/s^2"`: reverse lookup instead of a conversion.
+fn run_what(cli: &Cli, config: &Config, quantity: &str) -> Result<(), RUnitsError> {
+    let db = database::global();
+    let qty = runits::parser::parse_quantity(quantity, db)?;
+    let matches = runits::reverse::lookup(
+        &qty,
+        db,
+        database::constants::global(),
+        runits::reverse::MAX_MATCHES,
+    );
+    let opts = resolve_opts(cli, config, false);
+    if opts.json {
+        println!("{}", format::format_what_json(&qty, &matches, &opts));
+    } else {
+        println!("{}", format::format_what(&qty, &matches, &opts));
+    }
+    Ok(())
+}
+
 fn run_repl(cli: &Cli, config: &Config) -> Result<(), RUnitsError> {
     let opts = resolve_opts(cli, config, true);
     let banner = cli.intro_banner.unwrap_or_else(|| {
@@ -107,13 +237,20 @@ fn run_repl(cli: &Cli, config: &Config) -> Result<(), RUnitsError> {
             }
         }
     });
-    runits::repl::run(&opts, banner);
+    runits::repl::run(&opts, &resolve_convert_opts(cli, config), banner);
     Ok(())
 }
 
 fn run_batch(cli: &Cli, config: &Config) -> Result<(), RUnitsError> {
     let db = database::global();
     let opts = resolve_opts(cli, config, false);
+    let convert_opts = resolve_convert_opts(cli, config);
+
+    // `name = <qty>` lines define variables, and `name(params) = <expr>`
+    // lines functions, for the lines after them; `vars` and `del` work as
+    // in the REPL.
+    let mut variables = Variables::new();
+    let mut functions = runits::functions::global().clone();
 
     let stdin = std::io::stdin();
     for line in std::io::BufRead::lines(stdin.lock()) {
@@ -122,17 +259,134 @@ fn run_batch(cli: &Cli, config: &Config) -> Result<(), RUnitsError> {
         if line.is_empty() || line.starts_with('#') {
             continue;
         }
-        match runits::repl::parse_repl_line(line) {
-            Some((source, target)) => match convert::run_conversion(source, target, db) {
-                Ok(result) => println!("{}", format::format_result(&result, &opts)),
+        if runits::repl::handle_session_command(line, &mut variables, &mut functions, &opts) {
+            continue;
+        }
+        if let Some(definition) = runits::functions::parse_definition(line) {
+            match definition.and_then(|f| runits::functions::define(&mut functions, f, db)) {
+                Ok(function) => println!("{function}"),
                 Err(e) => eprintln!("Error: {e}"),
-            },
+            }
+            continue;
+        }
+        let ctx = EvalContext::one_shot(db)
+            .with_variables(&variables)
+            .with_functions(&functions);
+        if let Some((name, rhs)) = runits::repl::parse_assignment(line) {
+            match runits::repl::eval_assignment(name, rhs, &ctx, &convert_opts) {
+                Ok((result, warning)) => {
+                    if let Some(warning) = warning {
+                        eprintln!("Warning: {warning}");
+                    }
+                    runits::repl::print_assignment(name, &result, &opts);
+                    variables.insert(name.to_string(), result.result);
+                }
+                Err(e) => eprintln!("Error: {e}"),
+            }
+            continue;
+        }
+        match runits::repl::parse_repl_line(line) {
+            Some((source, target)) => {
+                match convert::run_conversion_in(source, target, &ctx, &convert_opts) {
+                    Ok(result) => println!("{}", format::format_result(&result, &opts)),
+                    Err(e) => eprintln!("Error: {e}"),
+                }
+            }
+            None if convert_opts.auto.is_some() => {
+                match convert::run_conversion_in(
+                    line,
+                    runits::auto::AUTO_TARGET,
+                    &ctx,
+                    &convert_opts,
+                ) {
+                    Ok(result) => println!("{}", format::format_result(&result, &opts)),
+                    Err(e) => eprintln!("Error: {e}"),
+                }
+            }
             None => eprintln!("malformed line: {line}"),
         }
     }
     Ok(())
 }
 
+/// Dry-run a GNU import against the current database and print the report,
+/// or its JSON form.
+fn run_import_gnu(path: &std::path::Path, opts: &FormatOptions) -> Result<(), RUnitsError> {
+    let mut units = database::UnitDatabase::new();
+    let report = database::gnu::import_file(path, &mut units, database::constants::global())?;
+    if opts.json {
+        println!("{}", database::gnu::format_json(&report, path));
+    } else {
+        println!("{}: {report}", path.display());
+    }
+    Ok(())
+}
+
+/// Audit the database (or just the given definition files) and exit
+/// non-zero when anything is found, so CI can gate custom unit files.
+///
+/// Files are loaded on top of the current tier, in order, and only the names
+/// they define are audited. Without files the whole database is audited,
+/// including `units.conf` — whose load errors `install_databases` only warns
+/// about, but which count as problems here.
+fn run_check(
+    files: &[std::path::PathBuf],
+    config: &Config,
+    opts: &FormatOptions,
+) -> Result<(), RUnitsError> {
+    use runits::check;
+
+    let database::tiers::TierDatabases {
+        mut units,
+        mut constants,
+        ..
+    } = build_tier(database::global().tier(), config)?;
+
+    let mut findings = Vec::new();
+    let mut scope = std::collections::HashSet::new();
+    let to_load: Vec<&std::path::Path> = if files.is_empty() {
+        config.units_file.iter().map(|p| p.as_path()).collect()
+    } else {
+        files.iter().map(|p| p.as_path()).collect()
+    };
+    for path in to_load {
+        match database::definitions::load_file(path, &mut units, &mut constants) {
+            Ok(summary) => scope.extend(summary.defined),
+            Err(e) => findings.push(check::load_error(&e)),
+        }
+    }
+
+    let scope = (!files.is_empty()).then_some(&scope);
+    findings.extend(check::audit(&units, &constants, scope));
+    let checked = scope.map_or(units.len() + constants.len(), |s| s.len());
+
+    if opts.json {
+        println!("{}", check::format_json(&findings, checked));
+    } else {
+        let t = runits::theme::Theme::new(opts.color);
+        println!("{}", check::format_report(&findings, checked, &t));
+    }
+    if !findings.is_empty() {
+        std::process::exit(1);
+    }
+    Ok(())
+}
+
+/// `runits element Fe`: print an element's properties, or its JSON form.
+fn run_element(name: &str, opts: &FormatOptions) -> Result<(), RUnitsError> {
+    let element =
+        runits::chemistry::elements::lookup(name).ok_or_else(|| RUnitsError::UnknownElement {
+            symbol: name.to_string(),
+            suggestions: runits::chemistry::elements::suggest(name, 3),
+        })?;
+    if opts.json {
+        println!("{}", format::format_element_json(element));
+    } else {
+        println!("{}", format::format_element_info(element, opts));
+    }
+    Ok(())
+}
+
 fn run_list(what: &ListWhat, opts: &FormatOptions) {
     let db = database::global();
 
@@ -156,8 +410,7 @@ fn list_units(filter: Option<&str>, db: &runits::database::UnitDatabase, opts: &
 
         if let Some(dims) = dims {
             let qty_name = runits::annotations::quantity_name(&dims).unwrap_or(query);
-            let dims_vec: Vec<_> = dims.iter().map(|(d, &e)| (d.clone(), e)).collect();
-            let synthetic = runits::Unit::new("_query", 1.0, &dims_vec);
+            let synthetic = runits::Unit::from_dimensions("_query", 1.0, dims.clone());
             let compat = db.compatible_units(&synthetic);
             if opts.json {
                 print_json_unit_list(qty_name, &compat);
@@ -214,7 +467,7 @@ fn list_dimensions(opts: &FormatOptions) {
 fn list_constants(opts: &FormatOptions) {
     let const_db = runits::database::constants::global();
     let mut constants = const_db.all_unique();
-    constants.sort_by_key(|c| c.name);
+    constants.sort_by(|a, b| a.name.cmp(&b.name));
     if opts.json {
         let entries: Vec<String> = constants
             .iter()
@@ -230,7 +483,7 @@ fn list_constants(opts: &FormatOptions) {
         let t = runits::theme::Theme::new(opts.color);
         for c in &constants {
             let val = runits::units::quantity::format_value(c.value, 6, false);
-            println!("  {} = {} {}", t.cst(c.name), t.num(&val), c.unit.name);
+            println!("  {} = {} {}", t.cst(&c.name), t.num(&val), c.unit.name);
         }
     }
 }
@@ -20,52 +20,110 @@
 //!
 //! ## Extension recipe
 //!
-//! 1. Add a variant to `MathFn`: e.g. `MathFn::Log10`.
-//! 2. Add matching arms in `name`, `signature`, `apply`, and add the variant
-//!    to `MathFn::ALL`.
-//! 3. For a scalar function, `apply`'s arm calls `apply_scalar(self.name(),
-//!    &args[0], f64::log10)`. For a dimension-transforming function, copy the
-//!    shape of `apply_sqrt` and validate dimensions before halving/doubling.
+//! 1. Add a variant to `MathFn`: e.g. `MathFn::Log1p`.
+//! 2. Add matching arms in `name`, `signature`, `description`, `apply`, and
+//!    add the variant to `MathFn::ALL`.
+//! 3. For a scalar function, `apply`'s arm calls `apply_scalar(name,
+//!    &args[0], f64::ln_1p)`. For a dimension-transforming function, copy the
+//!    shape of `apply_sqrt`: base-unit trip, then scale the dimensions.
 //! 4. Write a unit test.
 //!
 //! No grammar change, no evaluator change, no `Box`, no registration — the
 //! compiler will flag any arm you forgot.
 
 use crate::error::RUnitsError;
-use crate::units::dimension::Dimension;
-use crate::units::{Quantity, Unit};
+use crate::units::dimension::{Dimension, DimensionMap};
+use crate::units::{Quantity, Rational, Unit};
+use std::fmt;
 
 /// The set of built-in math functions.
 ///
 /// Covers all three dimensionality categories (following Numbat's
 /// classification):
 ///
-/// - **Dimension-transforming:** `sqrt(D²) → D`, `sqr(D) → D²`
-/// - **Dimension-generic:** `abs(D) → D`
-/// - **Scalar-only (transcendental / trig):** `sin`, `cos`, `tan`, `ln`, `exp`
+/// - **Dimension-transforming:** `sqrt(D) → D^(1/2)`, `cbrt(D) → D^(1/3)`,
+///   `sqr(D) → D²`
+/// - **Dimension-generic:** `abs`, `floor`, `ceil`, `round`, `trunc`
+///   (`D → D`, in the argument's own unit), and the variadic `hypot`, `min`,
+///   `max`, `sum`, `mean` (`D, D, ... → D`, in the first argument's unit)
+/// - **Scalar-only:** trig (`sin`, `cos`, `tan`, which also take an
+///   `Angle`), inverse trig and `atan2` (returning an `Angle` in radians),
+///   hyperbolic,
+///   logarithms, `exp`, `gamma`, `erf`
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum MathFn {
     // Dimension-transforming
     Sqrt,
+    Cbrt,
     Sqr,
     // Dimension-generic
     Abs,
-    // Scalar-only — transcendental + trig
+    Floor,
+    Ceil,
+    Round,
+    Trunc,
+    // Variadic, dimension-generic — arguments coerced to the first one's unit
+    Hypot,
+    Min,
+    Max,
+    Sum,
+    Mean,
+    // Trig — Angle or Scalar in, Scalar out; inverses the other way round
     Sin,
     Cos,
     Tan,
+    Asin,
+    Acos,
+    Atan,
+    Atan2,
+    // Scalar-only — hyperbolic, logarithms, special functions
+    Sinh,
+    Cosh,
+    Tanh,
+    Asinh,
+    Acosh,
+    Atanh,
     Ln,
+    Log10,
+    Log2,
+    Log,
     Exp,
+    Gamma,
+    Erf,
 }
 
 /// How many arguments a function accepts.
 ///
 /// Kept as an enum (rather than a `usize`) so `Exact(1)` reads clearly at
-/// call sites, and so future `AtLeast(n)` / `Range(min, max)` variants have
-/// a place to land if variadic functions (like `max(a, b, c, ...)`) arrive.
-#[derive(Debug, Clone, Copy)]
+/// call sites. `AtLeast` is for variadic functions (`max(a, b, c, ...)`),
+/// `Range` for optional trailing arguments; both bounds are inclusive.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Arity {
     Exact(usize),
+    AtLeast(usize),
+    Range(usize, usize),
+}
+
+impl Arity {
+    /// Whether a call with `n` arguments is allowed.
+    pub fn accepts(self, n: usize) -> bool {
+        match self {
+            Arity::Exact(m) => n == m,
+            Arity::AtLeast(min) => n >= min,
+            Arity::Range(min, max) => (min..=max).contains(&n),
+        }
+    }
+}
+
+/// `2`, `at least 2`, `1 to 2` — as used in arity errors.
+impl fmt::Display for Arity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Arity::Exact(n) => write!(f, "{n}"),
+            Arity::AtLeast(min) => write!(f, "at least {min}"),
+            Arity::Range(min, max) => write!(f, "{min} to {max}"),
+        }
+    }
 }
 
 impl MathFn {
@@ -74,47 +132,168 @@ impl MathFn {
     /// Used by `lookup`, `suggest`, and the completions helper.
     pub const ALL: &'static [MathFn] = &[
         MathFn::Sqrt,
+        MathFn::Cbrt,
         MathFn::Sqr,
         MathFn::Abs,
+        MathFn::Floor,
+        MathFn::Ceil,
+        MathFn::Round,
+        MathFn::Trunc,
+        MathFn::Hypot,
+        MathFn::Min,
+        MathFn::Max,
+        MathFn::Sum,
+        MathFn::Mean,
         MathFn::Sin,
         MathFn::Cos,
         MathFn::Tan,
+        MathFn::Asin,
+        MathFn::Acos,
+        MathFn::Atan,
+        MathFn::Atan2,
+        MathFn::Sinh,
+        MathFn::Cosh,
+        MathFn::Tanh,
+        MathFn::Asinh,
+        MathFn::Acosh,
+        MathFn::Atanh,
         MathFn::Ln,
+        MathFn::Log10,
+        MathFn::Log2,
+        MathFn::Log,
         MathFn::Exp,
+        MathFn::Gamma,
+        MathFn::Erf,
     ];
 
     /// The function's name as it appears in user input.
     pub fn name(self) -> &'static str {
         match self {
             MathFn::Sqrt => "sqrt",
+            MathFn::Cbrt => "cbrt",
             MathFn::Sqr => "sqr",
             MathFn::Abs => "abs",
+            MathFn::Floor => "floor",
+            MathFn::Ceil => "ceil",
+            MathFn::Round => "round",
+            MathFn::Trunc => "trunc",
+            MathFn::Hypot => "hypot",
+            MathFn::Min => "min",
+            MathFn::Max => "max",
+            MathFn::Sum => "sum",
+            MathFn::Mean => "mean",
             MathFn::Sin => "sin",
             MathFn::Cos => "cos",
             MathFn::Tan => "tan",
+            MathFn::Asin => "asin",
+            MathFn::Acos => "acos",
+            MathFn::Atan => "atan",
+            MathFn::Atan2 => "atan2",
+            MathFn::Sinh => "sinh",
+            MathFn::Cosh => "cosh",
+            MathFn::Tanh => "tanh",
+            MathFn::Asinh => "asinh",
+            MathFn::Acosh => "acosh",
+            MathFn::Atanh => "atanh",
             MathFn::Ln => "ln",
+            MathFn::Log10 => "log10",
+            MathFn::Log2 => "log2",
+            MathFn::Log => "log",
             MathFn::Exp => "exp",
+            MathFn::Gamma => "gamma",
+            MathFn::Erf => "erf",
         }
     }
 
-    /// Human-readable signature for `?`-help output.
+    /// Human-readable signature for `?`-help output and tab completion.
     pub fn signature(self) -> &'static str {
         match self {
-            MathFn::Sqrt => "sqrt<D>(D²) → D",
+            MathFn::Sqrt => "sqrt<D>(D) → D^(1/2)",
+            MathFn::Cbrt => "cbrt<D>(D) → D^(1/3)",
             MathFn::Sqr => "sqr<D>(D) → D²",
             MathFn::Abs => "abs<D>(D) → D",
-            MathFn::Sin | MathFn::Cos | MathFn::Tan => "(Scalar) → Scalar",
-            MathFn::Ln | MathFn::Exp => "(Scalar) → Scalar",
+            MathFn::Floor => "floor<D>(D) → D",
+            MathFn::Ceil => "ceil<D>(D) → D",
+            MathFn::Round => "round<D>(D) → D",
+            MathFn::Trunc => "trunc<D>(D) → D",
+            MathFn::Hypot => "hypot<D>(D, D, ...) → D",
+            MathFn::Min => "min<D>(D, ...) → D",
+            MathFn::Max => "max<D>(D, ...) → D",
+            MathFn::Sum => "sum<D>(D, ...) → D",
+            MathFn::Mean => "mean<D>(D, ...) → D",
+            MathFn::Sin => "sin(Angle | Scalar) → Scalar",
+            MathFn::Cos => "cos(Angle | Scalar) → Scalar",
+            MathFn::Tan => "tan(Angle | Scalar) → Scalar",
+            MathFn::Asin => "asin(Scalar) → Angle",
+            MathFn::Acos => "acos(Scalar) → Angle",
+            MathFn::Atan => "atan(Scalar) → Angle",
+            MathFn::Atan2 => "atan2<D>(y: D, x: D) → Angle",
+            MathFn::Sinh => "sinh(Scalar) → Scalar",
+            MathFn::Cosh => "cosh(Scalar) → Scalar",
+            MathFn::Tanh => "tanh(Scalar) → Scalar",
+            MathFn::Asinh => "asinh(Scalar) → Scalar",
+            MathFn::Acosh => "acosh(Scalar) → Scalar",
+            MathFn::Atanh => "atanh(Scalar) → Scalar",
+            MathFn::Ln => "ln(Scalar) → Scalar",
+            MathFn::Log10 => "log10(Scalar) → Scalar",
+            MathFn::Log2 => "log2(Scalar) → Scalar",
+            MathFn::Log => "log(Scalar, base: Scalar) → Scalar",
+            MathFn::Exp => "exp(Scalar) → Scalar",
+            MathFn::Gamma => "gamma(Scalar) → Scalar",
+            MathFn::Erf => "erf(Scalar) → Scalar",
+        }
+    }
+
+    /// One-line description for `?`-help output.
+    pub fn description(self) -> &'static str {
+        match self {
+            MathFn::Sqrt => "Square root; halves every dimension exponent",
+            MathFn::Cbrt => "Cube root; divides every dimension exponent by three",
+            MathFn::Sqr => "Square; doubles every dimension exponent",
+            MathFn::Abs => "Absolute value",
+            MathFn::Floor => "Round down, in the argument's own unit",
+            MathFn::Ceil => "Round up, in the argument's own unit",
+            MathFn::Round => "Round half away from zero, in the argument's own unit",
+            MathFn::Trunc => "Round toward zero, in the argument's own unit",
+            MathFn::Hypot => "Euclidean norm, sqrt(a² + b² + ...), in the first argument's unit",
+            MathFn::Min => "Smallest argument, in the first argument's unit",
+            MathFn::Max => "Largest argument, in the first argument's unit",
+            MathFn::Sum => "Sum of the arguments, in the first argument's unit",
+            MathFn::Mean => "Arithmetic mean, in the first argument's unit",
+            MathFn::Sin => "Sine of an angle (a bare number is radians)",
+            MathFn::Cos => "Cosine of an angle (a bare number is radians)",
+            MathFn::Tan => "Tangent of an angle (a bare number is radians)",
+            MathFn::Asin => "Inverse sine, in radians",
+            MathFn::Acos => "Inverse cosine, in radians",
+            MathFn::Atan => "Inverse tangent, in radians",
+            MathFn::Atan2 => "Angle of the point (x, y) from the x axis, in radians",
+            MathFn::Sinh => "Hyperbolic sine",
+            MathFn::Cosh => "Hyperbolic cosine",
+            MathFn::Tanh => "Hyperbolic tangent",
+            MathFn::Asinh => "Inverse hyperbolic sine",
+            MathFn::Acosh => "Inverse hyperbolic cosine",
+            MathFn::Atanh => "Inverse hyperbolic tangent",
+            MathFn::Ln => "Natural logarithm",
+            MathFn::Log10 => "Base-10 logarithm",
+            MathFn::Log2 => "Base-2 logarithm",
+            MathFn::Log => "Logarithm in the given base",
+            MathFn::Exp => "Exponential, e^x",
+            MathFn::Gamma => "Gamma function; gamma(n + 1) = n!",
+            MathFn::Erf => "Error function",
         }
     }
 
     /// Number of arguments the function accepts.
     ///
-    /// All initial variants are unary; widening this to support e.g.
-    /// `atan2(y, x)` means changing this method and the arity check in
-    /// `apply`, nothing else.
+    /// Most functions are unary; `log(x, base)` and `atan2(y, x)` take two,
+    /// and the aggregates any number from their minimum up.
     pub fn arity(self) -> Arity {
-        Arity::Exact(1)
+        match self {
+            MathFn::Log | MathFn::Atan2 => Arity::Exact(2),
+            MathFn::Hypot => Arity::AtLeast(2),
+            MathFn::Min | MathFn::Max | MathFn::Sum | MathFn::Mean => Arity::AtLeast(1),
+            _ => Arity::Exact(1),
+        }
     }
 
     /// Evaluate the function on a slice of quantity arguments.
@@ -123,23 +302,62 @@ impl MathFn {
     /// implementation. Per-function errors (domain violations, affine
     /// rejection, etc.) are produced inside each `apply_*` helper.
     pub fn apply(self, args: &[Quantity]) -> Result<Quantity, RUnitsError> {
-        let Arity::Exact(n) = self.arity();
-        if args.len() != n {
-            return Err(RUnitsError::ArityMismatch {
-                name: self.name(),
-                expected: n,
-                got: args.len(),
+        let arity = self.arity();
+        if !arity.accepts(args.len()) {
+            return Err(match arity {
+                Arity::Exact(n) => RUnitsError::ArityMismatch {
+                    name: self.name().to_string(),
+                    expected: n,
+                    got: args.len(),
+                },
+                _ => RUnitsError::ArityRangeMismatch {
+                    name: self.name().to_string(),
+                    expected: arity.to_string(),
+                    got: args.len(),
+                },
             });
         }
+        let name = self.name();
         match self {
             MathFn::Sqrt => apply_sqrt(&args[0]),
+            MathFn::Cbrt => apply_cbrt(&args[0]),
             MathFn::Sqr => apply_sqr(&args[0]),
             MathFn::Abs => apply_abs(&args[0]),
-            MathFn::Sin => apply_scalar(self.name(), &args[0], f64::sin),
-            MathFn::Cos => apply_scalar(self.name(), &args[0], f64::cos),
-            MathFn::Tan => apply_scalar(self.name(), &args[0], f64::tan),
-            MathFn::Ln => apply_scalar(self.name(), &args[0], f64::ln),
-            MathFn::Exp => apply_scalar(self.name(), &args[0], f64::exp),
+            MathFn::Floor => apply_rounding(name, &args[0], f64::floor),
+            MathFn::Ceil => apply_rounding(name, &args[0], f64::ceil),
+            MathFn::Round => apply_rounding(name, &args[0], f64::round),
+            MathFn::Trunc => apply_rounding(name, &args[0], f64::trunc),
+            MathFn::Hypot => {
+                apply_aggregate(name, args, |v| v.iter().map(|x| x * x).sum::<f64>().sqrt())
+            }
+            MathFn::Min => apply_aggregate(name, args, |v| {
+                v.iter().copied().fold(f64::INFINITY, f64::min)
+            }),
+            MathFn::Max => apply_aggregate(name, args, |v| {
+                v.iter().copied().fold(f64::NEG_INFINITY, f64::max)
+            }),
+            MathFn::Sum => apply_aggregate(name, args, |v| v.iter().sum()),
+            MathFn::Mean => apply_aggregate(name, args, |v| v.iter().sum::<f64>() / v.len() as f64),
+            MathFn::Sin => apply_trig(name, &args[0], f64::sin),
+            MathFn::Cos => apply_trig(name, &args[0], f64::cos),
+            MathFn::Tan => apply_trig(name, &args[0], f64::tan),
+            MathFn::Asin => apply_inverse_trig(name, &args[0], f64::asin),
+            MathFn::Acos => apply_inverse_trig(name, &args[0], f64::acos),
+            MathFn::Atan => apply_inverse_trig(name, &args[0], f64::atan),
+            MathFn::Atan2 => apply_atan2(args),
+            MathFn::Sinh => apply_scalar(name, &args[0], f64::sinh),
+            MathFn::Cosh => apply_scalar(name, &args[0], f64::cosh),
+            MathFn::Tanh => apply_scalar(name, &args[0], f64::tanh),
+            MathFn::Asinh => apply_scalar(name, &args[0], f64::asinh),
+            MathFn::Acosh => apply_scalar(name, &args[0], f64::acosh),
+            MathFn::Atanh => apply_scalar(name, &args[0], f64::atanh),
+            MathFn::Ln => apply_scalar(name, &args[0], f64::ln),
+            MathFn::Log10 => apply_scalar(name, &args[0], f64::log10),
+            MathFn::Log2 => apply_scalar(name, &args[0], f64::log2),
+            MathFn::Log => apply_log(&args[0], &args[1]),
+            MathFn::Exp => apply_scalar(name, &args[0], f64::exp),
+            MathFn::Gamma => apply_scalar(name, &args[0], gamma),
+            MathFn::Erf => apply_scalar(name, &args[0], erf),
         }
     }
 }
@@ -174,20 +392,13 @@ pub fn suggest(unknown: &str, max: usize) -> Vec<String> {
 
 // ---------------------------------------------------------------------------
 // Per-function helpers. Each produces a `FunctionDomainError` (or the shared
-// `AffineInExpression`) for inputs it can't handle.
+// `AffineInExpression` / `LogarithmicInExpression`) for inputs it can't handle.
 // ---------------------------------------------------------------------------
 
-/// Reject affine-unit arguments up front. Shared with every `apply_*` helper
-/// so the error path is uniform.
-fn require_non_affine(q: &Quantity, fn_name: &'static str) -> Result<(), RUnitsError> {
-    if q.unit.is_affine() {
-        Err(RUnitsError::AffineInExpression {
-            unit: q.unit.name.clone(),
-            op_context: fn_name.to_string(),
-        })
-    } else {
-        Ok(())
-    }
+/// Reject affine- and logarithmic-unit arguments up front. Shared with every
+/// `apply_*` helper so the error path is uniform.
+fn require_linear(q: &Quantity, fn_name: &'static str) -> Result<(), RUnitsError> {
+    crate::units::quantity::require_linear(&q.unit, fn_name)
 }
 
 /// Square-root of a dimensioned quantity requires both a value sqrt AND a
@@ -210,11 +421,9 @@ fn require_non_affine(q: &Quantity, fn_name: &'static str) -> Result<(), RUnitsE
 /// about sqrt-of-factor at all — we express the answer in base dimensions
 /// with factor 1.0.
 ///
-/// **Why validate before halving.** If any exponent is odd, we need to error
-/// before building the halved vector: producing a vec with fractional
-/// exponents would silently round (i8 division) and return a meaningless
-/// unit. Validation-before-construction is an invariant every dimension-
-/// transforming function in this module should follow.
+/// **Any dimension works.** Exponents are rational, so halving never loses
+/// information: `sqrt(m)` is `Length^(1/2)`, which is what noise densities
+/// (`V/√Hz`) and fracture toughness (`MPa·√m`) are made of.
 ///
 /// **Why `format!("sqrt({})", q.unit.name)` for the unit name.** Display-only
 /// placeholder. Proper compound-name simplification (turning
@@ -225,42 +434,24 @@ fn require_non_affine(q: &Quantity, fn_name: &'static str) -> Result<(), RUnitsE
 /// This function is the template for every future dimension-transforming
 /// function (`cbrt`, `hypot2`, `quadratic_equation`, ...). Copy its shape.
 fn apply_sqrt(q: &Quantity) -> Result<Quantity, RUnitsError> {
-    require_non_affine(q, "sqrt")?;
+    require_linear(q, "sqrt")?;
     if q.value < 0.0 {
         return Err(RUnitsError::FunctionDomainError {
-            name: "sqrt",
+            name: "sqrt".to_string(),
             reason: format!("negative argument ({})", q.value),
         });
     }
 
-    // Part A: every dimension exponent must be even.
-    for (dim, &exp) in q.unit.dimensions.iter() {
-        if exp % 2 != 0 {
-            return Err(RUnitsError::FunctionDomainError {
-                name: "sqrt",
-                reason: format!(
-                    "dimension {} has odd exponent {}; sqrt requires perfect-square dimensions",
-                    dim.name(),
-                    exp
-                ),
-            });
-        }
-    }
-
-    // Part B: halved dimension slice.
-    let halved_dims: Vec<(Dimension, i8)> = q
-        .unit
-        .dimensions
-        .iter()
-        .map(|(dim, &exp)| (dim.clone(), exp / 2))
-        .collect();
+    // Part A: halved dimensions.
+    let name = format!("sqrt({})", q.unit.name);
+    let halved_dims = scaled_dimensions(q, Rational::new(1, 2), &name)?;
 
-    // Part C: base-unit trip — convert value to base, sqrt, re-express.
+    // Part B: base-unit trip — convert value to base, sqrt, re-express.
     let base_value = q.unit.to_base_value(q.value);
     let sqrt_value = base_value.sqrt();
 
-    // Part D: new unit in base factor (1.0) with halved dims.
-    let new_unit = Unit::new(&format!("sqrt({})", q.unit.name), 1.0, &halved_dims);
+    // Part C: new unit in base factor (1.0) with halved dims.
+    let new_unit = Unit::from_dimensions(&name, 1.0, halved_dims);
 
     Ok(Quantity::new(sqrt_value, new_unit))
 }
@@ -268,54 +459,277 @@ fn apply_sqrt(q: &Quantity) -> Result<Quantity, RUnitsError> {
 /// `sqr(x) = x * x`, with dimensions doubled.
 ///
 /// Mirrors `apply_sqrt`'s shape but in the other direction: the base-unit
-/// trip avoids having to square the unit's conversion_factor, and the
-/// doubled dimensions are always valid (no parity check needed).
+/// trip avoids having to square the unit's conversion_factor.
 fn apply_sqr(q: &Quantity) -> Result<Quantity, RUnitsError> {
-    require_non_affine(q, "sqr")?;
+    require_linear(q, "sqr")?;
 
-    let doubled_dims: Vec<(Dimension, i8)> = q
-        .unit
-        .dimensions
-        .iter()
-        .map(|(dim, &exp)| (dim.clone(), exp * 2))
-        .collect();
+    let name = format!("sqr({})", q.unit.name);
+    let doubled_dims = scaled_dimensions(q, Rational::from(2), &name)?;
 
     let base_value = q.unit.to_base_value(q.value);
     let sqr_value = base_value * base_value;
 
-    let new_unit = Unit::new(&format!("sqr({})", q.unit.name), 1.0, &doubled_dims);
+    let new_unit = Unit::from_dimensions(&name, 1.0, doubled_dims);
     Ok(Quantity::new(sqr_value, new_unit))
 }
 
+/// Every dimension exponent of `q` multiplied by `by`, for the unit
+/// `name`; fails if an exponent overflows (`sqrt(m^(1/1073741824))`).
+fn scaled_dimensions(q: &Quantity, by: Rational, name: &str) -> Result<DimensionMap, RUnitsError> {
+    crate::units::unit::scale_dimensions(&q.unit.dimensions, by).ok_or_else(|| {
+        RUnitsError::ExponentOverflow {
+            expression: name.to_string(),
+        }
+    })
+}
+
 /// `abs(x)` — preserves unit, flips sign of the value if negative.
 ///
 /// Dimension-generic: any non-affine quantity is valid. We keep the original
 /// unit (name, factor, dimensions) rather than routing through base, because
 /// there's no dimension math to do — the unit is unchanged.
 fn apply_abs(q: &Quantity) -> Result<Quantity, RUnitsError> {
-    require_non_affine(q, "abs")?;
+    require_linear(q, "abs")?;
     Ok(Quantity::new(q.value.abs(), q.unit.clone()))
 }
 
+/// `cbrt(x)`, with every dimension exponent divided by three.
+///
+/// Same base-unit trip as `apply_sqrt`. A negative argument is fine: the
+/// real cube root exists (`cbrt(-8 m^3)` is `-2 m`).
+fn apply_cbrt(q: &Quantity) -> Result<Quantity, RUnitsError> {
+    require_linear(q, "cbrt")?;
+
+    let name = format!("cbrt({})", q.unit.name);
+    let third_dims = scaled_dimensions(q, Rational::new(1, 3), &name)?;
+
+    let base_value = q.unit.to_base_value(q.value);
+    let cbrt_value = base_value.cbrt();
+
+    let new_unit = Unit::from_dimensions(&name, 1.0, third_dims);
+    Ok(Quantity::new(cbrt_value, new_unit))
+}
+
+/// `floor`, `ceil`, `round`, `trunc` — dimension-generic like `abs`.
+///
+/// Rounding happens in the argument's own unit, not in base: `round(2.6 ft)`
+/// is `3 ft`, which is what the user wrote and expects.
+fn apply_rounding(
+    name: &'static str,
+    q: &Quantity,
+    f: fn(f64) -> f64,
+) -> Result<Quantity, RUnitsError> {
+    require_linear(q, name)?;
+    Ok(Quantity::new(f(q.value), q.unit.clone()))
+}
+
+/// `sin`, `cos`, `tan`: an `Angle` (any angle unit, read in radians) or a
+/// dimensionless number (taken as radians, as before angles had a
+/// dimension of their own).
+fn apply_trig(
+    name: &'static str,
+    q: &Quantity,
+    f: fn(f64) -> f64,
+) -> Result<Quantity, RUnitsError> {
+    require_linear(q, name)?;
+    let is_angle = q.unit.dimensions.len() == 1
+        && q.unit.dimensions.get(&Dimension::Angle) == Some(&Rational::ONE);
+    if !is_angle && !q.unit.dimensions.is_empty() {
+        return Err(RUnitsError::FunctionDomainError {
+            name: name.to_string(),
+            reason: format!(
+                "expects an angle or dimensionless argument (got {})",
+                q.unit.dimension_string()
+            ),
+        });
+    }
+    let radians = q.unit.to_base_value(q.value);
+    Ok(Quantity::new(
+        finite(name, radians, f(radians))?,
+        Unit::dimensionless(),
+    ))
+}
+
+/// `asin`, `acos`, `atan`: a dimensionless ratio in, an `Angle` in radians
+/// out — so `asin(0.5) -> deg` is `30 degree`, and the result feeds back
+/// into `sin` unchanged.
+fn apply_inverse_trig(
+    name: &'static str,
+    q: &Quantity,
+    f: fn(f64) -> f64,
+) -> Result<Quantity, RUnitsError> {
+    let x = scalar_value(name, q)?;
+    Ok(Quantity::new(finite(name, x, f(x))?, Unit::radian()))
+}
+
+/// `atan2(y, x)`: any dimension, as long as `y` and `x` agree; the angle
+/// comes back in radians.
+fn apply_atan2(args: &[Quantity]) -> Result<Quantity, RUnitsError> {
+    let (_, values) = coerce_to_first("atan2", args)?;
+    let [y, x] = values[..] else {
+        unreachable!("arity checked in `apply`");
+    };
+    Ok(Quantity::new(y.atan2(x), Unit::radian()))
+}
+
+/// `hypot`, `min`, `max`, `sum`, `mean`: every argument converted to the
+/// first one's unit, `f` applied to the values, the result in that unit.
+fn apply_aggregate(
+    name: &'static str,
+    args: &[Quantity],
+    f: fn(&[f64]) -> f64,
+) -> Result<Quantity, RUnitsError> {
+    let (unit, values) = coerce_to_first(name, args)?;
+    Ok(Quantity::new(f(&values), unit))
+}
+
+/// Values of `args` in the first argument's unit — `try_add`'s rule that
+/// the left-hand side picks the unit (`max(1 m, 2 ft)` is in meters). An
+/// argument of another dimension is named by position.
+fn coerce_to_first(name: &'static str, args: &[Quantity]) -> Result<(Unit, Vec<f64>), RUnitsError> {
+    let first = &args[0].unit;
+    let mut values = Vec::with_capacity(args.len());
+    for (i, q) in args.iter().enumerate() {
+        require_linear(q, name)?;
+        if !first.is_compatible_with(&q.unit) {
+            return Err(RUnitsError::TypedParameterMismatch {
+                name: name.to_string(),
+                what: format!("argument {}", i + 1),
+                expected: format!("{} like argument 1", quantity_label(first)),
+                got: quantity_label(&q.unit),
+            });
+        }
+        values.push(q.convert_to(first)?.value);
+    }
+    Ok((first.clone(), values))
+}
+
+/// `Length`, `Velocity`: the quantity name when there is one, else the
+/// dimension formula.
+fn quantity_label(unit: &Unit) -> String {
+    crate::annotations::quantity_name(&unit.dimensions)
+        .map(str::to_string)
+        .unwrap_or_else(|| unit.dimension_string())
+}
+
+/// `log(x, base)`: both dimensionless, `base` positive and not 1.
+fn apply_log(x: &Quantity, base: &Quantity) -> Result<Quantity, RUnitsError> {
+    let x = scalar_value("log", x)?;
+    let base = scalar_value("log", base)?;
+    if base <= 0.0 || base == 1.0 {
+        return Err(RUnitsError::FunctionDomainError {
+            name: "log".to_string(),
+            reason: format!("base must be positive and not 1 (got {base})"),
+        });
+    }
+    Ok(Quantity::new(
+        finite("log", x, x.ln() / base.ln())?,
+        Unit::dimensionless(),
+    ))
+}
+
 /// Scalar-only math function: require a dimensionless argument, then apply
-/// a plain `f64 -> f64` function. Shared between `sin`, `cos`, `tan`, `ln`,
-/// `exp`, and any future transcendental that obeys the same contract.
+/// a plain `f64 -> f64` function. Shared between the hyperbolic functions,
+/// the logarithms, `exp`, `gamma`, `erf`, and any future transcendental that
+/// obeys the same contract.
 fn apply_scalar(
     name: &'static str,
     q: &Quantity,
     f: fn(f64) -> f64,
 ) -> Result<Quantity, RUnitsError> {
-    require_non_affine(q, name)?;
+    let x = scalar_value(name, q)?;
+    Ok(Quantity::new(finite(name, x, f(x))?, Unit::dimensionless()))
+}
+
+/// The value of a dimensionless argument, with any factor applied
+/// (`50 percent` is `0.5`).
+fn scalar_value(name: &'static str, q: &Quantity) -> Result<f64, RUnitsError> {
+    require_linear(q, name)?;
     if !q.unit.dimensions.is_empty() {
         return Err(RUnitsError::FunctionDomainError {
-            name,
+            name: name.to_string(),
             reason: format!(
                 "expects dimensionless argument (got {})",
                 q.unit.dimension_string()
             ),
         });
     }
-    Ok(Quantity::new(f(q.value), Unit::dimensionless()))
+    Ok(q.unit.to_base_value(q.value))
+}
+
+/// Turn a NaN or infinite result (`ln(0)`, `asin(2)`, `gamma(-1)`) into a
+/// domain error instead of printing `NaN`.
+fn finite(name: &'static str, x: f64, result: f64) -> Result<f64, RUnitsError> {
+    if result.is_finite() || !x.is_finite() {
+        Ok(result)
+    } else {
+        Err(RUnitsError::FunctionDomainError {
+            name: name.to_string(),
+            reason: format!("no finite result for {x}"),
+        })
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Special functions. FUTURE(std-gamma): `f64::gamma` and `f64::erf` exist on
+// nightly (`float_gamma`, `float_erf`); switch once they are stable.
+// ---------------------------------------------------------------------------
+
+/// Lanczos approximation (g = 7, nine terms), accurate to about 15 digits,
+/// with the reflection formula below 1/2. NaN at the poles (0, -1, -2, ...).
+fn gamma(x: f64) -> f64 {
+    const G: f64 = 7.0;
+    const COEFFICIENTS: [f64; 9] = [
+        0.999_999_999_999_809_9,
+        676.520_368_121_885_1,
+        -1_259.139_216_722_402_8,
+        771.323_428_777_653_1,
+        -176.615_029_162_140_6,
+        12.507_343_278_686_905,
+        -0.138_571_095_265_720_12,
+        9.984_369_578_019_572e-6,
+        1.505_632_735_149_311_6e-7,
+    ];
+    if x <= 0.0 && x.fract() == 0.0 {
+        return f64::NAN;
+    }
+    if x < 0.5 {
+        let pi = std::f64::consts::PI;
+        return pi / ((pi * x).sin() * gamma(1.0 - x));
+    }
+    let x = x - 1.0;
+    let sum = COEFFICIENTS[1..]
+        .iter()
+        .enumerate()
+        .fold(COEFFICIENTS[0], |acc, (i, c)| {
+            acc + c / (x + i as f64 + 1.0)
+        });
+    let t = x + G + 0.5;
+    // t^(x + 1/2) split in two so it doesn't overflow before e^-t cancels it.
+    let half_power = t.powf((x + 0.5) / 2.0);
+    (2.0 * std::f64::consts::PI).sqrt() * half_power * (half_power * (-t).exp()) * sum
+}
+
+/// `erf(x) = 2/√π · e^(-x²) · Σ 2ⁿ x^(2n+1) / (1·3·…·(2n+1))`. Every term
+/// is positive, so unlike the Taylor series nothing cancels; beyond
+/// `|x| = 6` the answer is ±1 to double precision.
+fn erf(x: f64) -> f64 {
+    if x.is_nan() {
+        return x;
+    }
+    if x.abs() >= 6.0 {
+        return x.signum();
+    }
+    let x2 = x * x;
+    let mut term = x;
+    let mut sum = x;
+    let mut n = 0.0;
+    while term.abs() > sum.abs() * f64::EPSILON {
+        n += 1.0;
+        term *= 2.0 * x2 / (2.0 * n + 1.0);
+        sum += term;
+    }
+    2.0 / std::f64::consts::PI.sqrt() * (-x2).exp() * sum
 }
 
 #[cfg(test)]
@@ -366,10 +780,22 @@ mod tests {
     }
 
     #[test]
-    fn sqrt_odd_exponent_fails() {
+    fn sqrt_odd_exponent_gives_fractional_dimension() {
         let q = Quantity::new(9.0, Unit::meter());
+        let r = MathFn::Sqrt.apply(&[q]).unwrap();
+        assert!((r.value - 3.0).abs() < 1e-12);
+        assert_eq!(r.unit.dimension_string(), "Length^(1/2)");
+    }
+
+    #[test]
+    fn sqrt_exponent_overflow_fails() {
+        let tiny = Rational::new(1, 1 << 30);
+        let q = Quantity::new(4.0, Unit::meter()).pow(tiny).unwrap();
         let err = MathFn::Sqrt.apply(&[q]).unwrap_err();
-        assert!(matches!(err, RUnitsError::FunctionDomainError { .. }));
+        assert!(
+            matches!(err, RUnitsError::ExponentOverflow { .. }),
+            "{err:?}"
+        );
     }
 
     #[test]
@@ -434,6 +860,177 @@ mod tests {
         assert!((r.value - 1.0).abs() < 1e-12);
     }
 
+    #[test]
+    fn every_signature_names_its_function() {
+        for f in MathFn::ALL {
+            assert!(f.signature().starts_with(f.name()), "{}", f.signature());
+            assert!(!f.description().is_empty());
+        }
+    }
+
+    #[test]
+    fn trig_accepts_angles() {
+        let deg = Unit::new(
+            "degree",
+            std::f64::consts::PI / 180.0,
+            &[(Dimension::Angle, 1)],
+        );
+        let r = MathFn::Sin.apply(&[Quantity::new(30.0, deg)]).unwrap();
+        assert!((r.value - 0.5).abs() < 1e-12);
+        assert!(r.unit.dimensions.is_empty());
+    }
+
+    #[test]
+    fn inverse_trig_returns_radians() {
+        let q = Quantity::new(1.0, Unit::dimensionless());
+        let r = MathFn::Atan.apply(&[q]).unwrap();
+        assert!((r.value - std::f64::consts::FRAC_PI_4).abs() < 1e-12);
+        assert_eq!(r.unit.dimension_string(), "Angle");
+        // ...which round-trips through the forward function.
+        let back = MathFn::Tan.apply(&[r]).unwrap();
+        assert!((back.value - 1.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn out_of_domain_is_an_error_not_nan() {
+        for (f, x) in [
+            (MathFn::Asin, 2.0),
+            (MathFn::Acosh, 0.5),
+            (MathFn::Ln, 0.0),
+            (MathFn::Gamma, -1.0),
+        ] {
+            let err = f
+                .apply(&[Quantity::new(x, Unit::dimensionless())])
+                .unwrap_err();
+            assert!(
+                matches!(err, RUnitsError::FunctionDomainError { .. }),
+                "{f:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn cbrt_divides_dimensions_and_keeps_sign() {
+        let litre = Unit::new("liter", 1e-3, &[(Dimension::Length, 3)]);
+        let r = MathFn::Cbrt.apply(&[Quantity::new(-1.0, litre)]).unwrap();
+        assert!((r.value + 0.1).abs() < 1e-12);
+        assert_eq!(r.unit.dimension_string(), "Length");
+    }
+
+    #[test]
+    fn rounding_keeps_the_argument_unit() {
+        let apply = |f: MathFn, x: f64| f.apply(&[Quantity::new(x, Unit::meter())]).unwrap();
+        assert_eq!(apply(MathFn::Floor, 2.5).value, 2.0);
+        assert_eq!(apply(MathFn::Ceil, 2.5).value, 3.0);
+        assert_eq!(apply(MathFn::Round, 2.5).value, 3.0);
+        let r = apply(MathFn::Trunc, -2.5);
+        assert_eq!(r.value, -2.0);
+        assert_eq!(r.unit.name, "meter");
+    }
+
+    #[test]
+    fn log_takes_a_base() {
+        let n = |x: f64| Quantity::new(x, Unit::dimensionless());
+        let r = MathFn::Log.apply(&[n(81.0), n(3.0)]).unwrap();
+        assert!((r.value - 4.0).abs() < 1e-12);
+        let err = MathFn::Log.apply(&[n(8.0), n(1.0)]).unwrap_err();
+        assert!(matches!(err, RUnitsError::FunctionDomainError { .. }));
+        let err = MathFn::Log.apply(&[n(8.0)]).unwrap_err();
+        assert!(matches!(
+            err,
+            RUnitsError::ArityMismatch { expected: 2, .. }
+        ));
+        assert!((MathFn::Log10.apply(&[n(1e-3)]).unwrap().value + 3.0).abs() < 1e-12);
+        assert!((MathFn::Log2.apply(&[n(1024.0)]).unwrap().value - 10.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn gamma_matches_known_values() {
+        assert!((gamma(5.0) - 24.0).abs() < 1e-12);
+        assert!((gamma(0.5) - std::f64::consts::PI.sqrt()).abs() < 1e-14);
+        assert!((gamma(-0.5) + 2.0 * std::f64::consts::PI.sqrt()).abs() < 1e-13);
+        assert!((gamma(171.0) / 7.257_415_615_307_994e306 - 1.0).abs() < 1e-12);
+        assert!(gamma(0.0).is_nan());
+    }
+
+    #[test]
+    fn erf_matches_known_values() {
+        assert_eq!(erf(0.0), 0.0);
+        assert!((erf(0.5) - 0.520_499_877_813_046_5).abs() < 1e-15);
+        assert!((erf(1.0) - 0.842_700_792_949_714_9).abs() < 1e-15);
+        assert!((erf(-2.0) + 0.995_322_265_018_952_7).abs() < 1e-15);
+        assert_eq!(erf(7.0), 1.0);
+    }
+
+    #[test]
+    fn arity_bounds_are_inclusive() {
+        assert!(Arity::Exact(2).accepts(2) && !Arity::Exact(2).accepts(3));
+        assert!(Arity::AtLeast(1).accepts(5) && !Arity::AtLeast(1).accepts(0));
+        assert!(Arity::Range(1, 2).accepts(2) && !Arity::Range(1, 2).accepts(3));
+        assert_eq!(Arity::AtLeast(2).to_string(), "at least 2");
+        assert_eq!(Arity::Range(1, 2).to_string(), "1 to 2");
+    }
+
+    #[test]
+    fn aggregates_use_the_first_argument_unit() {
+        let ft = Unit::new("foot", 0.3048, &[(Dimension::Length, 1)]);
+        let args = [
+            Quantity::new(3.0, Unit::meter()),
+            Quantity::new(4.0 / 0.3048, ft.clone()),
+        ];
+        let r = MathFn::Hypot.apply(&args).unwrap();
+        assert!((r.value - 5.0).abs() < 1e-12);
+        assert_eq!(r.unit.name, "meter");
+
+        let args = [Quantity::new(2.0, ft), Quantity::new(1.0, Unit::meter())];
+        let max = MathFn::Max.apply(&args).unwrap();
+        assert!((max.value - 1.0 / 0.3048).abs() < 1e-12);
+        assert_eq!(max.unit.name, "foot");
+        assert_eq!(MathFn::Min.apply(&args).unwrap().value, 2.0);
+        let sum = MathFn::Sum.apply(&args).unwrap().value;
+        let mean = MathFn::Mean.apply(&args).unwrap().value;
+        assert!((sum - 2.0 * mean).abs() < 1e-12);
+    }
+
+    #[test]
+    fn aggregate_names_the_mismatched_argument() {
+        let args = [
+            Quantity::new(1.0, Unit::meter()),
+            Quantity::new(2.0, Unit::meter()),
+            Quantity::new(3.0, Unit::second()),
+        ];
+        let err = MathFn::Sum.apply(&args).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "function 'sum': argument 3 must be Length like argument 1, got Time"
+        );
+    }
+
+    #[test]
+    fn variadic_too_few_arguments_fails() {
+        let err = MathFn::Hypot
+            .apply(&[Quantity::new(1.0, Unit::meter())])
+            .unwrap_err();
+        match err {
+            RUnitsError::ArityRangeMismatch { expected, got, .. } => {
+                assert_eq!(expected, "at least 2");
+                assert_eq!(got, 1);
+            }
+            other => panic!("expected ArityRangeMismatch, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn atan2_returns_an_angle_in_any_quadrant() {
+        let args = [
+            Quantity::new(-1.0, Unit::meter()),
+            Quantity::new(-1.0, Unit::meter()),
+        ];
+        let r = MathFn::Atan2.apply(&args).unwrap();
+        assert!((r.value + 3.0 * std::f64::consts::FRAC_PI_4).abs() < 1e-12);
+        assert_eq!(r.unit.dimension_string(), "Angle");
+    }
+
     #[test]
     fn arity_mismatch_fails() {
         // MathFn::Sqrt is unary; passing 2 args must fail with ArityMismatch.
@@ -9,6 +9,8 @@
 //! - [`parse_unit_name`] takes a bare unit expression like `"m/s"` or
 //!   `"kg*m/s^2"` and resolves it to a composed [`Unit`]. Target-side
 //!   parsing stays pure — no math, no identifiers beyond unit names.
+//!   [`parse_unit_list`] also accepts a `;`-separated list (`"h;min;s"`)
+//!   for mixed-unit output.
 //!
 //! Grammar lives in [`grammar.pest`](../grammar.pest). Expression grammar
 //! is documented there; the AST walker lives in [`crate::expr`] and the
@@ -18,7 +20,8 @@ use crate::database::UnitDatabase;
 use crate::error::RUnitsError;
 use crate::eval::{EvalContext, eval};
 use crate::expr::parse_expression;
-use crate::units::{Quantity, Unit};
+use crate::units::unit::ConversionKind;
+use crate::units::{Quantity, Rational, Unit};
 use pest::Parser;
 use pest::iterators::Pair;
 
@@ -44,7 +47,8 @@ pub fn parse_and_eval(input: &str, ctx: &EvalContext) -> Result<Quantity, RUnits
 
 /// Parse a bare unit expression (e.g. `"m/s"`, `"kg*m/s^2"`).
 ///
-/// Target-side parser — no math, no identifiers beyond unit names. Returns
+/// Target-side parser — no math beyond numeric factors (`L/(100 km)`), no
+/// identifiers beyond unit names. Returns
 /// [`RUnitsError::UnknownUnit`] if a name isn't in the database or
 /// [`RUnitsError::Parse`] if the input doesn't match the grammar.
 pub fn parse_unit_name(input: &str, db: &UnitDatabase) -> Result<Unit, RUnitsError> {
@@ -57,13 +61,59 @@ pub fn parse_unit_name(input: &str, db: &UnitDatabase) -> Result<Unit, RUnitsErr
     resolve_unit_expr(unit_expr_pair, db)
 }
 
+/// Parse a conversion target that may be a unit list: `"ft;in"`,
+/// `"h;min;s"`. A single unit comes back as a one-element list.
+///
+/// List members must be linear, share one dimension, and run from largest
+/// to smallest so the decomposition reads naturally; anything else is a
+/// [`RUnitsError::UnitList`].
+pub fn parse_unit_list(input: &str, db: &UnitDatabase) -> Result<Vec<Unit>, RUnitsError> {
+    let mut pairs = QuantityParser::parse(Rule::unit_list, input.trim()).map_err(Box::new)?;
+    let units = pairs
+        .next()
+        .expect("grammar guarantees one unit_list")
+        .into_inner()
+        .filter(|pair| pair.as_rule() == Rule::unit_expr)
+        .map(|pair| resolve_unit_expr(pair, db))
+        .collect::<Result<Vec<_>, _>>()?;
+    if units.len() > 1 {
+        let invalid = |reason: String| RUnitsError::UnitList {
+            list: input.trim().to_string(),
+            reason,
+        };
+        for pair in units.windows(2) {
+            let (larger, smaller) = (&pair[0], &pair[1]);
+            if let Some(unit) = [larger, smaller].into_iter().find(|u| !u.is_linear()) {
+                return Err(invalid(format!("'{}' is not a linear unit", unit.name)));
+            }
+            if larger.dimensions != smaller.dimensions {
+                return Err(invalid(format!(
+                    "'{}' ({}) and '{}' ({}) are not compatible",
+                    larger.name,
+                    larger.dimension_string(),
+                    smaller.name,
+                    smaller.dimension_string()
+                )));
+            }
+            if larger.conversion_factor() <= smaller.conversion_factor() {
+                return Err(invalid(format!(
+                    "units must run from largest to smallest, but '{}' is not larger than '{}'",
+                    larger.name, smaller.name
+                )));
+            }
+        }
+    }
+    Ok(units)
+}
+
 /// Recursively resolve a pest `unit_expr` parse tree into a composed [`Unit`].
 ///
 /// Walks the tree according to operator precedence:
 /// - `unit_expr`: fold `unit_term`s with division
 /// - `unit_term`: fold `unit_factor`s with multiplication
 /// - `unit_factor`: resolve `unit_atom`, then apply exponentiation
-/// - `unit_atom`: DB lookup for `unit_name`, or recurse into parenthesized expr
+/// - `unit_atom`: DB lookup for `unit_name`, a dimensionless factor for a
+///   `number`, or recurse into parenthesized expr
 pub(crate) fn resolve_unit_expr(pair: Pair<Rule>, db: &UnitDatabase) -> Result<Unit, RUnitsError> {
     match pair.as_rule() {
         Rule::unit_expr => {
@@ -71,8 +121,7 @@ pub(crate) fn resolve_unit_expr(pair: Pair<Rule>, db: &UnitDatabase) -> Result<U
             let mut result = resolve_unit_expr(inner.next().unwrap(), db)?;
             for term in inner {
                 let rhs = resolve_unit_expr(term, db)?;
-                check_affine_composition(&result, &rhs)?;
-                result = result / rhs;
+                result = composable(result)?.checked_div(composable(rhs)?)?;
             }
             Ok(result)
         }
@@ -81,8 +130,7 @@ pub(crate) fn resolve_unit_expr(pair: Pair<Rule>, db: &UnitDatabase) -> Result<U
             let mut result = resolve_unit_expr(inner.next().unwrap(), db)?;
             for factor in inner {
                 let rhs = resolve_unit_expr(factor, db)?;
-                check_affine_composition(&result, &rhs)?;
-                result = result * rhs;
+                result = composable(result)?.checked_mul(composable(rhs)?)?;
             }
             Ok(result)
         }
@@ -90,14 +138,8 @@ pub(crate) fn resolve_unit_expr(pair: Pair<Rule>, db: &UnitDatabase) -> Result<U
             let mut inner = pair.into_inner();
             let base = resolve_unit_expr(inner.next().unwrap(), db)?;
             if let Some(exp_pair) = inner.next() {
-                let exp: i32 = exp_pair
-                    .as_str()
-                    .parse()
-                    .expect("grammar validated integer");
-                if base.is_affine() {
-                    return Err(RUnitsError::AffineComposition(base.name.clone()));
-                }
-                Ok(crate::units::unit::pow_unit(base, exp))
+                let exp = parse_exponent(exp_pair)?;
+                crate::units::unit::pow_unit_rational(composable(base)?, exp)
             } else {
                 Ok(base)
             }
@@ -106,6 +148,19 @@ pub(crate) fn resolve_unit_expr(pair: Pair<Rule>, db: &UnitDatabase) -> Result<U
             let inner = pair.into_inner().next().unwrap();
             resolve_unit_expr(inner, db)
         }
+        Rule::number => {
+            let text = pair.as_str();
+            let factor: f64 = text.parse().expect("grammar validated number");
+            if factor == 0.0 {
+                let message = "a unit factor must be non-zero".to_string();
+                let err = pest::error::Error::new_from_span(
+                    pest::error::ErrorVariant::CustomError { message },
+                    pair.as_span(),
+                );
+                return Err(Box::new(err).into());
+            }
+            Ok(Unit::new(text, factor, &[]))
+        }
         Rule::unit_name => {
             let name = pair.as_str();
             db.lookup(name).ok_or_else(|| RUnitsError::UnknownUnit {
@@ -117,15 +172,40 @@ pub(crate) fn resolve_unit_expr(pair: Pair<Rule>, db: &UnitDatabase) -> Result<U
     }
 }
 
-/// Check that neither operand is affine before composing.
-fn check_affine_composition(lhs: &Unit, rhs: &Unit) -> Result<(), RUnitsError> {
-    if lhs.is_affine() {
-        return Err(RUnitsError::AffineComposition(lhs.name.clone()));
-    }
-    if rhs.is_affine() {
-        return Err(RUnitsError::AffineComposition(rhs.name.clone()));
+/// Read an `exponent` pair (`2`, `-1`, `0.5`, `(3/2)`) as an exact
+/// [`Rational`]. A zero denominator or an exponent too large to represent
+/// is a parse error at the exponent.
+pub(crate) fn parse_exponent(pair: Pair<Rule>) -> Result<Rational, RUnitsError> {
+    debug_assert_eq!(pair.as_rule(), Rule::exponent);
+    let span = pair.as_span();
+    let value = pair.into_inner().next().expect("exponent has one child");
+    Rational::parse(value.as_str()).ok_or_else(|| {
+        let message = format!("invalid exponent '{}'", value.as_str());
+        let err = pest::error::Error::new_from_span(
+            pest::error::ErrorVariant::CustomError { message },
+            span,
+        );
+        Box::new(err).into()
+    })
+}
+
+/// Prepare a unit for composition (`*`, `/`, `^`): linear units pass
+/// through, an absolute temperature scale becomes its delta form
+/// (`J/(kg*degC)` is per Celsius *degree*), and every other non-linear kind
+/// gets its own error.
+fn composable(unit: Unit) -> Result<Unit, RUnitsError> {
+    match unit.conversion {
+        ConversionKind::Linear(_) => Ok(unit),
+        ConversionKind::Affine { .. } => {
+            Ok(unit.delta_form().expect("affine units have a delta form"))
+        }
+        ConversionKind::Logarithmic { .. } => {
+            Err(RUnitsError::LogarithmicComposition(unit.name.clone()))
+        }
+        ConversionKind::Nonlinear(_) | ConversionKind::Table(_) => {
+            Err(RUnitsError::NonlinearComposition(unit.name.clone()))
+        }
     }
-    Ok(())
 }
 
 #[cfg(test)]
@@ -300,6 +380,22 @@ mod tests {
         assert_eq!(u.dimension_string(), "Length^-1");
     }
 
+    #[test]
+    fn compound_fractional_exponent() {
+        let db = UnitDatabase::new();
+        let u = parse_unit_name("V/Hz^(1/2)", &db).unwrap();
+        // volt is Time^-3; dividing by Time^(-1/2) leaves Time^(-5/2).
+        assert!(u.dimension_string().contains("Time^(-5/2)"));
+        let v = parse_unit_name("V/Hz^0.5", &db).unwrap();
+        assert_eq!(u.dimensions, v.dimensions);
+        assert!((u.conversion_factor() - v.conversion_factor()).abs() < 1e-12);
+
+        let u = parse_unit_name("km^(3/2)", &db).unwrap();
+        assert_eq!(u.dimension_string(), "Length^(3/2)");
+        assert!((u.conversion_factor() - 1000f64.powf(1.5)).abs() < 1e-3);
+        assert!(parse_unit_name("m^(1/0)", &db).is_err());
+    }
+
     #[test]
     fn compound_parentheses() {
         let db = UnitDatabase::new();
@@ -310,6 +406,21 @@ mod tests {
         assert!(dims.contains("Time^-2"));
     }
 
+    #[test]
+    fn numeric_factor_in_target_unit() {
+        let db = UnitDatabase::new();
+        let u = parse_unit_name("L/(100 km)", &db).unwrap();
+        assert_eq!(u.dimension_string(), "Length^2");
+        assert!((u.conversion_factor() - 1e-8).abs() < 1e-20);
+    }
+
+    #[test]
+    fn zero_factor_in_target_unit_rejected() {
+        let db = UnitDatabase::new();
+        let err = parse_unit_name("L/(0 km)", &db).unwrap_err();
+        assert!(err.to_string().contains("non-zero"), "{err}");
+    }
+
     #[test]
     fn compound_density() {
         let db = UnitDatabase::new();
@@ -321,10 +432,33 @@ mod tests {
     }
 
     #[test]
-    fn affine_unit_in_compound_rejected() {
+    fn affine_unit_in_compound_uses_delta_form() {
         let db = UnitDatabase::new();
-        let err = parse_unit_name("celsius*m", &db).unwrap_err();
-        assert!(matches!(err, RUnitsError::AffineComposition(_)));
+        let unit = parse_unit_name("J/(kg*degC)", &db).unwrap();
+        assert!(unit.is_linear());
+        assert!(unit.is_temperature_delta());
+        assert!((unit.conversion_factor() - 1.0).abs() < 1e-12);
+        let unit = parse_unit_name("degF^-1", &db).unwrap();
+        assert!((unit.conversion_factor() - 1.8).abs() < 1e-12);
+    }
+
+    #[test]
+    fn function_unit_in_compound_rejected() {
+        let db = UnitDatabase::new();
+        let err = parse_unit_name("wiregauge/s", &db).unwrap_err();
+        assert!(matches!(err, RUnitsError::NonlinearComposition(_)));
+    }
+
+    #[test]
+    fn logarithmic_unit_in_compound_rejected() {
+        let db = UnitDatabase::new();
+        for input in ["dBm/s", "m*dB", "dBV^2"] {
+            let err = parse_unit_name(input, &db).unwrap_err();
+            assert!(
+                matches!(err, RUnitsError::LogarithmicComposition(_)),
+                "{input}: {err}"
+            );
+        }
     }
 
     #[test]
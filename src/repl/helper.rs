@@ -3,6 +3,8 @@
 use crate::annotations;
 use crate::database::UnitDatabase;
 use crate::database::constants;
+use crate::equiv::Equivalency;
+use crate::math;
 use crate::parser;
 use crate::theme::Theme;
 use rustyline::completion::{Completer, Pair};
@@ -28,7 +30,7 @@ impl Completer for UnitsHelper {
     ) -> rustyline::Result<(usize, Vec<Pair>)> {
         // Find the start of the current word: scan back from cursor to last delimiter.
         let word_start = line[..pos]
-            .rfind([' ', '*', '/', '(', '^', '?'])
+            .rfind([' ', '*', '/', '(', '^', '?', '~'])
             .map(|i| i + 1)
             .unwrap_or(0);
 
@@ -52,7 +54,8 @@ impl Completer for UnitsHelper {
         // REPL commands (only at the start of the line).
         if word_start == 0 {
             let commands = [
-                "const", "explain", "list", "search", "help", "info", "quit", "exit",
+                "const", "element", "explain", "list", "search", "using", "what", "vars", "del",
+                "help", "info", "quit", "exit",
             ];
             let cmd_matches: Vec<Pair> = commands
                 .iter()
@@ -82,6 +85,21 @@ impl Completer for UnitsHelper {
             return Ok((word_start, sub_matches));
         }
 
+        // After "using ", suggest equivalency names.
+        if prefix_cmd.eq_ignore_ascii_case("using") {
+            let names = Equivalency::ALL.map(Equivalency::name);
+            let matches: Vec<Pair> = names
+                .iter()
+                .chain(&["none"])
+                .filter(|s| s.starts_with(&partial.to_lowercase()))
+                .map(|s| Pair {
+                    display: s.to_string(),
+                    replacement: s.to_string(),
+                })
+                .collect();
+            return Ok((word_start, matches));
+        }
+
         // After "list units " or "search ", suggest dimension/quantity names.
         if prefix_cmd.eq_ignore_ascii_case("list units")
             || prefix_cmd.eq_ignore_ascii_case("search")
@@ -127,6 +145,17 @@ impl Completer for UnitsHelper {
                     replacement: name.to_string(),
                 });
             matches.extend(const_matches);
+
+            // Math functions show their signature in the candidate list and
+            // complete to `name(`.
+            let fn_matches = math::MathFn::ALL
+                .iter()
+                .filter(|f| f.name().starts_with(partial))
+                .map(|f| Pair {
+                    display: f.signature().to_string(),
+                    replacement: format!("{}(", f.name()),
+                });
+            matches.extend(fn_matches);
         }
 
         matches.sort_by(|a, b| a.display.cmp(&b.display));
@@ -170,7 +199,7 @@ impl Hinter for UnitsHelper {
         }
 
         let word_start = line[..pos]
-            .rfind([' ', '*', '/', '(', '^', '?'])
+            .rfind([' ', '*', '/', '(', '^', '?', '~'])
             .map(|i| i + 1)
             .unwrap_or(0);
         let partial = &line[word_start..pos];
@@ -271,7 +300,19 @@ impl Highlighter for UnitsHelper {
 
             if matches!(
                 token,
-                "to" | "in" | "as" | "const" | "explain" | "list" | "search" | "info" | "help"
+                "to" | "in"
+                    | "as"
+                    | "const"
+                    | "element"
+                    | "explain"
+                    | "list"
+                    | "search"
+                    | "using"
+                    | "what"
+                    | "vars"
+                    | "del"
+                    | "info"
+                    | "help"
             ) {
                 result.push_str(&t.kw(token));
             } else if token.starts_with(|c: char| c.is_ascii_digit() || c == '-' || c == '.') {
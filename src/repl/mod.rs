@@ -10,8 +10,11 @@ mod helper;
 use crate::annotations::{self, quantity_name};
 use crate::database::constants;
 use crate::database::{self, UnitDatabase};
-use crate::eval::EvalContext;
+use crate::equiv::Equivalency;
+use crate::eval::{EvalContext, Variables};
 use crate::format::{self, FormatOptions};
+use crate::functions::{self, Functions};
+use crate::math;
 use crate::parser;
 use crate::theme::Theme;
 use crate::units::Quantity;
@@ -38,7 +41,11 @@ pub fn parse_repl_line(line: &str) -> Option<(&str, &str)> {
 }
 
 /// Run the interactive REPL loop.
-pub fn run(opts: &FormatOptions, banner: crate::cli::BannerMode) {
+pub fn run(
+    opts: &FormatOptions,
+    convert_opts: &convert::ConvertOptions,
+    banner: crate::cli::BannerMode,
+) {
     let mut rl = Editor::new().expect("failed to initialize line editor");
     rl.set_helper(Some(UnitsHelper {
         db: database::global(),
@@ -52,12 +59,19 @@ pub fn run(opts: &FormatOptions, banner: crate::cli::BannerMode) {
 
     let t = Theme::new(opts.color);
     let db = database::global();
+    // Session copy: `using` changes the equivalencies for later lines.
+    let mut convert_opts = convert_opts.clone();
     let mut last_conversion: Option<convert::ConversionResult> = None;
     // `last_quantity` feeds the `_` previous-result variable. It's updated
     // by every successful evaluation (both plain echoes and conversions),
     // while `last_conversion` is only set on full source→target conversions
     // (since `explain` requires a target to make sense).
     let mut last_quantity: Option<Quantity> = None;
+    // Session variables from `name = <qty>` lines and functions from
+    // `name(params) = <expr>` lines, starting from units.conf's; `vars`
+    // lists both.
+    let mut variables = Variables::new();
+    let mut functions = functions::global().clone();
 
     print_banner(banner, &t, db);
 
@@ -77,7 +91,7 @@ pub fn run(opts: &FormatOptions, banner: crate::cli::BannerMode) {
                     continue;
                 }
 
-                // Argless commands: info, help, explain. Reject trailing args
+                // Argless commands: info, help, vars, explain. Reject trailing args
                 // uniformly via `print_extra_args_error`.
                 if let Some(args) = match_command(line, "info") {
                     if !args.is_empty() {
@@ -95,6 +109,11 @@ pub fn run(opts: &FormatOptions, banner: crate::cli::BannerMode) {
                     }
                     continue;
                 }
+                if let Some(args) = match_command(line, "vars") {
+                    let _ = rl.add_history_entry(line);
+                    handle_vars_command(args, &variables, &functions, opts);
+                    continue;
+                }
                 if let Some(args) = match_command(line, "explain") {
                     let _ = rl.add_history_entry(line);
                     if !args.is_empty() {
@@ -114,7 +133,38 @@ pub fn run(opts: &FormatOptions, banner: crate::cli::BannerMode) {
                     continue;
                 }
 
-                // Arged commands: const, list, search. Reject empty args.
+                // Function definition: `ke(m: Mass, v: Velocity) = 0.5 * m * v^2`.
+                if let Some(definition) = functions::parse_definition(line) {
+                    let _ = rl.add_history_entry(line);
+                    match definition.and_then(|f| functions::define(&mut functions, f, db)) {
+                        Ok(function) => println!("{function}"),
+                        Err(e) => print_error(&e, opts),
+                    }
+                    continue;
+                }
+
+                // Assignment: `x = 5 ft`, `v = 100 km/h -> m/s`.
+                if let Some((name, rhs)) = parse_assignment(line) {
+                    let _ = rl.add_history_entry(line);
+                    let ctx =
+                        EvalContext::with_previous(db, constants::global(), last_quantity.as_ref())
+                            .with_variables(&variables)
+                            .with_functions(&functions);
+                    match eval_assignment(name, rhs, &ctx, &convert_opts) {
+                        Ok((result, warning)) => {
+                            if let Some(warning) = warning {
+                                eprintln!("{}", t.err(&format!("Warning: {warning}")));
+                            }
+                            print_assignment(name, &result, opts);
+                            last_quantity = Some(result.result.clone());
+                            variables.insert(name.to_string(), result.result);
+                        }
+                        Err(e) => print_error(&e, opts),
+                    }
+                    continue;
+                }
+
+                // Arged commands: const, element, what, del, list, search. Reject empty args.
                 if let Some(args) = match_command(line, "const") {
                     let _ = rl.add_history_entry(line);
                     if args.is_empty() {
@@ -124,6 +174,36 @@ pub fn run(opts: &FormatOptions, banner: crate::cli::BannerMode) {
                     }
                     continue;
                 }
+                if let Some(args) = match_command(line, "element") {
+                    let _ = rl.add_history_entry(line);
+                    if args.is_empty() {
+                        print_missing_arg_error("element", "element <symbol|name>", &t);
+                    } else {
+                        handle_element_command(args, opts);
+                    }
+                    continue;
+                }
+                if let Some(args) = match_command(line, "what") {
+                    let _ = rl.add_history_entry(line);
+                    if args.is_empty() {
+                        print_missing_arg_error("what", "what <qty>", &t);
+                    } else {
+                        let ctx = EvalContext::with_previous(
+                            db,
+                            constants::global(),
+                            last_quantity.as_ref(),
+                        )
+                        .with_variables(&variables)
+                        .with_functions(&functions);
+                        handle_what_command(args, &ctx, opts);
+                    }
+                    continue;
+                }
+                if let Some(args) = match_command(line, "del") {
+                    let _ = rl.add_history_entry(line);
+                    handle_del_command(args, &mut variables, &mut functions, &t);
+                    continue;
+                }
                 if let Some(args) = match_command(line, "list") {
                     let _ = rl.add_history_entry(line);
                     if args.is_empty() {
@@ -137,6 +217,11 @@ pub fn run(opts: &FormatOptions, banner: crate::cli::BannerMode) {
                     }
                     continue;
                 }
+                if let Some(args) = match_command(line, "using") {
+                    let _ = rl.add_history_entry(line);
+                    handle_using_command(args, &mut convert_opts, &t);
+                    continue;
+                }
                 // Legacy alias: `search` → `list units`
                 if let Some(args) = match_command(line, "search") {
                     let _ = rl.add_history_entry(line);
@@ -153,11 +238,13 @@ pub fn run(opts: &FormatOptions, banner: crate::cli::BannerMode) {
                 // Build an EvalContext fresh each line so `_` sees the most
                 // recent successful evaluation.
                 let ctx =
-                    EvalContext::with_previous(db, constants::global(), last_quantity.as_ref());
-                match handle_input(line, db, &ctx, opts) {
+                    EvalContext::with_previous(db, constants::global(), last_quantity.as_ref())
+                        .with_variables(&variables)
+                        .with_functions(&functions);
+                match handle_input(line, db, &ctx, opts, &convert_opts) {
                     HandleOutcome::Conversion(conv) => {
                         last_quantity = Some(conv.result.clone());
-                        last_conversion = Some(conv);
+                        last_conversion = Some(*conv);
                     }
                     HandleOutcome::Quantity(q) => {
                         last_quantity = Some(q);
@@ -186,11 +273,12 @@ fn print_banner(mode: crate::cli::BannerMode, t: &Theme, db: &UnitDatabase) {
         BannerMode::Off => {}
         BannerMode::Short => {
             println!(
-                "{} {} — {}, {} units. Type {} or {} to exit.",
+                "{} {} — {}, {} units ({}). Type {} or {} to exit.",
                 t.kw("runits"),
                 env!("CARGO_PKG_VERSION"),
                 format::UNIT_SYSTEM,
                 db.len(),
+                db.tier(),
                 t.kw("quit"),
                 t.kw("Ctrl-D"),
             );
@@ -202,9 +290,10 @@ fn print_banner(mode: crate::cli::BannerMode, t: &Theme, db: &UnitDatabase) {
             println!();
             println!("  {} {}", t.dim("Unit system:"), format::UNIT_SYSTEM,);
             println!(
-                "  {} {} (builtin) + SI/binary prefixes",
+                "  {} {} ({}) + SI/binary prefixes",
                 t.dim("Database:"),
                 db.len(),
+                db.tier(),
             );
             let config_path = config_dir()
                 .map(|d| d.join("config.toml"))
@@ -245,10 +334,21 @@ fn print_help(t: &Theme) {
             format!("{} {} {}", t.dim("<qty>"), t.kw("to"), t.dim("<unit>")),
             "convert between units",
         ),
+        (
+            "<qty> to auto [imperial]",
+            format!(
+                "{} {} {} {}",
+                t.dim("<qty>"),
+                t.kw("to"),
+                t.kw("auto"),
+                t.dim("[imperial]")
+            ),
+            "pick the best unit prefix",
+        ),
         (
             "? <name>",
             format!("{} {}", t.kw("?"), t.dim("<name>")),
-            "unit or constant info",
+            "unit, constant or function info",
         ),
         (
             "list units|dimensions|constants [filter]",
@@ -265,11 +365,52 @@ fn print_help(t: &Theme) {
             format!("{} {}", t.kw("const"), t.dim("<name>")),
             "show constant value",
         ),
+        (
+            "element <symbol|name>",
+            format!("{} {}", t.kw("element"), t.dim("<symbol|name>")),
+            "show element properties",
+        ),
+        (
+            "what <qty>",
+            format!("{} {}", t.kw("what"), t.dim("<qty>")),
+            "find constants and units close to a value",
+        ),
+        (
+            "<name> = <qty>",
+            format!("{} {} {}", t.dim("<name>"), t.kw("="), t.dim("<qty>")),
+            "store a variable",
+        ),
+        (
+            "<name>(<args>) = <expr>",
+            format!(
+                "{}{} {} {}",
+                t.dim("<name>"),
+                t.dim("(<args>)"),
+                t.kw("="),
+                t.dim("<expr>")
+            ),
+            "define a function",
+        ),
+        ("vars", t.kw("vars"), "list variables and functions"),
+        (
+            "del <name>...",
+            format!("{} {}", t.kw("del"), t.dim("<name>...")),
+            "delete variables or functions",
+        ),
         (
             "explain",
             t.kw("explain"),
             "show last conversion step-by-step",
         ),
+        (
+            "using [none|spectral|mass-energy|thermal]",
+            format!(
+                "{} {}",
+                t.kw("using"),
+                t.dim("[none|spectral|mass-energy|thermal]")
+            ),
+            "equivalency contexts",
+        ),
         ("info", t.kw("info"), "database & config info"),
         ("help", t.kw("help"), "this help"),
         ("quit", t.kw("quit"), "exit"),
@@ -293,9 +434,10 @@ fn print_info(t: &Theme, db: &UnitDatabase) {
     // Unit system and database
     println!("  {} {}", t.dim("Unit system:"), format::UNIT_SYSTEM,);
     println!(
-        "  {} {} (builtin) + SI/binary prefixes",
+        "  {} {} ({}) + SI/binary prefixes",
         t.dim("Database:"),
         db.len(),
+        db.tier(),
     );
     // Config file
     let config_path = config_dir()
@@ -330,7 +472,7 @@ fn print_info(t: &Theme, db: &UnitDatabase) {
 enum HandleOutcome {
     /// A full source→target conversion succeeded. Updates both
     /// `last_conversion` (for `explain`) and `last_quantity` (for `_`).
-    Conversion(convert::ConversionResult),
+    Conversion(Box<convert::ConversionResult>),
     /// A bare expression was echoed. Updates only `last_quantity`.
     Quantity(Quantity),
     /// Help command, error, or anything else that shouldn't touch state.
@@ -346,6 +488,7 @@ fn handle_input(
     db: &UnitDatabase,
     ctx: &EvalContext,
     opts: &FormatOptions,
+    convert_opts: &convert::ConvertOptions,
 ) -> HandleOutcome {
     // 1. Delimiter-based input (check first, so "100 km/h -> ?" is caught).
     if let Some((source, target)) = parse_repl_line(line) {
@@ -353,10 +496,10 @@ fn handle_input(
             handle_quantity_help(source, db, ctx, opts);
             return HandleOutcome::None;
         }
-        match eval_and_convert(source, target, ctx) {
+        match convert::run_conversion_in(source, target, ctx, convert_opts) {
             Ok(result) => {
                 println!("{}", format::format_result(&result, opts));
-                return HandleOutcome::Conversion(result);
+                return HandleOutcome::Conversion(Box::new(result));
             }
             Err(e) => {
                 print_error(&e, opts);
@@ -378,16 +521,23 @@ fn handle_input(
         return HandleOutcome::None;
     }
 
-    // 3. No delimiter, no ? — try parsing as a bare expression.
+    // 3. No delimiter, no ? — try parsing as a bare expression. In auto
+    //    mode (`--auto`, `auto = ...`) it converts to the best unit instead.
+    if convert_opts.auto.is_some() {
+        match convert::run_conversion_in(line, crate::auto::AUTO_TARGET, ctx, convert_opts) {
+            Ok(result) => {
+                println!("{}", format::format_result(&result, opts));
+                return HandleOutcome::Conversion(Box::new(result));
+            }
+            Err(e) => {
+                print_error(&e, opts);
+                return HandleOutcome::None;
+            }
+        }
+    }
     match parser::parse_and_eval(line, ctx) {
         Ok(qty) => {
-            let annotation = quantity_name(&qty.unit.dimensions);
-            let result = convert::ConversionResult {
-                source: qty.clone(),
-                result: qty.clone(),
-                annotation,
-                source_expr: Some(line.to_string()),
-            };
+            let result = convert::ConversionResult::echo(qty.clone(), line);
             println!("{}", format::format_result(&result, opts));
             HandleOutcome::Quantity(qty)
         }
@@ -398,24 +548,78 @@ fn handle_input(
     }
 }
 
-/// Evaluate the source expression, parse the target, convert, and package
-/// into a `ConversionResult`. Shared entry point for the REPL's delimiter
-/// path — CLI one-shot still goes through `convert::run_conversion`.
-fn eval_and_convert(
-    source: &str,
-    target: &str,
+/// Parse `name = <rhs>` into the variable name and right-hand side. The
+/// name follows the grammar's identifier rule, so `x`, `rho` and `v_0`
+/// qualify; `_` is reserved for the previous result.
+pub fn parse_assignment(line: &str) -> Option<(&str, &str)> {
+    let (name, rhs) = line.split_once('=')?;
+    let (name, rhs) = (name.trim(), rhs.trim());
+    let mut chars = name.chars();
+    let first = chars.next()?;
+    let valid = (first.is_ascii_alphabetic() || first == 'µ' || first == '°')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_');
+    (valid && !rhs.is_empty()).then_some((name, rhs))
+}
+
+/// Evaluate the right-hand side of `name = <rhs>` — an expression, or a
+/// conversion such as `5 ft -> m` — without storing it. Also returns a
+/// warning when `name` is new and shadows a unit or constant. Shared by
+/// the REPL and `--batch`, which then insert the result.
+pub fn eval_assignment(
+    name: &str,
+    rhs: &str,
     ctx: &EvalContext,
-) -> Result<convert::ConversionResult, crate::error::RUnitsError> {
-    let source_qty = parser::parse_and_eval(source, ctx)?;
-    let target_unit = parser::parse_unit_name(target, ctx.units)?;
-    let converted = source_qty.clone().convert_to(&target_unit)?;
-    let annotation = quantity_name(&converted.unit.dimensions);
-    Ok(convert::ConversionResult {
-        source: source_qty,
-        result: converted,
-        annotation,
-        source_expr: Some(source.to_string()),
-    })
+    convert_opts: &convert::ConvertOptions,
+) -> Result<(convert::ConversionResult, Option<String>), crate::error::RUnitsError> {
+    let result = match parse_repl_line(rhs) {
+        Some((source, target)) => convert::run_conversion_in(source, target, ctx, convert_opts)?,
+        None => convert::ConversionResult::echo(parser::parse_and_eval(rhs, ctx)?, rhs),
+    };
+    let is_new = ctx.variables.is_none_or(|vars| !vars.contains_key(name));
+    let warning = ctx
+        .shadowed(name)
+        .filter(|_| is_new)
+        .map(|what| format!("'{name}' is now a variable, shadowing the {what}"));
+    Ok((result, warning))
+}
+
+/// `using [none | <equivalency>...]`: show or replace the session's
+/// equivalency contexts. Bare `using` reports the current set.
+fn handle_using_command(args: &str, convert_opts: &mut convert::ConvertOptions, t: &Theme) {
+    if !args.is_empty() {
+        let mut chosen = Vec::new();
+        for word in args.split([' ', ',']).filter(|w| !w.is_empty()) {
+            if word.eq_ignore_ascii_case("none") {
+                continue;
+            }
+            match Equivalency::from_name(word) {
+                Some(e) if !chosen.contains(&e) => chosen.push(e),
+                Some(_) => {}
+                None => {
+                    let known: Vec<&str> = Equivalency::ALL.iter().map(|e| e.name()).collect();
+                    eprintln!(
+                        "{}",
+                        t.err(&format!(
+                            "Unknown equivalency '{word}'. Known: {}, none",
+                            known.join(", ")
+                        ))
+                    );
+                    return;
+                }
+            }
+        }
+        convert_opts.equivalencies = chosen;
+    }
+    if convert_opts.equivalencies.is_empty() {
+        println!("  {} none", t.dim("Equivalencies:"));
+    } else {
+        let names: Vec<&str> = convert_opts
+            .equivalencies
+            .iter()
+            .map(|e| e.name())
+            .collect();
+        println!("  {} {}", t.dim("Equivalencies:"), names.join(", "));
+    }
 }
 
 /// Match a REPL command at the start of a line, returning its argument (possibly empty).
@@ -475,7 +679,8 @@ fn strip_question_mark(line: &str) -> Option<&str> {
 
 /// Handle `? meter` or `meter ?` — show unit info + compatible units.
 ///
-/// Falls back to the constants database if the query doesn't match any unit.
+/// Falls back to the constants database, then to the math functions, if the
+/// query doesn't match any unit.
 fn handle_unit_help(query: &str, db: &UnitDatabase, opts: &FormatOptions) {
     match parser::parse_unit_name(query, db) {
         Ok(unit) => {
@@ -492,6 +697,8 @@ fn handle_unit_help(query: &str, db: &UnitDatabase, opts: &FormatOptions) {
             let const_db = constants::global();
             if let Some(c) = const_db.lookup(query) {
                 println!("{}", format::format_constant_info(c, opts));
+            } else if let Some(f) = math::lookup(query) {
+                println!("{}", format::format_math_fn_info(f, opts));
             } else {
                 print_error(&e, opts);
             }
@@ -519,6 +726,10 @@ fn handle_quantity_help_from_qty(
         result: qty.clone(),
         annotation,
         source_expr: None,
+        reciprocal: false,
+        bridge: None,
+        substance: None,
+        unit_list: Vec::new(),
     };
     println!("{}", format::format_result(&result, opts));
 
@@ -678,10 +889,10 @@ fn handle_list_constants(opts: &FormatOptions) {
     let t = Theme::new(opts.color);
     let const_db = constants::global();
     let mut all = const_db.all_unique();
-    all.sort_by_key(|c| c.name);
+    all.sort_by(|a, b| a.name.cmp(&b.name));
     for c in &all {
         let val = crate::units::quantity::format_value(c.value, 6, false);
-        println!("  {} = {} {}", t.cst(c.name), t.num(&val), c.unit.name);
+        println!("  {} = {} {}", t.cst(&c.name), t.num(&val), c.unit.name);
     }
 }
 
@@ -693,8 +904,7 @@ fn print_unit_search_results(
     opts: &FormatOptions,
 ) {
     let qty_name = quantity_name(dims).unwrap_or(query);
-    let dims_vec: Vec<_> = dims.iter().map(|(d, &e)| (d.clone(), e)).collect();
-    let synthetic = crate::units::Unit::new("_query", 1.0, &dims_vec);
+    let synthetic = crate::units::Unit::from_dimensions("_query", 1.0, dims.clone());
     let compat = db.compatible_units(&synthetic);
     println!(
         "{}",
@@ -750,6 +960,100 @@ fn handle_const_command(name: &str, opts: &FormatOptions) {
     }
 }
 
+/// Echo an assignment as `name = <result>` (just the result under `--json`).
+pub fn print_assignment(name: &str, result: &convert::ConversionResult, opts: &FormatOptions) {
+    let shown = format::format_result(result, opts);
+    if opts.json {
+        println!("{shown}");
+    } else {
+        println!("{name} = {shown}");
+    }
+}
+
+/// Handle the session commands `--batch` shares with the REPL, `vars` and
+/// `del <name>...`. Returns `false`, printing nothing, when `line` is
+/// neither.
+pub fn handle_session_command(
+    line: &str,
+    variables: &mut Variables,
+    functions: &mut Functions,
+    opts: &FormatOptions,
+) -> bool {
+    if let Some(args) = match_command(line, "vars") {
+        handle_vars_command(args, variables, functions, opts);
+    } else if let Some(args) = match_command(line, "del") {
+        handle_del_command(args, variables, functions, &Theme::new(opts.color));
+    } else {
+        return false;
+    }
+    true
+}
+
+/// Handle `vars` — list session variables, then functions if any.
+fn handle_vars_command(
+    args: &str,
+    variables: &Variables,
+    functions: &Functions,
+    opts: &FormatOptions,
+) {
+    if !args.is_empty() {
+        print_extra_args_error("vars", args, &Theme::new(opts.color));
+    } else if functions.is_empty() {
+        println!("{}", format::format_variables(variables, opts));
+    } else {
+        if !variables.is_empty() {
+            println!("{}", format::format_variables(variables, opts));
+        }
+        println!("{}", format::format_functions(functions, opts));
+    }
+}
+
+/// Handle `del <name>...` — forget session variables and functions.
+fn handle_del_command(args: &str, variables: &mut Variables, functions: &mut Functions, t: &Theme) {
+    if args.is_empty() {
+        print_missing_arg_error("del", "del <name>...", t);
+        return;
+    }
+    for name in args.split_whitespace() {
+        if variables.remove(name).is_none() && functions.remove(name).is_none() {
+            eprintln!(
+                "{}",
+                t.err(&format!("Error: unknown variable or function: '{name}'"))
+            );
+        }
+    }
+}
+
+/// Handle `what <qty>` — list the constants and units close to a value.
+fn handle_what_command(source: &str, ctx: &EvalContext, opts: &FormatOptions) {
+    match parser::parse_and_eval(source, ctx) {
+        Ok(qty) => {
+            let matches = crate::reverse::lookup(
+                &qty,
+                ctx.units,
+                constants::global(),
+                crate::reverse::MAX_MATCHES,
+            );
+            println!("{}", format::format_what(&qty, &matches, opts));
+        }
+        Err(e) => print_error(&e, opts),
+    }
+}
+
+/// Handle `element <symbol|name>` — print an element's properties.
+fn handle_element_command(name: &str, opts: &FormatOptions) {
+    match crate::chemistry::elements::lookup(name) {
+        Some(element) => println!("{}", format::format_element_info(element, opts)),
+        None => print_error(
+            &crate::error::RUnitsError::UnknownElement {
+                symbol: name.to_string(),
+                suggestions: crate::chemistry::elements::suggest(name, 3),
+            },
+            opts,
+        ),
+    }
+}
+
 fn print_error(e: &crate::error::RUnitsError, opts: &FormatOptions) {
     let t = Theme::new(opts.color);
     eprintln!("{}", t.err(&format!("Error: {e}")));
@@ -810,6 +1114,19 @@ mod tests {
 
     // ---- ? help detection tests ----
 
+    #[test]
+    fn parse_assignment_splits_name_and_value() {
+        assert_eq!(parse_assignment("x = 5 ft"), Some(("x", "5 ft")));
+        assert_eq!(
+            parse_assignment("v_0=100 km/h -> m/s"),
+            Some(("v_0", "100 km/h -> m/s"))
+        );
+        assert!(parse_assignment("_ = 5 ft").is_none());
+        assert!(parse_assignment("2x = 5 ft").is_none());
+        assert!(parse_assignment("x =").is_none());
+        assert!(parse_assignment("5 ft -> m").is_none());
+    }
+
     #[test]
     fn strip_question_prefix() {
         assert_eq!(strip_question_mark("? meter"), Some("meter"));
@@ -0,0 +1,187 @@
+//! Reverse lookup: which constants and units is a value close to?
+//!
+//! `runits --what "9.81 m/s^2"` (or `what 9.81 m/s^2` in the REPL) answers
+//! "what is this?" rather than "what is this in X?". Candidates are every
+//! constant in the [`ConstantsDatabase`] and every linear unit in the
+//! [`UnitDatabase`] — one of each unit, read as `1 <unit>` — filtered by
+//! dimension first, then ranked by how close their magnitude is:
+//!
+//! ```text
+//! $ runits --what "9.81 m/s^2"
+//! 9.81 meter/second^2 [Acceleration] is close to:
+//!    99.97%  gravity (g0, g_n)  9.80665 meter/second^2  Standard acceleration ...
+//! ```
+//!
+//! The match percentage is `100 × min/max` of the two magnitudes in base
+//! units, so it is symmetric and never exceeds 100%. Candidates below
+//! [`MIN_MATCH`] (off by more than a factor of two) are dropped, as are
+//! those of the opposite sign.
+//!
+//! FUTURE(reverse-prefixes): prefixed forms aren't candidates, so `0.3 mm`
+//! finds `millimeter` (a seeded unit) but `0.3 Mm` finds nothing. Trying
+//! each prefix on each prefixable unit is cheap enough if it's wanted.
+
+use crate::database::UnitDatabase;
+use crate::database::constants::ConstantsDatabase;
+use crate::units::Quantity;
+use std::collections::HashSet;
+
+/// How many matches `--what` and `what` show.
+pub const MAX_MATCHES: usize = 8;
+
+/// The lowest match ratio (`min/max`) that still counts as close.
+pub const MIN_MATCH: f64 = 0.5;
+
+/// Whether a match is a physical constant or one of a unit.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MatchKind {
+    Constant,
+    Unit,
+}
+
+impl MatchKind {
+    /// Lowercase name, as used in `--json` output.
+    pub fn name(self) -> &'static str {
+        match self {
+            MatchKind::Constant => "constant",
+            MatchKind::Unit => "unit",
+        }
+    }
+}
+
+/// One candidate the queried value is close to.
+#[derive(Debug, Clone)]
+pub struct Match {
+    /// Canonical constant or unit name.
+    pub name: String,
+    /// Every other name it's known by, sorted (`g0`, `g_n` for `gravity`).
+    pub aliases: Vec<String>,
+    pub kind: MatchKind,
+    /// The candidate's magnitude expressed in the query's unit, so the two
+    /// read side by side.
+    pub value: Quantity,
+    /// The constant's description; `None` for units.
+    pub description: Option<String>,
+    /// `min/max` of the two magnitudes, in `(0, 1]`.
+    pub ratio: f64,
+}
+
+impl Match {
+    /// [`ratio`](Self::ratio) as a percentage.
+    pub fn percent(&self) -> f64 {
+        self.ratio * 100.0
+    }
+}
+
+/// The constants and units closest to `query`, best first, at most `max`.
+///
+/// Ties (a unit and a constant of the same size) list constants first, then
+/// go by name, so the order is stable.
+pub fn lookup(
+    query: &Quantity,
+    units: &UnitDatabase,
+    constants: &ConstantsDatabase,
+    max: usize,
+) -> Vec<Match> {
+    let target = query.unit.to_base_value(query.value);
+    let in_query_unit =
+        |base: f64| Quantity::new(query.unit.from_base_value(base), query.unit.clone());
+    let mut matches = Vec::new();
+
+    for c in constants.all_unique() {
+        if c.unit.dimensions != query.unit.dimensions {
+            continue;
+        }
+        let base = c.unit.to_base_value(c.value);
+        if let Some(ratio) = match_ratio(target, base) {
+            matches.push(Match {
+                name: c.name.clone(),
+                aliases: constants.aliases_for(&c.name),
+                kind: MatchKind::Constant,
+                value: in_query_unit(base),
+                description: Some(c.description.clone()),
+                ratio,
+            });
+        }
+    }
+
+    let mut seen = HashSet::new();
+    for (_, unit) in units.entries() {
+        if unit.dimensions != query.unit.dimensions
+            || !unit.is_linear()
+            || !seen.insert(unit.name.as_str())
+        {
+            continue;
+        }
+        let base = unit.conversion_factor();
+        if let Some(ratio) = match_ratio(target, base) {
+            matches.push(Match {
+                name: unit.name.clone(),
+                aliases: units.aliases_for(&unit.name),
+                kind: MatchKind::Unit,
+                value: in_query_unit(base),
+                description: None,
+                ratio,
+            });
+        }
+    }
+
+    matches.sort_by(|a, b| {
+        b.ratio
+            .total_cmp(&a.ratio)
+            .then((a.kind == MatchKind::Unit).cmp(&(b.kind == MatchKind::Unit)))
+            .then(a.name.cmp(&b.name))
+    });
+    matches.truncate(max);
+    matches
+}
+
+/// `min/max` of two same-signed magnitudes, when it reaches [`MIN_MATCH`].
+fn match_ratio(a: f64, b: f64) -> Option<f64> {
+    if a == 0.0 || b == 0.0 || a.signum() != b.signum() || !a.is_finite() || !b.is_finite() {
+        return None;
+    }
+    let (a, b) = (a.abs(), b.abs());
+    let ratio = a.min(b) / a.max(b);
+    (ratio >= MIN_MATCH).then_some(ratio)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn what(input: &str) -> Vec<Match> {
+        let units = UnitDatabase::new();
+        let constants = ConstantsDatabase::new();
+        let q = crate::parser::parse_quantity(input, &units).unwrap();
+        lookup(&q, &units, &constants, 5)
+    }
+
+    #[test]
+    fn finds_standard_gravity() {
+        let matches = what("9.81 m/s^2");
+        let first = &matches[0];
+        assert_eq!(first.name, "gravity");
+        assert_eq!(first.kind, MatchKind::Constant);
+        assert!(first.aliases.contains(&"g_n".to_string()));
+        assert!((first.percent() - 99.966).abs() < 1e-3);
+        assert!((first.value.value - 9.80665).abs() < 1e-12);
+    }
+
+    #[test]
+    fn units_rank_by_closeness_within_their_dimension() {
+        let matches = what("0.3 m");
+        assert_eq!(matches[0].name, "foot");
+        assert_eq!(matches[0].kind, MatchKind::Unit);
+        assert!(matches.iter().all(|m| m.ratio >= MIN_MATCH));
+        assert!(matches.windows(2).all(|w| w[0].ratio >= w[1].ratio));
+        assert!(matches.iter().all(|m| m.name != "second"));
+    }
+
+    #[test]
+    fn nothing_close_is_empty() {
+        assert!(what("1e30 m").is_empty());
+        assert!(what("0 m").is_empty());
+        assert!(what("-1 m").is_empty());
+    }
+}
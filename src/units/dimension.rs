@@ -4,6 +4,7 @@
 //! that form the basis of the unit system. Each dimension represents a category
 //! that units belong to, enabling type-safe conversions and dimensional analysis.
 
+use super::rational::Rational;
 use std::collections::HashMap;
 
 /// Represents a fundamental physical dimension.
@@ -174,13 +175,15 @@ impl Dimension {
 /// Type alias for dimension maps used in unit definitions.
 ///
 /// Maps each [`Dimension`] to its exponent in a unit's dimensional formula.
-/// For example, velocity (m/s) would be `{Length: 1, Time: -1}`.
-pub type DimensionMap = HashMap<Dimension, i8>;
+/// For example, velocity (m/s) would be `{Length: 1, Time: -1}`. Exponents
+/// are [`Rational`] so that `V/√Hz` (`Time^(1/2)`) is representable; zero
+/// exponents are never stored.
+pub type DimensionMap = HashMap<Dimension, Rational>;
 
 /// Creates a [`DimensionMap`] from a slice of (dimension, exponent) pairs.
 ///
 /// This is a convenience function for building the dimensional formula
-/// of a unit from a list of dimensions and their exponents.
+/// of a unit from a list of dimensions and their (integer) exponents.
 ///
 /// # Examples
 /// ```
@@ -192,11 +195,15 @@ pub type DimensionMap = HashMap<Dimension, i8>;
 ///     (Dimension::Time, -1)
 /// ]);
 ///
-/// assert_eq!(velocity_dims.get(&Dimension::Length), Some(&1));
-/// assert_eq!(velocity_dims.get(&Dimension::Time), Some(&-1));
+/// assert_eq!(velocity_dims[&Dimension::Length], 1);
+/// assert_eq!(velocity_dims[&Dimension::Time], -1);
 /// ```
 pub fn create_dimensions(dimensions: &[(Dimension, i8)]) -> DimensionMap {
-    dimensions.iter().cloned().collect()
+    dimensions
+        .iter()
+        .filter(|(_, exp)| *exp != 0)
+        .map(|(dim, exp)| (dim.clone(), Rational::from(*exp)))
+        .collect()
 }
 
 #[cfg(test)]
@@ -215,15 +222,15 @@ mod tests {
         // Test the helper function
         // Create a Length dimension and check that we can't return a Mass
         let dims = create_dimensions(&[(Dimension::Length, 1)]);
-        assert_eq!(dims.get(&Dimension::Length), Some(&1));
+        assert_eq!(dims[&Dimension::Length], 1);
         assert_eq!(dims.get(&Dimension::Mass), None);
     }
 
     #[test]
     fn test_compound_dimension_velocity() {
         let dims = create_dimensions(&[(Dimension::Length, 1), (Dimension::Time, -1)]);
-        assert_eq!(dims.get(&Dimension::Length), Some(&1));
-        assert_eq!(dims.get(&Dimension::Time), Some(&-1));
+        assert_eq!(dims[&Dimension::Length], 1);
+        assert_eq!(dims[&Dimension::Time], -1);
         assert_eq!(dims.get(&Dimension::Mass), None);
     }
 }
@@ -2,7 +2,10 @@
 // It's the "public interface" of the units module
 
 pub mod dimension;
+pub mod nonlinear;
 pub mod quantity;
+pub mod rational;
+pub mod table;
 pub mod unit;
 
 // Re-export the main types so users cna import them easily
@@ -10,4 +13,5 @@ pub mod unit;
 // Can do: use runits::units::Dimension;
 pub use dimension::Dimension;
 pub use quantity::Quantity;
+pub use rational::Rational;
 pub use unit::{ConversionKind, Unit};
@@ -0,0 +1,210 @@
+//! Function-defined (nonlinear) conversions.
+//!
+//! GNU Units defines some units by a function and its inverse rather than a
+//! factor:
+//!
+//! ```text
+//! tempC(x) units=[1;K] domain=[-273.15,) range=[0,) x K + stdtemp ; (tempC +(-stdtemp))/K
+//! ```
+//!
+//! [`NonlinearFn`] is the same idea: a `forward` map from the unit's value
+//! to the base unit, an `inverse` back, and the [`Interval`]s each accepts.
+//! Values outside those intervals are rejected with
+//! [`RUnitsError::OutOfDomain`](crate::error::RUnitsError::OutOfDomain)
+//! instead of propagating NaN.
+//!
+//! A function unit is used three ways:
+//!
+//! | Input | Meaning |
+//! |---|---|
+//! | `tempC(20)` | call: the base-unit quantity `293.15 K` |
+//! | `20 tempC` | a value *in* the unit, convertible like `20 degC` |
+//! | `~tempC(300 K)` | inverse call: the plain number `26.85` |
+//!
+//! The transforms are plain `fn` pointers, so `NonlinearFn` stays `Copy`
+//! and builtins are `const`s. Like affine and logarithmic units, function
+//! units never take part in `*`, `/` or `^`.
+
+use std::fmt;
+
+/// A range of real numbers with open or closed ends; infinite ends are
+/// always open.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Interval {
+    pub lo: f64,
+    pub hi: f64,
+    pub lo_closed: bool,
+    pub hi_closed: bool,
+}
+
+impl Interval {
+    /// Every real number.
+    pub const ALL: Interval = Interval {
+        lo: f64::NEG_INFINITY,
+        hi: f64::INFINITY,
+        lo_closed: false,
+        hi_closed: false,
+    };
+
+    /// `[lo, ∞)`.
+    pub const fn at_least(lo: f64) -> Self {
+        Interval {
+            lo,
+            hi: f64::INFINITY,
+            lo_closed: true,
+            hi_closed: false,
+        }
+    }
+
+    /// `(lo, ∞)`.
+    pub const fn above(lo: f64) -> Self {
+        Interval {
+            lo,
+            hi: f64::INFINITY,
+            lo_closed: false,
+            hi_closed: false,
+        }
+    }
+
+    /// `[lo, hi]`.
+    pub const fn closed(lo: f64, hi: f64) -> Self {
+        Interval {
+            lo,
+            hi,
+            lo_closed: true,
+            hi_closed: true,
+        }
+    }
+
+    /// Whether `x` lies in the interval. NaN never does.
+    pub fn contains(&self, x: f64) -> bool {
+        let above_lo = if self.lo_closed {
+            x >= self.lo
+        } else {
+            x > self.lo
+        };
+        let below_hi = if self.hi_closed {
+            x <= self.hi
+        } else {
+            x < self.hi
+        };
+        above_lo && below_hi
+    }
+
+    /// The interval's image under an increasing map, such as a linear or
+    /// affine unit conversion. Infinite ends stay infinite.
+    pub fn map(self, f: impl Fn(f64) -> f64) -> Self {
+        let end = |x: f64| if x.is_infinite() { x } else { f(x) };
+        Interval {
+            lo: end(self.lo),
+            hi: end(self.hi),
+            ..self
+        }
+    }
+}
+
+impl fmt::Display for Interval {
+    /// Bracket notation: `[-273.15, inf)`, `(0, inf)`.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let end = |x: f64| {
+            if x.is_infinite() {
+                if x > 0.0 { "inf" } else { "-inf" }.to_string()
+            } else {
+                crate::units::quantity::format_value(x, 6, false)
+            }
+        };
+        write!(
+            f,
+            "{}{}, {}{}",
+            if self.lo_closed { '[' } else { '(' },
+            end(self.lo),
+            end(self.hi),
+            if self.hi_closed { ']' } else { ')' },
+        )
+    }
+}
+
+/// A conversion given as a forward/inverse function pair.
+#[derive(Debug, Clone, Copy)]
+pub struct NonlinearFn {
+    /// Value in the unit → value in the base unit.
+    pub forward: fn(f64) -> f64,
+    /// Value in the base unit → value in the unit.
+    pub inverse: fn(f64) -> f64,
+    /// Unit values `forward` accepts.
+    pub domain: Interval,
+    /// Base values `inverse` accepts (the image of `domain`).
+    pub range: Interval,
+    /// `forward` written in terms of `x`, for `--explain` and `?` output.
+    pub formula: &'static str,
+}
+
+// ---- Builtins ----------------------------------------------------------------
+
+/// `tempC(x)`: Celsius as a function, defined down to absolute zero.
+pub const TEMP_C: NonlinearFn = NonlinearFn {
+    forward: |x| x + 273.15,
+    inverse: |k| k - 273.15,
+    domain: Interval::at_least(-273.15),
+    range: Interval::at_least(0.0),
+    formula: "x + 273.15",
+};
+
+/// `tempF(x)`: Fahrenheit as a function, defined down to absolute zero.
+pub const TEMP_F: NonlinearFn = NonlinearFn {
+    forward: |x| (x + 459.67) * 5.0 / 9.0,
+    inverse: |k| k * 9.0 / 5.0 - 459.67,
+    domain: Interval::at_least(-459.67),
+    range: Interval::at_least(0.0),
+    formula: "(x + 459.67) * 5/9",
+};
+
+/// `wiregauge(n)`: the American (Brown & Sharpe) wire gauge formula,
+/// `d = 0.005 in × 92^((36 − n) / 39)`. Gauge 0000 is `n = -3`. Any real
+/// gauge maps to a positive diameter, so only the range is restricted.
+pub const WIRE_GAUGE: NonlinearFn = NonlinearFn {
+    forward: |n| 0.005 * 0.0254 * 92f64.powf((36.0 - n) / 39.0),
+    inverse: |d| 36.0 - 39.0 * (d / (0.005 * 0.0254)).ln() / 92f64.ln(),
+    domain: Interval::ALL,
+    range: Interval::above(0.0),
+    formula: "0.000127 * 92^((36 - x) / 39)",
+};
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn interval_bounds() {
+        let i = Interval::at_least(-273.15);
+        assert!(i.contains(-273.15));
+        assert!(!i.contains(-273.16));
+        assert!(!Interval::above(0.0).contains(0.0));
+        assert!(Interval::closed(0.0, 1.0).contains(1.0));
+        assert!(!Interval::ALL.contains(f64::NAN));
+    }
+
+    #[test]
+    fn interval_display() {
+        assert_eq!(Interval::at_least(-273.15).to_string(), "[-273.15, inf)");
+        assert_eq!(Interval::above(0.0).to_string(), "(0, inf)");
+        assert_eq!(Interval::ALL.to_string(), "(-inf, inf)");
+    }
+
+    #[test]
+    fn builtins_round_trip() {
+        for (f, x) in [(TEMP_C, 20.0), (TEMP_F, 98.6), (WIRE_GAUGE, 10.0)] {
+            let back = (f.inverse)((f.forward)(x));
+            assert!((back - x).abs() < 1e-9, "{}: {back}", f.formula);
+        }
+    }
+
+    #[test]
+    fn wire_gauge_reference_points() {
+        // AWG 0000 = 0.46 in, AWG 36 = 0.005 in exactly.
+        assert!(((WIRE_GAUGE.forward)(-3.0) / 0.0254 - 0.46).abs() < 1e-9);
+        assert!(((WIRE_GAUGE.forward)(36.0) / 0.0254 - 0.005).abs() < 1e-12);
+        // AWG 10 ≈ 2.588 mm
+        assert!(((WIRE_GAUGE.forward)(10.0) * 1000.0 - 2.588).abs() < 1e-3);
+    }
+}
@@ -5,7 +5,8 @@
 //! [`DimensionMap`](crate::units::dimension::DimensionMap)) full dimensional
 //! information, enabling safe conversions with runtime dimensional checking.
 
-use super::unit::Unit;
+use super::rational::Rational;
+use super::unit::{ConversionKind, Unit};
 use crate::error::RUnitsError;
 use std::fmt;
 
@@ -41,19 +42,130 @@ impl Quantity {
             });
         }
 
-        // Convert through base units: source → base → target.
-        let base_value = self.unit.to_base_value(self.value);
-        let target_value = target_unit.from_base_value(base_value);
+        // Convert through base units: source → base → target. The checked
+        // variants reject values a function or logarithmic unit can't take.
+        let base_value = self.unit.try_to_base_value(self.value)?;
+        // A linear or affine source can state the target's range in its own
+        // unit (`0 W` vs `(0, inf) watt`), which reads better than the base
+        // dimensions the target would report on its own.
+        if let Some(range) = target_unit.base_range()
+            && !range.contains(base_value)
+            && matches!(
+                self.unit.conversion,
+                ConversionKind::Linear(_) | ConversionKind::Affine { .. }
+            )
+        {
+            return Err(RUnitsError::OutOfDomain {
+                unit: target_unit.name.clone(),
+                value: self.to_string(),
+                valid: format!(
+                    "{} {}",
+                    range.map(|x| self.unit.from_base_value(x)),
+                    self.unit.name
+                ),
+            });
+        }
+        let target_value = target_unit.try_from_base_value(base_value)?;
 
         Ok(Quantity::new(target_value, target_unit.clone()))
     }
 
+    /// Convert to a unit whose dimensions are the exact inverse of this
+    /// quantity's, via `1/x` in base units: `50 ohm` → `0.02 S`, `4 Hz` →
+    /// `0.25 s`. Both units must be linear, and zero has no reciprocal.
+    ///
+    /// Whether a reciprocal conversion is *allowed* is the caller's policy
+    /// (see [`crate::convert::ConvertOptions::strict`]); this only does it.
+    pub fn convert_to_reciprocal(&self, target_unit: &Unit) -> Result<Quantity, RUnitsError> {
+        if !self.unit.is_reciprocal_of(target_unit) {
+            return Err(RUnitsError::IncompatibleDimensions {
+                from: self.unit.name.clone(),
+                to: target_unit.name.clone(),
+                from_dim: self.unit.dimension_string(),
+                to_dim: target_unit.dimension_string(),
+            });
+        }
+        require_linear(&self.unit, "1/x")?;
+        require_linear(target_unit, "1/x")?;
+        let base_value = self.unit.to_base_value(self.value);
+        if base_value == 0.0 {
+            return Err(RUnitsError::OutOfDomain {
+                unit: target_unit.name.clone(),
+                value: self.to_string(),
+                valid: "non-zero values, for a reciprocal conversion".to_string(),
+            });
+        }
+        let target_value = target_unit.from_base_value(1.0 / base_value);
+        Ok(Quantity::new(target_value, target_unit.clone()))
+    }
+
     /// Like [`convert_to`](Self::convert_to) but returns just the numeric value.
     pub fn convert_value_to(&self, target_unit: &Unit) -> Result<f64, RUnitsError> {
         self.convert_to(target_unit).map(|q| q.value)
     }
 }
 
+/// Reject a non-linear unit (affine temperature scale, logarithmic level or
+/// function unit) as an operand of `op_context` — an operator or a function name. Every
+/// arithmetic method below, and every math function, goes through here so
+/// each non-linear kind gets its own, specific error.
+pub(crate) fn require_linear(unit: &Unit, op_context: &str) -> Result<(), RUnitsError> {
+    match unit.conversion {
+        ConversionKind::Linear(_) => Ok(()),
+        ConversionKind::Affine { .. } => Err(RUnitsError::AffineInExpression {
+            unit: unit.name.clone(),
+            op_context: op_context.to_string(),
+        }),
+        ConversionKind::Logarithmic { .. } => Err(RUnitsError::LogarithmicInExpression {
+            unit: unit.name.clone(),
+            op_context: op_context.to_string(),
+        }),
+        ConversionKind::Nonlinear(_) | ConversionKind::Table(_) => {
+            Err(RUnitsError::NonlinearInExpression {
+                unit: unit.name.clone(),
+                op_context: op_context.to_string(),
+            })
+        }
+    }
+}
+
+/// What a temperature operand of `+` / `-` stands for; see
+/// [`Quantity::temperature_point_op`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum TemperatureRole {
+    /// A reading on an affine scale: `20 degC`.
+    Point,
+    /// A difference on a scale: `5 deltaC`.
+    Delta,
+    /// A plain kelvin (or rankine) value, a reading or a difference
+    /// depending on where it stands.
+    Kelvin,
+}
+
+impl TemperatureRole {
+    fn of(unit: &Unit) -> Self {
+        if unit.is_affine() {
+            TemperatureRole::Point
+        } else if unit.is_temperature_delta() {
+            TemperatureRole::Delta
+        } else {
+            TemperatureRole::Kelvin
+        }
+    }
+}
+
+/// A temperature scale written as a unit name inside `*`, `/` or `^`
+/// (`kg*degC`, `J/degC`, `degC^-1`) is a unit being composed, so it stands
+/// for a degree of *difference*: swap in its delta form. Only the evaluator
+/// can tell a unit name from a value that happens to be 1, so it decides
+/// when to call this; the arithmetic below never guesses.
+pub(crate) fn compose_operand(q: Quantity) -> Quantity {
+    match q.unit.delta_form() {
+        Some(delta) => Quantity::new(q.value, delta),
+        None => q,
+    }
+}
+
 // ---------------------------------------------------------------------------
 // Quantity arithmetic — inherent methods (not `std::ops::*` trait impls).
 //
@@ -80,12 +192,14 @@ impl Quantity {
     /// `pow_i32`) is a variation on this shape. Three decisions are baked
     /// in and are not accidental:
     ///
-    /// **1. Error specificity order: affine-reject BEFORE dim-check.** If a
-    /// user writes `20 celsius + 5 second`, both the affine check and the
-    /// dimension check would fire — but "affine units don't support `+` in
+    /// **1. Error specificity order: non-linear reject BEFORE dim-check.**
+    /// If a user writes `30 dBm + 5 second`, both the level check and the
+    /// dimension check would fire — but "levels don't support `+` in
     /// expressions" is a *more specific* and more helpful error than
-    /// "Temperature + Time isn't compatible". Most-specific-wins is the
-    /// general rule; we order the guards accordingly.
+    /// "Power + Time isn't compatible". Most-specific-wins is the general
+    /// rule; we order the guards accordingly. Absolute temperatures are the
+    /// exception: they follow point/delta rules, see
+    /// [`temperature_point_op`](Self::temperature_point_op).
     ///
     /// **2. Always return self's unit, coerce rhs.** `5 m + 3 ft → 5.9144 m`,
     /// not `5.9144 ft`. Left-hand-side is the "preferred" unit. This matches
@@ -104,18 +218,11 @@ impl Quantity {
     /// rather than a shared helper because the match-site clarity is worth
     /// more than the three saved lines.
     pub fn try_add(self, rhs: Quantity) -> Result<Quantity, RUnitsError> {
-        if self.unit.is_affine() {
-            return Err(RUnitsError::AffineInExpression {
-                unit: self.unit.name.clone(),
-                op_context: "+".to_string(),
-            });
-        }
-        if rhs.unit.is_affine() {
-            return Err(RUnitsError::AffineInExpression {
-                unit: rhs.unit.name.clone(),
-                op_context: "+".to_string(),
-            });
+        if self.unit.is_affine() || rhs.unit.is_affine() {
+            return self.temperature_point_op(rhs, '+');
         }
+        require_linear(&self.unit, "+")?;
+        require_linear(&rhs.unit, "+")?;
         if !self.unit.is_compatible_with(&rhs.unit) {
             return Err(RUnitsError::IncompatibleAddition {
                 op: '+',
@@ -137,18 +244,11 @@ impl Quantity {
     /// See [`try_add`](Self::try_add) for the rationale — this is the same
     /// pattern with `op: '-'`.
     pub fn try_sub(self, rhs: Quantity) -> Result<Quantity, RUnitsError> {
-        if self.unit.is_affine() {
-            return Err(RUnitsError::AffineInExpression {
-                unit: self.unit.name.clone(),
-                op_context: "-".to_string(),
-            });
-        }
-        if rhs.unit.is_affine() {
-            return Err(RUnitsError::AffineInExpression {
-                unit: rhs.unit.name.clone(),
-                op_context: "-".to_string(),
-            });
+        if self.unit.is_affine() || rhs.unit.is_affine() {
+            return self.temperature_point_op(rhs, '-');
         }
+        require_linear(&self.unit, "-")?;
+        require_linear(&rhs.unit, "-")?;
         if !self.unit.is_compatible_with(&rhs.unit) {
             return Err(RUnitsError::IncompatibleAddition {
                 op: '-',
@@ -165,6 +265,77 @@ impl Quantity {
         ))
     }
 
+    /// `+` / `-` with at least one absolute temperature (affine) operand.
+    ///
+    /// A reading on an affine scale is a *point*; a delta unit (`deltaC`)
+    /// is a *difference*. A plain kelvin value can be either, so it takes
+    /// the role that makes sense: added to a point it is a difference in
+    /// either order (`20 °C + 5 K`, `5 K + 20 °C`), and a point subtracted
+    /// from it makes it a reading (`300 K - 20 °C`). The physically
+    /// meaningful combinations are:
+    ///
+    /// | Operation | Result |
+    /// |---|---|
+    /// | point ± difference | point, in the point's scale (`20 °C + 5 K` → `25 °C`) |
+    /// | difference + point | point, in the point's scale (`5 K + 20 °C` → `25 °C`) |
+    /// | point − point | difference, in kelvin (`30 °C - 20 °C` → `10 K`, `300 K - 20 °C` → `6.85 K`) |
+    /// | point + point | error: the sum depends on where the scale puts zero |
+    /// | difference − point | error: not a temperature on any scale |
+    fn temperature_point_op(self, rhs: Quantity, op: char) -> Result<Quantity, RUnitsError> {
+        let op_context = op.to_string();
+        for q in [&self, &rhs] {
+            if !q.unit.is_affine() {
+                require_linear(&q.unit, &op_context)?;
+            }
+        }
+        if !self.unit.is_compatible_with(&rhs.unit) {
+            return Err(RUnitsError::IncompatibleAddition {
+                op,
+                lhs_unit: self.unit.name.clone(),
+                rhs_unit: rhs.unit.name.clone(),
+                lhs_dim: self.unit.dimension_string(),
+                rhs_dim: rhs.unit.dimension_string(),
+            });
+        }
+        let illegal = |reason: &str| RUnitsError::TemperatureArithmetic {
+            expression: format!("{self} {op} {rhs}"),
+            reason: reason.to_string(),
+        };
+        let (lhs_base, rhs_base) = (
+            self.unit.to_base_value(self.value),
+            rhs.unit.to_base_value(rhs.value),
+        );
+        use TemperatureRole::{Delta, Kelvin, Point};
+        match (
+            TemperatureRole::of(&self.unit),
+            TemperatureRole::of(&rhs.unit),
+            op,
+        ) {
+            (Point | Kelvin, Point, '-') => Ok(Quantity::new(lhs_base - rhs_base, Unit::kelvin())),
+            (Point, Point, _) => Err(illegal(
+                "two absolute temperatures can't be added — their sum depends on where each scale puts zero (add a difference instead, e.g. 5 K or 5 deltaC)",
+            )),
+            (Point, _, _) => {
+                let base = if op == '+' {
+                    lhs_base + rhs_base
+                } else {
+                    lhs_base - rhs_base
+                };
+                Ok(Quantity::new(self.unit.from_base_value(base), self.unit))
+            }
+            (Delta | Kelvin, Point, '+') => Ok(Quantity::new(
+                rhs.unit.from_base_value(lhs_base + rhs_base),
+                rhs.unit,
+            )),
+            (Delta | Kelvin, Point, _) => Err(illegal(
+                "an absolute temperature can't be subtracted from a difference — the result is not a temperature on any scale (did you mean the reversed order?)",
+            )),
+            (Delta | Kelvin, Delta | Kelvin, _) => {
+                unreachable!("at least one operand is affine")
+            }
+        }
+    }
+
     /// Multiply two quantities.
     ///
     /// Three paths, in order:
@@ -175,7 +346,10 @@ impl Quantity {
     ///    it as "apply the scalar value to the affine unit" and return
     ///    `Quantity{ 98.6, degF }`. The affine side must still be the bare
     ///    unit reference (`value == 1.0`); `2 * 98.6 degF` is rejected
-    ///    because scaling a temperature is meaningless.
+    ///    because scaling a temperature is meaningless. Logarithmic levels
+    ///    (`30 dBm`) take the same path. A value of 1 can't tell `1 dBm`
+    ///    from a bare `dBm`, so the evaluator rejects non-linear operands
+    ///    that weren't written as a unit name before calling this.
     ///
     /// 2. **Scalar × non-affine unit** — e.g. `10 m`. Keep the unit name
     ///    clean ("meter", not "dimensionless*meter") by promoting the
@@ -185,46 +359,32 @@ impl Quantity {
     ///
     /// 3. **Full compound** — both sides carry dimensions. Fall through to
     ///    the existing `Unit * Unit` impl, which multiplies conversion
-    ///    factors and sums dimension exponents. Rejects affine.
+    ///    factors and sums dimension exponents. A temperature scale composed
+    ///    into a unit (`kg*degC`) arrives already in its delta form (see
+    ///    [`compose_operand`]); any other affine operand is rejected.
     ///
     /// "Scalar" here means "dimensionless, non-affine" — any unit with an
     /// empty dimension map.
     pub fn mul(self, rhs: Quantity) -> Result<Quantity, RUnitsError> {
-        let lhs_is_scalar = self.unit.dimensions.is_empty() && !self.unit.is_affine();
-        let rhs_is_scalar = rhs.unit.dimensions.is_empty() && !rhs.unit.is_affine();
+        let lhs_is_scalar = self.unit.dimensions.is_empty() && self.unit.is_linear();
+        let rhs_is_scalar = rhs.unit.dimensions.is_empty() && rhs.unit.is_linear();
 
-        // (1) Scalar × affine unit (and mirror).
-        if lhs_is_scalar && rhs.unit.is_affine() {
+        // (1) Scalar × affine/logarithmic unit (and mirror).
+        if lhs_is_scalar && !rhs.unit.is_linear() {
             if (rhs.value - 1.0).abs() < 1e-12 {
                 return Ok(Quantity::new(self.value, rhs.unit));
             }
-            return Err(RUnitsError::AffineInExpression {
-                unit: rhs.unit.name.clone(),
-                op_context: "*".to_string(),
-            });
+            require_linear(&rhs.unit, "*")?;
         }
-        if rhs_is_scalar && self.unit.is_affine() {
+        if rhs_is_scalar && !self.unit.is_linear() {
             if (self.value - 1.0).abs() < 1e-12 {
                 return Ok(Quantity::new(rhs.value, self.unit));
             }
-            return Err(RUnitsError::AffineInExpression {
-                unit: self.unit.name.clone(),
-                op_context: "*".to_string(),
-            });
+            require_linear(&self.unit, "*")?;
         }
 
-        if self.unit.is_affine() {
-            return Err(RUnitsError::AffineInExpression {
-                unit: self.unit.name.clone(),
-                op_context: "*".to_string(),
-            });
-        }
-        if rhs.unit.is_affine() {
-            return Err(RUnitsError::AffineInExpression {
-                unit: rhs.unit.name.clone(),
-                op_context: "*".to_string(),
-            });
-        }
+        require_linear(&self.unit, "*")?;
+        require_linear(&rhs.unit, "*")?;
 
         // (2) Scalar × non-affine unit: clean-unit fast path.
         if lhs_is_scalar {
@@ -236,11 +396,13 @@ impl Quantity {
 
         // (3) Full compound multiplication.
         let value = self.value * rhs.value;
-        let unit = self.unit * rhs.unit;
+        let unit = self.unit.checked_mul(rhs.unit)?;
         Ok(Quantity::new(value, unit))
     }
 
-    /// Divide two quantities. Rejects affine units on either side.
+    /// Divide two quantities. Both sides must be linear: a temperature
+    /// scale composed into a unit (`J/degC`, `2 degC/m`) arrives already in
+    /// its delta form (see [`compose_operand`]).
     ///
     /// Divide-by-scalar is short-circuited to keep the unit name clean
     /// (e.g. `10 m / 2` stays `5 m`, not `5 m/dimensionless`). The mirror
@@ -249,26 +411,15 @@ impl Quantity {
     /// `Length^-1` dimensions; that path is rare enough not to warrant its
     /// own fast lane.
     pub fn div(self, rhs: Quantity) -> Result<Quantity, RUnitsError> {
-        if self.unit.is_affine() {
-            return Err(RUnitsError::AffineInExpression {
-                unit: self.unit.name.clone(),
-                op_context: "/".to_string(),
-            });
-        }
-        if rhs.unit.is_affine() {
-            return Err(RUnitsError::AffineInExpression {
-                unit: rhs.unit.name.clone(),
-                op_context: "/".to_string(),
-            });
-        }
+        require_linear(&rhs.unit, "/")?;
+        require_linear(&self.unit, "/")?;
 
-        let rhs_is_scalar = rhs.unit.dimensions.is_empty() && !rhs.unit.is_affine();
-        if rhs_is_scalar {
+        if rhs.unit.dimensions.is_empty() {
             return Ok(Quantity::new(self.value / rhs.value, self.unit));
         }
 
         let value = self.value / rhs.value;
-        let unit = self.unit / rhs.unit;
+        let unit = self.unit.checked_div(rhs.unit)?;
         Ok(Quantity::new(value, unit))
     }
 
@@ -285,20 +436,43 @@ impl Quantity {
         Quantity::new(-self.value, self.unit)
     }
 
-    /// Raise a quantity to an integer power. Rejects affine units.
+    /// Raise a quantity to an integer power. Affine values are rejected; a
+    /// temperature scale written as a unit (`degC^-1`) arrives already in
+    /// its delta form (see [`compose_operand`]).
     ///
     /// Unit and value are both raised: the unit via [`pow_unit`](super::unit::pow_unit),
-    /// the value via `f64::powi`. Exponent 0 yields dimensionless `1.0`;
-    /// negative exponents invert first (via `pow_unit`'s recursion).
+    /// the value via `f64::powi`. Exponent 0 yields dimensionless `1.0`.
     pub fn pow_i32(self, exp: i32) -> Result<Quantity, RUnitsError> {
-        if self.unit.is_affine() {
-            return Err(RUnitsError::AffineInExpression {
-                unit: self.unit.name.clone(),
-                op_context: "^".to_string(),
+        require_linear(&self.unit, "^")?;
+        let value = self.value.powi(exp);
+        let unit = super::unit::pow_unit(self.unit, exp)?;
+        Ok(Quantity::new(value, unit))
+    }
+
+    /// Raise to a rational power: `^2`, `^0.5`, `^(3/2)`.
+    ///
+    /// Integer exponents are [`pow_i32`](Self::pow_i32). A fractional one
+    /// scales every dimension exponent (`(9 m^2)^(1/2)` is `3 m`), so it
+    /// works on any dimension, but needs a non-negative value: the real
+    /// root of a negative number isn't defined for even denominators, and
+    /// picking one for odd ones would make `(-8)^(1/3)` and `(-8)^(2/6)`
+    /// disagree.
+    pub fn pow(self, exp: Rational) -> Result<Quantity, RUnitsError> {
+        if let Some(n) = exp.to_integer() {
+            return self.pow_i32(n);
+        }
+        require_linear(&self.unit, "^")?;
+        if self.value < 0.0 {
+            return Err(RUnitsError::FunctionDomainError {
+                name: "^".to_string(),
+                reason: format!(
+                    "negative base ({}) with fractional exponent {exp}",
+                    self.value
+                ),
             });
         }
-        let value = self.value.powi(exp);
-        let unit = super::unit::pow_unit(self.unit, exp);
+        let value = self.value.powf(exp.to_f64());
+        let unit = super::unit::pow_unit_rational(self.unit, exp)?;
         Ok(Quantity::new(value, unit))
     }
 }
@@ -423,6 +597,7 @@ impl Quantity {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::units::dimension::Dimension;
 
     /// Shorthand for format_value with default args (6 sig figs, no forced scientific).
     fn fv(v: f64) -> String {
@@ -577,13 +752,91 @@ mod tests {
     }
 
     #[test]
-    fn try_add_affine_fails_before_dim_check() {
-        // Even if dimensions match, affine must be rejected first with
-        // the more-specific error.
+    fn try_add_two_absolute_temperatures_fails() {
         let a = Quantity::new(20.0, Unit::celsius());
         let b = Quantity::new(5.0, Unit::celsius());
         let err = a.try_add(b).unwrap_err();
-        assert!(matches!(err, RUnitsError::AffineInExpression { .. }));
+        assert!(matches!(err, RUnitsError::TemperatureArithmetic { .. }));
+        assert!(err.to_string().contains("20 celsius + 5 celsius"), "{err}");
+    }
+
+    #[test]
+    fn absolute_plus_or_minus_delta_is_absolute() {
+        let warm = Quantity::new(20.0, Unit::celsius())
+            .try_add(Quantity::new(5.0, Unit::kelvin()))
+            .unwrap();
+        assert_eq!(warm.unit.name, "celsius");
+        assert!((warm.value - 25.0).abs() < 1e-9);
+        let cool = Quantity::new(50.0, Unit::fahrenheit())
+            .try_sub(Quantity::new(5.0, Unit::kelvin()))
+            .unwrap();
+        assert!((cool.value - 41.0).abs() < 1e-9);
+        // Difference first: the result still takes the reading's scale.
+        let sum = Quantity::new(9.0, Unit::celsius().delta_form().unwrap())
+            .try_add(Quantity::new(32.0, Unit::fahrenheit()))
+            .unwrap();
+        assert_eq!(sum.unit.name, "fahrenheit");
+        assert!((sum.value - 48.2).abs() < 1e-9);
+    }
+
+    #[test]
+    fn absolute_minus_absolute_is_kelvin_delta() {
+        let diff = Quantity::new(30.0, Unit::celsius())
+            .try_sub(Quantity::new(20.0, Unit::celsius()))
+            .unwrap();
+        assert_eq!(diff.unit.name, "kelvin");
+        assert!((diff.value - 10.0).abs() < 1e-9);
+        let diff = Quantity::new(212.0, Unit::fahrenheit())
+            .try_sub(Quantity::new(0.0, Unit::celsius()))
+            .unwrap();
+        assert!((diff.value - 100.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn delta_minus_absolute_fails() {
+        let err = Quantity::new(5.0, Unit::celsius().delta_form().unwrap())
+            .try_sub(Quantity::new(20.0, Unit::celsius()))
+            .unwrap_err();
+        assert!(matches!(err, RUnitsError::TemperatureArithmetic { .. }));
+        assert!(err.to_string().contains("reversed order"), "{err}");
+    }
+
+    #[test]
+    fn kelvin_takes_the_role_its_operation_needs() {
+        // `300 K - 20 degC`: two readings, a difference in kelvin.
+        let diff = Quantity::new(300.0, Unit::kelvin())
+            .try_sub(Quantity::new(20.0, Unit::celsius()))
+            .unwrap();
+        assert_eq!(diff.unit.name, "kelvin");
+        assert!((diff.value - 6.85).abs() < 1e-9);
+        let diff = Quantity::new(300.0, Unit::kelvin())
+            .try_sub(Quantity::new(80.33, Unit::fahrenheit()))
+            .unwrap();
+        assert!(diff.value.abs() < 1e-2);
+        // Added to a reading, kelvin is a difference in either order.
+        let warm = Quantity::new(5.0, Unit::kelvin())
+            .try_add(Quantity::new(20.0, Unit::celsius()))
+            .unwrap();
+        assert_eq!(warm.unit.name, "celsius");
+        assert!((warm.value - 25.0).abs() < 1e-9);
+        let warm = Quantity::new(5.0, Unit::kelvin())
+            .try_add(Quantity::new(68.0, Unit::fahrenheit()))
+            .unwrap();
+        assert_eq!(warm.unit.name, "fahrenheit");
+        assert!((warm.value - 77.0).abs() < 1e-9);
+        // After a reading, kelvin is still a difference.
+        let warm = Quantity::new(68.0, Unit::fahrenheit())
+            .try_add(Quantity::new(5.0, Unit::kelvin()))
+            .unwrap();
+        assert!((warm.value - 77.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn absolute_temperature_plus_other_dimension_fails() {
+        let err = Quantity::new(20.0, Unit::celsius())
+            .try_add(Quantity::new(5.0, Unit::second()))
+            .unwrap_err();
+        assert!(matches!(err, RUnitsError::IncompatibleAddition { .. }));
     }
 
     #[test]
@@ -620,6 +873,24 @@ mod tests {
         assert!(matches!(err, RUnitsError::AffineInExpression { .. }));
     }
 
+    #[test]
+    fn composed_temperature_scale_is_a_delta() {
+        // `kg*degF`: the evaluator composes the scale before multiplying.
+        let kg = Quantity::new(1.0, Unit::kilogram());
+        let prod = kg
+            .mul(compose_operand(Quantity::new(1.0, Unit::fahrenheit())))
+            .unwrap();
+        assert!(prod.unit.is_temperature_delta());
+        assert!((prod.unit.conversion_factor() - 5.0 / 9.0).abs() < 1e-12);
+        // Uncomposed, a reading can't be divided, whatever the divisor.
+        for divisor in [Unit::meter(), Unit::dimensionless()] {
+            let err = Quantity::new(20.0, Unit::celsius())
+                .div(Quantity::new(1.0, divisor))
+                .unwrap_err();
+            assert!(matches!(err, RUnitsError::AffineInExpression { .. }));
+        }
+    }
+
     #[test]
     fn neg_preserves_unit() {
         let q = Quantity::new(5.0, Unit::meter());
@@ -658,4 +929,95 @@ mod tests {
         let err = q.pow_i32(2).unwrap_err();
         assert!(matches!(err, RUnitsError::AffineInExpression { .. }));
     }
+
+    #[test]
+    fn pow_fractional_scales_dimensions() {
+        let q = Quantity::new(4.0, Unit::meter());
+        let root = q.pow(Rational::new(1, 2)).unwrap();
+        assert!((root.value - 2.0).abs() < 1e-12);
+        assert_eq!(root.unit.dimension_string(), "Length^(1/2)");
+        assert_eq!(root.unit.name, "meter^(1/2)");
+
+        let area = Quantity::new(9.0, Unit::new("m2", 1.0, &[(Dimension::Length, 2)]));
+        let side = area.pow(Rational::new(1, 2)).unwrap();
+        assert_eq!(side.unit.dimension_string(), "Length");
+    }
+
+    #[test]
+    fn exponent_overflow_is_an_error() {
+        let root = |n| {
+            Quantity::new(2.0, Unit::meter())
+                .pow(Rational::new(1, n))
+                .unwrap()
+        };
+        let err = root(65537).mul(root(65539)).unwrap_err();
+        assert!(
+            matches!(err, RUnitsError::ExponentOverflow { .. }),
+            "{err:?}"
+        );
+        let err = root(65537).div(root(65539)).unwrap_err();
+        assert!(
+            matches!(err, RUnitsError::ExponentOverflow { .. }),
+            "{err:?}"
+        );
+        let err = root(65537).pow(Rational::new(1, 65539)).unwrap_err();
+        assert!(
+            matches!(err, RUnitsError::ExponentOverflow { .. }),
+            "{err:?}"
+        );
+    }
+
+    #[test]
+    fn extreme_integer_exponents_do_not_hang_or_panic() {
+        let m = || Quantity::new(1.0, Unit::meter());
+        let big = m().pow_i32(i32::MAX).unwrap();
+        assert_eq!(big.unit.name, "meter^2147483647");
+        let err = big.mul(m()).unwrap_err();
+        assert!(
+            matches!(err, RUnitsError::ExponentOverflow { .. }),
+            "{err:?}"
+        );
+        let small = m().pow_i32(i32::MIN).unwrap();
+        assert_eq!(small.unit.name, "meter^-2147483648");
+        let err = small.div(m()).unwrap_err();
+        assert!(
+            matches!(err, RUnitsError::ExponentOverflow { .. }),
+            "{err:?}"
+        );
+        let area = Quantity::new(1.0, Unit::meter() * Unit::meter());
+        let err = area.pow_i32(i32::MIN).unwrap_err();
+        assert!(
+            matches!(err, RUnitsError::ExponentOverflow { .. }),
+            "{err:?}"
+        );
+    }
+
+    #[test]
+    fn pow_fractional_of_negative_fails() {
+        let q = Quantity::new(-4.0, Unit::meter());
+        let err = q.pow(Rational::new(1, 2)).unwrap_err();
+        assert!(matches!(err, RUnitsError::FunctionDomainError { .. }));
+        // Integer exponents still take the `pow_i32` path.
+        let q = Quantity::new(-2.0, Unit::meter());
+        assert!((q.pow(Rational::from(3)).unwrap().value + 8.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn logarithmic_rejected_in_arithmetic() {
+        let dbm = || Quantity::new(3.0, Unit::new_logarithmic("dBm", 1e-3, 10.0, &[]));
+        let err = dbm().try_add(dbm()).unwrap_err();
+        assert!(matches!(err, RUnitsError::LogarithmicInExpression { .. }));
+        let err = dbm().pow_i32(2).unwrap_err();
+        assert!(matches!(err, RUnitsError::LogarithmicInExpression { .. }));
+    }
+
+    #[test]
+    fn scalar_times_bare_logarithmic_applies_value() {
+        let level = Quantity::new(1.0, Unit::new_logarithmic("dBm", 1e-3, 10.0, &[]));
+        let q = Quantity::new(30.0, Unit::dimensionless())
+            .mul(level)
+            .unwrap();
+        assert_eq!(q.value, 30.0);
+        assert_eq!(q.unit.name, "dBm");
+    }
 }
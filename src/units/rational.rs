@@ -0,0 +1,327 @@
+//! Rational numbers, used as dimension exponents.
+//!
+//! Integer exponents cover almost every unit, but not all of them: noise
+//! spectral density is `V/√Hz` (`Time^(1/2)`), fracture toughness `MPa·√m`
+//! (`Length^(-1/2)`), and `m^(3/2)` falls out of Kepler's third law. A
+//! [`DimensionMap`](super::dimension::DimensionMap) therefore maps each
+//! dimension to a `Rational`.
+//!
+//! The type is deliberately small: exact `+ - * /` on `i32` numerators and
+//! denominators, always stored in lowest terms with a positive denominator
+//! so that derived `Eq`/`Hash` agree with numeric equality. `From<i32>` and
+//! `From<i8>` keep integer literals working in seed tables
+//! (`&[(Dimension::Length, 1)]`).
+//!
+//! Sums and products of fractions can outgrow `i32` (`1/65537 + 1/65539`
+//! needs a denominator past 2³¹). The operators panic on that, so they are
+//! for values known to be small; anything built from user input goes
+//! through the `checked_*` methods, which return `None` instead.
+
+use std::cmp::Ordering;
+use std::fmt;
+use std::ops::{Add, AddAssign, Div, Mul, MulAssign, Neg, Sub, SubAssign};
+
+/// An exact fraction `numer / denom` in lowest terms, `denom > 0`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Rational {
+    numer: i32,
+    denom: i32,
+}
+
+impl Rational {
+    pub const ZERO: Rational = Rational { numer: 0, denom: 1 };
+    pub const ONE: Rational = Rational { numer: 1, denom: 1 };
+
+    /// `numer / denom`, reduced.
+    ///
+    /// # Panics
+    /// If `denom` is zero, or for `i32::MIN / -1`, whose reduced numerator
+    /// doesn't fit.
+    ///
+    /// ```
+    /// use runits::units::rational::Rational;
+    ///
+    /// assert_eq!(Rational::new(2, -4), Rational::new(-1, 2));
+    /// assert_eq!(Rational::new(6, 3), Rational::from(2));
+    /// ```
+    pub fn new(numer: i32, denom: i32) -> Self {
+        Self::reduce(numer as i64, denom as i64).expect("rational exponent overflow")
+    }
+
+    /// Reduce a wide fraction, or `None` if it doesn't fit in `i32`.
+    fn reduce(numer: i64, denom: i64) -> Option<Self> {
+        assert!(denom != 0, "rational with zero denominator");
+        let g = gcd(numer.abs(), denom.abs()).max(1);
+        let sign = denom.signum();
+        Some(Rational {
+            numer: i32::try_from(sign * numer / g).ok()?,
+            denom: i32::try_from(denom.abs() / g).ok()?,
+        })
+    }
+
+    /// Parse the exponent spellings the grammar accepts: an integer (`-2`),
+    /// a fraction (`3/2`, `-1/2`) or a decimal (`0.5`, `-1.25`), read
+    /// exactly. `None` for anything else, a zero denominator, or overflow.
+    ///
+    /// ```
+    /// use runits::units::rational::Rational;
+    ///
+    /// assert_eq!(Rational::parse("0.5"), Some(Rational::new(1, 2)));
+    /// assert_eq!(Rational::parse("-3/2"), Some(Rational::new(-3, 2)));
+    /// assert_eq!(Rational::parse("1/0"), None);
+    /// ```
+    pub fn parse(text: &str) -> Option<Self> {
+        let text = text.trim();
+        if let Some((n, d)) = text.split_once('/') {
+            let (n, d): (i64, i64) = (n.trim().parse().ok()?, d.trim().parse().ok()?);
+            return if d == 0 { None } else { Self::reduce(n, d) };
+        }
+        if let Some((whole, frac)) = text.split_once('.') {
+            if frac.is_empty() || !frac.chars().all(|c| c.is_ascii_digit()) {
+                return None;
+            }
+            let scale = 10_i64.checked_pow(u32::try_from(frac.len()).ok()?)?;
+            let negative = whole.starts_with('-');
+            let whole: i64 = match whole.trim_start_matches('-') {
+                "" => 0,
+                digits => digits.parse().ok()?,
+            };
+            let magnitude = whole.checked_mul(scale)?.checked_add(frac.parse().ok()?)?;
+            return Self::reduce(if negative { -magnitude } else { magnitude }, scale);
+        }
+        text.parse::<i32>().ok().map(Rational::from)
+    }
+
+    pub fn numer(self) -> i32 {
+        self.numer
+    }
+
+    pub fn denom(self) -> i32 {
+        self.denom
+    }
+
+    pub fn is_zero(self) -> bool {
+        self.numer == 0
+    }
+
+    pub fn is_integer(self) -> bool {
+        self.denom == 1
+    }
+
+    /// The value as an integer, when it is one.
+    pub fn to_integer(self) -> Option<i32> {
+        self.is_integer().then_some(self.numer)
+    }
+
+    pub fn to_f64(self) -> f64 {
+        self.numer as f64 / self.denom as f64
+    }
+
+    pub fn abs(self) -> Self {
+        Rational {
+            numer: self.numer.abs(),
+            denom: self.denom,
+        }
+    }
+
+    /// `-1`, `0` or `1`.
+    pub fn signum(self) -> i32 {
+        self.numer.signum()
+    }
+
+    /// `self + rhs`, or `None` if the result doesn't fit in `i32`.
+    pub fn checked_add(self, rhs: Rational) -> Option<Rational> {
+        let (a, b, c, d) = wide(self, rhs);
+        Self::reduce((a * d).checked_add(c * b)?, b * d)
+    }
+
+    /// `self - rhs`, or `None` if the result doesn't fit in `i32`.
+    pub fn checked_sub(self, rhs: Rational) -> Option<Rational> {
+        self.checked_add(-rhs)
+    }
+
+    /// `self * rhs`, or `None` if the result doesn't fit in `i32`.
+    pub fn checked_mul(self, rhs: Rational) -> Option<Rational> {
+        let (a, b, c, d) = wide(self, rhs);
+        Self::reduce(a * c, b * d)
+    }
+
+    /// `self / rhs`, or `None` if `rhs` is zero or the result doesn't fit
+    /// in `i32`.
+    pub fn checked_div(self, rhs: Rational) -> Option<Rational> {
+        if rhs.is_zero() {
+            return None;
+        }
+        let (a, b, c, d) = wide(self, rhs);
+        Self::reduce(a * d, b * c)
+    }
+}
+
+fn gcd(mut a: i64, mut b: i64) -> i64 {
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+    a
+}
+
+impl From<i32> for Rational {
+    fn from(n: i32) -> Self {
+        Rational { numer: n, denom: 1 }
+    }
+}
+
+impl From<i8> for Rational {
+    fn from(n: i8) -> Self {
+        Rational::from(n as i32)
+    }
+}
+
+impl PartialEq<i32> for Rational {
+    fn eq(&self, other: &i32) -> bool {
+        self.denom == 1 && self.numer == *other
+    }
+}
+
+impl Ord for Rational {
+    fn cmp(&self, other: &Self) -> Ordering {
+        (self.numer as i64 * other.denom as i64).cmp(&(other.numer as i64 * self.denom as i64))
+    }
+}
+
+impl PartialOrd for Rational {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Add for Rational {
+    type Output = Rational;
+    fn add(self, rhs: Rational) -> Rational {
+        self.checked_add(rhs).expect("rational exponent overflow")
+    }
+}
+
+impl Sub for Rational {
+    type Output = Rational;
+    fn sub(self, rhs: Rational) -> Rational {
+        self + -rhs
+    }
+}
+
+impl Mul for Rational {
+    type Output = Rational;
+    fn mul(self, rhs: Rational) -> Rational {
+        self.checked_mul(rhs).expect("rational exponent overflow")
+    }
+}
+
+impl Div for Rational {
+    type Output = Rational;
+    fn div(self, rhs: Rational) -> Rational {
+        self.checked_div(rhs)
+            .expect("rational division by zero or exponent overflow")
+    }
+}
+
+impl Neg for Rational {
+    type Output = Rational;
+    fn neg(self) -> Rational {
+        Rational {
+            numer: -self.numer,
+            denom: self.denom,
+        }
+    }
+}
+
+impl AddAssign for Rational {
+    fn add_assign(&mut self, rhs: Rational) {
+        *self = *self + rhs;
+    }
+}
+
+impl SubAssign for Rational {
+    fn sub_assign(&mut self, rhs: Rational) {
+        *self = *self - rhs;
+    }
+}
+
+impl MulAssign for Rational {
+    fn mul_assign(&mut self, rhs: Rational) {
+        *self = *self * rhs;
+    }
+}
+
+/// Both operands' parts widened to `i64`, so products can't overflow.
+fn wide(x: Rational, y: Rational) -> (i64, i64, i64, i64) {
+    (
+        x.numer as i64,
+        x.denom as i64,
+        y.numer as i64,
+        y.denom as i64,
+    )
+}
+
+/// `2`, `-1`, `3/2`, `-1/2`. Callers that append it to a `^` wrap
+/// non-integers in parentheses, as the grammar reads them back:
+/// `meter^(3/2)`.
+impl fmt::Display for Rational {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.denom == 1 {
+            write!(f, "{}", self.numer)
+        } else {
+            write!(f, "{}/{}", self.numer, self.denom)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn arithmetic_stays_in_lowest_terms() {
+        let half = Rational::new(1, 2);
+        assert_eq!(half + half, Rational::ONE);
+        assert_eq!(half * Rational::from(3), Rational::new(3, 2));
+        assert_eq!(
+            Rational::new(3, 2) - Rational::from(2),
+            Rational::new(-1, 2)
+        );
+        assert_eq!(Rational::from(3) / Rational::from(6), half);
+        assert!((half - half).is_zero());
+    }
+
+    #[test]
+    fn checked_arithmetic_reports_overflow() {
+        let (a, b) = (Rational::new(1, 65537), Rational::new(1, 65539));
+        assert_eq!(a.checked_add(b), None);
+        assert_eq!(a.checked_mul(b), None);
+        assert_eq!(a.checked_div(Rational::from(65539)), None);
+        assert_eq!(a.checked_div(Rational::ZERO), None);
+        assert_eq!(a.checked_sub(a), Some(Rational::ZERO));
+        assert_eq!(
+            Rational::new(1, 2).checked_mul(Rational::new(2, 3)),
+            Some(Rational::new(1, 3))
+        );
+    }
+
+    #[test]
+    fn parse_accepts_integers_fractions_and_decimals() {
+        assert_eq!(Rational::parse("-2"), Some(Rational::from(-2)));
+        assert_eq!(Rational::parse("2/4"), Some(Rational::new(1, 2)));
+        assert_eq!(Rational::parse("-1.5"), Some(Rational::new(-3, 2)));
+        assert_eq!(Rational::parse("0.25"), Some(Rational::new(1, 4)));
+        assert_eq!(Rational::parse("1.0"), Some(Rational::ONE));
+        assert_eq!(Rational::parse("x"), None);
+        assert_eq!(Rational::parse("1."), None);
+    }
+
+    #[test]
+    fn ordering_and_display() {
+        assert!(Rational::new(-1, 2) < Rational::ZERO);
+        assert!(Rational::new(3, 2) > Rational::ONE);
+        assert_eq!(Rational::new(-3, 2).to_string(), "-3/2");
+        assert_eq!(Rational::from(2).to_string(), "2");
+        assert_eq!(Rational::from(2), 2);
+    }
+}
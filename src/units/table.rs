@@ -0,0 +1,332 @@
+//! Piecewise-linear table conversions.
+//!
+//! Some scales have no formula at all, only a published table: sheet-metal
+//! gauges, shoe and ring sizes, oven gas marks. GNU Units writes them as a
+//! list of points,
+//!
+//! ```text
+//! ringsize[mm] 3 14.1 4 14.9 5 15.7 ...
+//! ```
+//!
+//! and interpolates linearly between neighbours. [`PiecewiseTable`] does the
+//! same: sorted `(x, y)` points, where `x` is the unit's own value (a gauge
+//! number, a size) and `y` the corresponding value in the dimension's base
+//! unit. Both directions interpolate, so `12 awg -> mm` and `2 mm -> awg`
+//! both work; `y` must therefore be strictly monotonic. Values outside the
+//! table are rejected with
+//! [`RUnitsError::OutOfDomain`](crate::error::RUnitsError::OutOfDomain) —
+//! there is no sensible extrapolation for a shoe size.
+//!
+//! Table units behave like function units ([`super::nonlinear`]): they can
+//! be called (`awg(12)`, `~awg(2 mm)`), used as a value's unit (`12 awg`)
+//! or as a conversion target, but never composed or used in arithmetic.
+//!
+//! Tables come from the builtin point lists below or from `table`
+//! statements in definition files ([`crate::database::definitions`]).
+
+use super::nonlinear::Interval;
+
+/// A strictly monotonic piecewise-linear map from unit values to base values.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PiecewiseTable {
+    /// Sorted by `x`, strictly increasing; `y` strictly monotonic.
+    points: Vec<(f64, f64)>,
+}
+
+impl PiecewiseTable {
+    /// Build a table from `(x, y)` points in any order. Needs at least two
+    /// finite points, distinct `x` values, and `y` strictly increasing or
+    /// strictly decreasing once sorted by `x` (otherwise the inverse would
+    /// be ambiguous).
+    pub fn new(mut points: Vec<(f64, f64)>) -> Result<Self, String> {
+        if points.len() < 2 {
+            return Err("a table needs at least two points".to_string());
+        }
+        if let Some((x, y)) = points
+            .iter()
+            .find(|(x, y)| !x.is_finite() || !y.is_finite())
+        {
+            return Err(format!("table point ({x}, {y}) is not finite"));
+        }
+        points.sort_by(|a, b| a.0.total_cmp(&b.0));
+        if let Some(pair) = points.windows(2).find(|w| w[0].0 == w[1].0) {
+            return Err(format!("table lists x = {} twice", pair[0].0));
+        }
+        let increasing = points[1].1 > points[0].1;
+        let monotonic = points.windows(2).all(|w| {
+            if increasing {
+                w[1].1 > w[0].1
+            } else {
+                w[1].1 < w[0].1
+            }
+        });
+        if !monotonic {
+            return Err(
+                "table values must be strictly increasing or strictly decreasing".to_string(),
+            );
+        }
+        Ok(PiecewiseTable { points })
+    }
+
+    /// Build a table whose `y` values are given in a unit `scale` times the
+    /// base unit (e.g. inches: `0.0254`).
+    pub fn scaled(points: &[(f64, f64)], scale: f64) -> Result<Self, String> {
+        Self::new(points.iter().map(|&(x, y)| (x, y * scale)).collect())
+    }
+
+    /// The points, sorted by `x`.
+    pub fn points(&self) -> &[(f64, f64)] {
+        &self.points
+    }
+
+    /// Unit values the table covers: `[x_first, x_last]`.
+    pub fn domain(&self) -> Interval {
+        let (first, last) = (self.points[0].0, self.points[self.points.len() - 1].0);
+        Interval::closed(first, last)
+    }
+
+    /// Base values the table covers: `[y_min, y_max]`.
+    pub fn range(&self) -> Interval {
+        let (a, b) = (self.points[0].1, self.points[self.points.len() - 1].1);
+        Interval::closed(a.min(b), a.max(b))
+    }
+
+    /// Unit value → base value; NaN outside [`domain`](Self::domain).
+    pub fn forward(&self, x: f64) -> f64 {
+        interpolate(self.points.iter().copied(), x)
+    }
+
+    /// Base value → unit value; NaN outside [`range`](Self::range).
+    pub fn inverse(&self, y: f64) -> f64 {
+        interpolate(self.points.iter().map(|&(x, y)| (y, x)), y)
+    }
+}
+
+/// Linear interpolation over consecutive `(from, to)` pairs whose `from`
+/// values are monotonic (in either direction).
+fn interpolate(pairs: impl Iterator<Item = (f64, f64)> + Clone, at: f64) -> f64 {
+    let next = pairs.clone().skip(1);
+    for ((a, fa), (b, fb)) in pairs.zip(next) {
+        let (lo, hi) = if a <= b { (a, b) } else { (b, a) };
+        if (lo..=hi).contains(&at) {
+            return fa + (fb - fa) * (at - a) / (b - a);
+        }
+    }
+    f64::NAN
+}
+
+// ---- Builtins ----------------------------------------------------------------
+
+/// American Wire Gauge, diameters in inches as published (ASTM B258).
+/// Gauges 00, 000 and 0000 are `-1`, `-2` and `-3`.
+pub const AWG_INCHES: &[(f64, f64)] = &[
+    (-3.0, 0.46),
+    (-2.0, 0.4096),
+    (-1.0, 0.3648),
+    (0.0, 0.3249),
+    (1.0, 0.2893),
+    (2.0, 0.2576),
+    (3.0, 0.2294),
+    (4.0, 0.2043),
+    (5.0, 0.1819),
+    (6.0, 0.162),
+    (7.0, 0.1443),
+    (8.0, 0.1285),
+    (9.0, 0.1144),
+    (10.0, 0.1019),
+    (11.0, 0.0907),
+    (12.0, 0.0808),
+    (13.0, 0.072),
+    (14.0, 0.0641),
+    (15.0, 0.0571),
+    (16.0, 0.0508),
+    (17.0, 0.0453),
+    (18.0, 0.0403),
+    (19.0, 0.0359),
+    (20.0, 0.032),
+    (21.0, 0.0285),
+    (22.0, 0.0253),
+    (23.0, 0.0226),
+    (24.0, 0.0201),
+    (25.0, 0.0179),
+    (26.0, 0.0159),
+    (27.0, 0.0142),
+    (28.0, 0.0126),
+    (29.0, 0.0113),
+    (30.0, 0.01),
+    (31.0, 0.0089),
+    (32.0, 0.008),
+    (33.0, 0.0071),
+    (34.0, 0.0063),
+    (35.0, 0.0056),
+    (36.0, 0.005),
+    (37.0, 0.0045),
+    (38.0, 0.004),
+    (39.0, 0.0035),
+    (40.0, 0.0031),
+];
+
+/// Manufacturers' Standard Gauge for steel sheet, thickness in inches.
+pub const SHEET_GAUGE_INCHES: &[(f64, f64)] = &[
+    (3.0, 0.2391),
+    (4.0, 0.2242),
+    (5.0, 0.2092),
+    (6.0, 0.1943),
+    (7.0, 0.1793),
+    (8.0, 0.1644),
+    (9.0, 0.1495),
+    (10.0, 0.1345),
+    (11.0, 0.1196),
+    (12.0, 0.1046),
+    (13.0, 0.0897),
+    (14.0, 0.0747),
+    (15.0, 0.0673),
+    (16.0, 0.0598),
+    (17.0, 0.0538),
+    (18.0, 0.0478),
+    (19.0, 0.0418),
+    (20.0, 0.0359),
+    (21.0, 0.0329),
+    (22.0, 0.0299),
+    (23.0, 0.0269),
+    (24.0, 0.0239),
+    (25.0, 0.0209),
+    (26.0, 0.0179),
+    (27.0, 0.0164),
+    (28.0, 0.0149),
+    (29.0, 0.0135),
+    (30.0, 0.012),
+    (31.0, 0.0105),
+    (32.0, 0.0097),
+    (33.0, 0.009),
+    (34.0, 0.0082),
+    (35.0, 0.0075),
+    (36.0, 0.0067),
+    (37.0, 0.0064),
+    (38.0, 0.006),
+];
+
+/// US men's shoe sizes, foot length in inches (Brannock scale).
+pub const SHOE_SIZE_MEN_INCHES: &[(f64, f64)] = &[
+    (6.0, 9.25),
+    (7.0, 9.625),
+    (8.0, 9.9375),
+    (9.0, 10.25),
+    (10.0, 10.5625),
+    (11.0, 10.9375),
+    (12.0, 11.25),
+    (13.0, 11.5625),
+    (14.0, 11.875),
+    (15.0, 12.1875),
+];
+
+/// US women's shoe sizes, foot length in inches (Brannock scale).
+pub const SHOE_SIZE_WOMEN_INCHES: &[(f64, f64)] = &[
+    (4.0, 8.1875),
+    (5.0, 8.5),
+    (6.0, 8.75),
+    (7.0, 9.0625),
+    (8.0, 9.375),
+    (9.0, 9.6875),
+    (10.0, 10.0),
+    (11.0, 10.3125),
+    (12.0, 10.625),
+];
+
+/// US/Canadian ring sizes, inside diameter in millimetres.
+pub const RING_SIZE_MM: &[(f64, f64)] = &[
+    (0.0, 11.63),
+    (1.0, 12.37),
+    (2.0, 13.21),
+    (3.0, 14.05),
+    (4.0, 14.86),
+    (5.0, 15.70),
+    (6.0, 16.51),
+    (7.0, 17.35),
+    (8.0, 18.19),
+    (9.0, 18.89),
+    (10.0, 19.84),
+    (11.0, 20.68),
+    (12.0, 21.49),
+    (13.0, 22.33),
+    (14.0, 23.01),
+    (15.0, 23.83),
+    (16.0, 24.64),
+];
+
+/// British oven gas marks, in degrees Fahrenheit.
+pub const GAS_MARK_FAHRENHEIT: &[(f64, f64)] = &[
+    (0.25, 225.0),
+    (0.5, 250.0),
+    (1.0, 275.0),
+    (2.0, 300.0),
+    (3.0, 325.0),
+    (4.0, 350.0),
+    (5.0, 375.0),
+    (6.0, 400.0),
+    (7.0, 425.0),
+    (8.0, 450.0),
+    (9.0, 475.0),
+    (10.0, 500.0),
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn table(points: &[(f64, f64)]) -> PiecewiseTable {
+        PiecewiseTable::new(points.to_vec()).unwrap()
+    }
+
+    #[test]
+    fn interpolates_both_directions() {
+        let t = table(&[(0.0, 10.0), (2.0, 20.0), (4.0, 40.0)]);
+        assert_eq!(t.forward(1.0), 15.0);
+        assert_eq!(t.forward(3.0), 30.0);
+        assert_eq!(t.inverse(15.0), 1.0);
+        assert_eq!(t.inverse(30.0), 3.0);
+        // Exact points hit exactly.
+        assert_eq!(t.forward(4.0), 40.0);
+        assert_eq!(t.inverse(10.0), 0.0);
+    }
+
+    #[test]
+    fn decreasing_tables_invert() {
+        let t = table(&[(10.0, 1.0), (0.0, 3.0), (5.0, 2.0)]);
+        assert_eq!(t.points()[0], (0.0, 3.0));
+        assert_eq!(t.forward(7.5), 1.5);
+        assert_eq!(t.inverse(2.5), 2.5);
+        assert_eq!(t.range(), Interval::closed(1.0, 3.0));
+    }
+
+    #[test]
+    fn outside_the_table_is_nan() {
+        let t = table(&[(0.0, 10.0), (1.0, 20.0)]);
+        assert!(t.forward(1.5).is_nan());
+        assert!(t.inverse(5.0).is_nan());
+        assert_eq!(t.domain(), Interval::closed(0.0, 1.0));
+    }
+
+    #[test]
+    fn rejects_bad_tables() {
+        assert!(PiecewiseTable::new(vec![(0.0, 1.0)]).is_err());
+        assert!(PiecewiseTable::new(vec![(0.0, 1.0), (0.0, 2.0)]).is_err());
+        let err = PiecewiseTable::new(vec![(0.0, 1.0), (1.0, 3.0), (2.0, 2.0)]).unwrap_err();
+        assert!(err.contains("strictly"), "{err}");
+        assert!(PiecewiseTable::new(vec![(0.0, f64::NAN), (1.0, 2.0)]).is_err());
+    }
+
+    #[test]
+    fn builtin_tables_are_valid() {
+        for points in [
+            AWG_INCHES,
+            SHEET_GAUGE_INCHES,
+            SHOE_SIZE_MEN_INCHES,
+            SHOE_SIZE_WOMEN_INCHES,
+            RING_SIZE_MM,
+            GAS_MARK_FAHRENHEIT,
+        ] {
+            PiecewiseTable::new(points.to_vec()).unwrap();
+        }
+    }
+}
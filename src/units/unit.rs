@@ -5,11 +5,17 @@
 //! Units are the building blocks of a "System of Units" (e.g., the SI, CGS or Gaussian),
 //! and there must be a given number of **base units** from which all other units can be derived.
 //! Each unit has a common name (plus an arbitrary number of short-names or symbols),
-//! a [`ConversionKind`] (linear factor or affine scale+offset) to convert to/from
+//! a [`ConversionKind`] (linear factor, affine scale+offset, logarithmic level, or
+//! function pair) to convert to/from
 //! the base unit, and a full specification of its dimensions (stored as a [`DimensionMap`]).
 
 use super::dimension::{Dimension, DimensionMap, create_dimensions};
+use super::nonlinear::NonlinearFn;
+use super::rational::Rational;
+use super::table::PiecewiseTable;
+use crate::error::RUnitsError;
 use std::ops::{Div, Mul};
+use std::sync::Arc;
 
 /// How a unit converts to/from its dimension's base unit.
 ///
@@ -18,12 +24,24 @@ use std::ops::{Div, Mul};
 ///
 /// Temperature scales like Celsius and Fahrenheit are
 /// [`Affine`](ConversionKind::Affine): they need both a scale *and* an offset
-/// (`to_base = value * scale + offset`). Affine units **cannot** participate
-/// in compound-unit multiplication or division — "celsius * meter" is
-/// physically meaningless.
+/// (`to_base = value * scale + offset`). An absolute reading **cannot**
+/// participate in compound-unit multiplication or division — "20 °C per
+/// meter" only makes sense as a *difference* — so composition swaps in the
+/// scale's linear [delta form](Unit::delta_form) instead.
 ///
-/// Adding a third variant later (e.g., logarithmic for dB) will trigger
-/// exhaustive-match errors at every use site, forcing correct handling.
+/// Levels like decibels and pH are
+/// [`Logarithmic`](ConversionKind::Logarithmic): the value is a logarithm of
+/// the ratio to a reference level. Like affine units they convert fine on
+/// their own but cannot be composed — "dBm per second" has no meaning.
+///
+/// Units defined by an arbitrary function pair (`tempC(x)`, `wiregauge(n)`)
+/// are [`Nonlinear`](ConversionKind::Nonlinear); see [`super::nonlinear`].
+/// Units given by a published table of points (sheet gauges, shoe sizes)
+/// are [`Table`](ConversionKind::Table); see [`super::table`]. Both count as
+/// function units: callable, convertible, never composable.
+///
+/// Any new variant will trigger exhaustive-match errors at every use site,
+/// forcing correct handling.
 #[derive(Debug, Clone)]
 pub enum ConversionKind {
     /// Pure scaling: `to_base = value * factor`.
@@ -31,12 +49,33 @@ pub enum ConversionKind {
     /// Scale + offset: `to_base = value * scale + offset`.
     /// Used for absolute temperature scales (Celsius, Fahrenheit, Réaumur).
     Affine { scale: f64, offset: f64 },
+    /// Logarithmic level: `to_base = reference * 10^(value / factor)`, so
+    /// `value = factor * log10(base / reference)`.
+    ///
+    /// `reference` is the 0-level in base units (1 mW for dBm, 20 µPa for
+    /// dBSPL). `factor` is 10 for power quantities and 20 for field
+    /// (root-power) quantities, whose power goes as the square; 1 for bels,
+    /// ln(10)/2 for nepers, and −1 for pH, which falls as concentration rises.
+    Logarithmic { reference: f64, factor: f64 },
+    /// Forward/inverse function pair with domain and range checks.
+    /// Builtins are `const`s, so this is a `'static` reference: it keeps
+    /// `ConversionKind` (and every `Unit`) small.
+    Nonlinear(&'static NonlinearFn),
+    /// Piecewise-linear interpolation over a table of points, with the
+    /// table's ends as domain and range. Tables can come from definition
+    /// files, so they are shared rather than `'static`.
+    Table(Arc<PiecewiseTable>),
 }
 
+/// Name prefix of a temperature scale's delta form: `delta_celsius` is one
+/// Celsius degree *of difference* (1 K), with no offset. See
+/// [`Unit::delta_form`].
+pub const DELTA_PREFIX: &str = "delta_";
+
 /// Represents a unit of measurement in a system of units.
 ///
 /// A Unit is a fundamental building block in a system of units, representing a specific quantity
-/// with a name, conversion kind (linear or affine), and dimensions. Units are used to express
+/// with a name, conversion kind (linear, affine or logarithmic), and dimensions. Units are used to express
 /// physical quantities in a consistent and standardized manner.
 ///
 /// # Examples
@@ -74,6 +113,11 @@ pub struct Unit {
     /// True for SI units (meter, second, newton, ...) and byte/liter.
     /// False for non-SI units (foot, mile, furlong, psi, ...).
     pub prefixable: bool,
+    /// Whether this unit is, or contains, the delta form of a temperature
+    /// scale (`delta_celsius`, `joule/delta_fahrenheit`): a difference on
+    /// that scale rather than a reading. Set by [`Unit::delta_form`] and
+    /// carried through `*`, `/` and powers.
+    pub temperature_delta: bool,
 }
 
 impl Unit {
@@ -108,6 +152,25 @@ impl Unit {
             conversion: ConversionKind::Linear(conversion_factor),
             dimensions: create_dimensions(dimensions),
             prefixable: false,
+            temperature_delta: false,
+        }
+    }
+
+    /// Creates a linear unit from an already-built [`DimensionMap`], for
+    /// derived units whose exponents may be fractional (`meter^(1/2)`).
+    /// Zero exponents are dropped.
+    pub fn from_dimensions(
+        name: &str,
+        conversion_factor: f64,
+        mut dimensions: DimensionMap,
+    ) -> Self {
+        dimensions.retain(|_, exp| !exp.is_zero());
+        Unit {
+            name: name.to_string(),
+            conversion: ConversionKind::Linear(conversion_factor),
+            dimensions,
+            prefixable: false,
+            temperature_delta: false,
         }
     }
 
@@ -118,6 +181,7 @@ impl Unit {
             conversion: ConversionKind::Linear(conversion_factor),
             dimensions: create_dimensions(dimensions),
             prefixable: true,
+            temperature_delta: false,
         }
     }
 
@@ -131,6 +195,55 @@ impl Unit {
             conversion: ConversionKind::Affine { scale, offset },
             dimensions: create_dimensions(dimensions),
             prefixable: false,
+            temperature_delta: false,
+        }
+    }
+
+    /// Creates a logarithmic level unit: `base_value = reference * 10^(value / factor)`.
+    ///
+    /// See [`ConversionKind::Logarithmic`] for the meaning of `factor`. Not
+    /// prefixable — `dB` is registered directly rather than as deci-bel.
+    pub fn new_logarithmic(
+        name: &str,
+        reference: f64,
+        factor: f64,
+        dimensions: &[(Dimension, i8)],
+    ) -> Self {
+        Unit {
+            name: name.to_string(),
+            conversion: ConversionKind::Logarithmic { reference, factor },
+            dimensions: create_dimensions(dimensions),
+            prefixable: false,
+            temperature_delta: false,
+        }
+    }
+
+    /// Creates a function-defined unit. `dimensions` are those of the
+    /// *result* of `function.forward`; the unit's own values are plain numbers.
+    pub fn new_nonlinear(
+        name: &str,
+        function: &'static NonlinearFn,
+        dimensions: &[(Dimension, i8)],
+    ) -> Self {
+        Unit {
+            name: name.to_string(),
+            conversion: ConversionKind::Nonlinear(function),
+            dimensions: create_dimensions(dimensions),
+            prefixable: false,
+            temperature_delta: false,
+        }
+    }
+
+    /// Creates a table-defined unit. `dimensions` are those of the table's
+    /// `y` values (already in base units); the unit's own values are plain
+    /// numbers.
+    pub fn new_table(name: &str, table: PiecewiseTable, dimensions: &[(Dimension, i8)]) -> Self {
+        Unit {
+            name: name.to_string(),
+            conversion: ConversionKind::Table(Arc::new(table)),
+            dimensions: create_dimensions(dimensions),
+            prefixable: false,
+            temperature_delta: false,
         }
     }
 
@@ -138,10 +251,19 @@ impl Unit {
     ///
     /// For linear units: `value * factor`.
     /// For affine units: `value * scale + offset`.
+    /// For logarithmic units: `reference * 10^(value / factor)`.
+    /// For function units: `forward(value)`, unchecked — see
+    /// [`try_to_base_value`](Self::try_to_base_value).
+    /// For table units: interpolation, NaN outside the table.
     pub fn to_base_value(&self, value: f64) -> f64 {
         match &self.conversion {
             ConversionKind::Linear(factor) => value * factor,
             ConversionKind::Affine { scale, offset } => value * scale + offset,
+            ConversionKind::Logarithmic { reference, factor } => {
+                reference * 10f64.powf(value / factor)
+            }
+            ConversionKind::Nonlinear(f) => (f.forward)(value),
+            ConversionKind::Table(t) => t.forward(value),
         }
     }
 
@@ -149,11 +271,95 @@ impl Unit {
     ///
     /// For linear units: `base_value / factor`.
     /// For affine units: `(base_value - offset) / scale`.
+    /// For logarithmic units: `factor * log10(base_value / reference)`
+    /// (`-inf` for zero, NaN for negative base values).
     pub fn from_base_value(&self, base_value: f64) -> f64 {
         match &self.conversion {
             ConversionKind::Linear(factor) => base_value / factor,
             ConversionKind::Affine { scale, offset } => (base_value - offset) / scale,
+            ConversionKind::Logarithmic { reference, factor } => {
+                factor * (base_value / reference).log10()
+            }
+            ConversionKind::Nonlinear(f) => (f.inverse)(base_value),
+            ConversionKind::Table(t) => t.inverse(base_value),
+        }
+    }
+
+    /// [`to_base_value`](Self::to_base_value), rejecting values outside a
+    /// function unit's domain with [`RUnitsError::OutOfDomain`].
+    pub fn try_to_base_value(&self, value: f64) -> Result<f64, RUnitsError> {
+        let domain = match &self.conversion {
+            ConversionKind::Nonlinear(f) => Some(f.domain),
+            ConversionKind::Table(t) => Some(t.domain()),
+            ConversionKind::Linear(_)
+            | ConversionKind::Affine { .. }
+            | ConversionKind::Logarithmic { .. } => None,
+        };
+        if let Some(domain) = domain
+            && !domain.contains(value)
+        {
+            return Err(RUnitsError::OutOfDomain {
+                unit: self.name.clone(),
+                value: super::quantity::format_value(value, 6, false),
+                valid: domain.to_string(),
+            });
         }
+        Ok(self.to_base_value(value))
+    }
+
+    /// [`from_base_value`](Self::from_base_value), rejecting base values
+    /// the unit can't express with [`RUnitsError::OutOfDomain`]: outside a
+    /// function unit's range, or non-positive for a logarithmic level.
+    pub fn try_from_base_value(&self, base_value: f64) -> Result<f64, RUnitsError> {
+        if let Some(valid) = self.base_range()
+            && !valid.contains(base_value)
+        {
+            return Err(RUnitsError::OutOfDomain {
+                unit: self.name.clone(),
+                value: format!(
+                    "{} {}",
+                    super::quantity::format_value(base_value, 6, false),
+                    self.to_base_unit_string()
+                ),
+                valid: format!("{valid} {}", self.to_base_unit_string()),
+            });
+        }
+        Ok(self.from_base_value(base_value))
+    }
+
+    /// The base values this unit can express, or `None` when it takes any:
+    /// a function unit's range, or the positive half-line for a
+    /// logarithmic level.
+    pub fn base_range(&self) -> Option<super::nonlinear::Interval> {
+        match &self.conversion {
+            ConversionKind::Nonlinear(f) => Some(f.range),
+            ConversionKind::Table(t) => Some(t.range()),
+            ConversionKind::Logarithmic { .. } => Some(super::nonlinear::Interval::above(0.0)),
+            ConversionKind::Linear(_) | ConversionKind::Affine { .. } => None,
+        }
+    }
+
+    /// The temperature-difference form of an affine scale: the same degree
+    /// size with no offset, named [`DELTA_PREFIX`] + the scale's name
+    /// (`celsius` → `delta_celsius`, 1 K per degree). `None` for every
+    /// other kind — a linear unit is already its own delta form.
+    pub fn delta_form(&self) -> Option<Unit> {
+        match self.conversion {
+            ConversionKind::Affine { scale, .. } => Some(Unit {
+                name: format!("{DELTA_PREFIX}{}", self.name),
+                conversion: ConversionKind::Linear(scale),
+                dimensions: self.dimensions.clone(),
+                prefixable: false,
+                temperature_delta: true,
+            }),
+            _ => None,
+        }
+    }
+
+    /// Returns `true` if this unit is, or contains, the delta form of a
+    /// temperature scale (`delta_celsius`, `joule/delta_fahrenheit`).
+    pub fn is_temperature_delta(&self) -> bool {
+        self.temperature_delta && self.dimensions.contains_key(&Dimension::Temperature)
     }
 
     /// Returns `true` if this unit uses an affine conversion (has an offset).
@@ -161,10 +367,30 @@ impl Unit {
         matches!(&self.conversion, ConversionKind::Affine { .. })
     }
 
+    /// Returns `true` if this unit is a logarithmic level (dB, neper, pH).
+    pub fn is_logarithmic(&self) -> bool {
+        matches!(&self.conversion, ConversionKind::Logarithmic { .. })
+    }
+
+    /// Returns `true` if this unit is a function unit: defined by a function
+    /// pair or by a table of points.
+    pub fn is_nonlinear(&self) -> bool {
+        matches!(
+            &self.conversion,
+            ConversionKind::Nonlinear(_) | ConversionKind::Table(_)
+        )
+    }
+
+    /// Returns `true` if this unit converts by a plain factor, i.e. it can be
+    /// multiplied, divided, raised to a power and used in arithmetic.
+    pub fn is_linear(&self) -> bool {
+        matches!(&self.conversion, ConversionKind::Linear(_))
+    }
+
     /// Returns the linear conversion factor.
     ///
     /// # Panics
-    /// Panics if the unit is affine. Use [`to_base_value`](Self::to_base_value)
+    /// Panics if the unit is not linear. Use [`to_base_value`](Self::to_base_value)
     /// / [`from_base_value`](Self::from_base_value) for general conversions.
     pub fn conversion_factor(&self) -> f64 {
         match &self.conversion {
@@ -172,6 +398,18 @@ impl Unit {
             ConversionKind::Affine { .. } => {
                 panic!("conversion_factor() called on affine unit '{}'", self.name)
             }
+            ConversionKind::Logarithmic { .. } => {
+                panic!(
+                    "conversion_factor() called on logarithmic unit '{}'",
+                    self.name
+                )
+            }
+            ConversionKind::Nonlinear(_) | ConversionKind::Table(_) => {
+                panic!(
+                    "conversion_factor() called on function unit '{}'",
+                    self.name
+                )
+            }
         }
     }
 
@@ -268,6 +506,30 @@ impl Unit {
         Self::new_affine("reaumur", 1.25, 273.15, &[(Dimension::Temperature, 1)])
     }
 
+    // Delisle runs backwards from boiling: to_base(v) = v * (-2/3) + 373.15
+    // Verification:   0°De → 373.15 K = 100°C ✓
+    //               150°De → 373.15 - 100 = 273.15 K = 0°C ✓
+    pub fn delisle() -> Self {
+        Self::new_affine(
+            "delisle",
+            -2.0 / 3.0,
+            373.15,
+            &[(Dimension::Temperature, 1)],
+        )
+    }
+
+    // Rømer: to_base(v) = (v - 7.5) * 40/21 + 273.15
+    // Verification: 7.5°Rø → 273.15 K = 0°C ✓
+    //               60°Rø  → 52.5 * 40/21 + 273.15 = 373.15 K = 100°C ✓
+    pub fn romer() -> Self {
+        Self::new_affine(
+            "romer",
+            40.0 / 21.0,
+            273.15 - 7.5 * 40.0 / 21.0,
+            &[(Dimension::Temperature, 1)],
+        )
+    }
+
     // ----- DERIVED UNITS -----
     // These units are defined in terms of base units
 
@@ -336,6 +598,19 @@ impl Unit {
         self.dimensions == other.dimensions
     }
 
+    /// Returns `true` if the two units' dimensions are exact inverses
+    /// (ohm and siemens, hertz and second). Dimensionless units are not
+    /// reciprocal to anything: `1/x` of a pure number is a different number,
+    /// not a conversion.
+    pub fn is_reciprocal_of(&self, other: &Unit) -> bool {
+        !self.dimensions.is_empty()
+            && self.dimensions.len() == other.dimensions.len()
+            && self
+                .dimensions
+                .iter()
+                .all(|(dim, &exp)| other.dimensions.get(dim) == Some(&-exp))
+    }
+
     /// Returns a human-readable description of what this unit measures.
     ///
     /// Uses flat notation with negative exponents (no `/`).
@@ -401,7 +676,7 @@ impl Unit {
                 if *exp == 1 {
                     sym.to_string()
                 } else {
-                    format!("{}^{}", sym, exp)
+                    format!("{}{}", sym, power_suffix(*exp))
                 }
             })
             .collect();
@@ -424,82 +699,143 @@ impl PartialEq for Unit {
 
 /// Raise a unit to an integer power.
 ///
-/// Positive exponents use repeated multiplication; negative exponents
-/// invert first (dimensionless / unit), then multiply. Zero returns the
-/// dimensionless identity. Shared between the target-side unit parser
-/// (`unit_factor`) and the source-side quantity evaluator (`Quantity::pow_i32`).
+/// Every dimension exponent is scaled by `exp` and the factor raised with
+/// `f64::powi`, so the cost doesn't grow with the exponent. The result is
+/// named `meter^2`, `meter^-1`, or `(meter/second)^2` when the base is
+/// compound. Zero returns the dimensionless identity. Shared between the
+/// target-side unit parser (`unit_factor`) and the source-side quantity
+/// evaluator (`Quantity::pow_i32`). Fails with
+/// [`RUnitsError::ExponentOverflow`] when an exponent outgrows `i32`.
 ///
-/// Callers are responsible for rejecting affine units — `pow_unit` will
-/// panic via the `debug_assert!` in `Mul`/`Div` if handed one.
-pub(crate) fn pow_unit(unit: Unit, exp: i32) -> Unit {
+/// Callers are responsible for rejecting affine units.
+pub(crate) fn pow_unit(unit: Unit, exp: i32) -> Result<Unit, RUnitsError> {
     if exp == 0 {
-        return Unit::dimensionless();
+        return Ok(Unit::dimensionless());
     }
     if exp == 1 {
-        return unit;
-    }
-    if exp < 0 {
-        let inv = Unit::dimensionless() / unit;
-        return pow_unit(inv, -exp);
+        return Ok(unit);
     }
-    let mut result = unit.clone();
-    for _ in 1..exp {
-        result = result * unit.clone();
+    let factor = unit.conversion_factor().powi(exp);
+    raise(unit, Rational::from(exp), factor)
+}
+
+/// Raise a unit to a rational power. Integer exponents go through
+/// [`pow_unit`]; fractional ones scale the factor with `powf` and are
+/// named the same way, `meter^(1/2)` or `(meter/second)^(3/2)`.
+pub(crate) fn pow_unit_rational(unit: Unit, exp: Rational) -> Result<Unit, RUnitsError> {
+    if let Some(n) = exp.to_integer() {
+        return pow_unit(unit, n);
     }
-    result
+    let factor = unit.conversion_factor().powf(exp.to_f64());
+    raise(unit, exp, factor)
 }
 
-// Implement multiplication for units: meter * second
-// Affine units (temperature) must not be composed — the parser rejects them
-// before reaching here, but the debug_assert catches programming errors.
-impl Mul for Unit {
-    type Output = Unit; // The result of multiplying two Units is a Unit
+/// Shared body of [`pow_unit`] and [`pow_unit_rational`]: name the power
+/// and scale every exponent, with `factor` already raised by the caller.
+fn raise(unit: Unit, exp: Rational, factor: f64) -> Result<Unit, RUnitsError> {
+    let base = if unit.name.contains(['*', '/', '^']) {
+        format!("({})", unit.name)
+    } else {
+        unit.name.clone()
+    };
+    let name = format!("{base}{}", power_suffix(exp));
+    let dimensions =
+        scale_dimensions(&unit.dimensions, exp).ok_or_else(|| RUnitsError::ExponentOverflow {
+            expression: name.clone(),
+        })?;
+    Ok(Unit {
+        temperature_delta: unit.temperature_delta,
+        ..Unit::from_dimensions(&name, factor, dimensions)
+    })
+}
 
-    fn mul(self, rhs: Unit) -> Unit {
+/// Every exponent of `dimensions` times `by`, or `None` if one overflows.
+pub(crate) fn scale_dimensions(dimensions: &DimensionMap, by: Rational) -> Option<DimensionMap> {
+    dimensions
+        .iter()
+        .map(|(dim, &e)| Some((dim.clone(), e.checked_mul(by)?)))
+        .collect()
+}
+
+/// `^2`, `^-1`, `^(3/2)`: an exponent as written after a name, with the
+/// parentheses the grammar needs around a fraction.
+pub fn power_suffix(exp: Rational) -> String {
+    if exp.is_integer() {
+        format!("^{exp}")
+    } else {
+        format!("^({exp})")
+    }
+}
+
+impl Unit {
+    /// `self * rhs`, failing with [`RUnitsError::ExponentOverflow`] when a
+    /// summed exponent outgrows `i32`. The `*` operator is this, panicking
+    /// instead; use it only on units with small, known exponents.
+    pub fn checked_mul(self, rhs: Unit) -> Result<Unit, RUnitsError> {
         debug_assert!(
             !self.is_affine() && !rhs.is_affine(),
             "cannot multiply affine units: '{}' * '{}'",
             self.name,
             rhs.name
         );
-        let result_unit_name = format!("{}*{}", self.name, rhs.name);
-        let mut result_dimensions: DimensionMap = self.dimensions.clone();
-        for (dimension, &exponent) in rhs.dimensions.iter() {
-            *result_dimensions.entry(dimension.clone()).or_insert(0) += exponent;
-        }
-        result_dimensions.retain(|_, &mut exp| exp != 0);
-        let dimensions_vec: Vec<(Dimension, i8)> = result_dimensions.into_iter().collect();
-        Unit::new(
-            &result_unit_name,
-            self.conversion_factor() * rhs.conversion_factor(),
-            &dimensions_vec,
-        )
+        let name = format!("{}*{}", self.name, rhs.name);
+        self.combine(rhs, name, Rational::ONE)
     }
-}
-
-// Implement division for units: meter / second = m/s
-impl Div for Unit {
-    type Output = Unit;
 
-    fn div(self, rhs: Unit) -> Unit {
+    /// `self / rhs`; see [`checked_mul`](Self::checked_mul).
+    pub fn checked_div(self, rhs: Unit) -> Result<Unit, RUnitsError> {
         debug_assert!(
             !self.is_affine() && !rhs.is_affine(),
             "cannot divide affine units: '{}' / '{}'",
             self.name,
             rhs.name
         );
-        let result_unit_name = format!("{}/{}", self.name, rhs.name);
-        let mut result_dimensions: DimensionMap = self.dimensions.clone();
+        let name = format!("{}/{}", self.name, rhs.name);
+        self.combine(rhs, name, -Rational::ONE)
+    }
+
+    /// Shared body of `checked_mul` (`sign` 1) and `checked_div` (−1).
+    fn combine(self, rhs: Unit, name: String, sign: Rational) -> Result<Unit, RUnitsError> {
+        let mut dimensions: DimensionMap = self.dimensions.clone();
         for (dimension, &exponent) in rhs.dimensions.iter() {
-            *result_dimensions.entry(dimension.clone()).or_insert(0) -= exponent;
+            let entry = dimensions
+                .entry(dimension.clone())
+                .or_insert(Rational::ZERO);
+            *entry = entry.checked_add(exponent * sign).ok_or_else(|| {
+                RUnitsError::ExponentOverflow {
+                    expression: name.clone(),
+                }
+            })?;
         }
-        result_dimensions.retain(|_, &mut exp| exp != 0);
-        let dimensions_vec: Vec<(Dimension, i8)> = result_dimensions.into_iter().collect();
-        Unit::new(
-            &result_unit_name,
-            self.conversion_factor() / rhs.conversion_factor(),
-            &dimensions_vec,
-        )
+        let factor = if sign == 1 {
+            self.conversion_factor() * rhs.conversion_factor()
+        } else {
+            self.conversion_factor() / rhs.conversion_factor()
+        };
+        Ok(Unit {
+            temperature_delta: self.temperature_delta || rhs.temperature_delta,
+            ..Unit::from_dimensions(&name, factor, dimensions)
+        })
+    }
+}
+
+// Implement multiplication for units: meter * second
+// Affine units (temperature) must not be composed — the parser rejects them
+// before reaching here, but the debug_assert catches programming errors.
+impl Mul for Unit {
+    type Output = Unit; // The result of multiplying two Units is a Unit
+
+    fn mul(self, rhs: Unit) -> Unit {
+        self.checked_mul(rhs).expect("dimension exponent overflow")
+    }
+}
+
+// Implement division for units: meter / second = m/s
+impl Div for Unit {
+    type Output = Unit;
+
+    fn div(self, rhs: Unit) -> Unit {
+        self.checked_div(rhs).expect("dimension exponent overflow")
     }
 }
 
@@ -760,4 +1096,123 @@ mod tests {
         assert!(!Unit::rankine().is_affine());
         assert!(!Unit::meter().is_affine());
     }
+
+    // ---- Logarithmic level tests ----
+
+    const POWER: &[(Dimension, i8)] = &[
+        (Dimension::Mass, 1),
+        (Dimension::Length, 2),
+        (Dimension::Time, -3),
+    ];
+
+    #[test]
+    fn test_dbm_to_watt() {
+        let dbm = Unit::new_logarithmic("dBm", 1e-3, 10.0, &[]);
+        // 30 dBm = 1 W, 0 dBm = 1 mW
+        assert!((dbm.to_base_value(30.0) - 1.0).abs() < 1e-12);
+        assert!((dbm.to_base_value(0.0) - 1e-3).abs() < 1e-15);
+        assert!((dbm.from_base_value(2.0) - 33.0103).abs() < 1e-4);
+    }
+
+    #[test]
+    fn test_field_level_uses_factor_20() {
+        let dbv = Unit::new_logarithmic("dBV", 1.0, 20.0, &[]);
+        // +20 dB on a field quantity is ×10, not ×100.
+        assert!((dbv.to_base_value(20.0) - 10.0).abs() < 1e-12);
+        assert!((dbv.from_base_value(0.5) - (-6.0206)).abs() < 1e-4);
+    }
+
+    #[test]
+    fn test_logarithmic_round_trip_through_quantity() {
+        let watt = Unit::new("watt", 1.0, POWER);
+        let dbm = Unit::new_logarithmic("dBm", 1e-3, 10.0, POWER);
+        let q = Quantity::new(2.0, watt.clone()).convert_to(&dbm).unwrap();
+        let back = q.convert_to(&watt).unwrap();
+        assert!((back.value - 2.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_logarithmic_unit_detection() {
+        let db = Unit::new_logarithmic("decibel", 1.0, 10.0, &[]);
+        assert!(db.is_logarithmic());
+        assert!(!db.is_linear());
+        assert!(!db.is_affine());
+        assert!(Unit::meter().is_linear());
+        assert!(!Unit::celsius().is_linear());
+    }
+
+    #[test]
+    fn test_logarithmic_rejects_non_positive_base() {
+        let dbm = Unit::new_logarithmic("dBm", 1e-3, 10.0, &[]);
+        assert!(dbm.try_from_base_value(0.0).is_err());
+        assert!(dbm.try_from_base_value(-1.0).is_err());
+        assert!(dbm.try_from_base_value(1.0).is_ok());
+    }
+
+    // ---- Function unit tests ----
+
+    #[test]
+    fn test_nonlinear_domain_and_range_checks() {
+        use crate::units::nonlinear::TEMP_C;
+        let temp_c = Unit::new_nonlinear("tempC", &TEMP_C, &[(Dimension::Temperature, 1)]);
+        assert!(temp_c.is_nonlinear());
+        assert!((temp_c.try_to_base_value(0.0).unwrap() - 273.15).abs() < 1e-12);
+        assert!(matches!(
+            temp_c.try_to_base_value(-274.0),
+            Err(RUnitsError::OutOfDomain { .. })
+        ));
+        assert!(matches!(
+            temp_c.try_from_base_value(-1.0),
+            Err(RUnitsError::OutOfDomain { .. })
+        ));
+        // Affine celsius and function tempC agree inside the domain.
+        let q = Quantity::new(37.0, temp_c)
+            .convert_to(&Unit::celsius())
+            .unwrap();
+        assert!((q.value - 37.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_table_unit_interpolates_and_checks_ends() {
+        use crate::units::table::PiecewiseTable;
+        let table = PiecewiseTable::new(vec![(1.0, 0.004), (2.0, 0.003), (3.0, 0.001)]).unwrap();
+        let gauge = Unit::new_table("gauge", table, &[(Dimension::Length, 1)]);
+        assert!(gauge.is_nonlinear());
+        assert!(!gauge.is_linear());
+        assert!((gauge.try_to_base_value(1.5).unwrap() - 0.0035).abs() < 1e-15);
+        assert!((gauge.try_from_base_value(0.002).unwrap() - 2.5).abs() < 1e-12);
+        let err = gauge.try_to_base_value(3.5).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "3.5 is out of range for 'gauge' (valid: [1, 3])"
+        );
+        assert!(matches!(
+            gauge.try_from_base_value(0.01),
+            Err(RUnitsError::OutOfDomain { .. })
+        ));
+    }
+
+    #[test]
+    fn test_delta_form_drops_the_offset() {
+        let delta = Unit::fahrenheit().delta_form().unwrap();
+        assert_eq!(delta.name, "delta_fahrenheit");
+        assert!(delta.is_linear());
+        assert!(delta.is_temperature_delta());
+        assert!((delta.conversion_factor() - 5.0 / 9.0).abs() < 1e-12);
+        assert!(Unit::kelvin().delta_form().is_none());
+        assert!(!Unit::kelvin().is_temperature_delta());
+        // The marker is structural: it survives composition, not the name.
+        assert!((Unit::kilogram() / delta.clone()).is_temperature_delta());
+        assert!(
+            !Unit::new("delta_kelvin", 1.0, &[(Dimension::Temperature, 1)]).is_temperature_delta()
+        );
+    }
+
+    #[test]
+    fn test_delisle_and_romer() {
+        assert!((Unit::delisle().to_base_value(150.0) - 273.15).abs() < 1e-9);
+        assert!((Unit::delisle().from_base_value(373.15)).abs() < 1e-9);
+        assert!((Unit::romer().to_base_value(60.0) - 373.15).abs() < 1e-9);
+        assert!((Unit::romer().from_base_value(273.15) - 7.5).abs() < 1e-9);
+    }
 }
@@ -282,205 +282,1406 @@ fn explain_affine_temperature_harmonized_labels() {
         .stdout(predicate::str::contains("37"));
 }
 
-// ---- Expression foundation (Phase 5a) ----
+#[test]
+fn explain_logarithmic_shows_log_formula() {
+    runits()
+        .arg("--explain")
+        .arg("2 W")
+        .arg("dBm")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("10^(dBm / 10)"))
+        .stdout(predicate::str::contains(
+            "10 * log10(2 / 0.001) = 33.0103 dBm",
+        ));
+}
+
+// ---- Logarithmic levels ----
 
 #[test]
-fn expression_implicit_multiplication() {
-    // `3*4 m` → 12 m → ~39.37 ft
+fn dbm_to_watt() {
     runits()
-        .arg("3*4 meter")
-        .arg("foot")
+        .args(["30 dBm", "W"])
         .assert()
         .success()
-        .stdout(predicate::str::contains("39.3"));
+        .stdout(predicate::str::contains("1 watt"));
 }
 
 #[test]
-fn expression_pow_then_juxtapose() {
-    // `2^10 byte` → 1024 byte → 1.024 kB
+fn watt_to_dbm() {
     runits()
-        .arg("2^10 byte")
-        .arg("kB")
+        .args(["2 W", "dBm"])
         .assert()
         .success()
-        .stdout(predicate::str::contains("1.024"));
+        .stdout(predicate::str::contains("33.0103 dBm"));
 }
 
 #[test]
-fn expression_addition_same_dim() {
-    // `5 m + 3 ft` → 5.9144 m → 591.44 cm
+fn neper_to_decibel() {
     runits()
-        .arg("5 m + 3 ft")
-        .arg("cm")
+        .args(["1 Np", "dB"])
         .assert()
         .success()
-        .stdout(predicate::str::contains("591.4"));
+        .stdout(predicate::str::contains("8.68589"));
 }
 
 #[test]
-fn expression_addition_incompatible() {
+fn ph_to_concentration() {
     runits()
-        .arg("5 m + 3 s")
-        .arg("cm")
+        .args(["7 pH", "mol/L"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("1e-7"));
+}
+
+#[test]
+fn logarithmic_in_compound_target_fails() {
+    runits()
+        .args(["1 W", "dBm/s"])
         .assert()
         .failure()
-        .code(1)
-        .stderr(predicate::str::contains("Length"))
-        .stderr(predicate::str::contains("Time"));
+        .stderr(predicate::str::contains("logarithmic unit 'dBm'"));
 }
 
 #[test]
-fn expression_constant_arithmetic() {
-    // `2 * c_0 * 1 s` ≈ 599584.916 km
+fn logarithmic_addition_fails() {
     runits()
-        .arg("2 * c_0 * 1 s")
-        .arg("km")
+        .args(["3 dBm + 3 dBm", "W"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("convert to a linear unit first"));
+}
+
+#[test]
+fn logarithmic_scaling_fails() {
+    runits()
+        .args(["2 * 1 dBm", "dBm"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("logarithmic unit 'dBm' with '*'"));
+}
+
+#[test]
+fn logarithmic_out_of_range_names_source_unit() {
+    runits()
+        .args(["0 mW", "dBm"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "0 milliwatt is out of range for 'dBm' (valid: (0, inf) milliwatt)",
+        ));
+}
+
+// ---- Function-defined units ----
+
+#[test]
+fn function_unit_call_converts() {
+    runits()
+        .args(["wiregauge(10)", "mm"])
         .assert()
         .success()
-        .stdout(predicate::str::contains("599584").or(predicate::str::contains("599585")));
+        .stdout(predicate::str::contains("2.58819 millimeter"));
 }
 
 #[test]
-fn expression_sqrt_dim_transform() {
-    // `sqrt(9 m^2)` → 3 m → 300 cm
+fn function_unit_as_target() {
     runits()
-        .arg("sqrt(9 m^2)")
-        .arg("cm")
+        .args(["2 mm", "wiregauge"])
         .assert()
         .success()
-        .stdout(predicate::str::contains("300"));
+        .stdout(predicate::str::contains("12.2236 wiregauge"));
 }
 
 #[test]
-fn expression_sqrt_odd_exponent_fails() {
+fn function_unit_out_of_domain_is_an_error() {
     runits()
-        .arg("sqrt(9 m)")
-        .arg("cm")
+        .args(["tempC(-300)", "K"])
         .assert()
         .failure()
-        .code(1)
-        .stderr(predicate::str::contains("sqrt"));
+        .stderr(predicate::str::contains("out of range for 'tempC'"))
+        .stderr(predicate::str::contains("NaN").not());
 }
 
 #[test]
-fn expression_sin_scalar_ok() {
-    // `sin(0) m` → 0 m → 0 cm
+fn inverse_function_call_in_repl() {
     runits()
-        .arg("sin(0) m")
-        .arg("cm")
+        .write_stdin("~wiregauge(0.09 in)\n")
         .assert()
         .success()
-        .stdout(predicate::str::contains("0"));
+        .stdout(predicate::str::contains("11.0708"));
 }
 
+// ---- Temperature differences ----
+
 #[test]
-fn expression_sin_dimensioned_fails() {
+fn delta_units_convert_without_offset() {
     runits()
-        .arg("sin(5 m)")
-        .arg("cm")
+        .args(["10 deltaC", "deltaF"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("18 delta_fahrenheit"));
+}
+
+#[test]
+fn specific_heat_composes_delta_form() {
+    runits()
+        .args(["4.18 J/(g*degC)", "BTU/(lb*degF)"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("0.998"));
+}
+
+#[test]
+fn temperature_gradient_composes_delta_form() {
+    runits()
+        .args(["2 degC/m", "degF/ft"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("1.09728"));
+}
+
+#[test]
+fn explain_marks_delta_conversion() {
+    runits()
+        .args(["--explain", "10 deltaC", "deltaF"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("temperature:  delta"));
+}
+
+// ---- Table units ----
+
+#[test]
+fn table_unit_as_source() {
+    runits()
+        .args(["12 awg", "mm"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("2.05232 millimeter"));
+}
+
+#[test]
+fn table_unit_as_target_interpolates() {
+    runits()
+        .args(["2 mm", "awg"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("12.2341 awg"));
+}
+
+#[test]
+fn table_unit_outside_the_table_is_an_error() {
+    runits()
+        .args(["2 cm", "awg"])
         .assert()
         .failure()
-        .code(1)
-        .stderr(predicate::str::contains("sin"));
+        .stderr(predicate::str::contains("out of range for 'awg'"));
 }
 
 #[test]
-fn expression_unknown_function_suggests() {
+fn gas_mark_converts_to_celsius() {
     runits()
-        .arg("sxrt(9 m^2)")
-        .arg("cm")
+        .args(["4 gasmark", "degC"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("176.667 celsius"));
+}
+
+// ---- Reciprocal conversions ----
+
+#[test]
+fn reciprocal_dimensions_convert_via_one_over_x() {
+    runits()
+        .args(["4 Hz", "ms"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("250 millisecond"));
+}
+
+#[test]
+fn reciprocal_conversion_is_marked_in_json_and_explain() {
+    runits()
+        .args(["--json", "50 V/A", "A/V"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(r#""value":0.02"#))
+        .stdout(predicate::str::contains(r#""reciprocal":true"#));
+    runits()
+        .args(["--explain", "4 Hz", "ms"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("reciprocal:"))
+        .stdout(predicate::str::contains("1 / 4 = 0.25"));
+}
+
+#[test]
+fn strict_refuses_reciprocal_conversion() {
+    runits()
+        .args(["--strict", "4 Hz", "ms"])
         .assert()
         .failure()
-        .code(1)
-        .stderr(predicate::str::contains("sqrt"));
+        .stderr(predicate::str::contains("incompatible dimensions"));
 }
 
 #[test]
-fn expression_negation() {
-    // `--` separates flags from positionals so clap doesn't interpret `-5`
-    // as a short flag.
+fn strict_config_key_refuses_reciprocal_conversion() {
+    let home = std::env::temp_dir().join(format!("runits-cli-{}-strict", std::process::id()));
+    std::fs::create_dir_all(home.join("runits")).unwrap();
+    std::fs::write(home.join("runits").join("config.toml"), "strict = true\n").unwrap();
     runits()
-        .arg("--")
-        .arg("-5 m")
-        .arg("cm")
+        .env("XDG_CONFIG_HOME", &home)
+        .args(["4 Hz", "ms"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("incompatible dimensions"));
+}
+
+// ---- Fuel economy ----
+
+#[test]
+fn mpg_to_litres_per_100km() {
+    runits()
+        .args(["30 mpg", "L/(100 km)"])
         .assert()
         .success()
-        .stdout(predicate::str::contains("-500"));
+        .stdout(predicate::str::contains("7.84049"));
 }
 
 #[test]
-fn expression_affine_addition_fails() {
+fn litres_per_100km_to_imperial_mpg() {
     runits()
-        .arg("20 celsius + 5 celsius")
-        .arg("K")
+        .args(["5 L_per_100km", "mpg_imp"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("56.4962 mpg_imp"));
+}
+
+// ---- Equivalency contexts ----
+
+#[test]
+fn equivalency_is_off_by_default() {
+    runits()
+        .args(["500 nm", "eV"])
         .assert()
         .failure()
-        .code(1)
-        .stderr(predicate::str::contains("celsius"));
+        .stderr(predicate::str::contains("incompatible dimensions"));
 }
 
 #[test]
-fn expression_underscore_prefix_fails_cleanly() {
-    // `_foo` must not be a partial `_` + dangling `foo` — it should fail
-    // the grammar at the parser level.
-    runits().arg("_foo m").arg("cm").assert().failure().code(1);
+fn spectral_equivalency_converts_wavelength_to_energy() {
+    runits()
+        .args(["--equiv", "spectral", "500 nm", "eV"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("2.47968 electronvolt"));
 }
 
 #[test]
-fn explain_preserves_expression_source() {
-    // --explain on a non-trivial expression should echo the original input
-    // on an `expression:` line.
+fn mass_energy_equivalency_explains_its_constant() {
     runits()
-        .arg("--explain")
-        .arg("5 m + 3 ft")
-        .arg("cm")
+        .args(["--equiv", "mass-energy", "--explain", "1 g", "J"])
         .assert()
         .success()
-        .stdout(predicate::str::contains("expression:"))
-        .stdout(predicate::str::contains("5 m + 3 ft"));
+        .stdout(predicate::str::contains("8.98755e13"))
+        .stdout(predicate::str::contains("equivalency:  mass-energy"))
+        .stdout(predicate::str::contains("speed_of_light"));
 }
 
 #[test]
-fn explain_trivial_conversion_skips_expression_row() {
-    // A simple `10 ft` should NOT get an "expression:" row — the source
-    // row already shows "10 foot" which is the same information.
+fn using_enables_equivalency_in_repl() {
     runits()
-        .arg("--explain")
-        .arg("10 ft")
-        .arg("m")
+        .env("NO_COLOR", "1")
+        .args(["--intro-banner", "off"])
+        .write_stdin("using thermal\n300 K -> meV\nusing none\n300 K -> meV\nquit\n")
         .assert()
         .success()
-        .stdout(predicate::str::contains("expression:").not());
+        .stdout(predicate::str::contains("Equivalencies: thermal"))
+        .stdout(predicate::str::contains("25.852 millielectronvolt"))
+        .stderr(predicate::str::contains("incompatible dimensions"));
 }
 
-// ---- REPL previous-result chain ----
+// ---- Substances ----
 
 #[test]
-fn repl_previous_result_chain() {
-    // Feed a three-line REPL session: establish a quantity, use `_` to
-    // double it, then convert via `_ to km`. Assert that the printed
-    // values form the expected sequence.
-    let mut cmd = runits();
-    cmd.env("NO_COLOR", "1")
-        .env_remove("CLICOLOR")
-        .arg("--intro-banner")
-        .arg("off")
-        .write_stdin("5 m + 3 ft\n_ * 2\n_ to km\nquit\n")
+fn cup_of_flour_to_grams() {
+    runits()
+        .args(["2 cup flour", "g"])
         .assert()
         .success()
-        .stdout(predicate::str::contains("5.9144"))
-        .stdout(predicate::str::contains("11.8288"))
-        .stdout(predicate::str::contains("0.0118288"));
+        .stdout(predicate::str::contains("250 gram"));
 }
 
 #[test]
-fn repl_previous_unavailable_errors_cleanly() {
-    let mut cmd = runits();
-    cmd.env("NO_COLOR", "1")
-        .arg("--intro-banner")
-        .arg("off")
-        .write_stdin("_ + 5 m\nquit\n")
+fn glucose_molar_to_mass_concentration() {
+    runits()
+        .args(["5.5 mmol/L glucose", "mg/dL"])
         .assert()
-        .success() // REPL doesn't exit on error, it prints and continues
-        .stderr(predicate::str::contains("no previous result"));
+        .success()
+        .stdout(predicate::str::contains("99.0858"));
+}
+
+#[test]
+fn volume_to_mass_without_substance_fails_clearly() {
+    runits()
+        .args(["2 cup", "g"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("without a substance"))
+        .stderr(predicate::str::contains("needs a density"));
+}
+
+// ---- Automatic target unit ----
+
+#[test]
+fn auto_target_picks_si_and_binary_prefixes() {
+    runits()
+        .args(["0.000047 F", "auto"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("47 microfarad"));
+    runits()
+        .args(["--precision", "4", "--auto", "123456789 byte"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("117.7 mebibyte"));
+}
+
+#[test]
+fn auto_target_prefers_named_time_and_the_rounded_value() {
+    runits()
+        .args(["5400 s", "auto"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("1.5 hour"));
+    runits()
+        .args(["999.9999 m", "auto"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("1 kilometer"));
+}
+
+#[test]
+fn auto_target_with_imperial_family() {
+    runits()
+        .args(["--auto=imperial", "1609.344 m"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("1 mile"));
+    runits()
+        .args(["1609.344 m", "auto metric"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("unknown unit family 'metric'"));
+}
+
+#[test]
+fn auto_config_key_converts_bare_repl_input() {
+    let home = std::env::temp_dir().join(format!("runits-cli-{}-auto", std::process::id()));
+    std::fs::create_dir_all(home.join("runits")).unwrap();
+    std::fs::write(home.join("runits").join("config.toml"), "auto = \"si\"\n").unwrap();
+    runits()
+        .env("XDG_CONFIG_HOME", &home)
+        .env("NO_COLOR", "1")
+        .args(["--intro-banner", "off"])
+        .write_stdin("0.0025 s\n2 km -> auto imperial\n")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("2.5 millisecond"))
+        .stdout(predicate::str::contains("1.24274 mile"));
+}
+
+// ---- Reverse lookup (--what) ----
+
+#[test]
+fn what_finds_standard_gravity() {
+    runits()
+        .args(["--what", "9.81 m/s^2"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("99.97%  gravity (g0, g_n)"));
+}
+
+#[test]
+fn what_json_and_no_match() {
+    runits()
+        .args(["--what", "--json", "0.3 m"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            r#"{"name":"foot","kind":"unit","aliases":["feet","ft"]"#,
+        ));
+    runits()
+        .args(["--what", "1e30 m"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("no close constants or units"));
+}
+
+#[test]
+fn repl_what_command_uses_previous_result() {
+    runits()
+        .env("NO_COLOR", "1")
+        .args(["--intro-banner", "off"])
+        .write_stdin("0.5 * 3.2e-19 C\nwhat _\n")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "elementary_charge (electron_charge)",
+        ));
+}
+
+// ---- Mixed-unit input ----
+
+#[test]
+fn mixed_feet_and_inches_input() {
+    runits()
+        .args(["6 ft 3 in", "cm"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("190.5 centimeter"));
+}
+
+#[test]
+fn mixed_input_round_trips_through_a_unit_list() {
+    runits()
+        .args(["1 h 20 min 5 s", "h;min;s"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("1 hour 20 minute 5 second"));
+}
+
+#[test]
+fn mixed_input_scaled_by_a_number() {
+    runits()
+        .args(["2 * 6 ft 3 in", "ft"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("12.5 foot"));
+}
+
+#[test]
+fn mixed_input_ambiguities_are_errors() {
+    runits()
+        .args(["6 ft 3", "m"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("trailing number '3' has no unit"));
+    runits()
+        .args(["6 ft 3 s", "m"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("does not match 'foot'"));
+}
+
+// ---- Unit lists ----
+
+#[test]
+fn unit_list_splits_feet_and_inches() {
+    runits()
+        .args(["6.25 ft", "ft;in"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("6 foot 3 inch"));
+}
+
+#[test]
+fn unit_list_json_has_value_unit_pairs() {
+    runits()
+        .args(["--json", "5400 s", "h;min;s"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            r#""parts":[{"value":1,"unit":"hour"},{"value":30,"unit":"minute"},{"value":0,"unit":"second"}]"#,
+        ));
+}
+
+#[test]
+fn unit_list_out_of_order_fails() {
+    runits()
+        .args(["1 ft", "in;ft"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("largest to smallest"));
+}
+
+// ---- Chemical formulas ----
+
+#[test]
+fn molar_mass_times_amount_to_grams() {
+    runits()
+        .args(["3 mol * molar_mass(NaCl)", "g"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("175.3"));
+}
+
+#[test]
+fn molar_mass_of_a_hydrate() {
+    runits()
+        .args(["molar_mass(CuSO4·5H2O)", "g/mol"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("249.67"));
+}
+
+#[test]
+fn molar_mass_unknown_element_suggests() {
+    runits()
+        .args(["molar_mass(Fx2O3)", "g/mol"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("unknown element: 'Fx'"))
+        .stderr(predicate::str::contains("Did you mean"));
+}
+
+#[test]
+fn element_density_in_an_expression() {
+    runits()
+        .args(["density(Fe) * 1 cm^3", "g"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("7.874 gram"));
+}
+
+#[test]
+fn element_command_prints_properties() {
+    runits()
+        .env("NO_COLOR", "1")
+        .args(["element", "iron"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Fe  iron"))
+        .stdout(predicate::str::contains("Atomic number: 26"))
+        .stdout(predicate::str::contains("Melting point: 1811 kelvin"));
+}
+
+#[test]
+fn density_of_a_substance() {
+    runits()
+        .args(["density(water) * 1 L", "g"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("998.2 gram"));
+}
+
+#[test]
+fn element_command_json_and_unknown() {
+    runits()
+        .args(["--json", "element", "Hg"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            r#""melting_point":{"value":234.43,"unit":"kelvin"}"#,
+        ));
+    runits()
+        .args(["element", "Irn"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("unknown element: 'Irn'"));
+}
+
+#[test]
+fn repl_element_command() {
+    runits()
+        .env("NO_COLOR", "1")
+        .args(["--intro-banner", "off"])
+        .write_stdin("element Au\n")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Au  gold"));
+}
+
+// ---- Expression foundation (Phase 5a) ----
+
+#[test]
+fn expression_implicit_multiplication() {
+    // `3*4 m` → 12 m → ~39.37 ft
+    runits()
+        .arg("3*4 meter")
+        .arg("foot")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("39.3"));
+}
+
+#[test]
+fn expression_pow_then_juxtapose() {
+    // `2^10 byte` → 1024 byte → 1.024 kB
+    runits()
+        .arg("2^10 byte")
+        .arg("kB")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("1.024"));
+}
+
+#[test]
+fn expression_addition_same_dim() {
+    // `5 m + 3 ft` → 5.9144 m → 591.44 cm
+    runits()
+        .arg("5 m + 3 ft")
+        .arg("cm")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("591.4"));
+}
+
+#[test]
+fn expression_addition_incompatible() {
+    runits()
+        .arg("5 m + 3 s")
+        .arg("cm")
+        .assert()
+        .failure()
+        .code(1)
+        .stderr(predicate::str::contains("Length"))
+        .stderr(predicate::str::contains("Time"));
+}
+
+#[test]
+fn expression_constant_arithmetic() {
+    // `2 * c_0 * 1 s` ≈ 599584.916 km
+    runits()
+        .arg("2 * c_0 * 1 s")
+        .arg("km")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("599584").or(predicate::str::contains("599585")));
+}
+
+#[test]
+fn expression_sqrt_dim_transform() {
+    // `sqrt(9 m^2)` → 3 m → 300 cm
+    runits()
+        .arg("sqrt(9 m^2)")
+        .arg("cm")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("300"));
+}
+
+#[test]
+fn expression_sqrt_odd_exponent_fails() {
+    // `sqrt(9 m)` is `Length^(1/2)` now, so it only fails against a `Length`
+    // target.
+    runits()
+        .arg("sqrt(9 m)")
+        .arg("cm")
+        .assert()
+        .failure()
+        .code(1)
+        .stderr(predicate::str::contains("sqrt"))
+        .stderr(predicate::str::contains("Length^(1/2) vs Length"));
+}
+
+#[test]
+fn expression_sin_scalar_ok() {
+    // `sin(0) m` → 0 m → 0 cm
+    runits()
+        .arg("sin(0) m")
+        .arg("cm")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("0"));
+}
+
+#[test]
+fn expression_sin_dimensioned_fails() {
+    runits()
+        .arg("sin(5 m)")
+        .arg("cm")
+        .assert()
+        .failure()
+        .code(1)
+        .stderr(predicate::str::contains("sin"));
+}
+
+#[test]
+fn expression_unknown_function_suggests() {
+    runits()
+        .arg("sxrt(9 m^2)")
+        .arg("cm")
+        .assert()
+        .failure()
+        .code(1)
+        .stderr(predicate::str::contains("sqrt"));
+}
+
+#[test]
+fn expression_negation() {
+    // `--` separates flags from positionals so clap doesn't interpret `-5`
+    // as a short flag.
+    runits()
+        .arg("--")
+        .arg("-5 m")
+        .arg("cm")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("-500"));
+}
+
+#[test]
+fn expression_affine_addition_fails() {
+    runits()
+        .arg("20 celsius + 5 celsius")
+        .arg("K")
+        .assert()
+        .failure()
+        .code(1)
+        .stderr(predicate::str::contains("celsius"))
+        .stderr(predicate::str::contains("two absolute temperatures"));
+}
+
+#[test]
+fn expression_absolute_plus_delta() {
+    runits()
+        .args(["20 degC + 5 K", "degC"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("25 celsius"));
+}
+
+#[test]
+fn expression_delta_plus_absolute() {
+    runits()
+        .args(["5 K + 20 degC", "degC"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("25 celsius"));
+}
+
+#[test]
+fn expression_absolute_minus_absolute_is_delta() {
+    runits()
+        .args(["30 degC - 20 degC", "K"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("10 kelvin"));
+}
+
+#[test]
+fn expression_underscore_prefix_fails_cleanly() {
+    // `_foo` must not be a partial `_` + dangling `foo` — it should fail
+    // the grammar at the parser level.
+    runits().arg("_foo m").arg("cm").assert().failure().code(1);
+}
+
+#[test]
+fn explain_preserves_expression_source() {
+    // --explain on a non-trivial expression should echo the original input
+    // on an `expression:` line.
+    runits()
+        .arg("--explain")
+        .arg("5 m + 3 ft")
+        .arg("cm")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("expression:"))
+        .stdout(predicate::str::contains("5 m + 3 ft"));
+}
+
+#[test]
+fn explain_trivial_conversion_skips_expression_row() {
+    // A simple `10 ft` should NOT get an "expression:" row — the source
+    // row already shows "10 foot" which is the same information.
+    runits()
+        .arg("--explain")
+        .arg("10 ft")
+        .arg("m")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("expression:").not());
+}
+
+// ---- Math prelude ----
+
+#[test]
+fn inverse_trig_returns_an_angle() {
+    runits()
+        .args(["asin(0.5)", "deg"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("30 degree"));
+    runits()
+        .args(["sin(30 deg) + cos(pi / 3)", "1"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("1 1"));
+}
+
+#[test]
+fn math_prelude_functions_and_constants() {
+    runits()
+        .arg("--batch")
+        .write_stdin(
+            "log(8, 2) -> 1\n\
+             cbrt(27 m^3) -> cm\n\
+             round(2.6 ft) -> ft\n\
+             gamma(5) -> 1\n\
+             ln(e) * tau -> 1\n",
+        )
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("3 1"))
+        .stdout(predicate::str::contains("300 centimeter"))
+        .stdout(predicate::str::contains("3 foot"))
+        .stdout(predicate::str::contains("24 1"))
+        .stdout(predicate::str::contains("6.28319 1"));
+}
+
+#[test]
+fn math_function_out_of_domain_is_an_error() {
+    runits()
+        .args(["acos(2)", "rad"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("function 'acos'"))
+        .stderr(predicate::str::contains("NaN").not());
+}
+
+#[test]
+fn variadic_functions_coerce_to_first_unit() {
+    runits()
+        .args(["hypot(3 m, 4 ft)", "m"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("3.23828 meter"));
+    runits()
+        .args(["sum(1 h, 30 min, 90 s)", "min"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("91.5 minute"));
+    runits()
+        .args(["atan2(1 m, -100 cm)", "deg"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("135 degree"));
+}
+
+#[test]
+fn variadic_function_reports_the_wrong_argument() {
+    runits()
+        .args(["max(1 m, 2 ft, 3 s)", "m"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "function 'max': argument 3 must be Length like argument 1, got Time",
+        ));
+    runits()
+        .args(["hypot(3 m)", "m"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "expects at least 2 argument(s), got 1",
+        ));
+}
+
+#[test]
+fn repl_question_mark_shows_function_signature() {
+    runits()
+        .env("NO_COLOR", "1")
+        .args(["--intro-banner", "off"])
+        .write_stdin("? atan\nquit\n")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("atan(Scalar) → Angle"));
+}
+
+// ---- REPL previous-result chain ----
+
+#[test]
+fn repl_previous_result_chain() {
+    // Feed a three-line REPL session: establish a quantity, use `_` to
+    // double it, then convert via `_ to km`. Assert that the printed
+    // values form the expected sequence.
+    let mut cmd = runits();
+    cmd.env("NO_COLOR", "1")
+        .env_remove("CLICOLOR")
+        .arg("--intro-banner")
+        .arg("off")
+        .write_stdin("5 m + 3 ft\n_ * 2\n_ to km\nquit\n")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("5.9144"))
+        .stdout(predicate::str::contains("11.8288"))
+        .stdout(predicate::str::contains("0.0118288"));
+}
+
+#[test]
+fn repl_previous_unavailable_errors_cleanly() {
+    let mut cmd = runits();
+    cmd.env("NO_COLOR", "1")
+        .arg("--intro-banner")
+        .arg("off")
+        .write_stdin("_ + 5 m\nquit\n")
+        .assert()
+        .success() // REPL doesn't exit on error, it prints and continues
+        .stderr(predicate::str::contains("no previous result"));
+}
+
+// ---- Session variables ----
+
+#[test]
+fn repl_variables_assign_list_and_delete() {
+    runits()
+        .env("NO_COLOR", "1")
+        .args(["--intro-banner", "off"])
+        .write_stdin("x = 5 ft\nx * 2 -> m\nvars\ndel x\nx\nquit\n")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("x = 5 foot [Length]"))
+        .stdout(predicate::str::contains("3.048 meter"))
+        .stdout(predicate::str::contains("  x = 5 foot [Length]"))
+        .stderr(predicate::str::contains("unknown identifier: 'x'"));
+}
+
+#[test]
+fn repl_variable_shadowing_a_unit_warns() {
+    runits()
+        .env("NO_COLOR", "1")
+        .args(["--intro-banner", "off"])
+        .write_stdin("m = 2 kg\nm * 3\nquit\n")
+        .assert()
+        .success()
+        .stderr(predicate::str::contains(
+            "'m' is now a variable, shadowing the unit 'meter'",
+        ))
+        .stdout(predicate::str::contains("6 kilogram"));
+}
+
+#[test]
+fn batch_lines_build_on_variables() {
+    runits()
+        .arg("--batch")
+        .write_stdin("rho = 1000 kg/m^3\nvol = 2 L\nrho * vol -> kg\n")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("vol = 2 liter"))
+        .stdout(predicate::str::contains("2 kilogram"));
+}
+
+#[test]
+fn batch_lines_list_and_delete_variables() {
+    runits()
+        .env("NO_COLOR", "1")
+        .arg("--batch")
+        .write_stdin("x = 3 ft\nvars\ndel x\nx -> m\n")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("  x = 3 foot"))
+        .stderr(predicate::str::contains("malformed line").not())
+        .stderr(predicate::str::contains("unknown identifier"));
+}
+
+// ---- User-defined functions ----
+
+#[test]
+fn repl_functions_define_call_and_check_annotations() {
+    runits()
+        .env("NO_COLOR", "1")
+        .args(["--intro-banner", "off"])
+        .write_stdin(
+            "ke(m: Mass, v: Velocity): Energy = 0.5 * m * v^2\n\
+             ke(2 kg, 3 m/s) -> J\n\
+             ke(2 m, 3 m/s)\n\
+             ke(2 kg)\n\
+             vars\n\
+             quit\n",
+        )
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "ke(m: Mass, v: Velocity): Energy = 0.5 * m * v^2",
+        ))
+        .stdout(predicate::str::contains("9 joule"))
+        .stderr(predicate::str::contains(
+            "function 'ke': parameter 'm' must be Mass, got Length",
+        ))
+        .stderr(predicate::str::contains(
+            "function 'ke' expects 2 argument(s), got 1",
+        ));
+}
+
+#[test]
+fn repl_function_cannot_call_itself() {
+    runits()
+        .env("NO_COLOR", "1")
+        .args(["--intro-banner", "off"])
+        .write_stdin("f(x) = 2 * f(x)\nquit\n")
+        .assert()
+        .success()
+        .stderr(predicate::str::contains("'f' would call itself: f -> f"));
+}
+
+#[test]
+fn batch_lines_use_functions() {
+    runits()
+        .arg("--batch")
+        .write_stdin("area(r: Length): Area = pi_ish * r^2\npi_ish = 3.14159\narea(2 m) -> m^2\n")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("12.5664 meter^2"));
+}
+
+#[test]
+fn batch_function_cannot_hide_a_function_unit() {
+    runits()
+        .arg("--batch")
+        .write_stdin("tempC(x) = x\ntempC(20) -> K\n")
+        .assert()
+        .success()
+        .stderr(predicate::str::contains(
+            "'tempC' is a builtin function unit",
+        ))
+        .stdout(predicate::str::contains("293.15 kelvin"));
+}
+
+// ---- Rational exponents ----
+
+#[test]
+fn fractional_exponents_on_both_sides() {
+    runits()
+        .args(["1 m^(3/2)", "ft^1.5"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("5.94261 foot^(3/2)"));
+    runits()
+        .args(["sqrt(9 m)", "cm^(1/2)"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("30 centimeter^(1/2)"));
+}
+
+#[test]
+fn fractional_power_of_expression() {
+    runits()
+        .arg("--batch")
+        .write_stdin("(8 m^3)^(1/3) -> cm\n(4 m)^0.5 * (4 m)^0.5 -> m\n")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("200 centimeter"))
+        .stdout(predicate::str::contains("4 meter"));
+}
+
+#[test]
+fn fractional_power_of_negative_fails() {
+    runits()
+        .args(["(-4 m)^0.5", "m^(1/2)"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("fractional exponent"));
+}
+
+#[test]
+fn exponent_overflow_is_an_error_not_a_panic() {
+    runits()
+        .args(["m^(1/65537)*m^(1/65539)", "m"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("dimension exponent overflow"))
+        .stderr(predicate::str::contains("panicked").not());
+}
+
+#[test]
+fn extreme_integer_exponents_fail_promptly() {
+    for expr in ["1 m^2147483647 * 1 m", "1 m^-2147483648 / 1 m"] {
+        runits()
+            .args([expr, "m"])
+            .timeout(std::time::Duration::from_secs(10))
+            .assert()
+            .failure()
+            .stderr(predicate::str::contains("dimension exponent overflow"))
+            .stderr(predicate::str::contains("panicked").not());
+    }
+}
+
+// ---- User units file (units.conf) ----
+
+/// A throwaway `$XDG_CONFIG_HOME` whose `runits/units.conf` holds `contents`.
+fn config_home_with_units(name: &str, contents: &str) -> std::path::PathBuf {
+    let home = std::env::temp_dir().join(format!("runits-cli-{}-{name}", std::process::id()));
+    std::fs::create_dir_all(home.join("runits")).unwrap();
+    std::fs::write(home.join("runits").join("units.conf"), contents).unwrap();
+    home
+}
+
+#[test]
+fn user_units_file_is_loaded_for_oneshot() {
+    let home = config_home_with_units(
+        "oneshot",
+        "unit rack_unit: Length = 1.75 inch @aliases(U)\n",
+    );
+    runits()
+        .env("XDG_CONFIG_HOME", &home)
+        .args(["42 U", "cm"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("186.69"));
+}
+
+#[test]
+fn user_units_file_may_redefine_standard_tier_units() {
+    // `knot` comes from a standard-tier module, so the REPL default tier
+    // would otherwise reject the file that one-shot mode accepts.
+    let home = config_home_with_units(
+        "tier-collision",
+        "unit knot: Velocity = 2 m/s\nunit rack_unit: Length = 1.75 inch\n",
+    );
+    runits()
+        .env("XDG_CONFIG_HOME", &home)
+        .env("NO_COLOR", "1")
+        .args(["--intro-banner", "off"])
+        .write_stdin("1 knot -> m/s\n1 rack_unit -> inch\nquit\n")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("2 m/s").or(predicate::str::contains("2 meter/second")))
+        .stdout(predicate::str::contains("1.75 inch"))
+        .stderr(predicate::str::contains(
+            "replaces the standard tier's unit",
+        ))
+        .stderr(predicate::str::contains("ignoring user units").not());
+}
+
+#[test]
+fn user_units_file_functions_are_callable() {
+    let home = config_home_with_units(
+        "functions",
+        "fn hypotenuse(a: Length, b: Length): Length = sqrt(a^2 + b^2)\n",
+    );
+    runits()
+        .env("XDG_CONFIG_HOME", &home)
+        .args(["hypotenuse(3 ft, 4 ft)", "ft"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("5 foot"));
+}
+
+#[test]
+fn user_units_file_is_loaded_for_repl() {
+    let home = config_home_with_units("repl", "unit pallet_len: Length = 1.2 m\n");
+    runits()
+        .env("XDG_CONFIG_HOME", &home)
+        .env("NO_COLOR", "1")
+        .args(["--intro-banner", "off"])
+        .write_stdin("10 pallet_len to m\nquit\n")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("12"));
+}
+
+#[test]
+fn user_units_override_requires_marker_and_warns() {
+    let home = config_home_with_units("override-ok", "unit cup: Volume = 250 mL @override\n");
+    runits()
+        .env("XDG_CONFIG_HOME", &home)
+        .args(["2 cup", "mL"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("500"))
+        .stderr(predicate::str::contains("overrides an existing unit"));
+
+    let home = config_home_with_units("override-missing", "unit cup: Volume = 250 mL\n");
+    runits()
+        .env("XDG_CONFIG_HOME", &home)
+        .args(["1 cup", "mL"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("236.588"))
+        .stderr(predicate::str::contains("units.conf:1"))
+        .stderr(predicate::str::contains("@override"));
+}
+
+// ---- Database tiers (--db) ----
+
+#[test]
+fn oneshot_defaults_to_builtin_tier() {
+    runits()
+        .env("XDG_CONFIG_HOME", "/nonexistent")
+        .args(["10 knots", "km/h"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("knots"));
+}
+
+#[test]
+fn db_flag_selects_standard_tier() {
+    runits()
+        .env("XDG_CONFIG_HOME", "/nonexistent")
+        .args(["--db", "standard", "10 knots", "km/h"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("18.52"));
+}
+
+#[test]
+fn db_config_key_selects_tier() {
+    let home = std::env::temp_dir().join(format!("runits-cli-{}-dbkey", std::process::id()));
+    std::fs::create_dir_all(home.join("runits")).unwrap();
+    std::fs::write(
+        home.join("runits").join("config.toml"),
+        "db = \"standard\"\n",
+    )
+    .unwrap();
+    runits()
+        .env("XDG_CONFIG_HOME", &home)
+        .args(["1 fortnight", "day"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("14"));
+}
+
+#[test]
+fn info_reports_tier() {
+    runits()
+        .env("XDG_CONFIG_HOME", "/nonexistent")
+        .env("NO_COLOR", "1")
+        .args(["--info", "--db", "standard"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("(standard)"));
+    runits()
+        .env("XDG_CONFIG_HOME", "/nonexistent")
+        .env("NO_COLOR", "1")
+        .arg("--info")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("(builtin)"));
+}
+
+#[test]
+fn full_tier_reports_gnu_import() {
+    let home = std::env::temp_dir().join(format!("runits-cli-{}-gnu", std::process::id()));
+    std::fs::create_dir_all(home.join("runits")).unwrap();
+    let gnu = home.join("definitions.units");
+    std::fs::write(&gnu, "m !\nsmoot 1.7018 m\nweird 1 m^0.5\n").unwrap();
+    std::fs::write(
+        home.join("runits").join("config.toml"),
+        format!("gnu_definitions = {:?}\n", gnu.display().to_string()),
+    )
+    .unwrap();
+    runits()
+        .env("XDG_CONFIG_HOME", &home)
+        .args(["--db", "full", "1 smoot", "cm"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("170.18"))
+        .stderr(predicate::str::contains("loaded 1 units"))
+        .stderr(predicate::str::contains("; skipped "));
+}
+
+#[test]
+fn repl_banner_reports_tier() {
+    runits()
+        .env("XDG_CONFIG_HOME", "/nonexistent")
+        .env("NO_COLOR", "1")
+        .args(["--intro-banner", "short"])
+        .write_stdin("quit\n")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("units (standard)"));
+}
+
+// ---- runits check ----
+
+/// Write `contents` to a fresh definition file for a check test.
+fn check_file(name: &str, contents: &str) -> std::path::PathBuf {
+    let dir = std::env::temp_dir().join(format!("runits-check-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let path = dir.join(name);
+    std::fs::write(&path, contents).unwrap();
+    path
+}
+
+#[test]
+fn import_gnu_json_reports_the_summary() {
+    let path = check_file(
+        "definitions.units",
+        "m !\nfurlong_x 201.168 m\ntempX(x) units=[1;K] x K\n",
+    );
+    runits()
+        .args(["import-gnu", "--json"])
+        .arg(&path)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(r#""units":1,"#))
+        .stdout(predicate::str::contains(r#""reason":"nonlinear","#))
+        .stdout(predicate::str::contains("loaded ").not());
+}
+
+#[test]
+fn check_clean_file_succeeds() {
+    let path = check_file(
+        "clean.units",
+        "unit rack_unit: Length = 1.75 inch @aliases(rack_units)\n",
+    );
+    runits()
+        .arg("check")
+        .arg(&path)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("No problems found"));
+}
+
+#[test]
+fn check_accepts_table_definitions() {
+    let path = check_file(
+        "tables.units",
+        "table ringsize_uk[mm]: Length = 1 12.04, 2 12.45, 3 12.85\n",
+    );
+    runits()
+        .arg("check")
+        .arg(&path)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("No problems found"));
+}
+
+#[test]
+fn check_cycle_fails() {
+    let path = check_file("cycle.units", "unit aa = 2 bb\nunit bb = 3 aa\n");
+    runits()
+        .arg("check")
+        .arg(&path)
+        .assert()
+        .code(1)
+        .stdout(predicate::str::contains("definition-cycle"))
+        .stdout(predicate::str::contains("aa -> bb -> aa"));
+}
+
+#[test]
+fn check_wrong_valued_constant_reports_its_quantity() {
+    let path = check_file("const.units", "const g_wrong: Acceleration = 9.8 m/s\n");
+    runits()
+        .arg("check")
+        .arg(&path)
+        .assert()
+        .code(1)
+        .stdout(predicate::str::contains("constant-quantity"))
+        .stdout(predicate::str::contains(
+            "g_wrong: declared as Acceleration but has dimensions Length*Time^-1",
+        ))
+        .stdout(predicate::str::contains("definition-error").not());
+}
+
+#[test]
+fn check_json_lists_problems() {
+    let path = check_file("naming.units", "unit Widget: Length = 2 m\n");
+    runits()
+        .args(["check", "--json"])
+        .arg(&path)
+        .assert()
+        .code(1)
+        .stdout(predicate::str::contains(
+            r#""kind":"naming-rule","subject":"Widget""#,
+        ));
+}
+
+#[test]
+fn check_builtin_database_reports_prefix_ambiguity() {
+    runits()
+        .env("XDG_CONFIG_HOME", "/nonexistent")
+        .arg("check")
+        .assert()
+        .code(1)
+        .stdout(predicate::str::contains("min: resolves to 'minute'"));
 }